@@ -0,0 +1,58 @@
+// Plain sentences describing the game, for screen readers and braille displays
+// Each sentence stands on its own, so nothing depends on layout, colour or
+// lines being redrawn
+use std::ops::RangeInclusive;
+
+use crate::game::{Bound, Game, GuessOutcome, Turn};
+
+/// Describes one turn, e.g. "Attempt 3. Your guess 40 is too low. The number
+/// is between 41 and 78." `feasible` is the range as it stood after the turn.
+pub fn turn_sentence(
+    game: &Game,
+    attempt: usize,
+    turn: &Turn,
+    feasible: &RangeInclusive<u32>,
+) -> String {
+    let verdict = match turn.outcome {
+        GuessOutcome::TooSmall => "is too low",
+        GuessOutcome::TooBig => "is too high",
+        GuessOutcome::Correct if turn.guess == game.secret() => "is correct",
+        GuessOutcome::Correct => "is close enough",
+    };
+    let after = match turn.outcome {
+        GuessOutcome::Correct => format!("The number was {}.", game.secret()),
+        _ => where_it_is(game, feasible),
+    };
+    format!(
+        "Attempt {attempt}. Your guess {} {verdict}. {after}",
+        turn.guess
+    )
+}
+
+/// One sentence per turn played so far, oldest first.
+pub fn history_sentences(game: &Game) -> Vec<String> {
+    if game.history().is_empty() {
+        return vec!["You have not made any guesses yet.".to_string()];
+    }
+    game.history()
+        .iter()
+        .zip(game.feasible_history())
+        .enumerate()
+        .map(|(i, (turn, feasible))| turn_sentence(game, i + 1, turn, &feasible))
+        .collect()
+}
+
+// In a blind game an end of the range that hasn't been revealed is left out,
+// so the sentence doesn't give away more than the bars would
+fn where_it_is(game: &Game, feasible: &RangeInclusive<u32>) -> String {
+    let (low, high) = (*feasible.start(), *feasible.end());
+    let low_known = low != *game.range().start() || game.is_revealed(Bound::Low);
+    let high_known = high != *game.range().end() || game.is_revealed(Bound::High);
+    match (low_known, high_known) {
+        _ if low == high => format!("The number must be {low}."),
+        (true, true) => format!("The number is between {low} and {high}."),
+        (true, false) => format!("The number is {low} or higher."),
+        (false, true) => format!("The number is {high} or lower."),
+        (false, false) => "The range is still unknown.".to_string(),
+    }
+}
@@ -0,0 +1,316 @@
+// Teaching aids that look at a game through the lens of information theory
+use std::ops::RangeInclusive;
+
+pub use crate::range_math::{eliminated, midpoint, percent_eliminated, width};
+
+/// Bits of information still needed to pin down the secret: log2 of the number
+/// of candidates. A single candidate (or none) needs 0 bits.
+pub fn remaining_bits(range: &RangeInclusive<u32>) -> f64 {
+    match width(range) {
+        0 => 0.0,
+        n => (n as f64).log2(),
+    }
+}
+
+/// The guesses a perfect binary search makes to find `secret` in `range`,
+/// or none if `secret` isn't in `range`.
+pub fn optimal_guesses(range: &RangeInclusive<u32>, secret: u32) -> Vec<u32> {
+    // Below, the secret is always inside low..=high, so guess + 1 and
+    // guess - 1 can only be reached with room to spare
+    if !range.contains(&secret) {
+        return Vec::new();
+    }
+    let (mut low, mut high) = (*range.start(), *range.end());
+    let mut guesses = Vec::new();
+    loop {
+        let guess = midpoint(&(low..=high));
+        guesses.push(guess);
+        match guess.cmp(&secret) {
+            std::cmp::Ordering::Less => low = guess + 1,
+            std::cmp::Ordering::Greater => high = guess - 1,
+            std::cmp::Ordering::Equal => return guesses,
+        }
+    }
+}
+
+/// A guess counts as good when it removes at least this share of the candidates.
+/// The midpoint always removes about half, so it can never be graded down.
+pub const GOOD_GUESS_PERCENT: f64 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Good { percent: f64 },
+    Suboptimal { better: u32, percent: f64 },
+}
+
+/// Grades one guess, given the candidates before and after it.
+/// Finding the secret eliminates every candidate, so it is always 100% and good.
+pub fn grade_guess(
+    before: &RangeInclusive<u32>,
+    after: &RangeInclusive<u32>,
+    solved: bool,
+) -> Verdict {
+    let percent = if solved {
+        100.0
+    } else {
+        percent_eliminated(before, after)
+    };
+    if percent >= GOOD_GUESS_PERCENT {
+        Verdict::Good { percent }
+    } else {
+        Verdict::Suboptimal {
+            better: midpoint(before),
+            percent,
+        }
+    }
+}
+
+/// The most guesses binary search ever needs on `range`: floor(log2(width)) + 1.
+pub fn worst_case_guesses(range: &RangeInclusive<u32>) -> u32 {
+    // The number of binary digits of the width is exactly floor(log2(width)) + 1
+    u64::BITS - width(range).leading_zeros()
+}
+
+/// How close a round came to binary search, best first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+}
+
+impl Grade {
+    pub fn letter(self) -> char {
+        match self {
+            Grade::A => 'A',
+            Grade::B => 'B',
+            Grade::C => 'C',
+            Grade::D => 'D',
+        }
+    }
+}
+
+impl std::fmt::Display for Grade {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.letter())
+    }
+}
+
+/// Grades `actual` guesses against the `optimal` count by their ratio: A for
+/// matching or beating it, B for up to half as many again, C for up to twice
+/// as many, D beyond that. An optimal count of 0 grades any guesses as A.
+pub fn efficiency_grade(actual: u32, optimal: u32) -> Grade {
+    // In u64, so that 3 * optimal can't overflow
+    let (actual, optimal) = (actual as u64, optimal as u64);
+    if actual <= optimal || optimal == 0 {
+        Grade::A
+    } else if 2 * actual <= 3 * optimal {
+        Grade::B
+    } else if actual <= 2 * optimal {
+        Grade::C
+    } else {
+        Grade::D
+    }
+}
+
+/// The grade for finding the secret of `range` in `attempts` guesses, against
+/// the most binary search ever needs there (see `worst_case_guesses`).
+pub fn round_grade(range: &RangeInclusive<u32>, attempts: u32) -> Grade {
+    efficiency_grade(attempts, worst_case_guesses(range))
+}
+
+/// The Brier score of `(confidence, was_correct)` pairs, with confidence as a
+/// probability from 0 to 1: the mean squared gap between what was claimed and
+/// what happened. 0 is perfect, 0.25 is what always saying 50% earns, 1 is
+/// confidently wrong every time. An empty slice scores 0.
+pub fn brier_score(pairs: &[(f64, bool)]) -> f64 {
+    if pairs.is_empty() {
+        return 0.0;
+    }
+    let total: f64 = pairs
+        .iter()
+        .map(|&(confidence, correct)| (confidence - if correct { 1.0 } else { 0.0 }).powi(2))
+        .sum();
+    total / pairs.len() as f64
+}
+
+/// The simple moving average of every full `window` of `data`, in order: one
+/// value per window, so `window - 1` fewer than `data`. A window of 0, or one
+/// longer than `data`, has no full windows and gives an empty vec.
+pub fn moving_average(data: &[f64], window: usize) -> Vec<f64> {
+    if window == 0 {
+        return Vec::new();
+    }
+    data.windows(window)
+        .map(|w| w.iter().sum::<f64>() / window as f64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, Guess, GuessOutcome};
+
+    #[test]
+    fn efficiency_grade_follows_the_ratio() {
+        // Beating or matching optimal
+        assert_eq!(efficiency_grade(3, 7), Grade::A);
+        assert_eq!(efficiency_grade(7, 7), Grade::A);
+        // Up to 1.5 times
+        assert_eq!(efficiency_grade(8, 7), Grade::B);
+        assert_eq!(efficiency_grade(9, 6), Grade::B);
+        // Up to twice
+        assert_eq!(efficiency_grade(10, 6), Grade::C);
+        assert_eq!(efficiency_grade(12, 6), Grade::C);
+        // More than twice
+        assert_eq!(efficiency_grade(13, 6), Grade::D);
+        assert_eq!(efficiency_grade(u32::MAX, u32::MAX / 2), Grade::D);
+    }
+
+    #[test]
+    fn efficiency_grade_with_no_optimal_is_an_a() {
+        assert_eq!(efficiency_grade(0, 0), Grade::A);
+        assert_eq!(efficiency_grade(5, 0), Grade::A);
+    }
+
+    #[test]
+    fn round_grade_uses_the_range_width() {
+        // 1..=100 needs at most 7 guesses
+        assert_eq!(round_grade(&(1..=100), 7), Grade::A);
+        assert_eq!(round_grade(&(1..=100), 10), Grade::B);
+        assert_eq!(round_grade(&(1..=100), 15), Grade::D);
+    }
+
+    #[test]
+    fn grades_print_as_letters() {
+        assert_eq!(Grade::B.to_string(), "B");
+        assert!(Grade::A < Grade::D);
+    }
+
+    // Close enough for values worked out by hand
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn remaining_bits_is_log2_of_the_candidates() {
+        assert_close(remaining_bits(&(7..=7)), 0.0);
+        assert_close(remaining_bits(&(1..=2)), 1.0);
+        assert_close(remaining_bits(&(1..=4)), 2.0);
+        assert_close(remaining_bits(&(1..=100)), 6.643_856_189_774_724);
+        assert_close(remaining_bits(&(0..=u32::MAX)), 32.0);
+    }
+
+    // The verdict on each guess of a round on 1..=100 hiding 42
+    fn verdicts(guesses: &[u32]) -> Vec<Verdict> {
+        let mut game = Game::with_secret(1..=100, 42);
+        let mut before = game.range().clone();
+        let mut verdicts = Vec::new();
+        for &value in guesses {
+            let guess = Guess::new(value, game.range()).unwrap();
+            let solved = game.guess(guess) == GuessOutcome::Correct;
+            verdicts.push(grade_guess(&before, game.feasible(), solved));
+            before = game.feasible().clone();
+        }
+        verdicts
+    }
+
+    #[test]
+    fn each_guess_is_graded_on_what_it_eliminated() {
+        let verdicts = verdicts(&[50, 10, 42]);
+        // The first guess is graded against the whole range: 51 of 100 gone
+        assert_eq!(verdicts[0], Verdict::Good { percent: 51.0 });
+        // 1..=49 down to 11..=49 removes 10 of 49, so the midpoint was better
+        let Verdict::Suboptimal { better, percent } = verdicts[1] else {
+            panic!("expected a suboptimal verdict, got {:?}", verdicts[1]);
+        };
+        assert_eq!(better, 25);
+        assert_close(percent, 1000.0 / 49.0);
+        // Hitting the secret always counts as eliminating everything
+        assert_eq!(verdicts[2], Verdict::Good { percent: 100.0 });
+    }
+
+    #[test]
+    fn a_first_guess_that_wins_is_100_percent() {
+        assert_eq!(verdicts(&[42]), [Verdict::Good { percent: 100.0 }]);
+    }
+
+    #[test]
+    fn the_good_guess_threshold_is_inclusive() {
+        // 40 of 100 gone is exactly GOOD_GUESS_PERCENT
+        assert_eq!(
+            grade_guess(&(1..=100), &(41..=100), false),
+            Verdict::Good { percent: 40.0 }
+        );
+        assert_eq!(
+            grade_guess(&(1..=100), &(40..=100), false),
+            Verdict::Suboptimal {
+                better: 50,
+                percent: 39.0
+            }
+        );
+    }
+
+    #[test]
+    fn a_calibrated_player_scores_what_their_confidence_implies() {
+        // 80% sure, right four times in five: (4 * 0.04 + 0.64) / 5
+        let pairs = [
+            (0.8, true),
+            (0.8, true),
+            (0.8, true),
+            (0.8, true),
+            (0.8, false),
+        ];
+        assert_close(brier_score(&pairs), 0.16);
+        // Always 50% is 0.25 whatever happens
+        assert_close(brier_score(&[(0.5, true), (0.5, false)]), 0.25);
+        assert_close(brier_score(&[(1.0, true), (0.0, false)]), 0.0);
+    }
+
+    #[test]
+    fn overconfidence_scores_worse_than_calibration() {
+        // 100% sure, right only half the time
+        let over = brier_score(&[(1.0, true), (1.0, false)]);
+        assert_close(over, 0.5);
+        assert!(over > brier_score(&[(0.5, true), (0.5, false)]));
+        assert_close(brier_score(&[(1.0, false)]), 1.0);
+    }
+
+    #[test]
+    fn underconfidence_scores_worse_than_calibration() {
+        // 60% sure, right every time
+        let under = brier_score(&[(0.6, true), (0.6, true)]);
+        assert_close(under, 0.16);
+        assert!(under > brier_score(&[(1.0, true), (1.0, true)]));
+    }
+
+    #[test]
+    fn no_claims_score_zero() {
+        assert_eq!(brier_score(&[]), 0.0);
+    }
+
+    const SERIES: [f64; 5] = [1.0, 2.0, 6.0, 3.0, 8.0];
+
+    #[test]
+    fn moving_average_over_windows_of_two_and_three() {
+        assert_eq!(moving_average(&SERIES, 2), [1.5, 4.0, 4.5, 5.5]);
+        assert_eq!(moving_average(&SERIES, 3), [3.0, 11.0 / 3.0, 17.0 / 3.0]);
+    }
+
+    #[test]
+    fn a_window_as_long_as_the_data_is_its_mean() {
+        assert_eq!(moving_average(&SERIES, SERIES.len()), [4.0]);
+        assert_eq!(moving_average(&SERIES, 1), SERIES);
+    }
+
+    #[test]
+    fn degenerate_windows_give_nothing() {
+        assert!(moving_average(&SERIES, 0).is_empty());
+        assert!(moving_average(&SERIES, SERIES.len() + 1).is_empty());
+        assert!(moving_average(&[], 2).is_empty());
+    }
+}
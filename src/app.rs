@@ -0,0 +1,2233 @@
+// The guessing game itself, everything src/main.rs does after parsing its
+// arguments. It lives in the library so that a test can play a round with
+// scripted input and read back what it wrote
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+
+use crate::accessible;
+use crate::analysis::{self, Verdict};
+use crate::batch;
+use crate::capabilities::{Capabilities, Feature};
+use crate::celebration;
+use crate::chart;
+use crate::commands::{self, Aliases};
+use crate::commitment;
+use crate::daily::{self, NaiveDate};
+use crate::grading::{self, FileStatus, Rubric};
+use crate::hints;
+use crate::input_policy::{InputGuard, Response};
+use crate::intervals::{self, IntervalOutcome};
+use crate::leaderboard::{self, Entry, Leaderboard, MAX_ENTRIES};
+use crate::number_words::parse_number_word;
+use crate::observers::BellOnWin;
+use crate::persisted_file;
+use crate::prelude::*;
+use crate::progress::{self, RoundResultBuilder};
+use crate::property_hints::PropertyHints;
+use crate::radix;
+use crate::range_math;
+use crate::save;
+use crate::season::{Season, SeasonView, Seasons};
+use crate::spectator::{Event, SpectatorLog};
+use crate::strategy;
+use crate::terminal::SymbolSet;
+use crate::timing::{self, Stopwatch, SystemClock};
+use crate::tutorial::{self, TutorialStep};
+use crate::warmup::{self, Warmup};
+use crate::weakness;
+
+// Every round starts with this many points to spend on hints
+const STARTING_POINTS: i32 = 10;
+
+/// Why `run` (or `play`) stopped without playing a round to the end.
+#[derive(Debug)]
+pub enum RunError {
+    /// Options that can't be used together; exits with status 2, like a bad flag.
+    Usage(String),
+    /// A round that can't start, like a missing save file.
+    Setup(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::Usage(message) | RunError::Setup(message) => write!(f, "{message}"),
+            RunError::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl From<io::Error> for RunError {
+    fn from(err: io::Error) -> RunError {
+        RunError::Io(err)
+    }
+}
+
+fn record_win(
+    game: &Game,
+    config: &Config,
+    input: &mut dyn InputSource,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let path = config.leaderboard.as_path();
+    let mut leaderboard = match Leaderboard::load(path) {
+        Ok(leaderboard) => leaderboard,
+        Err(err) => {
+            writeln!(out, "Could not read {}: {err}", path.display())?;
+            return Ok(());
+        }
+    };
+    // A round that started in the last season still counts in this one
+    let seasons = config.seasons;
+    let season = seasons.current(NaiveDate::today_utc());
+    if let Some(previous) = leaderboard.roll_season(season) {
+        announce_season(&leaderboard, seasons, previous, season, out)?;
+    }
+
+    writeln!(
+        out,
+        "Enter your name for the leaderboard (leave blank to skip):"
+    )?;
+    out.flush()?;
+    let name = input.read_line()?.filter(|name| !name.is_empty());
+
+    let range = game.range().clone();
+    // A clock before 1970 is wrong, but not worth losing the result over
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let entry = Entry {
+        name,
+        attempts: game.attempts(),
+        recorded_at: now,
+    };
+    let all_time = leaderboard.record(
+        range.clone(),
+        entry.name.clone(),
+        entry.attempts,
+        entry.recorded_at,
+    );
+    if config.all_time {
+        if let Some(position) = all_time {
+            writeln!(
+                out,
+                "You placed #{} on the all-time leaderboard!",
+                position + 1
+            )?;
+        }
+    } else if let Some(position) = leaderboard
+        .season_entries(&range, seasons, season)
+        .iter()
+        .position(|e| **e == entry)
+        .filter(|&position| position < MAX_ENTRIES)
+    {
+        writeln!(out, "You placed #{} in season {season}!", position + 1)?;
+    }
+    update_progress(&mut leaderboard, game, true, out)?;
+    if let Err(err) = leaderboard.save(path) {
+        writeln!(out, "Could not save {}: {err}", path.display())?;
+    }
+
+    if config.all_time {
+        writeln!(
+            out,
+            "Top scores for {}..={} of all time:",
+            range.start(),
+            range.end()
+        )?;
+        print_entries(&leaderboard.top(&range).iter().collect::<Vec<_>>(), out)
+    } else {
+        writeln!(
+            out,
+            "Top scores for {}..={} in season {season}:",
+            range.start(),
+            range.end()
+        )?;
+        print_entries(&leaderboard.top_in_season(&range, seasons, season), out)
+    }
+}
+
+fn print_entries(entries: &[&Entry], out: &mut dyn Write) -> io::Result<()> {
+    for (i, entry) in entries.iter().enumerate() {
+        let name = entry.name.as_deref().unwrap_or("anonymous");
+        writeln!(out, "  {}. {:<12} {} attempts", i + 1, name, entry.attempts)?;
+    }
+    Ok(())
+}
+
+// The final standings of a season that has just ended, with where the player
+// (the name given with their latest result) finished
+fn announce_season(
+    leaderboard: &Leaderboard,
+    seasons: Seasons,
+    season: Season,
+    current: Season,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(out, "Season {season} is over. The final standings:")?;
+    let mut any = false;
+    for range in leaderboard.ranges() {
+        let entries = leaderboard.season_entries(range, seasons, season);
+        if entries.is_empty() {
+            continue;
+        }
+        any = true;
+        writeln!(out, "{}..={}:", range.start(), range.end())?;
+        print_entries(&entries[..entries.len().min(MAX_ENTRIES)], out)?;
+        if let Some(player) = leaderboard.player()
+            && let Some(place) = leaderboard.placement(range, seasons, season, player)
+        {
+            writeln!(
+                out,
+                "  You ({player}) finished #{} of {}.",
+                place + 1,
+                entries.len()
+            )?;
+        }
+    }
+    if !any {
+        writeln!(out, "Nobody set a score in it.")?;
+    }
+    writeln!(out, "Season {current} starts now.")
+}
+
+// --season: one season's standings for every range, or the all-time ones
+fn print_season(config: &Config, view: SeasonView, out: &mut dyn Write) -> Result<(), RunError> {
+    let leaderboard = Leaderboard::load(&config.leaderboard).map_err(|err| {
+        RunError::Setup(format!(
+            "Could not read {}: {err}",
+            config.leaderboard.display()
+        ))
+    })?;
+    let view = match view {
+        SeasonView::Season(season) => SeasonView::Season(config.seasons.align(season)),
+        SeasonView::AllTime => SeasonView::AllTime,
+    };
+    match view {
+        SeasonView::AllTime => writeln!(out, "All-time standings:")?,
+        SeasonView::Season(season) => writeln!(out, "Standings for season {season}:")?,
+    }
+    let mut any = false;
+    for range in leaderboard.ranges() {
+        let entries = match view {
+            SeasonView::AllTime => leaderboard.top(range).iter().collect(),
+            SeasonView::Season(season) => leaderboard.top_in_season(range, config.seasons, season),
+        };
+        if entries.is_empty() {
+            continue;
+        }
+        any = true;
+        writeln!(out, "{}..={}:", range.start(), range.end())?;
+        print_entries(&entries, out)?;
+    }
+    if !any {
+        writeln!(out, "No scores yet.")?;
+    }
+    Ok(())
+}
+
+// The round so far, ready to build into a RoundResult; None if it wasn't
+// played on one of the difficulties
+fn round_builder(game: &Game, won: bool) -> Option<RoundResultBuilder> {
+    let difficulty = Difficulty::from_range(game.range())?;
+    Some(
+        RoundResult::builder(difficulty)
+            .won(won)
+            .attempts(game.attempts())
+            .secret(game.secret())
+            .history(game.history().iter().map(|turn| turn.guess).collect()),
+    )
+}
+
+// Counts the round towards unlocking the next difficulty, if it was played on one
+fn update_progress(
+    leaderboard: &mut Leaderboard,
+    game: &Game,
+    won: bool,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let Some(builder) = round_builder(game, won) else {
+        return Ok(());
+    };
+    let result = builder
+        .build()
+        .expect("a played game is a consistent round");
+    if let Some(unlocked) = progress::maybe_unlock(leaderboard.profile_mut(), &result) {
+        writeln!(
+            out,
+            "You unlocked {unlocked}! Play it with --difficulty {unlocked}."
+        )?;
+    }
+    Ok(())
+}
+
+// Giving up on a round at your hardest level ends your winning streak there
+fn record_loss(game: &Game, path: &Path, out: &mut dyn Write) -> io::Result<()> {
+    let Ok(mut leaderboard) = Leaderboard::load(path) else {
+        return Ok(());
+    };
+    let before = leaderboard.profile().clone();
+    update_progress(&mut leaderboard, game, false, out)?;
+    if *leaderboard.profile() != before
+        && let Err(err) = leaderboard.save(path)
+    {
+        writeln!(out, "Could not save {}: {err}", path.display())?;
+    }
+    Ok(())
+}
+
+// A number as the player reads and types them, in the --base they chose
+fn in_base(n: u32, config: &Config) -> String {
+    radix::format_in_base(n, config.base)
+}
+
+// --symbols puts a shape in front of the answer, so it doesn't rely on the words alone
+fn print_glyph(
+    symbols: Option<SymbolSet>,
+    outcome: GuessOutcome,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    match symbols {
+        Some(set) => write!(out, "{} ", set.glyph(outcome)),
+        None => Ok(()),
+    }
+}
+
+fn print_outcome(
+    game: &Game,
+    guess: Guess,
+    outcome: GuessOutcome,
+    config: &Config,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let symbols = config.symbols;
+    print_glyph(symbols, outcome, out)?;
+    match outcome {
+        GuessOutcome::TooSmall => writeln!(out, "Too small!"),
+        GuessOutcome::TooBig => writeln!(out, "Too big!"),
+        GuessOutcome::Correct => {
+            if guess.value() == game.secret() {
+                writeln!(out, "You win! It took you {} attempts.", game.attempts())
+            } else {
+                writeln!(
+                    out,
+                    "Close enough — you win! The secret was {}. It took you {} attempts.",
+                    in_base(game.secret(), config),
+                    game.attempts()
+                )
+            }
+        }
+    }
+}
+
+// --terse answers a guess in a few characters, like "50 low" or "65 won in 3"
+fn print_terse_outcome(
+    game: &Game,
+    guess: Guess,
+    outcome: GuessOutcome,
+    config: &Config,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    print_glyph(config.symbols, outcome, out)?;
+    let guess = in_base(guess.value(), config);
+    match outcome {
+        GuessOutcome::TooSmall => writeln!(out, "{guess} low"),
+        GuessOutcome::TooBig => writeln!(out, "{guess} high"),
+        GuessOutcome::Correct => writeln!(out, "{guess} won in {}", game.attempts()),
+    }
+}
+
+fn print_coach_summary(game: &Game, out: &mut dyn Write) -> io::Result<()> {
+    let yours: Vec<u32> = game.history().iter().map(|turn| turn.guess).collect();
+    let optimal = analysis::optimal_guesses(game.range(), game.secret());
+    writeln!(out, "Your guesses:   {yours:?}")?;
+    writeln!(out, "Binary search:  {optimal:?}")
+}
+
+// How wide to draw a chart: the terminal's width, but never past --max-line-width,
+// where the chart would be wrapped out of shape
+fn chart_width(config: &Config, caps: &Capabilities) -> usize {
+    let width = caps.width.unwrap_or(chart::DEFAULT_WIDTH);
+    config.max_line_width.map_or(width, |max| width.min(max))
+}
+
+// Plays the frames in place: after each one, move the cursor back up over it
+// and clear every line before redrawing, so a shorter line leaves nothing behind
+fn celebrate(game: &Game, width: usize, out: &mut dyn Write) -> io::Result<()> {
+    let frames = celebration::celebration_frames(game, width);
+    for (i, frame) in frames.iter().enumerate() {
+        if i > 0 {
+            write!(out, "\x1b[{}A", frame.lines().count())?;
+        }
+        for line in frame.lines() {
+            writeln!(out, "\x1b[2K{line}")?;
+        }
+        out.flush()?;
+        thread::sleep(Duration::from_millis(300));
+    }
+    Ok(())
+}
+
+fn plural(n: u32) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+// Asks until it gets a whole number from 0 to 100; None at end of input
+fn ask_confidence(input: &mut dyn InputSource, out: &mut dyn Write) -> io::Result<Option<f64>> {
+    loop {
+        writeln!(out, "How confident are you? (0-100)")?;
+        out.flush()?;
+        let Some(line) = input.read_line()? else {
+            return Ok(None);
+        };
+        match line.parse::<u32>() {
+            Ok(percent) if percent <= 100 => return Ok(Some(percent as f64 / 100.0)),
+            _ => writeln!(out, "Please type a whole number from 0 to 100.")?,
+        }
+    }
+}
+
+fn print_calibration(ratings: &[(f64, bool)], out: &mut dyn Write) -> io::Result<()> {
+    if ratings.is_empty() {
+        return Ok(());
+    }
+    writeln!(
+        out,
+        "Calibration: Brier score {:.3} over {} guesses (0 is perfect, 0.25 is always saying 50%).",
+        analysis::brier_score(ratings),
+        ratings.len()
+    )
+}
+
+fn print_timing(think_times: &[Duration], out: &mut dyn Write) -> io::Result<()> {
+    if let Some((fastest, slowest)) = timing::timing_summary(think_times) {
+        writeln!(
+            out,
+            "Fastest guess: {:.1}s. Slowest guess: {:.1}s.",
+            fastest.as_secs_f64(),
+            slowest.as_secs_f64()
+        )?;
+    }
+    Ok(())
+}
+
+// Asks for the next guess; --show-range puts the range in the prompt, unless
+// the game is blind, where the range is for the player to find out
+fn print_prompt(
+    game: &Game,
+    config: &Config,
+    hints_left: Option<u32>,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let range = game.range();
+    let range = (config.show_range && !config.blind).then(|| {
+        format!(
+            "{}-{}",
+            in_base(*range.start(), config),
+            in_base(*range.end(), config)
+        )
+    });
+    match (range, hints_left) {
+        (None, _) if config.terse => write!(out, "> "),
+        (Some(range), _) if config.terse => write!(out, "{range}> "),
+        (None, None) => writeln!(out, "Please input your guess:"),
+        (None, Some(n)) => writeln!(out, "Please input your guess ({n} hint{} left):", plural(n)),
+        (Some(range), None) => writeln!(out, "Guess ({range}):"),
+        (Some(range), Some(n)) => writeln!(out, "Guess ({range}, {n} hint{} left):", plural(n)),
+    }
+}
+
+// Everything the prompt understands besides a guess, with what 'help' says about it
+// The dispatcher in run only accepts names from this table, so the two can't drift apart
+const COMMANDS: &[(&str, &str)] = &[
+    (
+        "hint",
+        "get a hint: 'hint parity', 'hint zone', 'hint midpoint' or 'hint property'",
+    ),
+    ("history", "show your guesses so far"),
+    ("help", "list these commands"),
+    ("quit", "end the round"),
+];
+
+fn print_help(aliases: &Aliases, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "Type a number to guess it, or one of these commands:")?;
+    for (name, description) in COMMANDS {
+        let names: Vec<&str> = aliases.for_command(name).collect();
+        if names.is_empty() {
+            writeln!(out, "  {name:<8} {description}")?;
+        } else {
+            writeln!(out, "  {name:<8} {description} (or: {})", names.join(", "))?;
+        }
+    }
+    writeln!(
+        out,
+        "A command can be shortened to any start only it has, like 'hi' for history."
+    )
+}
+
+// The answer to the commitment printed before the round
+// What was hashed is always the decimal secret, whatever --base shows
+fn print_reveal(game: &Game, salt: &str, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        out,
+        "The secret was {} and the salt was {salt}. The SHA-256 of \"{}:{salt}\" is the commitment you were shown.",
+        in_base(game.secret(), config),
+        game.secret()
+    )
+}
+
+fn print_seed(seed: u64, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+    match (config.center, config.spread) {
+        (Some(center), Some(spread)) => writeln!(
+            out,
+            "This round's seed was {seed}. Replay it with --center {center} --spread {spread}% --seed {seed}."
+        ),
+        _ => writeln!(
+            out,
+            "This round's seed was {seed}. Replay it with --difficulty {} --seed {seed}.",
+            config.difficulty
+        ),
+    }
+}
+
+// What is kept back until the round ends: the commitment's salt and the seed
+struct Secrets {
+    salt: Option<String>,
+    seed: Option<u64>,
+}
+
+// Everything that is said when a round ends, won or not
+fn print_round_report(
+    game: &Game,
+    config: &Config,
+    ratings: &[(f64, bool)],
+    think_times: &[Duration],
+    secrets: &Secrets,
+    failures: u32,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    print_calibration(ratings, out)?;
+    if config.timing {
+        print_timing(think_times, out)?;
+    }
+    if failures > 0 {
+        writeln!(
+            out,
+            "{failures} line{} of invalid input counted as failures.",
+            plural(failures)
+        )?;
+    }
+    // A round saved to resume later isn't over, so its secret stays hidden
+    let won = game
+        .history()
+        .last()
+        .is_some_and(|turn| turn.outcome == GuessOutcome::Correct);
+    if won || config.save.is_none() {
+        if let Some(salt) = &secrets.salt {
+            print_reveal(game, salt, config, out)?;
+        }
+        if let Some(seed) = secrets.seed.filter(|_| config.show_seed) {
+            print_seed(seed, config, out)?;
+        }
+    }
+    Ok(())
+}
+
+// With --practice, the zone of the range the history shows is hardest, after
+// saying which it is; None without --practice or without a won round to go on
+fn practice_zone(config: &Config, out: &mut dyn Write) -> Result<Option<usize>, RunError> {
+    let Some(path) = config.history.as_ref().filter(|_| config.practice) else {
+        return Ok(None);
+    };
+    let sessions = weakness::load_history(path)
+        .map_err(|err| RunError::Setup(format!("Could not read {}: {err}", path.display())))?;
+    let report = weakness::weakness_profile(&sessions);
+    let Some(zone) = report.weakest else {
+        writeln!(
+            out,
+            "There are no won rounds in {} yet, so this is an ordinary round.",
+            path.display()
+        )?;
+        return Ok(None);
+    };
+    let zone_range = weakness::zone_range(&config.range(), zone);
+    writeln!(
+        out,
+        "Practice round: your weakest zone is {} to {}, where binary search needed {:.0}% as many guesses as you did.",
+        in_base(*zone_range.start(), config),
+        in_base(*zone_range.end(), config),
+        report.zones[zone].efficiency * 100.0
+    )?;
+    writeln!(
+        out,
+        "This secret will probably, but not certainly, be in it."
+    )?;
+    Ok(Some(zone))
+}
+
+// Redraws until the secret isn't one of the range's last `window`, notes the
+// new one in the leaderboard and returns the seed it came from
+fn draw_fresh_secret(
+    leaderboard: &mut Leaderboard,
+    path: &Path,
+    range: &std::ops::RangeInclusive<u32>,
+    seed: u64,
+    window: usize,
+    out: &mut dyn Write,
+) -> io::Result<u64> {
+    let draw = leaderboard.recent_secrets().draw(range, seed, window);
+    if draw.repeated {
+        writeln!(
+            out,
+            "This range is too small to avoid all of its last {window} secrets, so this one may have come up lately."
+        )?;
+    }
+    leaderboard
+        .recent_secrets_mut()
+        .remember(range, draw.secret, window);
+    if let Err(err) = leaderboard.save(path) {
+        writeln!(out, "Could not save {}: {err}", path.display())?;
+    }
+    Ok(draw.seed)
+}
+
+// Saves the round if --save was given, then says goodbye
+// A round that is saved isn't over, so only an unsaved one counts as a loss
+fn quit(game: &Game, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+    match &config.save {
+        Some(path) => match save::save_game(path, game) {
+            Ok(()) => writeln!(out, "Saved your round to {}.", path.display())?,
+            Err(err) => writeln!(out, "Could not save to {}: {err}", path.display())?,
+        },
+        // A tutorial is practice, so quitting it costs nothing
+        None if game.attempts() > 0 && game.tolerance() == 0 && !config.tutorial => {
+            record_loss(game, &config.leaderboard, out)?
+        }
+        None => {}
+    }
+    writeln!(out, "Goodbye!")
+}
+
+// The guess-the-range lead-in; returns false if input ran out
+fn run_warmup(
+    range: &std::ops::RangeInclusive<u32>,
+    input: &mut dyn InputSource,
+    out: &mut dyn Write,
+) -> io::Result<bool> {
+    writeln!(
+        out,
+        "Warm-up: what range is the secret hidden in? Answer like '10 20'."
+    )?;
+    let mut warmup = Warmup::new(range.clone());
+    // Each wrong answer earns the next clue; typos don't cost one
+    let mut clue = warmup.next_clue();
+    while !warmup.is_over() {
+        if let Some(text) = clue.take() {
+            writeln!(out, "Clue: {text}")?;
+        }
+        out.flush()?;
+        let Some(line) = input.read_line()? else {
+            return Ok(false);
+        };
+        match warmup::parse_bounds(&line) {
+            Some(bounds) => {
+                writeln!(out, "{}.", warmup.guess(bounds))?;
+                clue = warmup.next_clue();
+            }
+            None => writeln!(out, "Please type two numbers, like '10 20'.")?,
+        }
+    }
+    if !warmup.solved() {
+        writeln!(out, "It was {} to {}.", range.start(), range.end())?;
+    }
+    writeln!(out, "Now for the real game!\n")?;
+    Ok(true)
+}
+
+// --grade: checks and grades every replay file in a folder. Bad files go in the
+// report, so only failing to read the folder or write the report is an error
+fn run_grading(config: &Config, dir: &Path, out: &mut dyn Write) -> Result<(), RunError> {
+    let rubric = match &config.rubric {
+        Some(path) => Rubric::load(path)
+            .map_err(|err| RunError::Setup(format!("Could not read {}: {err}", path.display())))?,
+        None => Rubric::default(),
+    };
+    let reports = grading::grade_dir(dir, &rubric)
+        .map_err(|err| RunError::Setup(format!("Could not read {}: {err}", dir.display())))?;
+    let csv = grading::to_csv(&reports);
+    let Some(path) = &config.report else {
+        write!(out, "{csv}")?;
+        return Ok(());
+    };
+    fs::write(path, csv)?;
+    for report in &reports {
+        let name = report.path.display();
+        let rounds = report.rounds.len();
+        match &report.status {
+            FileStatus::Pass => writeln!(
+                out,
+                "{name}: pass, {rounds} round{}, {} points",
+                plural(rounds as u32),
+                report.points()
+            )?,
+            FileStatus::Invalid => {
+                let bad = report.rounds.iter().filter(|(_, r)| r.is_err()).count();
+                writeln!(
+                    out,
+                    "{name}: FAIL, {bad} of {rounds} rounds are invalid, {} points from the rest",
+                    report.points()
+                )?
+            }
+            FileStatus::Tampered(err) => writeln!(out, "{name}: FAIL, tampered: {err}")?,
+            FileStatus::Unreadable(err) => writeln!(out, "{name}: FAIL, unreadable: {err}")?,
+        }
+    }
+    writeln!(
+        out,
+        "Graded {} file{}; the full report is in {}.",
+        reports.len(),
+        plural(reports.len() as u32),
+        path.display()
+    )?;
+    Ok(())
+}
+
+// Batch mode: let a strategy play many seeded games and report the totals
+fn run_evaluation(
+    name: &str,
+    config: &Config,
+    caps: &Capabilities,
+    out: &mut dyn Write,
+) -> Result<(), RunError> {
+    let Some(mut strategy) = strategy::by_name(name) else {
+        return Err(RunError::Usage(format!(
+            "unknown strategy '{name}', expected one of: {}",
+            strategy::NAMES.join(", ")
+        )));
+    };
+    let range = config.range();
+    let budget = analysis::worst_case_guesses(&range);
+    let result = strategy::evaluate(strategy.as_mut(), &range, config.games, budget, 0);
+    writeln!(
+        out,
+        "{name}: won {} of {} games ({:.1}%) within {budget} guesses, {:.2} guesses on average",
+        result.wins,
+        result.games,
+        100.0 * result.win_rate(),
+        result.average_guesses()
+    )?;
+
+    let mut rows: Vec<(String, u32)> = (1..=budget)
+        .map(|n| {
+            (
+                format!("{n} guess{}", if n == 1 { "" } else { "es" }),
+                result.wins_by_guesses[n as usize],
+            )
+        })
+        .collect();
+    rows.push(("lost".to_string(), result.games - result.wins));
+    if config.accessible {
+        for (label, count) in rows {
+            writeln!(out, "{label}: {count} games.")?;
+        }
+    } else {
+        write!(
+            out,
+            "{}",
+            chart::render_histogram(&rows, chart_width(config, caps))
+        )?;
+    }
+    Ok(())
+}
+
+// Binary search plays one round on the chosen difficulty while the player watches
+// The solver only returns its guesses; how much of them to show is decided here
+fn run_auto(config: &Config, out: &mut dyn Write) -> io::Result<()> {
+    let seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let mut game = Game::seeded(config.range(), seed);
+    let guesses = strategy::auto_solve(&mut strategy::Midpoint, &mut game);
+    if !config.auto_quiet {
+        for (i, turn) in game.history().iter().enumerate() {
+            let answer = match turn.outcome {
+                GuessOutcome::TooSmall => "too small",
+                GuessOutcome::TooBig => "too big",
+                _ => "correct",
+            };
+            writeln!(out, "Guess {}: {} ({answer})", i + 1, turn.guess)?;
+        }
+    }
+    writeln!(
+        out,
+        "Binary search found {} in {} guess{}.",
+        game.secret(),
+        guesses.len(),
+        if guesses.len() == 1 { "" } else { "es" }
+    )
+}
+
+// Every built-in strategy against the same secrets, with a head-to-head table
+fn run_tournament(config: &Config, out: &mut dyn Write) -> io::Result<()> {
+    let range = config.range();
+    let secrets = strategy::shared_secrets(&range, config.games, 0);
+    // Generous enough that even counting up one at a time always finishes
+    let budget = analysis::width(&range).min(u32::MAX as u64) as u32;
+    let results: Vec<Vec<u32>> = strategy::NAMES
+        .iter()
+        .map(|name| {
+            let mut strategy = strategy::by_name(name).expect("built-in strategy");
+            strategy::guesses_per_secret(strategy.as_mut(), &range, &secrets, budget)
+        })
+        .collect();
+    let summaries: Vec<_> = results.iter().map(|r| strategy::summarize(r)).collect();
+    let wins = strategy::head_to_head(&results);
+    writeln!(
+        out,
+        "{} secrets between {} and {}:",
+        secrets.len(),
+        range.start(),
+        range.end()
+    )?;
+    write!(
+        out,
+        "{}",
+        chart::render_tournament(&strategy::NAMES, &summaries, &wins)
+    )
+}
+
+// Interval mode: each turn names a range, and the round is won by naming the
+// secret alone ("65 65"). Hits score by how narrow they were, misses nothing
+fn run_intervals(
+    config: &Config,
+    input: &mut dyn InputSource,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let seed = config.seed.unwrap_or_else(|| rand::rng().random());
+    let game = Game::seeded(config.range(), seed);
+    let range = game.range().clone();
+    writeln!(
+        out,
+        "I'm thinking of a number between {} and {}. Name a range like '{} {}' and I'll say whether it's in there.",
+        in_base(*range.start(), config),
+        in_base(*range.end(), config),
+        in_base(*range.start(), config),
+        in_base(range_math::midpoint(&range), config)
+    )?;
+    writeln!(
+        out,
+        "The narrower a range that holds it, the more points; a range of one number wins."
+    )?;
+    let (mut turns, mut score) = (0u32, 0u32);
+    loop {
+        write!(out, "Your range: ")?;
+        out.flush()?;
+        let Some(line) = input.read_line()? else {
+            break;
+        };
+        if line == "quit" {
+            break;
+        }
+        let bounds: Vec<Option<u32>> = line
+            .split_whitespace()
+            .map(|word| radix::parse_in_base(word, config.base))
+            .collect();
+        let [Some(low), Some(high)] = bounds[..] else {
+            writeln!(out, "Give two numbers, the low end and the high end.")?;
+            continue;
+        };
+        let outcome = match intervals::interval_outcome(low, high, game.secret()) {
+            Ok(outcome) => outcome,
+            // The low end comes first; an upside-down range isn't a turn
+            Err(_) => {
+                writeln!(
+                    out,
+                    "{} is above {}, so try '{} {}'.",
+                    in_base(low, config),
+                    in_base(high, config),
+                    in_base(high, config),
+                    in_base(low, config)
+                )?;
+                continue;
+            }
+        };
+        turns += 1;
+        match outcome {
+            IntervalOutcome::Below => writeln!(out, "It's below {}.", in_base(low, config))?,
+            IntervalOutcome::Above => writeln!(out, "It's above {}.", in_base(high, config))?,
+            IntervalOutcome::Contains => {
+                let points = intervals::interval_score(low, high, &range);
+                score += points;
+                if low == high {
+                    writeln!(
+                        out,
+                        "It's {}! {turns} range{}, {score} points.",
+                        in_base(low, config),
+                        if turns == 1 { "" } else { "s" }
+                    )?;
+                    return Ok(());
+                }
+                writeln!(out, "It's in there. +{points} points ({score} so far).")?;
+            }
+        }
+    }
+    writeln!(
+        out,
+        "The number was {}. {score} points.",
+        in_base(game.secret(), config)
+    )
+}
+
+/// Plays one round: reads every line from `input` and writes everything it
+/// says to `out`. Returns how the round went, or None if it ended before a
+/// guess could be made or wasn't played on one of the difficulties.
+///
+/// Files named in `config` are used as they are; `play` checks them first.
+pub fn run<W: Write>(
+    config: &Config,
+    caps: &Capabilities,
+    input: &mut dyn InputSource,
+    observers: &mut [Box<dyn OutcomeObserver>],
+    out: &mut W,
+) -> Result<Option<RoundResult>, RunError> {
+    // A resumed round has no seed: its secret comes from the save file
+    // The tutorial's advice is written for its own round, shown in full
+    if config.tutorial && (config.resume.is_some() || config.blind || config.warmup) {
+        return Err(RunError::Usage(
+            "--tutorial plays its own round, so it can't be combined with --resume, --blind or --warmup.".to_string(),
+        ));
+    }
+    // --daily picks the secret itself, so it can't also be given one
+    if config.daily && (config.seed.is_some() || config.resume.is_some() || config.tutorial) {
+        return Err(RunError::Usage(
+            "--daily plays the day's own secret, so it can't be combined with --seed, --resume or --tutorial.".to_string(),
+        ));
+    }
+    if config.center.is_some() != config.spread.is_some() {
+        return Err(RunError::Usage(
+            "--center and --spread describe the range together, so each needs the other."
+                .to_string(),
+        ));
+    }
+    // Everyone's daily puzzle is on the same range, and the others bring their own
+    if config.center.is_some() && (config.daily || config.resume.is_some() || config.tutorial) {
+        return Err(RunError::Usage(
+            "--center picks a range of its own, so it can't be combined with --daily, --resume or --tutorial.".to_string(),
+        ));
+    }
+    if config.date.is_some() && !config.daily {
+        return Err(RunError::Usage(
+            "--date picks which day's puzzle to play, so it needs --daily.".to_string(),
+        ));
+    }
+    // Practice works out its weak zone from the rounds --history kept
+    if config.practice && config.history.is_none() {
+        return Err(RunError::Usage(
+            "--practice looks for your weak spots in the rounds --history kept, so it needs --history.".to_string(),
+        ));
+    }
+    if config.practice && (config.daily || config.resume.is_some() || config.tutorial) {
+        return Err(RunError::Usage(
+            "--practice picks its own secret, so it can't be combined with --daily, --resume or --tutorial.".to_string(),
+        ));
+    }
+    if (config.report.is_some() || config.rubric.is_some()) && config.grade.is_none() {
+        return Err(RunError::Usage(
+            "--report and --rubric are for grading replay files, so they need --grade.".to_string(),
+        ));
+    }
+    // Earned hints are never paid for in points, so none can be free
+    if config.free_first_hint && config.earn_hints {
+        return Err(RunError::Usage(
+            "--free-first-hint waives a hint's points, so it can't be combined with --earn-hints."
+                .to_string(),
+        ));
+    }
+    let daily = config
+        .daily
+        .then(|| config.date.unwrap_or_else(NaiveDate::today_utc));
+    let (mut game, seed) = match &config.resume {
+        None if config.tutorial => (tutorial::tutorial_game(), Some(tutorial::SEED)),
+        Some(path) => match save::load_game(path) {
+            Ok(game) => (game, None),
+            Err(err) => {
+                return Err(RunError::Setup(format!(
+                    "Could not resume from {}: {err}",
+                    path.display()
+                )));
+            }
+        },
+        None => {
+            let path = config.leaderboard.as_path();
+            let mut leaderboard = match Leaderboard::load(path) {
+                Ok(mut leaderboard) => {
+                    // The first round of a new season opens with how the last one ended
+                    let current = config.seasons.current(NaiveDate::today_utc());
+                    if let Some(previous) = leaderboard.roll_season(current) {
+                        announce_season(&leaderboard, config.seasons, previous, current, out)?;
+                        if let Err(err) = leaderboard.save(path) {
+                            writeln!(out, "Could not save {}: {err}", path.display())?;
+                        }
+                    }
+                    Some(leaderboard)
+                }
+                Err(err) => {
+                    writeln!(out, "Could not read {}: {err}", path.display())?;
+                    None
+                }
+            };
+            let profile = leaderboard
+                .as_ref()
+                .map(|leaderboard| leaderboard.profile().clone())
+                .unwrap_or_default();
+            if let Err(locked) = profile.check(config.difficulty) {
+                return Err(RunError::Setup(locked.to_string()));
+            }
+            // Draw the seed ourselves rather than calling Game::new, so it can be shown later
+            let mut seed = match daily {
+                Some(date) => daily::daily_seed(date),
+                None => config.seed.unwrap_or_else(|| rand::rng().random()),
+            };
+            let game = match practice_zone(config, out)? {
+                Some(zone) => Game::with_secret(
+                    config.range(),
+                    weakness::practice_secret(&config.range(), zone, seed),
+                ),
+                None => {
+                    // A daily puzzle or a chosen seed means that exact secret, recent or not
+                    if let Some(window) = config.no_repeat
+                        && daily.is_none()
+                        && config.seed.is_none()
+                        && let Some(leaderboard) = &mut leaderboard
+                    {
+                        seed = draw_fresh_secret(
+                            leaderboard,
+                            path,
+                            &config.range(),
+                            seed,
+                            window,
+                            out,
+                        )?;
+                    }
+                    Game::seeded(config.range(), seed)
+                }
+            };
+            (game.with_tolerance(config.tolerance), Some(seed))
+        }
+    };
+    // Only in builds with --features verbose; the secret itself stays hidden,
+    // and so does the range in a blind game
+    #[cfg(feature = "verbose")]
+    {
+        let mut details = Vec::new();
+        if !config.blind {
+            details.push(format!("range {:?}", game.range()));
+        }
+        details.push(format!("tolerance {}", game.tolerance()));
+        // --center and --spread, or a resumed game, set the range instead
+        if config.center.is_none() && *game.range() == config.difficulty.range() {
+            details.push(format!("difficulty {}", config.difficulty.name()));
+        }
+        writeln!(out, "[verbose] {}", details.join(", "))?;
+    }
+    let mut wallet = Wallet::new(STARTING_POINTS);
+    if config.free_first_hint {
+        wallet = wallet.with_free_first();
+    }
+    let mut bank = HintBank::new();
+    let mut properties = PropertyHints::new();
+    // (confidence, was it correct) for every guess, when --calibrate is on
+    let mut ratings: Vec<(f64, bool)> = Vec::new();
+    // Thinking time for every guess, in the same order as game.history()
+    let mut think_times: Vec<Duration> = Vec::new();
+
+    if config.blind {
+        game = game.blind();
+    }
+    if config.warmup {
+        // The warm-up ends by telling you the range, which would spoil a blind game
+        if config.blind {
+            return Err(RunError::Usage(
+                "--warmup gives the range away, so it can't be combined with --blind.".to_string(),
+            ));
+        }
+        if !run_warmup(game.range(), input, out)? {
+            writeln!(out, "Goodbye!")?;
+            return Ok(None);
+        }
+    }
+
+    let command_names: Vec<&'static str> = COMMANDS.iter().map(|&(name, _)| name).collect();
+    let aliases = match &config.aliases {
+        Some(path) => match Aliases::load(path, &command_names) {
+            Ok(aliases) => aliases,
+            Err(err) => {
+                return Err(RunError::Setup(format!(
+                    "Could not read aliases from {}: {err}",
+                    path.display()
+                )));
+            }
+        },
+        None => Aliases::new(),
+    };
+    let mut spectator = match &config.spectator_log {
+        Some(path) => match SpectatorLog::append(path) {
+            Ok(log) => Some(log),
+            Err(err) => {
+                return Err(RunError::Setup(format!(
+                    "Could not open the spectator log {}: {err}",
+                    path.display()
+                )));
+            }
+        },
+        None => None,
+    };
+    if let Some(log) = &mut spectator {
+        log.record(&Event::Started {
+            range: game.range().clone(),
+            secret: game.secret(),
+        })?;
+    }
+
+    writeln!(out, "Guess the number!")?;
+    if let Some(date) = daily {
+        writeln!(
+            out,
+            "This is the daily puzzle for {date} (UTC): everyone playing it on {} gets the same secret.",
+            config.difficulty
+        )?;
+    }
+    if game.is_revealed(Bound::Low) && game.is_revealed(Bound::High) {
+        writeln!(
+            out,
+            "The secret is between {} and {}. Type 'quit' to exit.",
+            in_base(*game.range().start(), config),
+            in_base(*game.range().end(), config)
+        )?;
+    } else {
+        writeln!(
+            out,
+            "The secret is a positive whole number, but where the range ends is for you to find out. Type 'quit' to exit."
+        )?;
+    }
+    if config.base != 10 {
+        writeln!(
+            out,
+            "Numbers are in {} (base {}): type your guesses that way too.",
+            radix::base_name(config.base),
+            config.base
+        )?;
+    }
+    if game.tolerance() > 0 {
+        writeln!(
+            out,
+            "Anything within {} of the secret counts as a win.",
+            game.tolerance()
+        )?;
+    }
+    if config.earn_hints {
+        writeln!(
+            out,
+            "You have {} hint. Every guess that rules out at least half of the remaining numbers earns another.",
+            bank.balance()
+        )?;
+        let names: Vec<&str> = HintKind::ALL.iter().map(|kind| kind.name()).collect();
+        writeln!(out, "Type 'hint <kind>' to use one: {}.", names.join(", "))?;
+    } else {
+        writeln!(
+            out,
+            "You have {} points. Type 'hint <kind>' to buy a hint:",
+            wallet.points()
+        )?;
+        for kind in HintKind::ALL {
+            writeln!(out, "  {:<9} {} points", kind.name(), kind.cost())?;
+        }
+        if wallet.next_is_free() {
+            writeln!(out, "Your first hint is free.")?;
+        }
+    }
+    // A resumed round gets a fresh salt: the commitment covers the rest of the round
+    let secrets = Secrets {
+        salt: config.commit.then(commitment::new_salt),
+        seed,
+    };
+    if let Some(salt) = &secrets.salt {
+        writeln!(
+            out,
+            "Commitment to the secret: {}. The secret and salt are revealed when the round ends.",
+            commitment::commit(game.secret(), salt)
+        )?;
+    }
+    if config.resume.is_some() {
+        let guesses: Vec<u32> = game.history().iter().map(|turn| turn.guess).collect();
+        writeln!(
+            out,
+            "Resuming a saved round. Your guesses so far: {guesses:?}"
+        )?;
+    }
+    let mut step = TutorialStep::new();
+    let mut guard = InputGuard::new(config.invalid_input);
+    if config.tutorial {
+        writeln!(out, "{}", step.message(&game))?;
+    }
+
+    let mut stopwatch = Stopwatch::new(SystemClock::new());
+    // The rest of a line of several guesses, played one per turn before asking again
+    let mut pending: VecDeque<u32> = VecDeque::new();
+    loop {
+        let line = match pending.pop_front() {
+            Some(guess) => guess.to_string(),
+            None => {
+                let hints_left = Some(bank.balance()).filter(|&n| config.earn_hints && n > 0);
+                print_prompt(&game, config, hints_left, out)?;
+                // Everything said since the last prompt goes out in one write, so
+                // a slow terminal never shows half a turn while waiting for input
+                out.flush()?;
+                // End of input counts as quitting, so a saved round isn't lost to Ctrl-D
+                input.read_line()?.unwrap_or_else(|| "quit".to_string())
+            }
+        };
+
+        let (word, argument) = line.split_once(' ').unwrap_or((&line, ""));
+        let command = match commands::resolve(word, &command_names, &aliases) {
+            Ok(resolved) => resolved.map(|resolved| resolved.command),
+            Err(ambiguous) => {
+                writeln!(out, "{ambiguous}")?;
+                continue;
+            }
+        };
+        if let (Some(command), Some(log)) = (command, &mut spectator) {
+            log.record(&Event::Command {
+                typed: word.to_string(),
+                command: command.to_string(),
+            })?;
+        }
+        match command {
+            Some("quit") => {
+                print_round_report(
+                    &game,
+                    config,
+                    &ratings,
+                    &think_times,
+                    &secrets,
+                    guard.counted_failures(),
+                    out,
+                )?;
+                quit(&game, config, out)?;
+                break;
+            }
+            Some("history") => {
+                if config.accessible {
+                    for sentence in accessible::history_sentences(&game) {
+                        writeln!(out, "{sentence}")?;
+                    }
+                } else if game.history().is_empty() {
+                    writeln!(out, "No guesses yet.")?;
+                } else {
+                    write!(
+                        out,
+                        "{}",
+                        chart::render_history(&game, chart_width(config, caps))
+                    )?;
+                }
+                continue;
+            }
+            Some("hint") => {
+                let Some(kind) = HintKind::from_name(argument.trim()) else {
+                    writeln!(
+                        out,
+                        "Unknown hint. Try 'hint parity', 'hint zone', 'hint midpoint' or 'hint property'."
+                    )?;
+                    continue;
+                };
+                // Property hints are chosen before paying, so one that can't be
+                // given costs nothing, and only counted as told once paid for
+                let property = match kind {
+                    HintKind::Property => match properties.choose(game.feasible(), game.secret()) {
+                        Ok(hint) => Some(hint),
+                        Err(err) => {
+                            writeln!(out, "No hint: {err}.")?;
+                            continue;
+                        }
+                    },
+                    _ => None,
+                };
+                let text = property.map_or_else(|| kind.text(&game), |hint| hint.to_string());
+                // A refused hint leaves the wallet, the bank and the round untouched
+                let paid = if config.earn_hints {
+                    bank.spend()
+                        .map(|()| {
+                            format!("({} hint{} left)", bank.balance(), plural(bank.balance()))
+                        })
+                        .map_err(|err| err.to_string())
+                } else {
+                    let free = wallet.next_is_free();
+                    wallet
+                        .spend(kind.cost())
+                        .map(|()| {
+                            if free {
+                                format!("(free; {} points left)", wallet.points())
+                            } else {
+                                format!("({} points left)", wallet.points())
+                            }
+                        })
+                        .map_err(|err| err.to_string())
+                };
+                match paid {
+                    Ok(balance) => {
+                        writeln!(out, "{text} {balance}")?;
+                        if let Some(hint) = property {
+                            properties.give(hint);
+                        }
+                    }
+                    Err(err) => writeln!(out, "No hint: {err}.")?,
+                }
+                continue;
+            }
+            Some("help") => {
+                print_help(&aliases, out)?;
+                continue;
+            }
+            Some(other) => unreachable!("'{other}' is listed in COMMANDS but not handled"),
+            None => {}
+        }
+
+        // "25 50 75" queues three guesses; a win stops the loop with the rest unplayed
+        // ("twenty one" is one guess when number words are on)
+        if line.split_whitespace().nth(1).is_some()
+            && !(config.words && parse_number_word(&line).is_some())
+        {
+            match batch::parse_multi_in_base(&line, config.base)
+                .into_iter()
+                .collect()
+            {
+                Ok(guesses) => pending = guesses,
+                Err(err) => writeln!(out, "{err}, so none of those guesses were played.")?,
+            }
+            continue;
+        }
+
+        let parsed = match config.words.then(|| parse_number_word(&line)).flatten() {
+            Some(num) => Ok(num),
+            None => radix::parse_in_base(&line, config.base).ok_or(()),
+        };
+        let number: u32 = match parsed {
+            Ok(num) => num,
+            Err(_) => match guard.invalid(analysis::midpoint(game.feasible())) {
+                Response::Reprompt(message) => {
+                    writeln!(out, "{message}")?;
+                    continue;
+                }
+                Response::EndRound(message) => {
+                    writeln!(out, "{message}")?;
+                    print_round_report(
+                        &game,
+                        config,
+                        &ratings,
+                        &think_times,
+                        &secrets,
+                        guard.counted_failures(),
+                        out,
+                    )?;
+                    quit(&game, config, out)?;
+                    break;
+                }
+            },
+        };
+        guard.valid();
+
+        // Validate once here; from now on the game only ever sees a checked Guess
+        let guess = match Guess::new(number, game.range()) {
+            Ok(guess) => guess,
+            // Blind games give away only the end the guess ran into
+            Err(err) if config.blind => {
+                let (side, value) = match err.bound() {
+                    Bound::Low => ("below the minimum", *game.range().start()),
+                    Bound::High => ("above the maximum", *game.range().end()),
+                };
+                let value = in_base(value, config);
+                if game.reveal(err.bound()) {
+                    writeln!(out, "That's {side}, which is {value}.")?;
+                    if game.is_revealed(Bound::Low) && game.is_revealed(Bound::High) {
+                        writeln!(
+                            out,
+                            "Now you know the whole range: {} to {}.",
+                            in_base(*game.range().start(), config),
+                            in_base(*game.range().end(), config)
+                        )?;
+                    }
+                } else {
+                    writeln!(out, "That's still {side} of {value}!")?;
+                }
+                continue;
+            }
+            Err(err) => {
+                writeln!(
+                    out,
+                    "{} is not between {} and {}!",
+                    in_base(err.value, config),
+                    in_base(*err.range.start(), config),
+                    in_base(*err.range.end(), config)
+                )?;
+                continue;
+            }
+        };
+
+        // The accessible sentence and the terse line already say what was guessed
+        if config.echo && !config.accessible && !config.terse {
+            writeln!(out, "You guessed: {}", in_base(guess.value(), config))?;
+        }
+
+        // Asked before the answer is shown; the guess only counts once it is rated
+        let confidence = if config.calibrate {
+            let Some(confidence) = ask_confidence(input, out)? else {
+                print_round_report(
+                    &game,
+                    config,
+                    &ratings,
+                    &think_times,
+                    &secrets,
+                    guard.counted_failures(),
+                    out,
+                )?;
+                quit(&game, config, out)?;
+                break;
+            };
+            Some(confidence)
+        } else {
+            None
+        };
+
+        let before = game.feasible().clone();
+        think_times.push(stopwatch.lap());
+        let outcome = game.guess(guess);
+        for observer in observers.iter_mut() {
+            observer.on_outcome(guess.value(), &outcome);
+        }
+        if let Some(log) = &mut spectator {
+            log.record(&Event::Guessed {
+                attempt: game.attempts(),
+                guess: guess.value(),
+                outcome,
+            })?;
+        }
+        if let Some(confidence) = confidence {
+            ratings.push((confidence, outcome == GuessOutcome::Correct));
+        }
+        if config.accessible {
+            let turn = game.history().last().expect("a guess was just played");
+            let attempt = game.attempts() as usize;
+            writeln!(
+                out,
+                "{}",
+                accessible::turn_sentence(&game, attempt, turn, game.feasible())
+            )?;
+            if outcome == GuessOutcome::Correct {
+                writeln!(out, "You win. It took you {} attempts.", game.attempts())?;
+            }
+        } else if config.terse {
+            print_terse_outcome(&game, guess, outcome, config, out)?;
+        } else {
+            print_outcome(&game, guess, outcome, config, out)?;
+        }
+        #[cfg(feature = "verbose")]
+        if config.blind {
+            writeln!(out, "[verbose] turn {}: {:?}", game.attempts(), outcome)?;
+        } else {
+            writeln!(
+                out,
+                "[verbose] turn {}: {:?}, feasible range now {:?}",
+                game.attempts(),
+                outcome,
+                game.feasible()
+            )?;
+        }
+        if config.tutorial {
+            step = step.advance(outcome);
+            writeln!(out, "{}", step.message(&game))?;
+        }
+        if config.warmer_colder
+            && outcome != GuessOutcome::Correct
+            && let Some(temperature) = game.temperature(config.temp_policy)
+        {
+            match temperature {
+                Temperature::Warmer => writeln!(out, "Warmer!")?,
+                Temperature::Colder => writeln!(out, "Colder!")?,
+                Temperature::Same => writeln!(out, "Same distance as last time.")?,
+                Temperature::JumpedOver => writeln!(out, "You jumped over it!")?,
+            }
+        }
+        if config.coach {
+            let solved = outcome == GuessOutcome::Correct;
+            match analysis::grade_guess(&before, game.feasible(), solved) {
+                Verdict::Good { percent } => {
+                    writeln!(out, "Good — you eliminated {percent:.0}% of candidates.")?
+                }
+                Verdict::Suboptimal { better, percent } => writeln!(
+                    out,
+                    "Suboptimal — {better} would have been better, you only eliminated {percent:.0}%."
+                )?,
+            }
+        }
+
+        if config.influence {
+            let ruled_out = analysis::eliminated(&before, game.feasible());
+            if outcome == GuessOutcome::Correct {
+                writeln!(
+                    out,
+                    "That ruled out the other {ruled_out} number{}; only the secret remains.",
+                    plural(ruled_out)
+                )?;
+            } else {
+                let left = analysis::width(game.feasible());
+                writeln!(
+                    out,
+                    "That ruled out {ruled_out} number{}; {left} remain{}.",
+                    plural(ruled_out),
+                    if left == 1 { "s" } else { "" }
+                )?;
+            }
+        }
+
+        // Every guess so far was wrong, or the round would be over
+        if config.auto_hints
+            && outcome != GuessOutcome::Correct
+            && let Some(kind) = hints::hint_for_attempt(game.attempts())
+        {
+            writeln!(out, "Free hint: {}", kind.text(&game))?;
+        }
+
+        if config.earn_hints
+            && outcome != GuessOutcome::Correct
+            && bank.earn(&before, game.feasible())
+        {
+            writeln!(out, "Nice cut! That guess earned you a hint.")?;
+        }
+
+        if outcome == GuessOutcome::Correct {
+            // The animation redraws in place, which only a real terminal can show
+            if config.celebrate
+                && !config.accessible
+                && !config.terse
+                && caps.supports(Feature::Celebration)
+                && let Some(width) = caps.width
+            {
+                let width = config.max_line_width.map_or(width, |max| width.min(max));
+                celebrate(&game, width, out)?;
+            }
+            writeln!(out, "You earned {} points.", wallet.award(true))?;
+            if !config.terse {
+                let optimal = analysis::worst_case_guesses(game.range());
+                writeln!(
+                    out,
+                    "Efficiency: {} ({} guess{}, optimal {optimal})",
+                    analysis::round_grade(game.range(), game.attempts()),
+                    game.attempts(),
+                    if game.attempts() == 1 { "" } else { "es" }
+                )?;
+            }
+            // Bars mean nothing to a screen reader; 'history' still works in sentences
+            if !config.accessible && !config.terse {
+                write!(
+                    out,
+                    "{}",
+                    chart::render_history(&game, chart_width(config, caps))
+                )?;
+            }
+            if config.coach {
+                print_coach_summary(&game, out)?;
+            }
+            print_round_report(
+                &game,
+                config,
+                &ratings,
+                &think_times,
+                &secrets,
+                guard.counted_failures(),
+                out,
+            )?;
+            // Tolerant games are easier, so they don't compete on the leaderboard,
+            // and the tutorial's secret is the same every time
+            if game.tolerance() == 0 && !config.tutorial {
+                record_win(&game, config, input, out)?;
+            }
+            break;
+        }
+
+        if config.entropy {
+            let feasible = game.feasible();
+            writeln!(
+                out,
+                "{:.2} bits of information remain ({} candidates left).",
+                analysis::remaining_bits(feasible),
+                analysis::width(feasible)
+            )?;
+        }
+    }
+
+    let won = game
+        .history()
+        .last()
+        .is_some_and(|turn| turn.outcome == GuessOutcome::Correct);
+    if let Some(path) = &config.history
+        && game.attempts() > 0
+    {
+        // The round itself is over either way, so a failed write is only reported
+        let record = game.clone().into_record();
+        if let Err(err) = weakness::append_history(path, &record) {
+            writeln!(out, "Could not add the round to {}: {err}", path.display())?;
+        }
+    }
+    if let Some(log) = &mut spectator {
+        // As in the round report, a round saved to resume later isn't over
+        if won || config.save.is_none() {
+            log.record(&Event::Ended {
+                secret: game.secret(),
+                attempts: game.attempts(),
+                won,
+            })?;
+        } else {
+            log.record(&Event::Saved {
+                attempts: game.attempts(),
+            })?;
+        }
+    }
+    Ok(round_builder(&game, won).map(|builder| {
+        let builder = builder.score(wallet.points());
+        let builder = if config.timing {
+            builder.duration(think_times.iter().sum())
+        } else {
+            builder
+        };
+        builder
+            .build()
+            .expect("a played game is a consistent round")
+    }))
+}
+
+// The reactions --bell and friends add to every guess
+// The bell goes to stderr: it is for the terminal, not for a transcript of stdout
+fn observers(config: &Config) -> Vec<Box<dyn OutcomeObserver>> {
+    let mut observers: Vec<Box<dyn OutcomeObserver>> = Vec::new();
+    if config.bell {
+        observers.push(Box::new(BellOnWin::new(io::stderr())));
+    }
+    observers
+}
+
+// Checks every file this run may read or write before any of it is used, so
+// a damaged file is dealt with once, up front, rather than differently by
+// whichever feature reads it first
+fn check_files(
+    config: &Config,
+    input: &mut dyn InputSource,
+    out: &mut dyn Write,
+) -> Result<(), RunError> {
+    let mut files = vec![(config.leaderboard.clone(), leaderboard::FILE_KIND)];
+    for (path, kind) in [
+        (&config.save, save::FILE_KIND),
+        (&config.resume, save::FILE_KIND),
+        (&config.history, weakness::HISTORY_KIND),
+    ] {
+        if let Some(path) = path {
+            files.push((path.clone(), kind));
+        }
+    }
+    let mut damaged = Vec::new();
+    for (path, kind) in files {
+        if let Some(err) = persisted_file::check(&path, kind)? {
+            damaged.push((path, kind, err));
+        }
+    }
+    if damaged.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "Some saved files are damaged:")?;
+    for (path, _, err) in &damaged {
+        writeln!(out, "  {}: {err}", path.display())?;
+    }
+    for (path, kind, _) in &damaged {
+        let backup = persisted_file::has_good_backup(path, *kind);
+        loop {
+            if backup {
+                writeln!(
+                    out,
+                    "{}: type 'repair' to restore it from {}, 'quarantine' to move it aside and start afresh, or 'abort' to stop.",
+                    path.display(),
+                    persisted_file::backup_path(path).display()
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "{}: there is no undamaged backup. Type 'quarantine' to move it aside and start afresh, or 'abort' to stop.",
+                    path.display()
+                )?;
+            }
+            out.flush()?;
+            match input.read_line()?.as_deref() {
+                Some("repair") if backup => {
+                    persisted_file::restore(path, *kind)?;
+                    writeln!(out, "Restored {} from its backup.", path.display())?;
+                    break;
+                }
+                Some("quarantine") => {
+                    let aside = persisted_file::quarantine(path)?;
+                    writeln!(out, "Moved it to {}.", aside.display())?;
+                    break;
+                }
+                // The end of input can't answer, so it stops rather than guess
+                None | Some("abort") => {
+                    return Err(RunError::Setup(format!(
+                        "Stopped, leaving {} as it is.",
+                        path.display()
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Does whatever `config` asks for, as the game binary does: checks the saved
+/// files, then plays a round or runs one of the other modes (--tournament,
+/// --grade and so on) instead.
+pub fn play<W: Write>(
+    config: &Config,
+    caps: &Capabilities,
+    input: &mut dyn InputSource,
+    out: &mut W,
+) -> Result<(), RunError> {
+    check_files(config, input, out)?;
+    if config.tournament {
+        run_tournament(config, out).map_err(RunError::from)
+    } else if config.auto || config.auto_quiet {
+        run_auto(config, out).map_err(RunError::from)
+    } else if config.intervals {
+        run_intervals(config, input, out).map_err(RunError::from)
+    } else if let Some(dir) = &config.grade {
+        run_grading(config, dir, out)
+    } else if let Some(view) = config.season {
+        print_season(config, view, out)
+    } else if let Some(name) = &config.evaluate {
+        run_evaluation(name, config, caps, out)
+    } else {
+        run(config, caps, input, &mut observers(config), out).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::observers::OutcomeStats;
+    use crate::progress::WINS_TO_UNLOCK;
+    use crate::testing::{CapturedOutput, ScriptedInput};
+
+    // No terminal, so no animation, and output that doesn't depend on where
+    // the tests run
+    const CAPS: Capabilities = Capabilities {
+        stdin_tty: false,
+        stdout_tty: false,
+        utf8: true,
+        width: None,
+    };
+
+    // A directory of its own for each test's leaderboard and other files
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> TempDir {
+            let path = std::env::temp_dir().join(format!("app-{}-{test}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    // Plays with `args` and `lines` as input, keeping every file in `dir`,
+    // and returns what the game wrote
+    fn play_in(dir: &TempDir, args: &[&str], lines: &[&str]) -> CapturedOutput {
+        let mut config = Config::from_args(args.iter().map(|arg| arg.to_string())).unwrap();
+        config.leaderboard = dir.0.join("leaderboard.json");
+        let mut out = CapturedOutput::new();
+        play(
+            &config,
+            &CAPS,
+            &mut ScriptedInput::new(lines.iter().copied()),
+            &mut out,
+        )
+        .unwrap();
+        out
+    }
+
+    #[test]
+    fn a_scripted_round_is_won() {
+        let dir = TempDir::new("scripted-win");
+        // Seed 3 hides 65; the last line skips the leaderboard name
+        let out = play_in(&dir, &["--seed", "3"], &["50", "75", "65", ""]);
+        let lines = out.lines();
+        out.assert_contains_line("Too small!");
+        out.assert_contains_line("Too big!");
+        out.assert_contains_line("You win! It took you 3 attempts.");
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("You guessed"))
+                .count(),
+            3
+        );
+        // The win was recorded in the test's own leaderboard
+        assert!(dir.0.join("leaderboard.json").exists());
+    }
+
+    #[test]
+    fn an_unseeded_round_reports_a_seed_that_replays_it() {
+        let dir = TempDir::new("unseeded-replay");
+        // Counting up from 1 wins exactly at the secret
+        let numbers: Vec<String> = (1..=100).map(|n| n.to_string()).collect();
+        let lines: Vec<&str> = numbers.iter().map(String::as_str).collect();
+        let out = play_in(&dir, &["--show-seed"], &lines);
+        let secret = out
+            .lines()
+            .iter()
+            .rev()
+            .filter_map(|line| line.strip_prefix("You guessed: "))
+            .next()
+            .expect("at least one guess")
+            .to_string();
+        let seed = out
+            .lines()
+            .iter()
+            .find_map(|line| {
+                let rest = line.strip_prefix("This round's seed was ")?;
+                Some(rest.split('.').next()?.to_string())
+            })
+            .expect("the seed is reported");
+        assert!(seed.parse::<u64>().is_ok(), "{seed}");
+
+        let replay = play_in(&dir, &["--seed", &seed], &[&secret, ""]);
+        replay.assert_contains_line("You win! It took you 1 attempts.");
+    }
+
+    #[test]
+    fn strikes_end_the_round_but_never_count_as_attempts() {
+        let dir = TempDir::new("input-strikes");
+        let args = ["--seed", "3", "--invalid-input", "strict:2"];
+        let out = play_in(&dir, &args, &["x", "50", "y", "65", ""]);
+        out.assert_contains_line(
+            "Please type a number, or 'help' to see what else you can type. (1 strike left)",
+        );
+        out.assert_contains_line("You win! It took you 2 attempts.");
+
+        let out = play_in(&dir, &args, &["50", "x", "y", "65"]);
+        out.assert_contains_line("That's 2 strikes in a row, so the round is over.");
+        assert!(!out.text().contains("You win"));
+    }
+
+    #[test]
+    fn counted_input_is_reported_apart_from_the_attempts() {
+        let dir = TempDir::new("input-counted");
+        let args = ["--seed", "3", "--invalid-input", "counted"];
+        let out = play_in(&dir, &args, &["x", "65", ""]);
+        out.assert_contains_line("You win! It took you 1 attempts.");
+        out.assert_contains_line("1 line of invalid input counted as failures.");
+    }
+
+    #[test]
+    fn every_command_in_help_is_dispatched() {
+        let dir = TempDir::new("command-table");
+        let help = play_in(&dir, &["--seed", "3"], &["help", "quit"]);
+        for (name, description) in COMMANDS {
+            help.assert_contains_line(&format!("  {name:<8} {description}"));
+            // A name the dispatcher didn't handle would hit its unreachable arm
+            let out = play_in(&dir, &["--seed", "3"], &[name, "quit"]);
+            out.assert_contains_line("Goodbye!");
+        }
+    }
+
+    #[test]
+    fn a_word_missing_from_the_table_is_not_a_command() {
+        let dir = TempDir::new("command-unknown");
+        let out = play_in(&dir, &["--seed", "3"], &["save", "quit"]);
+        out.assert_contains_line("Please type a number, or 'help' to see what else you can type.");
+    }
+
+    #[test]
+    fn an_accessible_round_reads_as_plain_sentences() {
+        let dir = TempDir::new("accessible-golden");
+        let out = play_in(
+            &dir,
+            &["--accessible", "--seed", "3"],
+            &["fifty", "50", "hint parity", "75", "history", "65", ""],
+        );
+        // Up to the leaderboard, whose season depends on today's date
+        let transcript: Vec<String> = out
+            .lines()
+            .into_iter()
+            .take_while(|line| !line.starts_with("Enter your name"))
+            .collect();
+        assert_eq!(
+            transcript,
+            [
+                "Guess the number!",
+                "The secret is between 1 and 100. Type 'quit' to exit.",
+                "You have 10 points. Type 'hint <kind>' to buy a hint:",
+                "  parity    2 points",
+                "  zone      3 points",
+                "  midpoint  5 points",
+                "  property  2 points",
+                "Please input your guess:",
+                "Please type a number, or 'help' to see what else you can type.",
+                "Please input your guess:",
+                "Attempt 1. Your guess 50 is too low. The number is between 51 and 100.",
+                "Please input your guess:",
+                "The number is odd. (8 points left)",
+                "Please input your guess:",
+                "Attempt 2. Your guess 75 is too high. The number is between 51 and 74.",
+                "Please input your guess:",
+                "Attempt 1. Your guess 50 is too low. The number is between 51 and 100.",
+                "Attempt 2. Your guess 75 is too high. The number is between 51 and 74.",
+                "Please input your guess:",
+                "Attempt 3. Your guess 65 is correct. The number was 65.",
+                "You win. It took you 3 attempts.",
+                "You earned 8 points.",
+                "Efficiency: A (3 guesses, optimal 7)",
+            ]
+        );
+    }
+
+    #[test]
+    fn hard_is_locked_until_three_medium_wins() {
+        let dir = TempDir::new("unlock-hard");
+        let mut config =
+            Config::from_args(["--difficulty".to_string(), "hard".to_string()]).unwrap();
+        config.leaderboard = dir.0.join("leaderboard.json");
+        let err = play(
+            &config,
+            &CAPS,
+            &mut ScriptedInput::new(["quit"]),
+            &mut CapturedOutput::new(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            &err,
+            RunError::Setup(message)
+                if message == "hard is locked. Win 3 medium games in a row to unlock hard."
+        ));
+
+        for round in 1..=WINS_TO_UNLOCK {
+            let out = play_in(&dir, &["--seed", "3"], &["65", ""]);
+            assert_eq!(
+                out.text()
+                    .contains("You unlocked hard! Play it with --difficulty hard."),
+                round == WINS_TO_UNLOCK
+            );
+        }
+        let out = play_in(&dir, &["--difficulty", "hard", "--seed", "3"], &["quit"]);
+        out.assert_contains_line("The secret is between 1 and 1000. Type 'quit' to exit.");
+    }
+
+    #[test]
+    fn the_interval_example_stays_inside_an_offset_range() {
+        let dir = TempDir::new("interval-example");
+        let out = play_in(
+            &dir,
+            &["--intervals", "--center", "1000", "--spread", "10"],
+            &[],
+        );
+        assert!(
+            out.text()
+                .contains("between 900 and 1100. Name a range like '900 1000'")
+        );
+    }
+
+    #[test]
+    fn a_finished_season_is_announced_once() {
+        let dir = TempDir::new("season-rollover");
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.roll_season(Season::Starting {
+            year: 2000,
+            month: 1,
+        });
+        leaderboard.save(&dir.0.join("leaderboard.json")).unwrap();
+
+        let over = "Season 2000-01 is over. The final standings:";
+        let first = play_in(&dir, &["--seed", "3"], &["65", ""]);
+        first.assert_contains_line(over);
+        let second = play_in(&dir, &["--seed", "3"], &["65", ""]);
+        assert!(!second.text().contains("is over"));
+    }
+
+    #[test]
+    fn a_blind_round_hides_the_range_at_startup() {
+        let dir = TempDir::new("blind-start");
+        let out = play_in(&dir, &["--blind", "--seed", "3"], &[]);
+        let startup = out.text();
+        assert!(!startup.contains("between"));
+        assert!(!startup.contains("100"));
+        let out = play_in(&dir, &["--seed", "3"], &[]);
+        out.assert_contains_line("The secret is between 1 and 100. Type 'quit' to exit.");
+    }
+
+    #[test]
+    fn a_blind_round_reveals_the_bound_a_guess_ran_into() {
+        let dir = TempDir::new("blind-reveal");
+        let out = play_in(
+            &dir,
+            &["--blind", "--seed", "3"],
+            &["101", "150", "0", "65", ""],
+        );
+        out.assert_contains_line("That's above the maximum, which is 100.");
+        out.assert_contains_line("That's still above the maximum of 100!");
+        out.assert_contains_line("That's below the minimum, which is 1.");
+        out.assert_contains_line("Now you know the whole range: 1 to 100.");
+        // Out-of-range guesses aren't attempts
+        out.assert_contains_line("You win! It took you 1 attempts.");
+    }
+
+    #[test]
+    fn influence_says_how_many_numbers_each_guess_ruled_out() {
+        let dir = TempDir::new("influence");
+        let out = play_in(
+            &dir,
+            &["--influence", "--seed", "3"],
+            &["50", "75", "65", ""],
+        );
+        out.assert_contains_line("That ruled out 50 numbers; 50 remain.");
+        out.assert_contains_line("That ruled out 26 numbers; 24 remain.");
+        out.assert_contains_line("That ruled out the other 23 numbers; only the secret remains.");
+    }
+
+    #[test]
+    fn auto_shows_every_guess_and_auto_quiet_only_the_summary() {
+        let dir = TempDir::new("auto-quiet");
+        let summary = "Binary search found 65 in 5 guesses.";
+        let loud = play_in(&dir, &["--auto", "--seed", "3"], &[]);
+        assert_eq!(
+            loud.lines(),
+            [
+                "Guess 1: 50 (too small)",
+                "Guess 2: 75 (too big)",
+                "Guess 3: 62 (too small)",
+                "Guess 4: 68 (too big)",
+                "Guess 5: 65 (correct)",
+                summary,
+            ]
+        );
+        let quiet = play_in(&dir, &["--auto-quiet", "--seed", "3"], &[]);
+        assert_eq!(quiet.lines(), [summary]);
+    }
+
+    // The lines from the first guess on, without the menu of hints
+    fn after_first_prompt(out: &CapturedOutput) -> Vec<String> {
+        let lines = out.lines();
+        let start = lines
+            .iter()
+            .position(|line| line == "Please input your guess:")
+            .expect("a prompt");
+        lines[start..].to_vec()
+    }
+
+    #[test]
+    fn a_batch_stops_at_its_winner() {
+        let dir = TempDir::new("batch-winner");
+        let out = play_in(&dir, &["--seed", "3"], &["50 65 10 20", ""]);
+        let lines = after_first_prompt(&out);
+        assert_eq!(
+            lines[..6],
+            [
+                "Please input your guess:",
+                "You guessed: 50",
+                "Too small!",
+                "You guessed: 65",
+                "You win! It took you 2 attempts.",
+                "You earned 10 points.",
+            ]
+        );
+        assert!(!out.text().contains("You guessed: 10"));
+    }
+
+    #[test]
+    fn a_batch_with_no_winner_plays_every_guess() {
+        let dir = TempDir::new("batch-all-wrong");
+        let out = play_in(&dir, &["--seed", "3"], &["10 20 90"]);
+        let lines = after_first_prompt(&out);
+        assert_eq!(
+            lines[..8],
+            [
+                "Please input your guess:",
+                "You guessed: 10",
+                "Too small!",
+                "You guessed: 20",
+                "Too small!",
+                "You guessed: 90",
+                "Too big!",
+                "Please input your guess:",
+            ]
+        );
+    }
+
+    #[test]
+    fn a_batch_with_a_bad_token_plays_none_of_it() {
+        let dir = TempDir::new("batch-bad-token");
+        let out = play_in(&dir, &["--seed", "3"], &["50 x 65", "65", ""]);
+        out.assert_contains_line("'x' is not a number, so none of those guesses were played.");
+        assert!(!out.text().contains("You guessed: 50"));
+        out.assert_contains_line("You win! It took you 1 attempts.");
+    }
+
+    #[test]
+    fn terse_answers_each_guess_in_one_short_line() {
+        let dir = TempDir::new("terse");
+        let out = play_in(&dir, &["--terse", "--seed", "3"], &["50", "75", "65", ""]);
+        let text = out.text();
+        assert!(text.contains("> 50 low\n> 75 high\n> 65 won in 3\n"));
+        assert!(!text.contains("You guessed"));
+        assert!(!text.contains("Too small!"));
+    }
+
+    #[test]
+    fn no_echo_drops_the_echo_line_but_keeps_the_answer() {
+        let dir = TempDir::new("no-echo");
+        let lines = ["50", "65", ""];
+        let echoed = play_in(&dir, &["--seed", "3"], &lines);
+        echoed.assert_contains_line("You guessed: 50");
+        echoed.assert_contains_line("Too small!");
+        let quiet = play_in(&dir, &["--no-echo", "--seed", "3"], &lines);
+        assert!(!quiet.text().contains("You guessed"));
+        quiet.assert_contains_line("Too small!");
+        quiet.assert_contains_line("You win! It took you 2 attempts.");
+    }
+
+    fn prompt(args: &[&str], hints_left: Option<u32>) -> String {
+        let config = Config::from_args(args.iter().map(|arg| arg.to_string())).unwrap();
+        let game = Game::with_secret(config.range(), 42);
+        let mut out = Vec::new();
+        print_prompt(&game, &config, hints_left, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn show_range_puts_the_range_in_the_prompt() {
+        assert_eq!(prompt(&[], None), "Please input your guess:\n");
+        assert_eq!(prompt(&["--show-range"], None), "Guess (1-100):\n");
+        assert_eq!(
+            prompt(&["--show-range"], Some(2)),
+            "Guess (1-100, 2 hints left):\n"
+        );
+        assert_eq!(prompt(&["--show-range", "--terse"], None), "1-100> ");
+        assert_eq!(
+            prompt(&["--show-range", "--base", "16"], None),
+            "Guess (1-64):\n"
+        );
+    }
+
+    #[test]
+    fn a_blind_game_keeps_the_range_out_of_the_prompt() {
+        assert_eq!(
+            prompt(&["--show-range", "--blind"], None),
+            "Please input your guess:\n"
+        );
+        assert_eq!(prompt(&["--show-range", "--blind", "--terse"], None), "> ");
+    }
+
+    #[test]
+    fn run_plays_into_a_vec() {
+        let dir = TempDir::new("run-vec");
+        let mut config = Config::from_args(["--seed".to_string(), "3".to_string()]).unwrap();
+        config.leaderboard = dir.0.join("leaderboard.json");
+        let mut out: Vec<u8> = Vec::new();
+        let result = run(
+            &config,
+            &CAPS,
+            &mut ScriptedInput::new(["65", ""]),
+            &mut [],
+            &mut out,
+        )
+        .unwrap()
+        .expect("a round on a difficulty");
+        assert_eq!(result.attempts, 1);
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("You win! It took you 1 attempts."), "{text}");
+    }
+
+    #[test]
+    fn every_observer_hears_every_outcome_in_order() {
+        let dir = TempDir::new("observers");
+        let mut config = Config::from_args(["--seed".to_string(), "3".to_string()]).unwrap();
+        config.leaderboard = dir.0.join("leaderboard.json");
+        let stats = Rc::new(RefCell::new(OutcomeStats::new()));
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let log_for_observer = Rc::clone(&log);
+        let mut observers: Vec<Box<dyn OutcomeObserver>> = vec![
+            Box::new(Rc::clone(&stats)),
+            Box::new(move |guess: u32, outcome: &GuessOutcome| {
+                log_for_observer.borrow_mut().push((guess, *outcome))
+            }),
+        ];
+        run(
+            &config,
+            &CAPS,
+            &mut ScriptedInput::new(["50", "75", "65", ""]),
+            &mut observers,
+            &mut Vec::new(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            *log.borrow(),
+            [
+                (50, GuessOutcome::TooSmall),
+                (75, GuessOutcome::TooBig),
+                (65, GuessOutcome::Correct),
+            ]
+        );
+        let stats = stats.borrow();
+        assert_eq!(stats.guesses, [50, 75, 65]);
+        assert_eq!((stats.too_small, stats.too_big, stats.correct), (1, 1, 1));
+    }
+
+    #[test]
+    fn symbols_mark_each_answer_in_both_glyph_sets() {
+        let dir = TempDir::new("symbols");
+        let lines = ["50", "75", "65", ""];
+        let unicode = play_in(&dir, &["--symbols", "--seed", "3"], &lines);
+        unicode.assert_contains_line("▼ Too small!");
+        unicode.assert_contains_line("▲ Too big!");
+        unicode.assert_contains_line("★ You win! It took you 3 attempts.");
+        let ascii = play_in(&dir, &["--ascii-symbols", "--seed", "3"], &lines);
+        ascii.assert_contains_line("v Too small!");
+        ascii.assert_contains_line("^ Too big!");
+        ascii.assert_contains_line("* You win! It took you 3 attempts.");
+    }
+
+    #[test]
+    fn the_free_hint_comes_back_every_round() {
+        let dir = TempDir::new("free-first-hint");
+        let lines = ["hint parity", "hint zone", "65", ""];
+        for _round in 0..2 {
+            let out = play_in(&dir, &["--free-first-hint", "--seed", "3"], &lines);
+            out.assert_contains_line("The number is odd. (free; 10 points left)");
+            out.assert_contains_line(
+                "The number is in the middle third of the range. (7 points left)",
+            );
+            out.assert_contains_line("You earned 7 points.");
+        }
+    }
+}
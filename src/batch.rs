@@ -0,0 +1,65 @@
+// Several guesses typed on one line, like "25 50 75"
+use std::fmt;
+
+use crate::radix;
+
+/// A token in a batch of guesses that isn't a whole number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGuessError {
+    pub token: String,
+}
+
+impl fmt::Display for ParseGuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "'{}' is not a number", self.token)
+    }
+}
+
+impl std::error::Error for ParseGuessError {}
+
+/// Splits `input` on whitespace and parses every token as a guess, in order.
+pub fn parse_multi(input: &str) -> Vec<Result<u32, ParseGuessError>> {
+    parse_multi_in_base(input, 10)
+}
+
+/// Like `parse_multi`, with every token read in `base` (see `radix::parse_in_base`).
+pub fn parse_multi_in_base(input: &str, base: u32) -> Vec<Result<u32, ParseGuessError>> {
+    input
+        .split_whitespace()
+        .map(|token| {
+            radix::parse_in_base(token, base).ok_or_else(|| ParseGuessError {
+                token: token.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bad(token: &str) -> Result<u32, ParseGuessError> {
+        Err(ParseGuessError {
+            token: token.to_string(),
+        })
+    }
+
+    #[test]
+    fn every_token_is_parsed_in_order() {
+        assert_eq!(parse_multi("25 50  75\t"), [Ok(25), Ok(50), Ok(75)]);
+        assert!(parse_multi("   ").is_empty());
+    }
+
+    #[test]
+    fn a_token_that_is_not_a_number_is_an_error_in_its_place() {
+        assert_eq!(parse_multi("10 x 30"), [Ok(10), bad("x"), Ok(30)]);
+        assert_eq!(parse_multi("-5 7.5"), [bad("-5"), bad("7.5")]);
+        assert_eq!(bad("x").unwrap_err().to_string(), "'x' is not a number");
+    }
+
+    #[test]
+    fn tokens_are_read_in_the_given_base() {
+        assert_eq!(parse_multi_in_base("ff 10", 16), [Ok(255), Ok(16)]);
+        assert_eq!(parse_multi_in_base("12", 2), [bad("12")]);
+    }
+}
@@ -0,0 +1,200 @@
+// Counting every heap allocation with a custom global allocator
+// #[global_allocator] swaps out the allocator behind Box, Vec, String, HashMap...
+// for the whole program. This one forwards to the normal system allocator and
+// just keeps score
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator {
+    // Total bytes ever handed out, and ever given back
+    allocated: AtomicUsize,
+    freed: AtomicUsize,
+    // Bytes currently in use: allocated - freed
+    live: AtomicUsize,
+    // Number of calls, to tell one big allocation from many small ones
+    allocations: AtomicUsize,
+}
+
+// SAFETY: every method passes its arguments straight to System, which upholds
+// the GlobalAlloc contract; the counters don't touch the memory at all
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: forwarded unchanged from our caller
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            self.allocated.fetch_add(layout.size(), Ordering::Relaxed);
+            self.live.fetch_add(layout.size(), Ordering::Relaxed);
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: forwarded unchanged from our caller
+        unsafe { System.dealloc(ptr, layout) };
+        self.freed.fetch_add(layout.size(), Ordering::Relaxed);
+        self.live.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    // A growing Vec calls realloc; count it as freeing the old block and allocating a new one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: forwarded unchanged from our caller
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            self.freed.fetch_add(layout.size(), Ordering::Relaxed);
+            self.allocated.fetch_add(new_size, Ordering::Relaxed);
+            self.live.fetch_sub(layout.size(), Ordering::Relaxed);
+            self.live.fetch_add(new_size, Ordering::Relaxed);
+            self.allocations.fetch_add(1, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+// A static can't call non-const functions, but AtomicUsize::new is const
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    allocated: AtomicUsize::new(0),
+    freed: AtomicUsize::new(0),
+    live: AtomicUsize::new(0),
+    allocations: AtomicUsize::new(0),
+};
+
+#[derive(Debug, Clone, Copy)]
+struct AllocSnapshot {
+    allocated: usize,
+    freed: usize,
+    live: usize,
+    allocations: usize,
+}
+
+fn snapshot() -> AllocSnapshot {
+    AllocSnapshot {
+        allocated: ALLOCATOR.allocated.load(Ordering::Relaxed),
+        freed: ALLOCATOR.freed.load(Ordering::Relaxed),
+        live: ALLOCATOR.live.load(Ordering::Relaxed),
+        allocations: ALLOCATOR.allocations.load(Ordering::Relaxed),
+    }
+}
+
+// What happened between two snapshots
+#[derive(Debug, Clone, Copy)]
+struct AllocDelta {
+    bytes: usize,
+    allocations: usize,
+}
+
+// Runs `f` and reports what it allocated. The result is returned rather than
+// dropped, so freeing it doesn't get mixed into the measurement
+// Nothing in between may print: println! can allocate too
+fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocDelta) {
+    let before = snapshot();
+    let value = f();
+    let after = snapshot();
+    let delta = AllocDelta {
+        bytes: after.allocated - before.allocated,
+        allocations: after.allocations - before.allocations,
+    };
+    (value, delta)
+}
+
+fn report(what: &str, delta: AllocDelta) {
+    println!(
+        "{what:<38} {:>5} allocations, {:>6} bytes",
+        delta.allocations, delta.bytes
+    );
+}
+
+// Just enough of an interner to count words by id (see string_interning.rs)
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<String, usize>,
+}
+
+impl Interner {
+    fn intern(&mut self, word: &str) -> usize {
+        // Looking up with a &str allocates nothing; only a new word is copied
+        if let Some(&id) = self.ids.get(word) {
+            return id;
+        }
+        let id = self.ids.len();
+        self.ids.insert(word.to_string(), id);
+        id
+    }
+}
+
+const TEXT: &str = "it was the best of times it was the worst of times it was the age of \
+                    wisdom it was the age of foolishness it was the epoch of belief";
+
+fn main() {
+    // The first println! sets up stdout's buffer; get that out of the way
+    // before taking the baseline, so it doesn't look like a leak
+    println!("Counting allocations with a #[global_allocator]\n");
+    let baseline = snapshot();
+
+    let (boxed, delta) = measure(|| Box::new([0u8; 256]));
+    report("Box::new([0u8; 256])", delta);
+    assert_eq!(delta.allocations, 1);
+
+    // with_capacity asks for all the room up front, so the pushes never allocate
+    let (reserved, delta) = measure(|| {
+        let mut v = Vec::with_capacity(1000);
+        for i in 0..1000u32 {
+            v.push(i);
+        }
+        v
+    });
+    report("Vec::with_capacity(1000) + 1000 pushes", delta);
+    assert_eq!(delta.allocations, 1);
+
+    // Vec::new starts empty and doubles its capacity when it runs out:
+    // 4, 8, 16, ... 1024, about log2(1000) allocations
+    let (grown, delta) = measure(|| {
+        let mut v = Vec::new();
+        for i in 0..1000u32 {
+            v.push(i);
+        }
+        v
+    });
+    report("Vec::new() + 1000 pushes", delta);
+    assert!(delta.allocations > 1 && delta.allocations <= 12);
+
+    // Word counts: a fresh String key for every single word...
+    let words: Vec<&str> = TEXT.split_whitespace().cycle().take(5_000).collect();
+    let (naive, delta) = measure(|| {
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for word in &words {
+            *counts.entry(word.to_string()).or_insert(0) += 1;
+        }
+        counts
+    });
+    report("word counts keyed by String", delta);
+    let naive_allocations = delta.allocations;
+
+    // ...versus interning: a String per distinct word, and ids for the rest
+    let (interned, delta) = measure(|| {
+        let mut interner = Interner::default();
+        let mut counts: Vec<u32> = Vec::new();
+        for word in &words {
+            let id = interner.intern(word);
+            if id == counts.len() {
+                counts.push(0);
+            }
+            counts[id] += 1;
+        }
+        (interner, counts)
+    });
+    report("word counts keyed by interned id", delta);
+    assert!(delta.allocations < naive_allocations / 10);
+    assert_eq!(interned.1.len(), naive.len());
+
+    // Give everything back; live memory should be exactly where it started
+    drop((boxed, reserved, grown, naive, interned, words));
+    let end = snapshot();
+    println!(
+        "\nlive bytes: {} at the start, {} after dropping everything ({} allocated, {} freed in total)",
+        baseline.live, end.live, end.allocated, end.freed
+    );
+    assert_eq!(end.live, baseline.live);
+}
@@ -0,0 +1,351 @@
+// Arbitrary precision integers ("big integers") built from scratch
+// Rust's largest built-in integer is u128/i128, which tops out around 3.4 * 10^38
+// 100! has 158 digits, so we need a type that can grow as large as memory allows
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+// A BigInt stores its magnitude as a Vec of 32-bit "limbs" (base 2^32 digits)
+// digits[0] is the LEAST significant limb, so the number is:
+//   digits[0] + digits[1] * 2^32 + digits[2] * 2^64 + ...
+// The sign is kept separately in `negative` (sign-magnitude representation)
+// Invariants kept by normalize():
+//   - no trailing zero limbs (the most significant limb is never 0)
+//   - zero is represented by an empty Vec and is never negative
+#[derive(Debug, Clone)]
+struct BigInt {
+    digits: Vec<u32>,
+    negative: bool,
+}
+
+// Error returned when a string isn't a valid decimal integer
+#[derive(Debug, PartialEq)]
+struct ParseBigIntError;
+
+impl fmt::Display for ParseBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl BigInt {
+    fn zero() -> BigInt {
+        BigInt {
+            digits: Vec::new(),
+            negative: false,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    // Restore the invariants after an operation
+    fn normalize(mut self) -> BigInt {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        if self.digits.is_empty() {
+            self.negative = false;
+        }
+        self
+    }
+
+    // Multiply the magnitude in place by a small number and add a small number
+    // This is all FromStr needs: for each decimal digit do value = value * 10 + digit
+    fn mul_small_add(&mut self, mul: u32, add: u32) {
+        let mut carry = add as u64;
+        for limb in self.digits.iter_mut() {
+            // u32 * u32 + u32 always fits in a u64, so no overflow is possible here
+            let product = (*limb as u64) * (mul as u64) + carry;
+            *limb = product as u32; // keep the low 32 bits
+            carry = product >> 32; // carry the high 32 bits into the next limb
+        }
+        if carry > 0 {
+            self.digits.push(carry as u32);
+        }
+    }
+
+    // Divide the magnitude in place by a small number, returning the remainder
+    // We walk from the most significant limb down, like long division on paper
+    fn div_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder: u64 = 0;
+        for limb in self.digits.iter_mut().rev() {
+            let current = (remainder << 32) | (*limb as u64);
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        remainder as u32
+    }
+}
+
+// ---- Magnitude helpers (ignore the sign) ----
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    // With no trailing zeros, a longer Vec is always the bigger number
+    // For equal lengths, compare from the most significant limb down
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        // unwrap_or(&0) treats the shorter number as if it had leading zeros
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+// Requires |a| >= |b|, otherwise the final borrow would be lost
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut diff = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        if diff < 0 {
+            diff += 1 << 32;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+        result.push(diff as u32);
+    }
+    result
+}
+
+// Schoolbook multiplication: every limb of `a` times every limb of `b`, O(n * m)
+// Exactly the method taught in primary school, only in base 2^32 instead of base 10
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            // x * y + result + carry <= (2^32 - 1)^2 + 2 * (2^32 - 1) = 2^64 - 1, so it fits
+            let current = (x as u64) * (y as u64) + result[i + j] as u64 + carry;
+            result[i + j] = current as u32;
+            carry = current >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+// ---- Conversions ----
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> BigInt {
+        BigInt {
+            digits: vec![value as u32, (value >> 32) as u32],
+            negative: false,
+        }
+        .normalize()
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<BigInt, ParseBigIntError> {
+        // An optional leading sign, then at least one ASCII digit
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let mut value = BigInt::zero();
+        for b in digits.bytes() {
+            // value = value * 10 + digit
+            value.mul_small_add(10, (b - b'0') as u32);
+        }
+        value.negative = negative;
+        Ok(value.normalize())
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Repeatedly divide by 10^9 (the largest power of 10 that fits in a u32)
+        // Each remainder is 9 decimal digits, produced least significant first
+        let mut magnitude = self.clone();
+        let mut chunks = Vec::new();
+        while !magnitude.is_zero() {
+            chunks.push(magnitude.div_small(1_000_000_000));
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+        // The most significant chunk is printed as-is, the rest are zero-padded to 9 digits
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+// ---- Comparison ----
+
+impl PartialEq for BigInt {
+    fn eq(&self, other: &BigInt) -> bool {
+        // Thanks to normalize() there is exactly one representation per number
+        self.negative == other.negative && self.digits == other.digits
+    }
+}
+
+impl Eq for BigInt {}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.digits, &other.digits),
+            // Both negative: the bigger magnitude is the smaller number
+            (true, true) => cmp_magnitude(&other.digits, &self.digits),
+        }
+    }
+}
+
+// ---- Arithmetic ----
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(mut self) -> BigInt {
+        self.negative = !self.negative;
+        self.normalize() // -0 must stay 0
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> BigInt {
+        if self.negative == other.negative {
+            // Same sign: add the magnitudes, keep the sign
+            BigInt {
+                digits: add_magnitude(&self.digits, &other.digits),
+                negative: self.negative,
+            }
+            .normalize()
+        } else {
+            // Different signs: subtract the smaller magnitude from the larger one
+            // and take the sign of the larger one
+            match cmp_magnitude(&self.digits, &other.digits) {
+                Ordering::Less => BigInt {
+                    digits: sub_magnitude(&other.digits, &self.digits),
+                    negative: other.negative,
+                },
+                _ => BigInt {
+                    digits: sub_magnitude(&self.digits, &other.digits),
+                    negative: self.negative,
+                },
+            }
+            .normalize()
+        }
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    // a - b is just a + (-b)
+    fn sub(self, other: BigInt) -> BigInt {
+        self + (-other)
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: BigInt) -> BigInt {
+        BigInt {
+            digits: mul_magnitude(&self.digits, &other.digits),
+            // The product is negative when exactly one side is negative
+            negative: self.negative != other.negative,
+        }
+        .normalize()
+    }
+}
+
+fn factorial(n: u64) -> BigInt {
+    (1..=n).fold(BigInt::from(1), |acc, k| acc * BigInt::from(k))
+}
+
+fn big(s: &str) -> BigInt {
+    s.parse().expect("valid integer literal")
+}
+
+fn main() {
+    // Parsing and printing round-trips
+    let a = big("123456789012345678901234567890");
+    println!("a = {}", a);
+    assert_eq!(a.to_string(), "123456789012345678901234567890");
+    assert_eq!(big("-0").to_string(), "0");
+    assert_eq!("12x".parse::<BigInt>(), Err(ParseBigIntError));
+
+    // Addition with carries across limbs: (2^64 - 1) + 1 = 2^64
+    let sum = BigInt::from(u64::MAX) + BigInt::from(1);
+    println!("u64::MAX + 1 = {}", sum);
+    assert_eq!(sum, big("18446744073709551616"));
+
+    // Subtraction that crosses zero
+    let diff = big("100") - big("250");
+    println!("100 - 250 = {}", diff);
+    assert_eq!(diff, big("-150"));
+    assert_eq!(big("-5") - big("-5"), BigInt::zero());
+
+    // Multiplication far beyond u128
+    let x = big("99999999999999999999");
+    let square = x.clone() * x;
+    println!("99999999999999999999^2 = {}", square);
+    assert_eq!(square, big("9999999999999999999800000000000000000001"));
+    assert_eq!(big("-3") * big("4"), big("-12"));
+
+    // Ordering respects signs
+    let mut values = [
+        big("10"),
+        big("-20"),
+        big("0"),
+        big("-3"),
+        big("99999999999999999999"),
+    ];
+    values.sort();
+    let sorted: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    println!("sorted: {:?}", sorted);
+    assert_eq!(sorted, ["-20", "-3", "0", "10", "99999999999999999999"]);
+
+    // 100! has 158 digits and ends in 24 zeros (one per factor of 5 paired with a 2)
+    let f = factorial(100).to_string();
+    println!("100! = {}", f);
+    println!("100! has {} digits", f.len());
+    assert_eq!(f.len(), 158);
+    assert!(f.ends_with("000"));
+    assert!(f.starts_with("93326215443944152681"));
+}
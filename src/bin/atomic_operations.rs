@@ -0,0 +1,396 @@
+// Atomics and the five memory orderings, each where it fits
+// An atomic operation can't be torn: no thread ever sees half of a store or
+// a read-modify-write done twice. The ordering says something else: which
+// *other* memory a thread is guaranteed to see along with the atomic value.
+//   Relaxed  the operation is atomic, and that's all
+//   Release  (on a store) every write before it is visible to a thread that
+//            Acquires the value it stored
+//   Acquire  (on a load) sees everything written before the Release it read
+//   AcqRel   both, for read-modify-writes such as swap and fetch_add
+//   SeqCst   Acquire/Release, plus one order of all SeqCst operations that
+//            every thread agrees on
+use std::cell::UnsafeCell;
+use std::hint;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicPtr, AtomicUsize, Ordering};
+use std::thread;
+
+const THREADS: usize = 4;
+const PER_THREAD: usize = 100_000;
+
+// ---- Relaxed: a counter nothing else depends on ----
+// Each fetch_add is atomic whatever the ordering, so no increment is lost.
+// Relaxed is enough because nothing is read *because of* the counter; the
+// final total is read after join(), and join already makes every write of
+// the joined thread visible
+static HITS: AtomicUsize = AtomicUsize::new(0);
+
+fn count_hits() -> usize {
+    thread::scope(|s| {
+        for _ in 0..THREADS {
+            s.spawn(|| {
+                for _ in 0..PER_THREAD {
+                    HITS.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    HITS.load(Ordering::Relaxed)
+}
+
+// ---- Acquire/Release: a spinlock ----
+// Taking the lock Acquires and giving it back Releases, so whatever one
+// holder wrote to `value` is visible to the next holder. That pairing is the
+// whole job of a lock; the atomic flag alone only says who holds it
+struct SpinLock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// SAFETY: only the thread holding the lock can reach `value`, so sharing the
+// lock between threads is fine as long as the value itself may move between
+// them (T: Send)
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    const fn new(value: T) -> SpinLock<T> {
+        SpinLock {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    fn lock(&self) -> SpinGuard<'_, T> {
+        // compare_exchange_weak may fail even when the value matches, which
+        // is fine inside a retry loop and cheaper on some CPUs (ARM). The
+        // failure ordering is Relaxed: a failed attempt took nothing
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            // Tells the CPU this is a busy-wait, so it can ease off
+            hint::spin_loop();
+        }
+        SpinGuard { lock: self }
+    }
+}
+
+// Unlocks when dropped, so an early return or a panic can't leave it held
+struct SpinGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+}
+
+impl<T> Deref for SpinGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the guard exists only while the lock is held
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for SpinGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: as in deref, and &mut self means no other reference to the
+        // guard is in use
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for SpinGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+// The same lock with Relaxed everywhere, which compiles and is wrong:
+//
+//     fn lock(&self) {
+//         while self.locked.swap(true, Ordering::Relaxed) {}
+//     }
+//     fn unlock(&self) {
+//         self.locked.store(false, Ordering::Relaxed);
+//     }
+//
+// The flag still changes hands atomically, so two threads never both think
+// they hold it. But nothing ties the writes to `value` to the flag: the CPU
+// or the compiler may make the unlocking store visible before the writes
+// made while holding the lock, or let the next holder read `value` before
+// its swap. The next holder can then see stale data, a data race on the
+// value and so undefined behaviour. It often passes on x86, whose stores
+// are ordered anyway, and fails on ARM
+
+// ---- AcqRel: the last one out ----
+// Each worker writes its result, then counts itself out with fetch_sub. The
+// Release half publishes its own result; the Acquire half lets the worker
+// that counts out last see every earlier worker's result, because each
+// fetch_sub read the value the one before it wrote. Arc's drop works this
+// way, so that the last owner sees all others' writes before freeing
+static RESULTS: [AtomicUsize; THREADS] = [const { AtomicUsize::new(0) }; THREADS];
+static REMAINING: AtomicUsize = AtomicUsize::new(THREADS);
+
+// Returns the total, from the worker that finished last only
+fn worker(index: usize) -> Option<usize> {
+    RESULTS[index].store((index + 1) * 10, Ordering::Relaxed);
+    if REMAINING.fetch_sub(1, Ordering::AcqRel) == 1 {
+        Some(RESULTS.iter().map(|r| r.load(Ordering::Relaxed)).sum())
+    } else {
+        None
+    }
+}
+
+// ---- SeqCst: one-time initialisation, and the case that needs it ----
+const UNINIT: i32 = 0;
+const RUNNING: i32 = 1;
+const DONE: i32 = 2;
+
+static INIT_STATE: AtomicI32 = AtomicI32::new(UNINIT);
+static INIT_CALLS: AtomicUsize = AtomicUsize::new(0);
+static SETTING: AtomicUsize = AtomicUsize::new(0);
+
+// Runs the set-up once, however many threads ask at the same time; the rest
+// wait until it is done. Acquire/Release would do here too; SeqCst is the
+// easy default when it isn't worth working out the weakest ordering. This is
+// what std::sync::Once and OnceLock do, with a proper wait instead of a spin
+fn once_initialized() -> usize {
+    match INIT_STATE.compare_exchange(UNINIT, RUNNING, Ordering::SeqCst, Ordering::SeqCst) {
+        Ok(_) => {
+            INIT_CALLS.fetch_add(1, Ordering::SeqCst);
+            SETTING.store(42, Ordering::SeqCst);
+            INIT_STATE.store(DONE, Ordering::SeqCst);
+        }
+        Err(_) => {
+            while INIT_STATE.load(Ordering::SeqCst) != DONE {
+                hint::spin_loop();
+            }
+        }
+    }
+    SETTING.load(Ordering::SeqCst)
+}
+
+// Two threads each raise their own flag, then look at the other's. With
+// SeqCst all four operations fall in one order every thread agrees on, so
+// whichever store comes first in it is seen by the other thread's load: at
+// least one thread sees the other's flag. With Acquire/Release both loads may
+// read false, since each store can still be waiting in its own core's store
+// buffer when the other core loads. Lock algorithms like Dekker's and
+// Peterson's depend on this
+fn store_then_load() -> (bool, bool) {
+    let (a, b) = (AtomicBool::new(false), AtomicBool::new(false));
+    thread::scope(|s| {
+        let saw_b = s.spawn(|| {
+            a.store(true, Ordering::SeqCst);
+            b.load(Ordering::SeqCst)
+        });
+        let saw_a = s.spawn(|| {
+            b.store(true, Ordering::SeqCst);
+            a.load(Ordering::SeqCst)
+        });
+        (saw_b.join().unwrap(), saw_a.join().unwrap())
+    })
+}
+
+// ---- compare_exchange: a lock-free stack ----
+// A Treiber stack: `head` points at the top node and each node at the one
+// below. Pushing reads the head, points the new node at it, and swaps the new
+// node in only if the head is still what was read; otherwise another push got
+// in first, and it tries again
+struct Node<T> {
+    value: T,
+    next: *mut Node<T>,
+}
+
+struct Stack<T> {
+    head: AtomicPtr<Node<T>>,
+}
+
+// SAFETY: values go in on one thread and may come out on another
+unsafe impl<T: Send> Sync for Stack<T> {}
+
+impl<T> Stack<T> {
+    fn new() -> Stack<T> {
+        Stack {
+            head: AtomicPtr::new(ptr::null_mut()),
+        }
+    }
+
+    fn push(&self, value: T) {
+        let node = Box::into_raw(Box::new(Node {
+            value,
+            next: ptr::null_mut(),
+        }));
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            // SAFETY: `node` isn't shared until the exchange succeeds
+            unsafe { (*node).next = head };
+            // Release, so a thread that Acquires the new head sees the node's
+            // fields. On failure the current head comes back, ready to retry
+            match self
+                .head
+                .compare_exchange_weak(head, node, Ordering::Release, Ordering::Relaxed)
+            {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    // Pushes only if the stack is empty, with one strong compare_exchange: a
+    // single attempt that must not fail spuriously, since it isn't retried
+    fn push_if_empty(&self, value: T) -> Result<(), T> {
+        let node = Box::into_raw(Box::new(Node {
+            value,
+            next: ptr::null_mut(),
+        }));
+        match self.head.compare_exchange(
+            ptr::null_mut(),
+            node,
+            Ordering::Release,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => Ok(()),
+            // SAFETY: the exchange failed, so `node` was never shared
+            Err(_) => Err(unsafe { Box::from_raw(node) }.value),
+        }
+    }
+
+    // Takes &mut self, so no other thread can be pushing or popping. A
+    // lock-free pop is possible, but freeing a node another thread may still
+    // be reading needs hazard pointers or epochs (crossbeam-epoch), and a
+    // node freed and reallocated at the same address fools compare_exchange
+    // (the ABA problem)
+    fn pop(&mut self) -> Option<T> {
+        let head = *self.head.get_mut();
+        if head.is_null() {
+            return None;
+        }
+        // SAFETY: `head` came from Box::into_raw and, with &mut self, nobody
+        // else can reach it
+        let node = unsafe { Box::from_raw(head) };
+        *self.head.get_mut() = node.next;
+        Some(node.value)
+    }
+}
+
+impl<T> Drop for Stack<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+fn main() {
+    // ---- Relaxed ----
+    let hits = count_hits();
+    assert_eq!(hits, THREADS * PER_THREAD);
+    println!("Relaxed counter: {hits} hits from {THREADS} threads, none lost");
+
+    // ---- Acquire/Release ----
+    // The Vec isn't atomic at all; the lock makes every push see the last one
+    let log = SpinLock::new(Vec::new());
+    thread::scope(|s| {
+        for t in 0..THREADS {
+            let log = &log;
+            s.spawn(move || {
+                for i in 0..1000 {
+                    log.lock().push((t, i));
+                }
+            });
+        }
+    });
+    let log = log.lock();
+    assert_eq!(log.len(), THREADS * 1000);
+    // Each thread's own pushes stay in order
+    for t in 0..THREADS {
+        let mine: Vec<usize> = log.iter().filter(|e| e.0 == t).map(|e| e.1).collect();
+        assert_eq!(mine, (0..1000).collect::<Vec<_>>());
+    }
+    println!("Spinlock: {} pushes to a shared Vec, none lost", log.len());
+    drop(log);
+
+    // ---- AcqRel ----
+    let totals: Vec<usize> = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS).map(|i| s.spawn(move || worker(i))).collect();
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().unwrap())
+            .collect()
+    });
+    // Exactly one worker was last, and it saw 10 + 20 + 30 + 40
+    assert_eq!(totals, [(1..=THREADS).map(|n| n * 10).sum::<usize>()]);
+    println!("AcqRel: the last of {THREADS} workers saw all their results");
+
+    // ---- SeqCst ----
+    let settings: Vec<usize> = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS).map(|_| s.spawn(once_initialized)).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert!(settings.iter().all(|&setting| setting == 42));
+    assert_eq!(INIT_CALLS.load(Ordering::SeqCst), 1);
+    println!("once_initialized: {THREADS} callers, set up exactly once");
+    for _ in 0..200 {
+        let (saw_b, saw_a) = store_then_load();
+        assert!(
+            saw_b || saw_a,
+            "SeqCst guarantees one thread sees the other"
+        );
+    }
+    println!("Store then load: with SeqCst one thread always saw the other's flag");
+
+    // ---- compare_exchange and compare_exchange_weak ----
+    let mut stack = Stack::new();
+    assert_eq!(stack.push_if_empty(0), Ok(()));
+    assert_eq!(stack.push_if_empty(99), Err(99));
+    thread::scope(|s| {
+        for t in 0..THREADS {
+            let stack = &stack;
+            s.spawn(move || {
+                for i in 0..1000 {
+                    stack.push(t * 1000 + i + 1);
+                }
+            });
+        }
+    });
+    let mut popped = Vec::new();
+    while let Some(value) = stack.pop() {
+        popped.push(value);
+    }
+    assert_eq!(popped.last(), Some(&0));
+    popped.sort_unstable();
+    assert_eq!(popped, (0..=THREADS * 1000).collect::<Vec<_>>());
+    println!(
+        "Lock-free stack: {} pushes from {THREADS} threads",
+        popped.len()
+    );
+
+    // ---- The fetch_ operations ----
+    // Each returns the value from before, so a thread knows what it changed
+    let tickets = AtomicI32::new(3);
+    assert_eq!(tickets.fetch_sub(1, Ordering::Relaxed), 3);
+    assert_eq!(tickets.fetch_add(5, Ordering::Relaxed), 2);
+    assert_eq!(tickets.load(Ordering::Relaxed), 7);
+    // Bit flags: or sets, and with the complement clears, xor flips
+    const READ: usize = 0b001;
+    const WRITE: usize = 0b010;
+    const EXEC: usize = 0b100;
+    let flags = AtomicUsize::new(READ);
+    assert_eq!(flags.fetch_or(WRITE, Ordering::Relaxed), READ);
+    assert_eq!(flags.fetch_and(!READ, Ordering::Relaxed), READ | WRITE);
+    assert_eq!(flags.fetch_xor(EXEC | WRITE, Ordering::Relaxed), WRITE);
+    assert_eq!(flags.load(Ordering::Relaxed), EXEC);
+    // The returned old value makes "was I the first?" one operation
+    let claimed = AtomicUsize::new(0);
+    let winners = thread::scope(|s| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| s.spawn(|| claimed.fetch_or(1, Ordering::Relaxed) == 0))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .filter(|&won| won)
+            .count()
+    });
+    assert_eq!(winners, 1);
+    println!("fetch_add/sub/or/and/xor: each returned the value before it");
+}
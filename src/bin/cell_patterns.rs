@@ -0,0 +1,333 @@
+// Cell and RefCell in the places they earn their keep
+// Both let something be changed through a shared & reference ("interior
+// mutability"). Cell does it by moving whole values in and out, so it never
+// hands out a reference to its contents; RefCell hands out references and
+// counts them at run time, panicking on a second borrow_mut where the
+// compiler would have refused to build. Neither is Sync, so all of this stays
+// on one thread; Mutex and the atomics are the multi-threaded versions
+use std::cell::{Cell, RefCell, UnsafeCell};
+use std::collections::VecDeque;
+use std::rc::{Rc, Weak};
+
+// ---- 1. A graph that algorithms share as &Graph ----
+// Each node's neighbours sit in their own RefCell, so edges can be added
+// through &Graph. Every function below takes &Graph, and one graph can be
+// handed to all of them at once without a &mut anywhere
+struct Graph {
+    adjacency: Vec<RefCell<Vec<usize>>>,
+}
+
+impl Graph {
+    fn new(nodes: usize) -> Graph {
+        Graph {
+            adjacency: (0..nodes).map(|_| RefCell::new(Vec::new())).collect(),
+        }
+    }
+
+    fn add_edge(&self, a: usize, b: usize) {
+        self.adjacency[a].borrow_mut().push(b);
+        self.adjacency[b].borrow_mut().push(a);
+    }
+}
+
+// Nodes in the order breadth-first search reaches them from `start`
+fn bfs(graph: &Graph, start: usize) -> Vec<usize> {
+    let mut seen = vec![false; graph.adjacency.len()];
+    let mut order = Vec::new();
+    let mut queue = VecDeque::from([start]);
+    seen[start] = true;
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        // The borrow lasts only for this loop, not the whole search
+        for &next in graph.adjacency[node].borrow().iter() {
+            if !seen[next] {
+                seen[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    order
+}
+
+fn edge_count(graph: &Graph) -> usize {
+    graph
+        .adjacency
+        .iter()
+        .map(|n| n.borrow().len())
+        .sum::<usize>()
+        / 2
+}
+
+// Joins every node that has no edges to node 0, reading and writing the same
+// graph through one shared reference
+fn connect_isolated(graph: &Graph) {
+    for node in 1..graph.adjacency.len() {
+        // is_empty's borrow ends before add_edge borrows mutably
+        if graph.adjacency[node].borrow().is_empty() {
+            graph.add_edge(0, node);
+        }
+    }
+}
+
+// ---- 2. A mock that counts calls through &self ----
+// The code under test only gets a &dyn Log, as it would with a real logger,
+// so the mock can't take &mut self to count; a Cell<usize> counts anyway
+trait Log {
+    fn log(&self, message: &str);
+}
+
+struct MockLogger {
+    calls: Cell<usize>,
+    // A RefCell for the messages, since a Vec can't be moved in and out of a
+    // Cell as cheaply as a number
+    messages: RefCell<Vec<String>>,
+}
+
+impl MockLogger {
+    fn new() -> MockLogger {
+        MockLogger {
+            calls: Cell::new(0),
+            messages: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl Log for MockLogger {
+    fn log(&self, message: &str) {
+        self.calls.set(self.calls.get() + 1);
+        self.messages.borrow_mut().push(message.to_string());
+    }
+}
+
+// The code under test: logs each odd number it skips
+fn sum_even(numbers: &[i32], log: &dyn Log) -> i32 {
+    numbers
+        .iter()
+        .filter(|&&n| {
+            let even = n % 2 == 0;
+            if !even {
+                log.log(&format!("skipped {n}"));
+            }
+            even
+        })
+        .sum()
+}
+
+// ---- 3. A doubly linked list, leaking and then fixed ----
+// Values that count their own drops, to show which nodes were freed
+thread_local! {
+    static DROPS: Cell<usize> = const { Cell::new(0) };
+}
+
+struct Tracked(i32);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        DROPS.with(|drops| drops.set(drops.get() + 1));
+    }
+}
+
+fn drops() -> usize {
+    DROPS.with(Cell::get)
+}
+
+// With Rc both ways, each pair of neighbours holds the other alive: the
+// strong counts never reach 0 and no node is ever freed
+struct LeakyNode<T> {
+    value: T,
+    next: RefCell<Option<Rc<LeakyNode<T>>>>,
+    prev: RefCell<Option<Rc<LeakyNode<T>>>>,
+}
+
+fn leaky_pair(a: i32, b: i32) -> Rc<LeakyNode<Tracked>> {
+    let first = Rc::new(LeakyNode {
+        value: Tracked(a),
+        next: RefCell::new(None),
+        prev: RefCell::new(None),
+    });
+    let second = Rc::new(LeakyNode {
+        value: Tracked(b),
+        next: RefCell::new(None),
+        prev: RefCell::new(Some(Rc::clone(&first))),
+    });
+    *first.next.borrow_mut() = Some(second);
+    first
+}
+
+// The fix: `next` owns, `prev` only points back. A Weak doesn't keep its
+// node alive, so dropping the head frees the whole chain front to back
+struct Node<T> {
+    value: T,
+    next: RefCell<Option<Rc<Node<T>>>>,
+    prev: RefCell<Weak<Node<T>>>,
+}
+
+struct List<T> {
+    head: Option<Rc<Node<T>>>,
+    tail: Option<Rc<Node<T>>>,
+}
+
+impl<T> List<T> {
+    fn new() -> List<T> {
+        List {
+            head: None,
+            tail: None,
+        }
+    }
+
+    fn push_back(&mut self, value: T) {
+        let node = Rc::new(Node {
+            value,
+            next: RefCell::new(None),
+            prev: RefCell::new(Weak::new()),
+        });
+        match self.tail.take() {
+            Some(old) => {
+                *node.prev.borrow_mut() = Rc::downgrade(&old);
+                *old.next.borrow_mut() = Some(Rc::clone(&node));
+            }
+            None => self.head = Some(Rc::clone(&node)),
+        }
+        self.tail = Some(node);
+    }
+
+    // Head to tail, following the owning links
+    fn forward(&self) -> Vec<Rc<Node<T>>> {
+        let mut nodes = Vec::new();
+        let mut current = self.head.clone();
+        while let Some(node) = current {
+            current = node.next.borrow().clone();
+            nodes.push(node);
+        }
+        nodes
+    }
+
+    // Tail to head, upgrading each Weak; None means the start was reached
+    fn backward(&self) -> Vec<Rc<Node<T>>> {
+        let mut nodes = Vec::new();
+        let mut current = self.tail.clone();
+        while let Some(node) = current {
+            current = node.prev.borrow().upgrade();
+            nodes.push(node);
+        }
+        nodes
+    }
+}
+
+// ---- 4. A Cell of our own, on UnsafeCell ----
+// UnsafeCell is the one way Rust allows changing data behind a & reference;
+// Cell, RefCell, Mutex and the atomics are all built on it. It only hands
+// out a raw pointer, and the wrapper's job is to make using it sound. It also
+// makes MyCell !Sync automatically, so two threads can never share one
+struct MyCell<T> {
+    value: UnsafeCell<T>,
+}
+
+impl<T> MyCell<T> {
+    fn new(value: T) -> MyCell<T> {
+        MyCell {
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    fn set(&self, value: T) {
+        // SAFETY: MyCell isn't Sync, so no other thread can be in here, and
+        // no method returns a reference into the value, so nothing can be
+        // reading it while it is overwritten
+        unsafe { *self.value.get() = value }
+    }
+
+    fn replace(&self, value: T) -> T {
+        // SAFETY: as in set
+        unsafe { std::mem::replace(&mut *self.value.get(), value) }
+    }
+
+    fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: Copy> MyCell<T> {
+    // A copy, never a reference: a reference would let set change the value
+    // under someone who is still reading it
+    fn get(&self) -> T {
+        // SAFETY: as in set; the value is copied out before anything else runs
+        unsafe { *self.value.get() }
+    }
+}
+
+fn main() {
+    // ---- 1. Graph ----
+    let graph = Graph::new(6);
+    graph.add_edge(0, 1);
+    graph.add_edge(1, 2);
+    graph.add_edge(0, 3);
+    let (bfs_graph, edges_graph) = (&graph, &graph);
+    assert_eq!(bfs(bfs_graph, 0), [0, 1, 3, 2]);
+    assert_eq!(edge_count(edges_graph), 3);
+    connect_isolated(&graph);
+    assert_eq!(bfs(&graph, 0), [0, 1, 3, 4, 5, 2]);
+    assert_eq!(edge_count(&graph), 5);
+    // Two borrows at once where one is mutable is still an error, found at
+    // run time; try_borrow_mut reports it instead of panicking
+    let reading = graph.adjacency[0].borrow();
+    assert!(graph.adjacency[0].try_borrow_mut().is_err());
+    drop(reading);
+    assert!(graph.adjacency[0].try_borrow_mut().is_ok());
+    println!("Graph: bfs from 0 visits {:?}", bfs(&graph, 0));
+
+    // ---- 2. Mock logger ----
+    let logger = MockLogger::new();
+    assert_eq!(sum_even(&[1, 2, 3, 4, 5], &logger), 6);
+    assert_eq!(logger.calls.get(), 3);
+    assert_eq!(
+        *logger.messages.borrow(),
+        ["skipped 1", "skipped 3", "skipped 5"]
+    );
+    println!("MockLogger: counted {} calls", logger.calls.get());
+
+    // ---- 3. Linked lists ----
+    let before = drops();
+    let leaky = leaky_pair(1, 2);
+    // It works as a list, both ways
+    let second = leaky.next.borrow().clone().expect("a second node");
+    let back = second.prev.borrow().clone().expect("a link back");
+    assert_eq!((leaky.value.0, second.value.0, back.value.0), (1, 2, 1));
+    drop((second, back));
+    // One count from our handle and one from the second node's prev
+    assert_eq!(Rc::strong_count(&leaky), 2);
+    drop(leaky);
+    // Nothing was freed: the two nodes still own each other
+    assert_eq!(drops(), before);
+    println!("LeakyNode: both nodes of the Rc cycle are still allocated");
+
+    let mut list = List::new();
+    for n in 1..=4 {
+        list.push_back(Tracked(n));
+    }
+    let forward: Vec<i32> = list.forward().iter().map(|node| node.value.0).collect();
+    let backward: Vec<i32> = list.backward().iter().map(|node| node.value.0).collect();
+    assert_eq!(forward, [1, 2, 3, 4]);
+    assert_eq!(backward, [4, 3, 2, 1]);
+    // Each node is owned once, by the one before it (the head by the list);
+    // the tail has the list's tail handle as well
+    let head = list.head.as_ref().expect("the list isn't empty");
+    assert_eq!((Rc::strong_count(head), Rc::weak_count(head)), (1, 1));
+    let before = drops();
+    drop(list);
+    assert_eq!(drops(), before + 4);
+    println!("List: forward {forward:?}, backward {backward:?}, all 4 nodes freed");
+
+    // ---- 4. MyCell ----
+    let cell = MyCell::new(5);
+    let shared = (&cell, &cell);
+    shared.0.set(6);
+    assert_eq!(shared.1.get(), 6);
+    assert_eq!(cell.replace(7), 6);
+    assert_eq!(cell.into_inner(), 7);
+    // Non-Copy values work too, through replace rather than get
+    let names = MyCell::new(String::from("ann"));
+    let old = names.replace(String::from("bob"));
+    assert_eq!((old.as_str(), names.into_inner().as_str()), ("ann", "bob"));
+    println!("MyCell: set, get and replace through shared references");
+}
@@ -0,0 +1,207 @@
+// Conditional compilation: code that only exists on some builds
+// #[cfg(...)] on an item keeps it or throws it away before type checking, so
+// the code for other platforms doesn't have to compile (or even make sense)
+// here. cfg!(...) is the expression form: both branches are compiled and
+// the condition is a plain true or false, which the optimiser folds away.
+// Options are set by the compiler (target_os, target_arch, debug_assertions,
+// test) or by Cargo (feature = "..."). See what this machine has with
+//     rustc --print cfg
+// Try: cargo run --bin cfg_attributes
+//      cargo run --release --bin cfg_attributes
+//      cargo run --features verbose --bin cfg_attributes
+use std::io::{self, IsTerminal, Write};
+
+// ---- One function, a body per platform ----
+// Exactly one of these survives on any target, so callers don't care which
+#[cfg(target_os = "linux")]
+fn os_name() -> &'static str {
+    "Linux"
+}
+
+#[cfg(target_os = "macos")]
+fn os_name() -> &'static str {
+    "macOS"
+}
+
+#[cfg(windows)]
+fn os_name() -> &'static str {
+    "Windows"
+}
+
+// not(any(...)): everything the ones above don't cover, so there is always
+// exactly one os_name and never zero or two
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn os_name() -> &'static str {
+    "some other OS"
+}
+
+// ---- all(...): both conditions at once ----
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn platform_note() -> &'static str {
+    "64-bit x86 Linux, the most common CI machine"
+}
+
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+fn platform_note() -> &'static str {
+    "64-bit ARM Linux, like a Raspberry Pi or a Graviton server"
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+fn platform_note() -> &'static str {
+    "neither x86_64 nor aarch64 Linux"
+}
+
+// ---- debug_assertions: on in `cargo build`, off in `cargo build --release` ----
+#[cfg(debug_assertions)]
+fn build_profile() -> &'static str {
+    "debug"
+}
+
+#[cfg(not(debug_assertions))]
+fn build_profile() -> &'static str {
+    "release"
+}
+
+// cfg_attr(condition, attribute) applies the attribute only when the condition
+// holds: Debug is derived in debug builds and left out of release ones
+#[cfg_attr(debug_assertions, derive(Debug))]
+struct Settings {
+    rounds: u32,
+    range: (u32, u32),
+}
+
+// ---- Clearing the screen, three ways ----
+// An ANSI escape on Unix terminals (clear, then move the cursor home)
+#[cfg(unix)]
+fn clear_screen() {
+    print!("\x1B[2J\x1B[H");
+    let _ = io::stdout().flush();
+}
+
+// The console's own cls on Windows, which is a cmd built-in rather than a
+// program, so it has to go through cmd /C. Older consoles don't understand
+// the escape codes
+#[cfg(windows)]
+fn clear_screen() {
+    let cleared = std::process::Command::new("cmd")
+        .args(["/C", "cls"])
+        .status()
+        .is_ok_and(|status| status.success());
+    if !cleared {
+        scroll_away();
+    }
+}
+
+// Anywhere else there is no known way, so push the old text off the top
+#[cfg(not(any(unix, windows)))]
+fn clear_screen() {
+    scroll_away();
+}
+
+// Only Windows and the catch-all use this, so on Unix it would be dead code
+#[cfg(not(unix))]
+fn scroll_away() {
+    print!("{}", "\n".repeat(50));
+}
+
+// ---- feature = "...": switched on by whoever builds the crate ----
+// Features are listed in Cargo.toml under [features]; `verbose` turns on
+// extra output here and in the guessing game (see app.rs)
+#[cfg(feature = "verbose")]
+fn verbose(message: &str) {
+    println!("[verbose] {message}");
+}
+
+// The same call compiles to nothing without the feature, so callers need no
+// #[cfg] of their own
+#[cfg(not(feature = "verbose"))]
+fn verbose(_message: &str) {}
+
+fn main() {
+    // Clearing would wipe out output that is being piped or captured
+    if io::stdout().is_terminal() {
+        clear_screen();
+    }
+
+    println!("Built for {} ({})", os_name(), platform_note());
+    println!(
+        "Architecture {}, family {}, pointer width {} bits",
+        std::env::consts::ARCH,
+        std::env::consts::FAMILY,
+        usize::BITS
+    );
+
+    // ---- #[cfg] and cfg!() agree ----
+    // The item that survived must be the one cfg!() says holds
+    let expected = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    assert_eq!(build_profile(), expected);
+    println!("This is a {} build", build_profile());
+    if cfg!(target_os = "linux") {
+        assert_eq!(os_name(), "Linux");
+    }
+    assert_eq!(cfg!(windows), os_name() == "Windows");
+    // cfg!() keeps both branches type-checked, which #[cfg] doesn't: a
+    // typo in a branch for another platform shows up here and now
+    let separator = if cfg!(windows) { '\\' } else { '/' };
+    assert_eq!(separator, std::path::MAIN_SEPARATOR);
+
+    let settings = Settings {
+        rounds: 3,
+        range: (1, 100),
+    };
+    println!(
+        "Settings: {} rounds on {}-{}",
+        settings.rounds, settings.range.0, settings.range.1
+    );
+    // Settings only has Debug in debug builds, so printing it with {:?} has
+    // to be left out too; cfg!() wouldn't do, as both branches must compile
+    #[cfg(debug_assertions)]
+    println!("Settings derive Debug here: {settings:?}");
+
+    // ---- debug_assertions also controls the checks themselves ----
+    // debug_assert! is skipped in release builds, and integer overflow panics
+    // in debug builds but wraps in release ones
+    debug_assert!(settings.range.0 <= settings.range.1);
+    let big = std::hint::black_box(u8::MAX);
+    // A silent panic hook, so the expected panic doesn't print a message
+    std::panic::set_hook(Box::new(|_| {}));
+    let wrapped = std::panic::catch_unwind(|| big + 1);
+    drop(std::panic::take_hook());
+    assert_eq!(wrapped.is_err(), cfg!(debug_assertions));
+    println!(
+        "255u8 + 1 {}",
+        if wrapped.is_err() {
+            "panicked"
+        } else {
+            "wrapped to 0"
+        }
+    );
+
+    // ---- test ----
+    // cfg(test) is on only when compiling with `cargo test`, which builds each
+    // file again as a test harness. Helpers only tests use go under it:
+    //
+    //     #[cfg(test)]
+    //     fn settings_for_tests() -> Settings { ... }
+    //
+    // A normal run is never compiled with it, so this always prints false
+    println!("cfg!(test) is {}", cfg!(test));
+
+    // ---- features ----
+    verbose("the verbose feature is on");
+    println!(
+        "The verbose feature is {}",
+        if cfg!(feature = "verbose") {
+            "on"
+        } else {
+            "off; add --features verbose to turn it on"
+        }
+    );
+}
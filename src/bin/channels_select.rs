@@ -0,0 +1,377 @@
+// Waiting on several channels at once
+// std::sync::mpsc can only block on one receiver at a time, so a thread that
+// serves an alert channel, a work channel and a timer needs "select": sleep
+// until any of them has something, then take it. crossbeam-channel provides
+// this as select!; it isn't available offline, so `mod chan` below is a small
+// stand-in with the same shape: unbounded and bounded channels, a ticker, and
+// a Signal every channel pokes when something happens, so one thread can wait
+// on all of them
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+mod chan {
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Condvar, Mutex};
+    use std::time::{Duration, Instant};
+
+    // Shared by every channel one thread selects over. The generation counts
+    // events (a send, a sender going away); a waiter remembers the generation it
+    // last looked at and sleeps until it changes, so an event between looking and
+    // sleeping is never missed
+    #[derive(Default)]
+    pub struct Signal {
+        generation: Mutex<u64>,
+        changed: Condvar,
+    }
+
+    impl Signal {
+        pub fn new() -> Arc<Signal> {
+            Arc::new(Signal::default())
+        }
+
+        pub fn generation(&self) -> u64 {
+            *self.generation.lock().unwrap()
+        }
+
+        fn notify(&self) {
+            *self.generation.lock().unwrap() += 1;
+            self.changed.notify_all();
+        }
+
+        // Sleeps until something happens after `seen`, or `until` passes
+        pub fn wait(&self, seen: u64, until: Instant) {
+            let mut generation = self.generation.lock().unwrap();
+            while *generation == seen {
+                let now = Instant::now();
+                if now >= until {
+                    return;
+                }
+                generation = self
+                    .changed
+                    .wait_timeout(generation, until - now)
+                    .unwrap()
+                    .0;
+            }
+        }
+    }
+
+    struct State<T> {
+        queue: VecDeque<T>,
+        senders: usize,
+        receiver_alive: bool,
+    }
+
+    struct Shared<T> {
+        state: Mutex<State<T>>,
+        // Senders of a full bounded channel wait here for the receiver to take something
+        space: Condvar,
+        capacity: Option<usize>,
+        signal: Arc<Signal>,
+    }
+
+    pub struct Sender<T>(Arc<Shared<T>>);
+    pub struct Receiver<T>(Arc<Shared<T>>);
+
+    #[derive(Debug, PartialEq)]
+    pub enum TryRecvError {
+        Empty,
+        // Every sender is gone and the queue is empty: nothing will ever arrive
+        Disconnected,
+    }
+
+    fn channel<T>(capacity: Option<usize>, signal: &Arc<Signal>) -> (Sender<T>, Receiver<T>) {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                senders: 1,
+                receiver_alive: true,
+            }),
+            space: Condvar::new(),
+            capacity,
+            signal: Arc::clone(signal),
+        });
+        (Sender(Arc::clone(&shared)), Receiver(shared))
+    }
+
+    pub fn unbounded<T>(signal: &Arc<Signal>) -> (Sender<T>, Receiver<T>) {
+        channel(None, signal)
+    }
+
+    // A full bounded channel makes send wait: backpressure on a fast producer
+    pub fn bounded<T>(capacity: usize, signal: &Arc<Signal>) -> (Sender<T>, Receiver<T>) {
+        channel(Some(capacity), signal)
+    }
+
+    impl<T> Sender<T> {
+        // Err gives the value back if the receiver has gone away
+        pub fn send(&self, value: T) -> Result<(), T> {
+            let shared = &self.0;
+            let mut state = shared.state.lock().unwrap();
+            while state.receiver_alive
+                && shared
+                    .capacity
+                    .is_some_and(|capacity| state.queue.len() >= capacity)
+            {
+                state = shared.space.wait(state).unwrap();
+            }
+            if !state.receiver_alive {
+                return Err(value);
+            }
+            state.queue.push_back(value);
+            drop(state);
+            shared.signal.notify();
+            Ok(())
+        }
+    }
+
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Sender<T> {
+            self.0.state.lock().unwrap().senders += 1;
+            Sender(Arc::clone(&self.0))
+        }
+    }
+
+    // The last sender leaving is an event too: the receiver should notice it
+    impl<T> Drop for Sender<T> {
+        fn drop(&mut self) {
+            self.0.state.lock().unwrap().senders -= 1;
+            self.0.signal.notify();
+        }
+    }
+
+    impl<T> Receiver<T> {
+        pub fn try_recv(&self) -> Result<T, TryRecvError> {
+            let mut state = self.0.state.lock().unwrap();
+            match state.queue.pop_front() {
+                Some(value) => {
+                    self.0.space.notify_one();
+                    Ok(value)
+                }
+                None if state.senders == 0 => Err(TryRecvError::Disconnected),
+                None => Err(TryRecvError::Empty),
+            }
+        }
+
+        // Whether try_recv would return something other than Empty; with one
+        // receiver nothing can take the value in between, so this can't go stale
+        pub fn is_ready(&self) -> bool {
+            let state = self.0.state.lock().unwrap();
+            !state.queue.is_empty() || state.senders == 0
+        }
+
+        pub fn len(&self) -> usize {
+            self.0.state.lock().unwrap().queue.len()
+        }
+    }
+
+    impl<T> Drop for Receiver<T> {
+        fn drop(&mut self) {
+            self.0.state.lock().unwrap().receiver_alive = false;
+            self.0.space.notify_all();
+        }
+    }
+
+    // crossbeam's tick(): ready once every period. Nobody has to send on it;
+    // the select loop just never sleeps past its next deadline
+    pub struct Ticker {
+        period: Duration,
+        next: Instant,
+    }
+
+    impl Ticker {
+        pub fn new(period: Duration) -> Ticker {
+            Ticker {
+                period,
+                next: Instant::now() + period,
+            }
+        }
+
+        pub fn is_ready(&self) -> bool {
+            Instant::now() >= self.next
+        }
+
+        pub fn deadline(&self) -> Instant {
+            self.next
+        }
+
+        pub fn take(&mut self) {
+            self.next += self.period;
+        }
+    }
+}
+
+use chan::{Signal, TryRecvError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ready {
+    Work,
+    Tick,
+    Done,
+}
+
+const WORK_ITEMS: u32 = 100;
+const ALERTS: u32 = 5;
+
+// ---- 1. The select loop ----
+
+fn serve() {
+    let signal = Signal::new();
+    let (alert_tx, alerts) = chan::unbounded::<String>(&signal);
+    let (work_tx, work) = chan::bounded::<u32>(32, &signal);
+    let (done_tx, done) = chan::bounded::<()>(1, &signal);
+    let mut heartbeat = chan::Ticker::new(Duration::from_millis(500));
+
+    // Sends faster than the work is done, so the bounded channel fills up and
+    // the producer has to wait for room
+    let producer = thread::spawn(move || {
+        for i in 0..WORK_ITEMS {
+            work_tx.send(i).expect("the consumer outlives the producer");
+            if i % 20 == 10 {
+                alert_tx.send(format!("alert after work item {i}")).unwrap();
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        // Everything has been handed over; "done" doesn't mean it has all been processed
+        done_tx.send(()).unwrap();
+    });
+
+    let mut rng = rand::rng();
+    let (mut alerts_seen, mut work_seen, mut heartbeats) = (0, Vec::new(), 0);
+    let mut most_queued = 0;
+    loop {
+        let seen = signal.generation();
+        // Alerts jump the queue: checked on every pass before anything else
+        if let Ok(alert) = alerts.try_recv() {
+            println!("  ALERT: {alert}");
+            alerts_seen += 1;
+            continue;
+        }
+        most_queued = most_queued.max(work.len());
+        // Among the rest, like select!, pick one of the ready ones at random,
+        // so that none of them can crowd out the others
+        let mut ready = Vec::new();
+        if work.is_ready() {
+            ready.push(Ready::Work);
+        }
+        if heartbeat.is_ready() {
+            ready.push(Ready::Tick);
+        }
+        if done.is_ready() {
+            ready.push(Ready::Done);
+        }
+        if ready.is_empty() {
+            signal.wait(seen, heartbeat.deadline());
+            continue;
+        }
+        match ready[rng.random_range(0..ready.len())] {
+            Ready::Work => {
+                if let Ok(item) = work.try_recv() {
+                    work_seen.push(item);
+                    thread::sleep(Duration::from_millis(8));
+                }
+            }
+            Ready::Tick => {
+                heartbeat.take();
+                heartbeats += 1;
+                println!("  heartbeat ({} work items so far)", work_seen.len());
+            }
+            Ready::Done => {
+                // Graceful shutdown: the producer has stopped, but messages it sent
+                // before "done" may still be queued. Drain them before exiting
+                let _ = done.try_recv();
+                while let Ok(alert) = alerts.try_recv() {
+                    println!("  ALERT (drained): {alert}");
+                    alerts_seen += 1;
+                }
+                let mut drained = 0;
+                while let Ok(item) = work.try_recv() {
+                    work_seen.push(item);
+                    drained += 1;
+                }
+                println!("  done: drained {drained} queued work items");
+                break;
+            }
+        }
+    }
+    producer.join().unwrap();
+
+    // All 105 arrived, each once and (for work, with one producer) in order
+    assert_eq!(alerts_seen, ALERTS);
+    assert_eq!(work_seen, (0..WORK_ITEMS).collect::<Vec<_>>());
+    assert_eq!(alerts.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(work.try_recv(), Err(TryRecvError::Disconnected));
+    assert!(most_queued <= 32, "the bounded channel held {most_queued}");
+    assert!(heartbeats >= 1);
+    println!(
+        "Consumed {} work items and {alerts_seen} alerts, with {heartbeats} heartbeat(s); at most {most_queued} work items were waiting.",
+        work_seen.len()
+    );
+}
+
+// ---- 2. Starvation ----
+
+// A flood of alerts, each taking longer to handle than the gap before the next,
+// against a loop that takes alerts strictly first (`fair: false`) or one that
+// chooses at random whenever both are ready (`fair: true`). Returns how many
+// work items got done in the time
+fn flood(fair: bool) -> u32 {
+    let signal = Signal::new();
+    let (alert_tx, alerts) = chan::unbounded::<u32>(&signal);
+    let (work_tx, work) = chan::unbounded::<u32>(&signal);
+    for i in 0..10_000 {
+        work_tx.send(i).unwrap();
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let flooding = Arc::clone(&stop);
+    let flooder = thread::spawn(move || {
+        // 20 alerts a millisecond, against 10 handled (at 100 µs each)
+        while !flooding.load(Ordering::Relaxed) {
+            for i in 0..20 {
+                alert_tx.send(i).unwrap();
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    });
+    // Give the flood a head start, so the alert queue is never empty
+    thread::sleep(Duration::from_millis(10));
+
+    let mut rng = rand::rng();
+    let mut work_done = 0;
+    let until = Instant::now() + Duration::from_millis(200);
+    while Instant::now() < until {
+        let take_alert = match (alerts.is_ready(), work.is_ready()) {
+            (true, true) => !fair || rng.random_bool(0.5),
+            (alert, _) => alert,
+        };
+        if take_alert {
+            let _ = alerts.try_recv();
+            // Handling an alert: a busy 100 µs
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_micros(100) {}
+        } else if work.try_recv().is_ok() {
+            work_done += 1;
+        }
+    }
+    stop.store(true, Ordering::Relaxed);
+    flooder.join().unwrap();
+    work_done
+}
+
+fn main() {
+    println!("Serving alerts, work and heartbeats until the producer is done:");
+    serve();
+
+    // Taking alerts strictly first is fine while they are rare, as above; once they
+    // arrive faster than they are handled, the work channel never gets a turn
+    let strict = flood(false);
+    let fair = flood(true);
+    println!(
+        "\nUnder an alert flood, 200 ms got through {strict} work items with strict priority, {fair} choosing at random."
+    );
+    assert!(strict < fair);
+}
@@ -0,0 +1,154 @@
+// Colored terminal output with raw ANSI escape codes, no crates needed
+// An escape sequence starts with ESC (byte 27, written "\x1b") followed by "[",
+// some numbers separated by ";" and a final letter. "m" means "set graphics":
+//   \x1b[31m  red text        \x1b[1m  bold        \x1b[0m  back to normal
+// The terminal interprets these instead of printing them, so they cost nothing on
+// screen, but they do end up as garbage in a file if the output is redirected
+use std::cmp::Ordering;
+use std::env;
+use std::io::{self, IsTerminal};
+
+use rand::Rng;
+
+// The number is the SGR (Select Graphic Rendition) code for a foreground color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AnsiColor(u8);
+
+impl AnsiColor {
+    const RED: AnsiColor = AnsiColor(31);
+    const GREEN: AnsiColor = AnsiColor(32);
+    const YELLOW: AnsiColor = AnsiColor(33);
+    const BLUE: AnsiColor = AnsiColor(34);
+    const MAGENTA: AnsiColor = AnsiColor(35);
+    const CYAN: AnsiColor = AnsiColor(36);
+    const WHITE: AnsiColor = AnsiColor(37);
+    // 0 resets every attribute, colors and bold alike
+    const RESET: AnsiColor = AnsiColor(0);
+}
+
+fn colorize(text: &str, fg: AnsiColor, bold: bool) -> String {
+    // Several codes can share one sequence: "1;32" is bold and green
+    let codes = if bold {
+        format!("1;{}", fg.0)
+    } else {
+        fg.0.to_string()
+    };
+    format!("\x1b[{codes}m{text}\x1b[{}m", AnsiColor::RESET.0)
+}
+
+// Removes every "ESC [ ... letter" sequence and keeps everything else
+// Useful for measuring the visible width of a string or logging it to a file
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next();
+            // Parameters are digits and ';'; the sequence ends at the first letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// Colors only help a person looking at a terminal
+// - stdout piped into a file or another program: IsTerminal says no
+// - TERM=dumb: the terminal can't show them
+// - NO_COLOR set (https://no-color.org): the user asked for none
+fn is_terminal() -> bool {
+    io::stdout().is_terminal()
+        && env::var("TERM").map_or(true, |term| term != "dumb")
+        && env::var_os("NO_COLOR").is_none()
+}
+
+// Decide once, then every message goes through here
+fn paint(text: &str, fg: AnsiColor, bold: bool, enabled: bool) -> String {
+    if enabled {
+        colorize(text, fg, bold)
+    } else {
+        text.to_string()
+    }
+}
+
+fn main() {
+    assert_eq!(
+        strip_ansi(&colorize("hello", AnsiColor::RED, false)),
+        "hello"
+    );
+    assert_eq!(
+        colorize("win", AnsiColor::GREEN, true),
+        "\x1b[1;32mwin\x1b[0m"
+    );
+    assert_eq!(strip_ansi("plain text"), "plain text");
+    assert_eq!(strip_ansi("a\x1b[1;33mb\x1b[0mc"), "abc");
+
+    let colors = is_terminal();
+    let palette = [
+        ("red", AnsiColor::RED),
+        ("green", AnsiColor::GREEN),
+        ("yellow", AnsiColor::YELLOW),
+        ("blue", AnsiColor::BLUE),
+        ("magenta", AnsiColor::MAGENTA),
+        ("cyan", AnsiColor::CYAN),
+        ("white", AnsiColor::WHITE),
+    ];
+    let swatches: Vec<String> = palette
+        .iter()
+        .map(|&(name, color)| paint(name, color, false, colors))
+        .collect();
+    println!("Colors: {}", swatches.join(" "));
+
+    // The guessing game again, with colored feedback
+    println!("Guess the number!");
+    println!("Type 'quit' to exit.");
+    let secret_number = rand::rng().random_range(1..=100);
+
+    loop {
+        println!("Please input your guess:");
+        let mut guess = String::new();
+        // End of input (Ctrl-D, or a pipe that ran dry) also ends the game
+        if io::stdin()
+            .read_line(&mut guess)
+            .expect("Failed to read line")
+            == 0
+        {
+            break;
+        }
+        let guess = guess.trim();
+        if guess == "quit" {
+            println!("Goodbye!");
+            break;
+        }
+        let guess: u32 = match guess.parse() {
+            Ok(num) => num,
+            Err(_) => {
+                println!(
+                    "{}",
+                    paint(
+                        "Please type a number or 'quit'!",
+                        AnsiColor::YELLOW,
+                        false,
+                        colors
+                    )
+                );
+                continue;
+            }
+        };
+
+        println!("You guessed: {guess}");
+        match guess.cmp(&secret_number) {
+            Ordering::Less => println!("{}", paint("Too small!", AnsiColor::BLUE, false, colors)),
+            Ordering::Greater => println!("{}", paint("Too big!", AnsiColor::RED, false, colors)),
+            Ordering::Equal => {
+                println!("{}", paint("You win!", AnsiColor::GREEN, true, colors));
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+// The command pattern: every edit is an object that knows how to do itself
+// and how to undo itself, so undo/redo is just two stacks of those objects
+// Text positions here are byte offsets, and the example sticks to ASCII so
+// every byte offset is also a character boundary
+use std::fmt;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct AppState {
+    text: String,
+    cursor: usize,
+    clipboard: String,
+}
+
+impl fmt::Display for AppState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Show the cursor as | inside the text
+        let (before, after) = self.text.split_at(self.cursor);
+        write!(f, "\"{before}|{after}\"  clipboard: \"{}\"", self.clipboard)
+    }
+}
+
+// A command remembers whatever it needs to reverse itself, which is why
+// execute and undo take &mut self: executing may record what it destroyed
+trait Command {
+    fn execute(&mut self, state: &mut AppState);
+    fn undo(&mut self, state: &mut AppState);
+    fn describe(&self) -> String;
+}
+
+struct InsertText {
+    pos: usize,
+    text: String,
+}
+
+impl Command for InsertText {
+    fn execute(&mut self, state: &mut AppState) {
+        state.text.insert_str(self.pos, &self.text);
+        state.cursor = self.pos + self.text.len();
+    }
+
+    fn undo(&mut self, state: &mut AppState) {
+        state
+            .text
+            .replace_range(self.pos..self.pos + self.text.len(), "");
+        state.cursor = self.pos;
+    }
+
+    fn describe(&self) -> String {
+        format!("insert {:?} at {}", self.text, self.pos)
+    }
+}
+
+// `deleted` starts empty and is filled in by execute, so undo can put it back
+struct DeleteText {
+    pos: usize,
+    len: usize,
+    deleted: String,
+}
+
+impl DeleteText {
+    fn new(pos: usize, len: usize) -> DeleteText {
+        DeleteText {
+            pos,
+            len,
+            deleted: String::new(),
+        }
+    }
+}
+
+impl Command for DeleteText {
+    fn execute(&mut self, state: &mut AppState) {
+        self.deleted = state.text.drain(self.pos..self.pos + self.len).collect();
+        state.cursor = self.pos;
+    }
+
+    fn undo(&mut self, state: &mut AppState) {
+        state.text.insert_str(self.pos, &self.deleted);
+        state.cursor = self.pos + self.len;
+    }
+
+    fn describe(&self) -> String {
+        format!("delete {} bytes at {}", self.len, self.pos)
+    }
+}
+
+// Execute swaps `to` with the current cursor, so afterwards `to` holds the
+// old position and the same swap undoes it (and a second swap redoes it)
+struct MoveCursor {
+    to: usize,
+}
+
+impl Command for MoveCursor {
+    fn execute(&mut self, state: &mut AppState) {
+        std::mem::swap(&mut self.to, &mut state.cursor);
+    }
+
+    fn undo(&mut self, state: &mut AppState) {
+        std::mem::swap(&mut self.to, &mut state.cursor);
+    }
+
+    fn describe(&self) -> String {
+        format!("move cursor to {}", self.to)
+    }
+}
+
+// Copying doesn't change the text, but it does overwrite the clipboard
+// (naming a struct Copy hides the Copy trait in this file, which is fine
+// as long as nothing here needs to name the trait)
+struct Copy {
+    start: usize,
+    end: usize,
+    previous: String,
+}
+
+impl Copy {
+    fn new(start: usize, end: usize) -> Copy {
+        Copy {
+            start,
+            end,
+            previous: String::new(),
+        }
+    }
+}
+
+impl Command for Copy {
+    fn execute(&mut self, state: &mut AppState) {
+        let copied = state.text[self.start..self.end].to_string();
+        self.previous = std::mem::replace(&mut state.clipboard, copied);
+    }
+
+    fn undo(&mut self, state: &mut AppState) {
+        state.clipboard = std::mem::take(&mut self.previous);
+    }
+
+    fn describe(&self) -> String {
+        format!("copy {}..{}", self.start, self.end)
+    }
+}
+
+// The clipboard may have changed by the time this is undone, so remember
+// what was actually pasted
+struct Paste {
+    pos: usize,
+    pasted: String,
+}
+
+impl Paste {
+    fn new(pos: usize) -> Paste {
+        Paste {
+            pos,
+            pasted: String::new(),
+        }
+    }
+}
+
+impl Command for Paste {
+    fn execute(&mut self, state: &mut AppState) {
+        self.pasted = state.clipboard.clone();
+        state.text.insert_str(self.pos, &self.pasted);
+        state.cursor = self.pos + self.pasted.len();
+    }
+
+    fn undo(&mut self, state: &mut AppState) {
+        state
+            .text
+            .replace_range(self.pos..self.pos + self.pasted.len(), "");
+        state.cursor = self.pos;
+    }
+
+    fn describe(&self) -> String {
+        format!("paste at {}", self.pos)
+    }
+}
+
+// Box<dyn Command> lets one Vec hold every kind of command
+// Doing something new after an undo throws the redo stack away, the way
+// every editor behaves: the undone future no longer follows from the present
+#[derive(Default)]
+struct History {
+    done: Vec<Box<dyn Command>>,
+    undone: Vec<Box<dyn Command>>,
+}
+
+impl History {
+    fn do_command(&mut self, mut command: Box<dyn Command>, state: &mut AppState) {
+        command.execute(state);
+        self.done.push(command);
+        self.undone.clear();
+    }
+
+    // Both return false when there is nothing to undo or redo
+    fn undo(&mut self, state: &mut AppState) -> bool {
+        let Some(mut command) = self.done.pop() else {
+            return false;
+        };
+        command.undo(state);
+        self.undone.push(command);
+        true
+    }
+
+    fn redo(&mut self, state: &mut AppState) -> bool {
+        let Some(mut command) = self.undone.pop() else {
+            return false;
+        };
+        command.execute(state);
+        self.done.push(command);
+        true
+    }
+}
+
+fn insert(pos: usize, text: &str) -> Box<dyn Command> {
+    Box::new(InsertText {
+        pos,
+        text: text.to_string(),
+    })
+}
+
+fn state(text: &str, cursor: usize, clipboard: &str) -> AppState {
+    AppState {
+        text: text.to_string(),
+        cursor,
+        clipboard: clipboard.to_string(),
+    }
+}
+
+fn main() {
+    let mut app = AppState::default();
+    let mut history = History::default();
+
+    // 10 edits
+    let edits: Vec<Box<dyn Command>> = vec![
+        insert(0, "Hello"),
+        insert(5, " world"),
+        Box::new(MoveCursor { to: 0 }),
+        Box::new(Copy::new(0, 5)),
+        Box::new(Paste::new(11)),
+        insert(11, ", "),
+        Box::new(DeleteText::new(0, 6)),
+        insert(12, "!"),
+        Box::new(MoveCursor { to: 5 }),
+        Box::new(Copy::new(0, 5)),
+    ];
+    for command in edits {
+        let description = command.describe();
+        history.do_command(command, &mut app);
+        println!("{description:<28} -> {app}");
+    }
+    assert_eq!(app, state("world, Hello!", 5, "world"));
+
+    // 5 undos walk back through the last five edits
+    for _ in 0..5 {
+        assert!(history.undo(&mut app));
+        println!("{:<28} -> {app}", "undo");
+    }
+    assert_eq!(app, state("Hello worldHello", 11, "Hello"));
+
+    // 3 redos replay three of them
+    for _ in 0..3 {
+        assert!(history.redo(&mut app));
+        println!("{:<28} -> {app}", "redo");
+    }
+    assert_eq!(app, state("world, Hello!", 13, "Hello"));
+    assert_eq!((history.done.len(), history.undone.len()), (8, 2));
+
+    // A new edit discards the two commands still waiting to be redone
+    history.do_command(insert(0, "> "), &mut app);
+    println!("{:<28} -> {app}", "insert \"> \" at 0");
+    assert!(!history.redo(&mut app));
+
+    // Undoing everything gets back to the empty start
+    while history.undo(&mut app) {}
+    assert_eq!(app, AppState::default());
+    println!("{:<28} -> {app}", "undo all");
+}
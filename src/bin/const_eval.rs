@@ -0,0 +1,172 @@
+// const fn: functions the compiler can run while compiling
+// A `const` item's value is worked out at compile time and baked into the
+// binary, and the only functions it may call are const fns. Inside one, the
+// language is restricted: no heap, no trait methods (so no `for` loops, which
+// go through Iterator), no floating point in older compilers. while loops,
+// if/match, arrays and plain arithmetic are all fine
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// Iterative, in a while loop. Recursion is actually allowed in const fn as well,
+// but the naive recursive fibonacci makes ~2^n calls, and the compiler's const
+// evaluator is far slower than the compiled code, so FIB_TABLE would never finish
+const fn fibonacci(n: usize) -> u64 {
+    // fib(93) is the last one below u64::MAX; fib(94) would overflow
+    assert!(n < 94, "fibonacci(n) only fits a u64 for n < 94");
+    if n == 0 {
+        return 0;
+    }
+    // fib(i - 1) and fib(i); stopping at b = fib(n) rather than one step past it
+    // keeps fib(93) from computing fib(94) on the way, which would overflow
+    let (mut a, mut b) = (0u64, 1u64);
+    let mut i = 1;
+    while i < n {
+        let next = a + b;
+        a = b;
+        b = next;
+        i += 1;
+    }
+    b
+}
+
+// A whole table, filled in by the compiler: no code runs for it at startup
+const FIB_TABLE: [u64; 94] = {
+    let mut table = [0; 94];
+    let mut i = 0;
+    while i < 94 {
+        table[i] = fibonacci(i);
+        i += 1;
+    }
+    table
+};
+
+// A power of two has exactly one bit set, and n - 1 turns that bit off and
+// every bit below it on, so n & (n - 1) clears it: zero only for powers of two
+const fn is_power_of_two(n: u64) -> bool {
+    n != 0 && n & (n - 1) == 0
+}
+
+// The smallest power of two >= n: smear the highest set bit of n - 1 into every
+// bit below it, giving 2^k - 1, then add one
+const fn next_power_of_two(n: u64) -> u64 {
+    assert!(n <= 1 << 63, "the next power of two doesn't fit a u64");
+    if n <= 1 {
+        return 1;
+    }
+    let mut v = n - 1;
+    v |= v >> 1;
+    v |= v >> 2;
+    v |= v >> 4;
+    v |= v >> 8;
+    v |= v >> 16;
+    v |= v >> 32;
+    v + 1
+}
+
+// Popcount by hand: n & (n - 1) clears the lowest set bit, so count how many
+// times that can happen before n is zero (u64::count_ones compiles to one
+// instruction on most CPUs; this is the portable version)
+const fn count_ones(mut n: u64) -> u32 {
+    let mut count = 0;
+    while n != 0 {
+        n &= n - 1;
+        count += 1;
+    }
+    count
+}
+
+// Popcount of every byte, for counting a long bit string a byte at a time
+const LOOKUP: [u8; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        table[i] = count_ones(i as u64) as u8;
+        i += 1;
+    }
+    table
+};
+
+fn count_ones_by_table(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|&b| LOOKUP[b as usize] as u32).sum()
+}
+
+// Checked while compiling: a false one is a compile error, not a test failure
+// `const _` is a const with no name, there only to be evaluated
+const _: () = assert!(fibonacci(10) == 55);
+const _: () = assert!(FIB_TABLE[93] == 12_200_160_415_121_876_738);
+const _: () = assert!(is_power_of_two(1024) && !is_power_of_two(1000));
+const _: () = assert!(next_power_of_two(1000) == 1024);
+const _: () = assert!(count_ones(0xFF00_FF00) == 16);
+const _: () = assert!(LOOKUP[0b1011_0110] == 5);
+// The assert inside fibonacci fires at compile time too: uncommenting this gives
+// "error[E0080]: evaluation panicked: fibonacci(n) only fits a u64 for n < 94"
+// const _: () = assert!(fibonacci(94) > 0);
+
+// ---- const vs static ----
+// A const has no address of its own: every use is replaced by the value, as if
+// typed in by hand, so two uses can be two different copies
+// A static is one place in memory for the whole program run, with a fixed
+// address; that makes it the one to use for something that changes, like a
+// counter (through an atomic or a Mutex, since a static is shared by every thread)
+const GREETING: &str = "hello";
+static CALLS: AtomicU32 = AtomicU32::new(0);
+
+// Had CALLS been declared `const`, every use would be a fresh AtomicU32 and this
+// would return 1 every time (clippy warns about such consts for that reason)
+fn counted() -> u32 {
+    CALLS.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+fn main() {
+    println!(
+        "fib(10) = {}, fib(50) = {}, fib(93) = {}",
+        FIB_TABLE[10], FIB_TABLE[50], FIB_TABLE[93]
+    );
+    // The table agrees with the same function run at run time
+    for (n, &fib) in FIB_TABLE.iter().enumerate() {
+        assert_eq!(fib, fibonacci(n));
+    }
+    assert!(FIB_TABLE.windows(3).all(|w| w[0] + w[1] == w[2]));
+
+    // The bit tricks agree with the standard library's versions
+    for n in (0..5000u64).chain([1 << 40, (1 << 40) + 1, 1 << 63, u64::MAX]) {
+        assert_eq!(is_power_of_two(n), n.is_power_of_two(), "{n}");
+        if n <= 1 << 63 {
+            assert_eq!(next_power_of_two(n), n.next_power_of_two(), "{n}");
+        }
+        assert_eq!(count_ones(n), n.count_ones(), "{n}");
+    }
+    for (byte, &ones) in LOOKUP.iter().enumerate() {
+        assert_eq!(ones as u32, (byte as u8).count_ones());
+    }
+    let data = b"const evaluation";
+    let ones = count_ones_by_table(data);
+    assert_eq!(ones, data.iter().map(|b| b.count_ones()).sum::<u32>());
+    println!(
+        "{:?} has {ones} bits set",
+        std::str::from_utf8(data).unwrap()
+    );
+    println!(
+        "next power of two after 1000: {}; 4096 a power of two? {}",
+        next_power_of_two(1000),
+        is_power_of_two(4096)
+    );
+
+    // A const fn is still an ordinary function at run time, with values only known then
+    let n = std::env::args().count() + 19;
+    println!("fib({n}) computed at run time: {}", fibonacci(n));
+
+    // const: two uses may be two copies. static: always the same address
+    let a: *const u8 = GREETING.as_ptr();
+    let b: *const u8 = GREETING.as_ptr();
+    println!("A const &str's data may or may not be shared: {}", a == b);
+    let first: *const AtomicU32 = &CALLS;
+    let second: *const AtomicU32 = &CALLS;
+    assert_eq!(first, second);
+    counted();
+    counted();
+    assert_eq!(counted(), 3);
+    println!(
+        "The static counter was called {} times",
+        CALLS.load(Ordering::Relaxed)
+    );
+}
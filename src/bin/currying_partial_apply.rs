@@ -0,0 +1,175 @@
+// Currying and partial application
+// Currying turns a function of two arguments, f(a, b), into a function of one
+// that returns another: f(a)(b). Partial application fixes some arguments now
+// and takes the rest later. Both come for free in Haskell or OCaml; in Rust every
+// closure has its own unnameable type, which is where it gets interesting
+use std::rc::Rc;
+
+// The direct translation doesn't compile on stable Rust:
+//
+//     fn curry<A, B, C, F: Fn(A, B) -> C>(f: F) -> impl Fn(A) -> impl Fn(B) -> C {
+//         move |a| move |b| f(a, b)
+//     }
+//
+//     error[E0562]: `impl Trait` is not allowed in the return type of `Fn` trait bounds
+//
+// `impl Trait` may appear as a function's own return type, but not nested inside
+// a Fn bound's return type, which is written like one (rust-lang/rust#99697).
+// The inner closure's type has no name to write there instead, so one of two
+// things has to give: box the inner closure (below), or name the type by
+// writing it as a struct
+
+// ---- Workaround 1: a Box for the inner function ----
+
+// A boxed closure has a nameable type, dyn Fn(B) -> C. The cost is one
+// allocation per call of the outer function and a dynamic call for the inner one.
+// f sits in an Rc because every inner closure needs it, and they may outlive
+// the call that made them
+fn curry_boxed<A, B, C, F>(f: F) -> impl Fn(A) -> Box<dyn Fn(B) -> C>
+where
+    A: Clone + 'static,
+    B: 'static,
+    C: 'static,
+    F: Fn(A, B) -> C + 'static,
+{
+    let f = Rc::new(f);
+    move |a| {
+        let f = Rc::clone(&f);
+        Box::new(move |b| f(a.clone(), b))
+    }
+}
+
+// ---- Workaround 2: wrapper structs ----
+
+// Structs have names, so the return types can be spelled out and nothing is
+// boxed. Stable Rust doesn't let a struct implement Fn itself, so calling one is
+// .apply(x) rather than (x)
+struct Curry<F> {
+    f: Rc<F>,
+}
+
+// A curried function with its first argument filled in
+struct Curried<F, A> {
+    f: Rc<F>,
+    a: A,
+}
+
+fn curry<F>(f: F) -> Curry<F> {
+    Curry { f: Rc::new(f) }
+}
+
+impl<F> Curry<F> {
+    fn apply<A, B, C>(&self, a: A) -> Curried<F, A>
+    where
+        F: Fn(A, B) -> C,
+    {
+        Curried {
+            f: Rc::clone(&self.f),
+            a,
+        }
+    }
+}
+
+impl<F, A: Clone> Curried<F, A> {
+    // A clone of `a` for each call, since f takes it by value and this can be
+    // called any number of times
+    fn apply<B, C>(&self, b: B) -> C
+    where
+        F: Fn(A, B) -> C,
+    {
+        (self.f)(self.a.clone(), b)
+    }
+}
+
+// ---- Partial application and composition ----
+
+// Only one level of closure, so impl Fn is fine as the return type. `a` is
+// moved into the closure once and cloned for each call
+fn partial<A: Clone, B, C, F: Fn(A, B) -> C>(f: F, a: A) -> impl Fn(B) -> C {
+    move |b| f(a.clone(), b)
+}
+
+// g after f: the output of f is fed to g
+fn compose<A, B, C>(f: impl Fn(A) -> B, g: impl Fn(B) -> C) -> impl Fn(A) -> C {
+    move |a| g(f(a))
+}
+
+// Any number of steps, applied left to right. The steps have different closure
+// types, so they can only share a Vec as trait objects
+fn pipeline<T: 'static>(steps: Vec<Box<dyn Fn(T) -> T>>) -> impl Fn(T) -> T {
+    move |value| steps.iter().fold(value, |value, step| step(value))
+}
+
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+fn mul(a: i32, b: i32) -> i32 {
+    a * b
+}
+
+fn greet(greeting: String, name: &str) -> String {
+    format!("{greeting}, {name}!")
+}
+
+fn main() {
+    // Curried with boxes: add(2, 3) becomes add(2)(3)
+    let curried_add = curry_boxed(add);
+    let add_two = curried_add(2);
+    assert_eq!(add_two(3), 5);
+    assert_eq!(curried_add(10)(-4), 6);
+    println!("curry_boxed(add)(2)(3) = {}", add_two(3));
+
+    // Curried with structs: same answers, no allocation per call
+    let curried_mul = curry(mul);
+    let triple = curried_mul.apply(3);
+    assert_eq!(triple.apply(7), 21);
+    assert_eq!(curried_mul.apply(-1).apply(8), -8);
+    println!("curry(mul).apply(3).apply(7) = {}", triple.apply(7));
+
+    // The first argument can be anything Clone, here an owned String
+    let hello = curry(greet).apply("Hello".to_string());
+    assert_eq!(hello.apply("Ferris"), "Hello, Ferris!");
+    assert_eq!(hello.apply("world"), "Hello, world!");
+
+    // Partial application: fix the first argument now
+    let add5 = partial(add, 5);
+    let double = partial(mul, 2);
+    assert_eq!(add5(1), 6);
+    assert_eq!(double(21), 42);
+    let howdy = partial(greet, "Howdy".to_string());
+    assert_eq!(howdy("partner"), "Howdy, partner!");
+
+    // Composition: the order matters, (10 + 5) * 2 vs 10 * 2 + 5
+    let add_then_double = compose(add5, double);
+    let double_then_add = compose(partial(mul, 2), partial(add, 5));
+    assert_eq!(add_then_double(10), 30);
+    assert_eq!(double_then_add(10), 25);
+    println!(
+        "compose(add 5, double)(10) = {}, compose(double, add 5)(10) = {}",
+        add_then_double(10),
+        double_then_add(10)
+    );
+
+    // Composition changes types along the way: i32 -> i32 -> String
+    let describe = compose(add_then_double, |n: i32| {
+        format!("{n} is even: {}", n % 2 == 0)
+    });
+    assert_eq!(describe(1), "12 is even: true");
+
+    // A longer pipeline built from partial applications
+    let steps: Vec<Box<dyn Fn(i32) -> i32>> = vec![
+        Box::new(partial(add, 1)),
+        Box::new(partial(mul, 10)),
+        Box::new(partial(add, -3)),
+        Box::new(curry_boxed(mul)(2)),
+    ];
+    let run = pipeline(steps);
+    // ((4 + 1) * 10 - 3) * 2
+    assert_eq!(run(4), 94);
+    println!("pipeline [+1, *10, -3, *2] on 4 = {}", run(4));
+
+    // An empty pipeline is the identity function
+    let identity = pipeline::<i32>(Vec::new());
+    assert_eq!(identity(17), 17);
+}
@@ -0,0 +1,301 @@
+// Decoding UTF-8 by hand, byte by byte, without std::str::from_utf8
+// A character takes 1 to 4 bytes. The first byte says how many:
+//   0xxxxxxx                             1 byte,  U+0000..U+007F
+//   110xxxxx 10xxxxxx                    2 bytes, U+0080..U+07FF
+//   1110xxxx 10xxxxxx 10xxxxxx           3 bytes, U+0800..U+FFFF
+//   11110xxx 10xxxxxx 10xxxxxx 10xxxxxx  4 bytes, U+10000..U+10FFFF
+// and the x bits, read left to right, are the code point
+// Everything else is an error, including encodings that are merely
+// *possible* but not allowed: too long, surrogates, or past U+10FFFF
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ErrorKind {
+    // 10xxxxxx where a character should start
+    UnexpectedContinuation,
+    // 11111xxx: no valid character starts with this byte
+    InvalidLeadingByte,
+    // Fewer continuation bytes than the first byte promised
+    TruncatedSequence,
+    // A code point written with more bytes than it needs, like C0 AF for '/'
+    // Allowing these would give one character several spellings, which has
+    // been used to sneak "../" past filters that only looked for the short one
+    OverlongEncoding,
+    // U+D800..U+DFFF are reserved for UTF-16 surrogate pairs, not characters
+    Surrogate,
+    // Above U+10FFFF, the last code point Unicode will ever assign
+    OutOfRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Utf8Error {
+    // Where the bad sequence starts; everything before it decoded fine
+    byte_offset: usize,
+    kind: ErrorKind,
+}
+
+impl fmt::Display for Utf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} at byte {}", self.kind, self.byte_offset)
+    }
+}
+
+// What one step of decoding found at a position
+enum Step {
+    Char(char, usize),
+    Error(ErrorKind),
+    // The bytes so far are a valid start, but the input ended; the streaming
+    // decoder waits for more, the one-shot decoder reports it as truncated
+    Incomplete,
+}
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+fn decode_one(bytes: &[u8]) -> Step {
+    let lead = bytes[0];
+    // How many bytes, the payload bits of the first byte, and the smallest
+    // code point that genuinely needs this many bytes
+    let (len, bits, min) = match lead {
+        0x00..=0x7F => return Step::Char(lead as char, 1),
+        0x80..=0xBF => return Step::Error(ErrorKind::UnexpectedContinuation),
+        0xC0..=0xDF => (2, (lead & 0b0001_1111) as u32, 0x80),
+        0xE0..=0xEF => (3, (lead & 0b0000_1111) as u32, 0x800),
+        0xF0..=0xF7 => (4, (lead & 0b0000_0111) as u32, 0x10000),
+        0xF8..=0xFF => return Step::Error(ErrorKind::InvalidLeadingByte),
+    };
+    let mut code_point = bits;
+    for i in 1..len {
+        match bytes.get(i) {
+            None => return Step::Incomplete,
+            Some(&byte) if is_continuation(byte) => {
+                code_point = code_point << 6 | (byte & 0b0011_1111) as u32;
+            }
+            Some(_) => return Step::Error(ErrorKind::TruncatedSequence),
+        }
+    }
+    if code_point < min {
+        return Step::Error(ErrorKind::OverlongEncoding);
+    }
+    if (0xD800..=0xDFFF).contains(&code_point) {
+        return Step::Error(ErrorKind::Surrogate);
+    }
+    // char::from_u32 rejects exactly what's left: anything above U+10FFFF
+    match char::from_u32(code_point) {
+        Some(c) => Step::Char(c, len),
+        None => Step::Error(ErrorKind::OutOfRange),
+    }
+}
+
+fn decode_utf8(bytes: &[u8]) -> Result<Vec<char>, Utf8Error> {
+    let mut chars = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let kind = match decode_one(&bytes[offset..]) {
+            Step::Char(c, len) => {
+                chars.push(c);
+                offset += len;
+                continue;
+            }
+            Step::Error(kind) => kind,
+            Step::Incomplete => ErrorKind::TruncatedSequence,
+        };
+        return Err(Utf8Error {
+            byte_offset: offset,
+            kind,
+        });
+    }
+    Ok(chars)
+}
+
+// Streaming: bytes arrive in buffers (from a socket, a file read in blocks...)
+// and a character may be split across two of them. The decoder keeps the
+// unfinished start of a character, at most 3 bytes, until the next buffer
+#[derive(Debug, Default)]
+struct Decoder {
+    pending: Vec<u8>,
+    // Bytes consumed before `pending`, so errors report stream offsets
+    consumed: usize,
+}
+
+impl Decoder {
+    fn feed(&mut self, chunk: &[u8], out: &mut Vec<char>) -> Result<(), Utf8Error> {
+        // Only the carried-over bytes get copied; normally that's 0 to 3
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(chunk);
+        let mut offset = 0;
+        while offset < bytes.len() {
+            match decode_one(&bytes[offset..]) {
+                Step::Char(c, len) => {
+                    out.push(c);
+                    offset += len;
+                }
+                Step::Incomplete => {
+                    self.pending = bytes[offset..].to_vec();
+                    break;
+                }
+                Step::Error(kind) => {
+                    return Err(Utf8Error {
+                        byte_offset: self.consumed + offset,
+                        kind,
+                    });
+                }
+            }
+        }
+        self.consumed += offset;
+        Ok(())
+    }
+
+    // The stream is over: a character still waiting for bytes never got them
+    fn finish(self) -> Result<(), Utf8Error> {
+        if self.pending.is_empty() {
+            Ok(())
+        } else {
+            Err(Utf8Error {
+                byte_offset: self.consumed,
+                kind: ErrorKind::TruncatedSequence,
+            })
+        }
+    }
+}
+
+fn decode_in_chunks(bytes: &[u8], sizes: &[usize]) -> Result<Vec<char>, Utf8Error> {
+    let mut decoder = Decoder::default();
+    let mut out = Vec::new();
+    let mut rest = bytes;
+    for &size in sizes.iter().cycle() {
+        if rest.is_empty() {
+            break;
+        }
+        let (chunk, tail) = rest.split_at(size.min(rest.len()));
+        decoder.feed(chunk, &mut out)?;
+        rest = tail;
+    }
+    decoder.finish()?;
+    Ok(out)
+}
+
+// Our answer agrees with std when both accept with the same characters, or
+// both reject at the same offset (std calls it valid_up_to)
+fn agrees_with_std(bytes: &[u8]) -> bool {
+    match (decode_utf8(bytes), std::str::from_utf8(bytes)) {
+        (Ok(chars), Ok(s)) => chars.iter().copied().eq(s.chars()),
+        (Err(ours), Err(theirs)) => ours.byte_offset == theirs.valid_up_to(),
+        _ => false,
+    }
+}
+
+// Random bytes alone are almost always invalid within a byte or two, so most
+// inputs are built from real characters with the occasional corrupted byte
+fn random_bytes(rng: &mut StdRng) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for _ in 0..rng.random_range(0..12) {
+        match rng.random_range(0..10) {
+            0 => bytes.push(rng.random()),
+            1 => bytes.extend_from_slice(&[0xED, rng.random_range(0x80..=0xBF), 0x80]),
+            _ => {
+                let c: char = rng.random();
+                bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+        }
+    }
+    if !bytes.is_empty() && rng.random_bool(0.1) {
+        bytes.pop(); // cut the last character short, sometimes
+    }
+    bytes
+}
+
+fn main() {
+    use ErrorKind::*;
+
+    // 1. Well-formed sequences at the edges of every length (Unicode Table 3-7)
+    let valid: [(&[u8], char); 10] = [
+        (&[0x00], '\u{0}'),
+        (&[0x7F], '\u{7F}'),
+        (&[0xC2, 0x80], '\u{80}'),
+        (&[0xDF, 0xBF], '\u{7FF}'),
+        (&[0xE0, 0xA0, 0x80], '\u{800}'),
+        (&[0xED, 0x9F, 0xBF], '\u{D7FF}'),
+        (&[0xEE, 0x80, 0x80], '\u{E000}'),
+        (&[0xEF, 0xBF, 0xBF], '\u{FFFF}'),
+        (&[0xF0, 0x90, 0x80, 0x80], '\u{10000}'),
+        (&[0xF4, 0x8F, 0xBF, 0xBF], '\u{10FFFF}'),
+    ];
+    for (bytes, c) in valid {
+        assert_eq!(decode_utf8(bytes), Ok(vec![c]), "{bytes:02X?}");
+        assert!(agrees_with_std(bytes));
+    }
+    let text = "héllo, 世界 🦀";
+    let decoded = decode_utf8(text.as_bytes()).unwrap();
+    println!("{:?} -> {} chars", text, decoded.len());
+    assert_eq!(decoded, text.chars().collect::<Vec<char>>());
+
+    // 2. Ill-formed sequences, in the spirit of the Unicode conformance cases
+    let invalid: [(&[u8], usize, ErrorKind); 16] = [
+        (&[0x80], 0, UnexpectedContinuation),
+        (&[b'a', 0xBF, b'b'], 1, UnexpectedContinuation),
+        (&[0xF8, 0x88, 0x80, 0x80, 0x80], 0, InvalidLeadingByte),
+        (&[0xFE], 0, InvalidLeadingByte),
+        (&[0xFF], 0, InvalidLeadingByte),
+        (&[0xC2], 0, TruncatedSequence),
+        (&[0xC2, b'A'], 0, TruncatedSequence),
+        (&[b'A', 0xE1, 0x80], 1, TruncatedSequence),
+        (&[0xF0, 0x9F, 0xA6], 0, TruncatedSequence),
+        (&[0xC0, 0xAF], 0, OverlongEncoding),
+        (&[0xE0, 0x80, 0xAF], 0, OverlongEncoding),
+        (&[0xF0, 0x80, 0x80, 0xAF], 0, OverlongEncoding),
+        (&[0xED, 0xA0, 0x80], 0, Surrogate),
+        (&[0xED, 0xBF, 0xBF], 0, Surrogate),
+        (&[0xF4, 0x90, 0x80, 0x80], 0, OutOfRange),
+        (&[0xF7, 0xBF, 0xBF, 0xBF], 0, OutOfRange),
+    ];
+    for (bytes, byte_offset, kind) in invalid {
+        let expected = Utf8Error { byte_offset, kind };
+        assert_eq!(decode_utf8(bytes), Err(expected), "{bytes:02X?}");
+        assert!(agrees_with_std(bytes), "{bytes:02X?}");
+        println!("{:<28} {expected}", format!("{bytes:02X?}"));
+    }
+
+    // 3. Agreement with std on 10 000 random inputs
+    let mut rng = StdRng::seed_from_u64(127);
+    let mut rejected = 0;
+    for _ in 0..10_000 {
+        let bytes = random_bytes(&mut rng);
+        assert!(agrees_with_std(&bytes), "disagree on {bytes:02X?}");
+        rejected += decode_utf8(&bytes).is_err() as u32;
+    }
+    println!("Agreed with std::str::from_utf8 on 10000 inputs ({rejected} invalid).");
+
+    // 4. Streaming: splitting the input anywhere, even inside a character,
+    // gives the same result as decoding it in one go
+    let crab = "🦀 crab, ü".as_bytes();
+    for size in 1..=crab.len() {
+        assert_eq!(decode_in_chunks(crab, &[size]), decode_utf8(crab));
+    }
+    for _ in 0..1_000 {
+        let bytes = random_bytes(&mut rng);
+        let sizes: Vec<usize> = (0..3).map(|_| rng.random_range(1..=5)).collect();
+        assert_eq!(
+            decode_in_chunks(&bytes, &sizes),
+            decode_utf8(&bytes),
+            "{bytes:02X?} in chunks of {sizes:?}"
+        );
+    }
+    // A stream that ends halfway through a character
+    let mut decoder = Decoder::default();
+    let mut out = Vec::new();
+    decoder.feed(&[b'o', b'k', 0xF0, 0x9F], &mut out).unwrap();
+    assert_eq!(out, ['o', 'k']);
+    assert_eq!(
+        decoder.finish(),
+        Err(Utf8Error {
+            byte_offset: 2,
+            kind: TruncatedSequence
+        })
+    );
+    println!("Streaming decoder matches for every chunk size.");
+}
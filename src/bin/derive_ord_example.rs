@@ -0,0 +1,203 @@
+// Ordering values: deriving PartialOrd/Ord, and writing them by hand when the
+// derived order is wrong
+use std::cmp::Ordering;
+
+// Semantic versions: 1.2.3, or 1.2.3-beta for a pre-release
+// The derived order would compare `pre` as an Option, where None < Some(_),
+// which would put 1.0.0 before 1.0.0-alpha. Semver says the opposite
+#[derive(Debug, Clone)]
+struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+    pre: Option<String>,
+}
+
+impl Version {
+    fn new(major: u32, minor: u32, patch: u32) -> Version {
+        Version {
+            major,
+            minor,
+            patch,
+            pre: None,
+        }
+    }
+
+    fn pre(major: u32, minor: u32, patch: u32, pre: &str) -> Version {
+        Version {
+            pre: Some(pre.to_string()),
+            ..Version::new(major, minor, patch)
+        }
+    }
+}
+
+// Eq and Ord must agree: a == b exactly when a.cmp(b) == Equal
+// Defining eq through cmp guarantees it
+impl PartialEq for Version {
+    fn eq(&self, other: &Version) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        // Tuples compare field by field, which is exactly the order we want here
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            // then_with only runs when the numbers are equal
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                // A pre-release comes before the release it leads up to
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+// PartialOrd is for types where some pairs can't be compared (like f64 and NaN)
+// Ours always can, so it just forwards to Ord
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Without `pre`, the derived order is what we want: fields are compared top to
+// bottom, in the order they are declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Plain {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+fn manual_cmp(a: &Plain, b: &Plain) -> Ordering {
+    a.major
+        .cmp(&b.major)
+        .then(a.minor.cmp(&b.minor))
+        .then(a.patch.cmp(&b.patch))
+}
+
+// A release with a name that takes no part in the ordering, so two equal
+// versions can still be told apart after sorting
+#[derive(Debug)]
+struct Release {
+    version: Version,
+    name: &'static str,
+}
+
+impl PartialEq for Release {
+    fn eq(&self, other: &Release) -> bool {
+        self.version == other.version
+    }
+}
+
+impl Eq for Release {}
+
+impl PartialOrd for Release {
+    fn partial_cmp(&self, other: &Release) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Release {
+    fn cmp(&self, other: &Release) -> Ordering {
+        self.version.cmp(&other.version)
+    }
+}
+
+fn main() {
+    let mut versions = [
+        Version::new(1, 0, 0),
+        Version::pre(1, 0, 0, "beta"),
+        Version::new(0, 9, 12),
+        Version::new(1, 10, 0),
+        Version::pre(1, 0, 0, "alpha"),
+        Version::new(1, 2, 0),
+        Version::new(0, 10, 0),
+    ];
+    versions.sort();
+    for v in &versions {
+        match &v.pre {
+            Some(pre) => println!("{}.{}.{}-{pre}", v.major, v.minor, v.patch),
+            None => println!("{}.{}.{}", v.major, v.minor, v.patch),
+        }
+    }
+    assert_eq!(
+        versions,
+        [
+            Version::new(0, 9, 12),
+            // Numbers compare as numbers, so 0.10.0 comes after 0.9.12
+            Version::new(0, 10, 0),
+            Version::pre(1, 0, 0, "alpha"),
+            Version::pre(1, 0, 0, "beta"),
+            Version::new(1, 0, 0),
+            Version::new(1, 2, 0),
+            Version::new(1, 10, 0),
+        ]
+    );
+    assert!(Version::pre(1, 0, 0, "alpha") < Version::new(1, 0, 0));
+    assert!(Version::pre(1, 0, 0, "rc") > Version::pre(1, 0, 0, "beta"));
+
+    // The derived Ord on Plain agrees with comparing the fields by hand, for every pair
+    let plain = [
+        Plain {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        },
+        Plain {
+            major: 0,
+            minor: 9,
+            patch: 12,
+        },
+        Plain {
+            major: 1,
+            minor: 10,
+            patch: 0,
+        },
+        Plain {
+            major: 1,
+            minor: 2,
+            patch: 3,
+        },
+        Plain {
+            major: 1,
+            minor: 2,
+            patch: 3,
+        },
+    ];
+    for a in &plain {
+        for b in &plain {
+            assert_eq!(a.cmp(b), manual_cmp(a, b));
+        }
+    }
+    println!(
+        "derived Ord matches the manual comparison on all {} pairs",
+        plain.len() * plain.len()
+    );
+
+    // sort() is stable: equal elements keep the order they were in
+    // (sort_unstable() is a little faster but makes no such promise)
+    let mut releases = [
+        Release {
+            version: Version::new(2, 0, 0),
+            name: "first 2.0.0",
+        },
+        Release {
+            version: Version::new(1, 0, 0),
+            name: "1.0.0",
+        },
+        Release {
+            version: Version::new(2, 0, 0),
+            name: "second 2.0.0",
+        },
+    ];
+    releases.sort();
+    let names: Vec<&str> = releases.iter().map(|r| r.name).collect();
+    println!("sorted releases: {names:?}");
+    assert_eq!(names, ["1.0.0", "first 2.0.0", "second 2.0.0"]);
+}
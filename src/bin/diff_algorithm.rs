@@ -0,0 +1,250 @@
+// The Myers diff algorithm, the one behind `git diff`
+// Eugene Myers, "An O(ND) Difference Algorithm and Its Variations" (1986)
+//
+// Turning `a` into `b` is a walk through an "edit graph" from the top-left
+// corner (0, 0) to the bottom-right corner (a.len(), b.len()):
+//   - a step right (x + 1) deletes a[x]
+//   - a step down (y + 1) inserts b[y]
+//   - a diagonal step (both + 1) keeps a line, and is only allowed where a[x] == b[y]
+// Diagonals are free, so the shortest edit script is the path with the fewest
+// right/down steps. Myers finds it by trying 0 edits, then 1, then 2...
+// and for each number of edits d remembering only how far along each diagonal
+// k = x - y the best path so far has reached
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq, Eq)]
+enum DiffOp<'a, T> {
+    Keep(&'a T),
+    Insert(&'a T),
+    Delete(&'a T),
+}
+
+// The forward pass. v[k] is the furthest x reached on diagonal k; a copy of v is
+// saved before every round so the path can be walked back afterwards
+// k runs from -max to max, so every index is shifted by `offset`
+fn shortest_edit<T: Eq>(a: &[T], b: &[T]) -> Vec<Vec<usize>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    let offset = max;
+    let idx = |k: isize| (k + offset) as usize;
+    let mut v = vec![0usize; 2 * max as usize + 2];
+    let mut trace = Vec::new();
+
+    for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            // Arrive on diagonal k either by stepping down from k + 1 (an insert)
+            // or right from k - 1 (a delete), whichever got further
+            let mut x = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+                v[idx(k + 1)] as isize
+            } else {
+                v[idx(k - 1)] as isize + 1
+            };
+            let mut y = x - k;
+            // Then slide down the diagonal for free while the lines match (the "snake")
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx(k)] = x as usize;
+            if x >= n && y >= m {
+                return trace;
+            }
+        }
+    }
+    unreachable!("max = n + m edits always reach the end")
+}
+
+fn diff<'a, T: Eq>(a: &'a [T], b: &'a [T]) -> Vec<DiffOp<'a, T>> {
+    let trace = shortest_edit(a, b);
+    let offset = (a.len() + b.len()) as isize;
+    let idx = |k: isize| (k + offset) as usize;
+
+    // Backtrack from the end: for each d, work out which diagonal the path came
+    // from, emit the snake (Keeps) and then the one edit that led onto it
+    let (mut x, mut y) = (a.len() as isize, b.len() as isize);
+    let mut ops = Vec::new();
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        // The same choice the forward pass made
+        let prev_k = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[idx(prev_k)] as isize;
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push(DiffOp::Keep(&a[x as usize - 1]));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                ops.push(DiffOp::Insert(&b[y as usize - 1]));
+            } else {
+                ops.push(DiffOp::Delete(&a[x as usize - 1]));
+            }
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    ops.reverse();
+    ops
+}
+
+const CONTEXT: usize = 3;
+
+// A unified diff like `git diff` prints: hunks of changed lines, each with up to
+// CONTEXT unchanged lines around it and a "@@ -old +new @@" header
+fn format_diff(old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = diff(&a, &b);
+
+    // Where each op sits in the old and new files, counted from 0
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for op in &ops {
+        positions.push((old_line, new_line));
+        match op {
+            DiffOp::Keep(_) => (old_line, new_line) = (old_line + 1, new_line + 1),
+            DiffOp::Delete(_) => old_line += 1,
+            DiffOp::Insert(_) => new_line += 1,
+        }
+    }
+
+    // An op is shown if a change is within CONTEXT ops of it
+    let changes: Vec<usize> = (0..ops.len())
+        .filter(|&i| !matches!(ops[i], DiffOp::Keep(_)))
+        .collect();
+    let shown = |i: usize| changes.iter().any(|&c| c.abs_diff(i) <= CONTEXT);
+
+    let mut out = String::new();
+    let mut i = 0;
+    while i < ops.len() {
+        if !shown(i) {
+            i += 1;
+            continue;
+        }
+        // A hunk is a run of shown ops; two changes closer than 2 * CONTEXT share one
+        let start = i;
+        while i < ops.len() && shown(i) {
+            i += 1;
+        }
+        let hunk = &ops[start..i];
+        let old_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Insert(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Delete(_)))
+            .count();
+        // Line numbers start at 1; an empty side names the line before the hunk
+        let (old_start, new_start) = positions[start];
+        let first = |start: usize, count: usize| if count == 0 { start } else { start + 1 };
+        out.push_str(&format!(
+            "@@ -{},{old_count} +{},{new_count} @@\n",
+            first(old_start, old_count),
+            first(new_start, new_count)
+        ));
+        for op in hunk {
+            let (sign, line) = match op {
+                DiffOp::Keep(line) => (' ', line),
+                DiffOp::Delete(line) => ('-', line),
+                DiffOp::Insert(line) => ('+', line),
+            };
+            out.push_str(&format!("{sign}{line}\n"));
+        }
+    }
+    out
+}
+
+// The edit script as a compact string, e.g. "-A -B C +B"
+fn script<T: Debug>(ops: &[DiffOp<T>]) -> String {
+    let words: Vec<String> = ops
+        .iter()
+        .map(|op| match op {
+            DiffOp::Keep(x) => format!("{x:?}"),
+            DiffOp::Delete(x) => format!("-{x:?}"),
+            DiffOp::Insert(x) => format!("+{x:?}"),
+        })
+        .collect();
+    words.join(" ")
+}
+
+fn main() {
+    // Identical inputs: nothing but Keeps
+    let same = ["a", "b", "c"];
+    assert!(
+        diff(&same, &same)
+            .iter()
+            .all(|op| matches!(op, DiffOp::Keep(_)))
+    );
+    assert_eq!(format_diff("a\nb\nc\n", "a\nb\nc\n"), "");
+
+    // From nothing: everything is inserted
+    let lines = [1, 2, 3];
+    assert_eq!(
+        diff(&[], &lines),
+        [DiffOp::Insert(&1), DiffOp::Insert(&2), DiffOp::Insert(&3)]
+    );
+    assert_eq!(
+        diff(&lines, &[]),
+        [DiffOp::Delete(&1), DiffOp::Delete(&2), DiffOp::Delete(&3)]
+    );
+    assert!(diff::<u8>(&[], &[]).is_empty());
+
+    // The example from the paper: ABCABBA -> CBABAC takes 5 edits
+    let a: Vec<char> = "ABCABBA".chars().collect();
+    let b: Vec<char> = "CBABAC".chars().collect();
+    let ops = diff(&a, &b);
+    let edits = ops
+        .iter()
+        .filter(|op| !matches!(op, DiffOp::Keep(_)))
+        .count();
+    assert_eq!(edits, 5);
+    assert_eq!(script(&ops), "-'A' -'B' 'C' +'B' 'A' 'B' -'B' 'A' +'C'");
+    println!("ABCABBA -> CBABAC: {}", script(&ops));
+
+    // A 5-line file with two lines changed
+    let old = "fn main() {\n    let x = 1;\n    let y = 2;\n    println!(\"{x}\");\n}\n";
+    let new = "fn main() {\n    let x = 10;\n    let y = 2;\n    println!(\"{x} {y}\");\n}\n";
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    assert_eq!(
+        diff(&a, &b),
+        [
+            DiffOp::Keep(&"fn main() {"),
+            DiffOp::Delete(&"    let x = 1;"),
+            DiffOp::Insert(&"    let x = 10;"),
+            DiffOp::Keep(&"    let y = 2;"),
+            DiffOp::Delete(&"    println!(\"{x}\");"),
+            DiffOp::Insert(&"    println!(\"{x} {y}\");"),
+            DiffOp::Keep(&"}"),
+        ]
+    );
+    let unified = format_diff(old, new);
+    print!("{unified}");
+    assert_eq!(
+        unified,
+        "@@ -1,5 +1,5 @@\n fn main() {\n-    let x = 1;\n+    let x = 10;\n     let y = 2;\n-    println!(\"{x}\");\n+    println!(\"{x} {y}\");\n }\n"
+    );
+
+    // Changes far apart get separate hunks, each with 3 lines of context
+    let old: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+    let new = old
+        .replace("line 2\n", "line two\n")
+        .replace("line 18\n", "");
+    let unified = format_diff(&old, &new);
+    print!("{unified}");
+    assert_eq!(
+        unified
+            .lines()
+            .filter(|l| l.starts_with("@@"))
+            .collect::<Vec<_>>(),
+        ["@@ -1,5 +1,5 @@", "@@ -15,6 +15,5 @@"]
+    );
+    assert_eq!(format_diff("", "x\n"), "@@ -0,0 +1,1 @@\n+x\n");
+}
@@ -0,0 +1,394 @@
+// Byte order: how a number bigger than one byte is laid out in memory or on the wire
+// 0x12345678 is the bytes 78 56 34 12 little-endian (x86, ARM, most CPUs today)
+// and 12 34 56 78 big-endian ("network byte order", used by most internet protocols)
+// A binary format has to pick one and say so, or two machines will disagree about
+// what its numbers are
+use std::fmt;
+
+// ---- Doing it by hand ----
+
+// Little-endian puts the lowest byte first: shift each byte down into place
+fn encode_u16_le(v: u16) -> [u8; 2] {
+    [v as u8, (v >> 8) as u8]
+}
+
+fn decode_u16_le(b: [u8; 2]) -> u16 {
+    b[0] as u16 | (b[1] as u16) << 8
+}
+
+// Big-endian is the same bytes in the opposite order
+fn encode_u16_be(v: u16) -> [u8; 2] {
+    [(v >> 8) as u8, v as u8]
+}
+
+fn decode_u16_be(b: [u8; 2]) -> u16 {
+    (b[0] as u16) << 8 | b[1] as u16
+}
+
+// The wider types follow the same pattern with more bytes, so a macro writes them
+// Byte i of a little-endian number holds bits 8*i to 8*i+7; big-endian counts
+// from the other end. Signed numbers go through the unsigned type of the same
+// size: `as` between them keeps the bits and only changes how they are read
+macro_rules! byte_order {
+    ($unsigned:ty, $signed:ty, $bytes:literal,
+     $enc_le:ident, $dec_le:ident, $enc_be:ident, $dec_be:ident,
+     $enc_le_signed:ident, $dec_le_signed:ident, $enc_be_signed:ident, $dec_be_signed:ident) => {
+        fn $enc_le(v: $unsigned) -> [u8; $bytes] {
+            let mut b = [0; $bytes];
+            for (i, byte) in b.iter_mut().enumerate() {
+                *byte = (v >> (8 * i)) as u8;
+            }
+            b
+        }
+
+        fn $dec_le(b: [u8; $bytes]) -> $unsigned {
+            b.iter()
+                .enumerate()
+                .fold(0, |v, (i, &byte)| v | (byte as $unsigned) << (8 * i))
+        }
+
+        fn $enc_be(v: $unsigned) -> [u8; $bytes] {
+            let mut b = $enc_le(v);
+            b.reverse();
+            b
+        }
+
+        fn $dec_be(mut b: [u8; $bytes]) -> $unsigned {
+            b.reverse();
+            $dec_le(b)
+        }
+
+        fn $enc_le_signed(v: $signed) -> [u8; $bytes] {
+            $enc_le(v as $unsigned)
+        }
+
+        fn $dec_le_signed(b: [u8; $bytes]) -> $signed {
+            $dec_le(b) as $signed
+        }
+
+        fn $enc_be_signed(v: $signed) -> [u8; $bytes] {
+            $enc_be(v as $unsigned)
+        }
+
+        fn $dec_be_signed(b: [u8; $bytes]) -> $signed {
+            $dec_be(b) as $signed
+        }
+    };
+}
+
+// i16 by the same route as u16
+fn encode_i16_le(v: i16) -> [u8; 2] {
+    encode_u16_le(v as u16)
+}
+
+fn decode_i16_le(b: [u8; 2]) -> i16 {
+    decode_u16_le(b) as i16
+}
+
+fn encode_i16_be(v: i16) -> [u8; 2] {
+    encode_u16_be(v as u16)
+}
+
+fn decode_i16_be(b: [u8; 2]) -> i16 {
+    decode_u16_be(b) as i16
+}
+
+byte_order!(
+    u32,
+    i32,
+    4,
+    encode_u32_le,
+    decode_u32_le,
+    encode_u32_be,
+    decode_u32_be,
+    encode_i32_le,
+    decode_i32_le,
+    encode_i32_be,
+    decode_i32_be
+);
+byte_order!(
+    u64,
+    i64,
+    8,
+    encode_u64_le,
+    decode_u64_le,
+    encode_u64_be,
+    decode_u64_be,
+    encode_i64_le,
+    decode_i64_le,
+    encode_i64_be,
+    decode_i64_be
+);
+
+// ---- A binary record format ----
+// All numbers little-endian:
+//   magic    4 bytes  b"RECS"
+//   version  2 bytes
+//   count    4 bytes
+//   then `count` records of:
+//     id      8 bytes
+//     length  4 bytes  (of the name, in bytes)
+//     name    `length` bytes of UTF-8
+
+const MAGIC: [u8; 4] = *b"RECS";
+const VERSION: u16 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    id: u64,
+    name: String,
+}
+
+// Appends to a growing buffer; writing to memory can't fail
+#[derive(Debug, Default)]
+struct BinaryWriter {
+    buf: Vec<u8>,
+}
+
+impl BinaryWriter {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.bytes(&encode_u16_le(v));
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.bytes(&encode_u32_le(v));
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.bytes(&encode_u64_le(v));
+    }
+
+    fn write_file(records: &[Record]) -> Vec<u8> {
+        let mut writer = BinaryWriter::default();
+        writer.bytes(&MAGIC);
+        writer.u16(VERSION);
+        writer.u32(records.len() as u32);
+        for record in records {
+            writer.u64(record.id);
+            writer.u32(record.name.len() as u32);
+            writer.bytes(record.name.as_bytes());
+        }
+        writer.buf
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ReadError {
+    // Wanted this many more bytes at this offset
+    UnexpectedEnd { pos: usize, wanted: usize },
+    BadMagic([u8; 4]),
+    UnsupportedVersion(u16),
+    BadName { pos: usize },
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::UnexpectedEnd { pos, wanted } => {
+                write!(f, "needed {wanted} more bytes at offset {pos}")
+            }
+            ReadError::BadMagic(magic) => write!(f, "not a record file (magic {magic:02x?})"),
+            ReadError::UnsupportedVersion(v) => write!(f, "unsupported version {v}"),
+            ReadError::BadName { pos } => write!(f, "the name at offset {pos} isn't UTF-8"),
+            ReadError::TrailingBytes(n) => write!(f, "{n} bytes left over after the last record"),
+        }
+    }
+}
+
+// Reads from a borrowed slice, keeping a cursor; every read checks there is
+// enough left, so a truncated file is an error, never a panic
+struct BinaryReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BinaryReader<'a> {
+    fn new(data: &'a [u8]) -> BinaryReader<'a> {
+        BinaryReader { data, pos: 0 }
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], ReadError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.data.len())
+            .ok_or(ReadError::UnexpectedEnd {
+                pos: self.pos,
+                wanted: n,
+            })?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    // try_into turns the slice into a fixed-size array; bytes(N) returned exactly N
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ReadError> {
+        Ok(self.bytes(N)?.try_into().expect("bytes(N) returns N bytes"))
+    }
+
+    fn u16(&mut self) -> Result<u16, ReadError> {
+        Ok(decode_u16_le(self.array()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, ReadError> {
+        Ok(decode_u32_le(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, ReadError> {
+        Ok(decode_u64_le(self.array()?))
+    }
+
+    fn read_file(data: &[u8]) -> Result<Vec<Record>, ReadError> {
+        let mut reader = BinaryReader::new(data);
+        let magic = reader.array()?;
+        if magic != MAGIC {
+            return Err(ReadError::BadMagic(magic));
+        }
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(ReadError::UnsupportedVersion(version));
+        }
+        let count = reader.u32()?;
+        // Not Vec::with_capacity(count): a corrupt count could ask for gigabytes
+        let mut records = Vec::new();
+        for _ in 0..count {
+            let id = reader.u64()?;
+            let length = reader.u32()? as usize;
+            let pos = reader.pos;
+            let name = std::str::from_utf8(reader.bytes(length)?)
+                .map_err(|_| ReadError::BadName { pos })?
+                .to_string();
+            records.push(Record { id, name });
+        }
+        match data.len() - reader.pos {
+            0 => Ok(records),
+            left => Err(ReadError::TrailingBytes(left)),
+        }
+    }
+}
+
+fn main() {
+    // 1. The layouts themselves
+    assert_eq!(encode_u16_le(0x1234), [0x34, 0x12]);
+    assert_eq!(encode_u16_be(0x1234), [0x12, 0x34]);
+    assert_eq!(encode_u32_le(0x12345678), [0x78, 0x56, 0x34, 0x12]);
+    assert_eq!(encode_u32_be(0x12345678), [0x12, 0x34, 0x56, 0x78]);
+    // -2 is all ones except the lowest bit, in either order
+    assert_eq!(encode_i32_le(-2), [0xfe, 0xff, 0xff, 0xff]);
+    assert_eq!(encode_i32_be(-2), [0xff, 0xff, 0xff, 0xfe]);
+    println!(
+        "0x12345678 little-endian: {:02x?}, big-endian: {:02x?}",
+        encode_u32_le(0x12345678),
+        encode_u32_be(0x12345678)
+    );
+    // The CPU this runs on has an order of its own, which to_ne_bytes ("native") uses
+    let native = if 1u16.to_ne_bytes() == [1, 0] {
+        "little"
+    } else {
+        "big"
+    };
+    println!("This machine is {native}-endian.");
+
+    // 2. The hand-written versions agree with the standard library's to_le_bytes and friends
+    // wrapping_mul by a large odd number spreads the bits across every byte
+    for i in 0..1000u64 {
+        let v = i.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let (v16, v32) = (v as u16, v as u32);
+        assert_eq!(encode_u16_le(v16), v16.to_le_bytes());
+        assert_eq!(encode_u16_be(v16), v16.to_be_bytes());
+        assert_eq!(decode_u16_le(v16.to_le_bytes()), v16);
+        assert_eq!(decode_u16_be(v16.to_be_bytes()), v16);
+        assert_eq!(encode_u32_le(v32), v32.to_le_bytes());
+        assert_eq!(encode_u32_be(v32), v32.to_be_bytes());
+        assert_eq!(decode_u32_le(v32.to_le_bytes()), v32);
+        assert_eq!(decode_u32_be(v32.to_be_bytes()), v32);
+        assert_eq!(encode_u64_le(v), v.to_le_bytes());
+        assert_eq!(encode_u64_be(v), v.to_be_bytes());
+        assert_eq!(decode_u64_le(v.to_le_bytes()), v);
+        assert_eq!(decode_u64_be(v.to_be_bytes()), v);
+
+        let (s16, s32, s64) = (v as i16, v as i32, v as i64);
+        assert_eq!(encode_i16_le(s16), s16.to_le_bytes());
+        assert_eq!(encode_i16_be(s16), s16.to_be_bytes());
+        assert_eq!(decode_i16_le(s16.to_le_bytes()), s16);
+        assert_eq!(decode_i16_be(s16.to_be_bytes()), s16);
+        assert_eq!(encode_i32_le(s32), s32.to_le_bytes());
+        assert_eq!(encode_i32_be(s32), s32.to_be_bytes());
+        assert_eq!(decode_i32_le(s32.to_le_bytes()), s32);
+        assert_eq!(decode_i32_be(s32.to_be_bytes()), s32);
+        assert_eq!(encode_i64_le(s64), s64.to_le_bytes());
+        assert_eq!(encode_i64_be(s64), s64.to_be_bytes());
+        assert_eq!(decode_i64_le(s64.to_le_bytes()), s64);
+        assert_eq!(decode_i64_be(s64.to_be_bytes()), s64);
+    }
+    println!("Hand-written encoders match to_le_bytes/to_be_bytes on 1000 values of every type.");
+
+    // 3. 20 records through the file format and back
+    let records: Vec<Record> = (0..20u64)
+        .map(|i| Record {
+            id: i * 1_000_003 + (i << 40),
+            // Some empty, some with multi-byte chars: length is in bytes, not chars
+            name: match i % 4 {
+                0 => String::new(),
+                1 => format!("record {i}"),
+                2 => format!("é{}", "x".repeat(i as usize)),
+                _ => format!("{i} 🦀"),
+            },
+        })
+        .collect();
+    let wire = BinaryWriter::write_file(&records);
+    let decoded = BinaryReader::read_file(&wire).expect("a file we just wrote");
+    assert_eq!(decoded, records);
+
+    // Byte-exact: build the expected file with the standard library alone and compare
+    let mut expected = b"RECS".to_vec();
+    expected.extend(1u16.to_le_bytes());
+    expected.extend(20u32.to_le_bytes());
+    for record in &records {
+        expected.extend(record.id.to_le_bytes());
+        expected.extend((record.name.len() as u32).to_le_bytes());
+        expected.extend(record.name.as_bytes());
+    }
+    assert_eq!(wire, expected);
+    // Writing what was read gives back the same bytes
+    assert_eq!(BinaryWriter::write_file(&decoded), wire);
+    println!(
+        "20 records round-trip through {} bytes; the header is {:02x?}",
+        wire.len(),
+        &wire[..10]
+    );
+
+    // 4. Damaged files are errors, not panics
+    let errors = [
+        BinaryReader::read_file(&wire[..7]).unwrap_err(),
+        BinaryReader::read_file(&wire[..wire.len() - 1]).unwrap_err(),
+        BinaryReader::read_file(b"NOPE\x01\x00\x00\x00\x00\x00").unwrap_err(),
+        BinaryReader::read_file(b"RECS\x02\x00\x00\x00\x00\x00").unwrap_err(),
+        BinaryReader::read_file(&[wire.as_slice(), b"!"].concat()).unwrap_err(),
+    ];
+    assert_eq!(errors[0], ReadError::UnexpectedEnd { pos: 6, wanted: 4 });
+    assert!(matches!(errors[1], ReadError::UnexpectedEnd { .. }));
+    assert_eq!(errors[2], ReadError::BadMagic(*b"NOPE"));
+    assert_eq!(errors[3], ReadError::UnsupportedVersion(2));
+    assert_eq!(errors[4], ReadError::TrailingBytes(1));
+    // A name that isn't UTF-8: one record, id 0, length 1, byte 0xff
+    let mut bad = BinaryWriter::default();
+    bad.bytes(&MAGIC);
+    bad.u16(VERSION);
+    bad.u32(1);
+    bad.u64(0);
+    bad.u32(1);
+    bad.bytes(&[0xff]);
+    assert_eq!(
+        BinaryReader::read_file(&bad.buf),
+        Err(ReadError::BadName { pos: 22 })
+    );
+    for err in &errors {
+        println!("  {err}");
+    }
+}
@@ -0,0 +1,220 @@
+// Reading configuration from environment variables, with defaults and validation
+// Try it with:
+//   APP_SECRET=hunter2 APP_PORT=3000 cargo run --bin environment_variables
+use std::env;
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::thread;
+
+const LOG_LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+
+#[derive(Debug)]
+struct AppConfig {
+    host: String,
+    port: u16,
+    log_level: String,
+    workers: usize,
+    secret: String,
+}
+
+// One variant per way the configuration can be wrong
+// Carrying the offending value makes the error message actionable
+#[derive(Debug, PartialEq)]
+enum ConfigError {
+    // The variable exists but isn't valid UTF-8, so it can't become a String
+    NotUnicode(&'static str),
+    InvalidPort(String),
+    PortOutOfRange(u32),
+    InvalidLogLevel(String),
+    InvalidWorkers(String),
+    MissingSecret,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::NotUnicode(key) => write!(f, "{key} is not valid unicode"),
+            ConfigError::InvalidPort(raw) => write!(f, "APP_PORT '{raw}' is not a number"),
+            ConfigError::PortOutOfRange(port) => {
+                write!(f, "APP_PORT {port} must be between 1024 and 65535")
+            }
+            ConfigError::InvalidLogLevel(raw) => write!(
+                f,
+                "APP_LOG_LEVEL '{raw}' must be one of {}",
+                LOG_LEVELS.join(", ")
+            ),
+            ConfigError::InvalidWorkers(raw) => {
+                write!(f, "APP_WORKERS '{raw}' must be a positive number")
+            }
+            ConfigError::MissingSecret => write!(f, "APP_SECRET is required but not set"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+// env::var returns Result<String, VarError>
+// - Err(NotPresent) means "use the default", so we turn it into None
+// - Err(NotUnicode) is a real problem, so it becomes an error
+fn read_var(key: &'static str) -> Result<Option<String>, ConfigError> {
+    match env::var(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(env::VarError::NotPresent) => Ok(None),
+        Err(env::VarError::NotUnicode(_)) => Err(ConfigError::NotUnicode(key)),
+    }
+}
+
+fn load_config() -> Result<AppConfig, ConfigError> {
+    // unwrap_or_else only builds the default String when the variable is missing
+    let host = read_var("APP_HOST")?.unwrap_or_else(|| "localhost".to_string());
+
+    let port = match read_var("APP_PORT")? {
+        None => 8080,
+        Some(raw) => {
+            // Parsed wider than u16, so 70000 is reported as out of range
+            // rather than as not being a number
+            let port: u32 = raw.parse().map_err(|_| ConfigError::InvalidPort(raw))?;
+            // Ports below 1024 need root on most systems
+            match u16::try_from(port) {
+                Ok(port) if port >= 1024 => port,
+                _ => return Err(ConfigError::PortOutOfRange(port)),
+            }
+        }
+    };
+
+    let log_level = read_var("APP_LOG_LEVEL")?.unwrap_or_else(|| "info".to_string());
+    if !LOG_LEVELS.contains(&log_level.as_str()) {
+        return Err(ConfigError::InvalidLogLevel(log_level));
+    }
+
+    let workers = match read_var("APP_WORKERS")? {
+        // available_parallelism can fail (e.g. in some sandboxes), so fall back to 1
+        None => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        Some(raw) => match raw.parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(ConfigError::InvalidWorkers(raw)),
+        },
+    };
+
+    // No default: ok_or turns None into our error, and ? returns it early
+    let secret = read_var("APP_SECRET")?.ok_or(ConfigError::MissingSecret)?;
+
+    Ok(AppConfig {
+        host,
+        port,
+        log_level,
+        workers,
+        secret,
+    })
+}
+
+fn print_config(config: &AppConfig) {
+    println!("  host      = {}", config.host);
+    println!("  port      = {}", config.port);
+    println!("  log_level = {}", config.log_level);
+    println!("  workers   = {}", config.workers);
+    // Never print secrets in full, even in a demo
+    let first: String = config.secret.chars().take(1).collect();
+    println!("  secret    = {first}***");
+}
+
+fn main() {
+    // 1. Whatever the real environment says
+    println!("Configuration from the real environment:");
+    match load_config() {
+        Ok(config) => print_config(&config),
+        Err(err) => println!("  error: {err}"),
+    }
+
+    // 2. Changing the environment from inside the program
+    // Since Rust 2024 set_var and remove_var are `unsafe`: on most platforms the
+    // environment is a process-wide global that other threads (or C libraries)
+    // may read at the same time, which is a data race
+    // SAFETY: this program is single-threaded, nothing else reads the environment concurrently
+    unsafe {
+        env::set_var("APP_SECRET", "s3cret");
+        env::set_var("APP_PORT", "3000");
+        env::set_var("APP_LOG_LEVEL", "debug");
+        env::set_var("APP_WORKERS", "4");
+        env::remove_var("APP_HOST");
+    }
+    println!("\nAfter set_var/remove_var:");
+    let config = load_config().expect("valid demo configuration");
+    print_config(&config);
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 3000);
+    assert_eq!(config.log_level, "debug");
+    assert_eq!(config.workers, 4);
+    assert_eq!(config.secret, "s3cret");
+
+    // 3. Every failure mode produces its own error
+    println!("\nInvalid configurations:");
+    let cases = [
+        (
+            "APP_PORT",
+            Some("http"),
+            ConfigError::InvalidPort("http".to_string()),
+            "APP_PORT 'http' is not a number",
+        ),
+        (
+            "APP_PORT",
+            Some("80"),
+            ConfigError::PortOutOfRange(80),
+            "APP_PORT 80 must be between 1024 and 65535",
+        ),
+        (
+            "APP_PORT",
+            Some("70000"),
+            ConfigError::PortOutOfRange(70000),
+            "APP_PORT 70000 must be between 1024 and 65535",
+        ),
+        (
+            "APP_LOG_LEVEL",
+            Some("loud"),
+            ConfigError::InvalidLogLevel("loud".to_string()),
+            "APP_LOG_LEVEL 'loud' must be one of trace, debug, info, warn, error",
+        ),
+        (
+            "APP_WORKERS",
+            Some("0"),
+            ConfigError::InvalidWorkers("0".to_string()),
+            "APP_WORKERS '0' must be a positive number",
+        ),
+        (
+            "APP_SECRET",
+            None,
+            ConfigError::MissingSecret,
+            "APP_SECRET is required but not set",
+        ),
+    ];
+    for (key, value, expected, message) in cases {
+        let previous = env::var(key).ok();
+        // SAFETY: still single-threaded
+        unsafe {
+            match value {
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key),
+            }
+        }
+
+        let err = load_config().expect_err("configuration should be rejected");
+        println!("  {key}={value:?} -> {err}");
+        assert_eq!(err, expected);
+        assert_eq!(err.to_string(), message);
+
+        // Put the valid value back so the next case only breaks one thing
+        // SAFETY: still single-threaded
+        if let Some(previous) = previous {
+            unsafe { env::set_var(key, previous) };
+        }
+    }
+
+    // 4. Dumping the whole environment, sorted by name
+    // env::vars() panics on non-unicode entries; env::vars_os() never does
+    let mut vars: Vec<(String, String)> = env::vars().collect();
+    vars.sort();
+    println!("\nAll {} environment variables:", vars.len());
+    for (key, value) in &vars {
+        println!("  {key}={value}");
+    }
+}
@@ -0,0 +1,178 @@
+// Every part of a format string, one example at a time
+// Inside the braces of format!, println! and friends, the spec reads
+//   {argument:fill align sign # 0 width .precision type}
+// and every part is optional: {} alone means Display, {:?} Debug, and so on
+use std::f64::consts::PI;
+use std::fmt::{self, Write};
+
+// Formats with the spec, prints spec, arguments and result, and checks the
+// result against the expected string given first. The spec has to be a literal for format!
+// to check it at compile time, so this is a macro rather than a function
+macro_rules! show {
+    ($expected:expr, $spec:literal $(, $($args:tt)*)?) => {{
+        let result = format!($spec $(, $($args)*)?);
+        println!("{:<22} {:<28} {:?}", $spec, stringify!($($($args)*)?), result);
+        assert_eq!(result, $expected, "{}", $spec);
+        CHECKED.with(|n| n.set(n.get() + 1));
+    }};
+}
+
+thread_local! {
+    static CHECKED: std::cell::Cell<u32> = const { std::cell::Cell::new(0) };
+}
+
+#[derive(Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+// A type that reads the formatter's flags: {} gives "3h 5m", {:#} spells it
+// out, and a width pads the result like any other value
+struct Duration {
+    minutes: u32,
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (hours, minutes) = (self.minutes / 60, self.minutes % 60);
+        let text = if f.alternate() {
+            let s = |n: u32| if n == 1 { "" } else { "s" };
+            format!(
+                "{hours} hour{} and {minutes} minute{}",
+                s(hours),
+                s(minutes)
+            )
+        } else {
+            format!("{hours}h {minutes}m")
+        };
+        // pad (not write_str) applies the caller's width, fill and alignment
+        f.pad(&text)
+    }
+}
+
+fn main() {
+    println!("{:<22} {:<28} result", "spec", "arguments");
+
+    // ---- Display and Debug ----
+    show!("plain", "{}", "plain");
+    show!("\"quoted\"", "{:?}", "quoted");
+    show!("'c'", "{:?}", 'c');
+    show!("Point { x: 1, y: -2 }", "{:?}", Point { x: 1, y: -2 });
+    // {:#?} is pretty-printed Debug: one field per line, indented
+    show!(
+        "Point {\n    x: 1,\n    y: -2,\n}",
+        "{:#?}",
+        Point { x: 1, y: -2 }
+    );
+    // Debug is what derive(Debug) gives; Display has to be written by hand
+    let corner = Point { x: 1, y: -2 };
+    show!("(1, -2)", "({}, {})", corner.x, corner.y);
+    show!("Some([1, 2])", "{:?}", Some(vec![1, 2]));
+
+    // ---- Integers in other bases ----
+    show!("1010", "{:b}", 10);
+    show!("100", "{:o}", 64);
+    show!("ff", "{:x}", 255);
+    show!("FF", "{:X}", 255);
+    // # adds the base's prefix
+    show!("0xff", "{:#x}", 255);
+    show!("0b101", "{:#b}", 5);
+    // 0 pads with zeros after the sign and prefix; the width counts them
+    show!("00000101", "{:08b}", 5u8);
+    show!("0x000000ff", "{:#010x}", 255);
+    // Negative numbers print as their two's complement bits
+    show!("ff", "{:x}", -1i8);
+
+    // ---- Scientific notation ----
+    show!("1.2345e3", "{:e}", 1234.5);
+    show!("1.2E-4", "{:E}", 0.00012);
+    show!("1.23e5", "{:.2e}", 123456.0);
+
+    // ---- Width and alignment ----
+    // Strings go left by default, numbers right
+    show!("ab      |", "{:8}|", "ab");
+    show!("      42|", "{:8}|", 42);
+    show!("42      |", "{:<8}|", 42);
+    show!("      ab|", "{:>8}|", "ab");
+    show!("   ab   |", "{:^8}|", "ab");
+    // Any character can fill: it goes before the alignment
+    show!("***mid***", "{:*^9}", "mid");
+    show!("00042", "{:0>5}", 42);
+    show!("-----x", "{:->6}", 'x');
+    // Width never truncates: a longer value just overflows it
+    show!("overflow|", "{:3}|", "overflow");
+    // The width can come from an argument: width$ names it, 1$ is positional
+    let width = 6;
+    show!("     7|", "{:>width$}|", 7);
+    show!("   7|", "{:>1$}|", 7, 4);
+    show!("000031", "{:0>width$}", 31);
+
+    // ---- Sign ----
+    show!("+5", "{:+}", 5);
+    show!("-5", "{:+}", -5);
+    show!("+0.2", "{:+.1}", 0.25);
+
+    // ---- Precision ----
+    // For floats it is digits after the point, rounded to even at a tie.
+    // 0.25 is exact in binary, so it is a real tie; 0.35 isn't exactly 0.35
+    show!("3.14", "{:.2}", PI);
+    show!("2", "{:.0}", 2.5);
+    show!("0.3", "{:.1}", 0.35);
+    show!("1.000", "{:.3}", 1.0);
+    show!("    3.14|", "{:8.2}|", PI);
+    show!("-0003.14", "{:08.2}", -PI);
+    let precision = 4;
+    show!("1.4142", "{:.precision$}", 2.0f64.sqrt());
+    show!("1.23", "{:.*}", 2, 1.23456);
+    // For strings, precision is the most characters to print
+    show!("tru", "{:.3}", "truncated");
+    show!("    ab|", "{:>6.2}|", "abc");
+    // Without a precision, Debug shows just enough digits to read the float back
+    show!("0.30000000000000004", "{:?}", 0.1 + 0.2);
+    show!("1", "{}", 1.0);
+    show!("1.0", "{:?}", 1.0);
+
+    // ---- Naming arguments ----
+    show!("a b a", "{0} {1} {0}", "a", "b");
+    show!("\"y\" x", "{1:?} {0}", "x", "y");
+    show!("Ada is 36", "{name} is {age}", name = "Ada", age = 36);
+    // Or straight from variables in scope, as everywhere else in this crate
+    let city = "Lisbon";
+    show!("  Lisbon", "{city:>8}");
+    show!("{literal braces} 1", "{{literal braces}} {}", 1);
+
+    // ---- Pointers ----
+    // {:p} prints an address, different on every run, so only its shape is checked
+    let value = 5;
+    let address = format!("{:p}", &value);
+    println!("{:<22} {:<28} {:?}", "{:p}", "&value", address);
+    assert!(address.starts_with("0x"));
+
+    // ---- A type that reads the flags itself ----
+    let long = Duration { minutes: 185 };
+    show!("3h 5m", "{}", long);
+    show!("1 hour and 1 minute", "{:#}", Duration { minutes: 61 });
+    show!("[   3h 5m]", "[{:>8}]", Duration { minutes: 185 });
+    show!(
+        "[  1 hour and 0 minutes  ]",
+        "[{:^#24}]",
+        Duration { minutes: 60 }
+    );
+
+    // ---- write! and writeln! into a String ----
+    // fmt::Write is the String-side twin of io::Write; writing to a String can't
+    // actually fail, but the Result is still there because other writers can
+    let mut report = String::new();
+    writeln!(report, "{:<6}{:>6}", "name", "score").unwrap();
+    for (name, score) in [("ann", 91.5), ("bo", 7.25)] {
+        writeln!(report, "{name:<6}{score:>6.1}").unwrap();
+    }
+    write!(report, "{} rows", 2).unwrap();
+    println!("\n{report}");
+    assert_eq!(report, "name   score\nann     91.5\nbo       7.2\n2 rows");
+
+    let checked = CHECKED.with(|n| n.get());
+    println!("\n{checked} formatted strings matched what they should be.");
+    assert!(checked >= 30);
+}
@@ -0,0 +1,244 @@
+// A tokenizer written as a finite-state machine
+// All the tokenizer knows at any moment is which State it is in; `step` looks at
+// that state and one character and says what the next state is, and whether a
+// token just ended. Nothing else is remembered between characters, so every
+// behaviour is one line in the transition table below
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    InNumber,
+    InIdent,
+    InString,
+    // Just saw a backslash inside a string: the next character is taken as is
+    InStringEscape,
+    // Just saw '/': a comment starts if '/' or '*' follows, otherwise it was a symbol
+    InSlash,
+    InLineComment,
+    // depth counts how many /* are still open, so comments can nest
+    InBlockComment { depth: usize },
+    // Inside a block comment, just after '*' (a '/' now closes one level)
+    InBlockCommentStar { depth: usize },
+    // Inside a block comment, just after '/' (a '*' now opens another level)
+    InBlockCommentSlash { depth: usize },
+    // A token ended *before* this character, which still has to be read from Start
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Number,
+    Ident,
+    Str,
+    Symbol(char),
+    // End of input inside a string or a block comment
+    Unterminated,
+}
+
+// The whole machine. `d` is a block-comment depth, "other" means any character
+// not matched by an earlier row for the same state
+//
+//   state                   char                next state                     token
+//   Start                   whitespace          Start                          -
+//   Start                   digit               InNumber                       -
+//   Start                   letter or _         InIdent                        -
+//   Start                   "                   InString                       -
+//   Start                   /                   InSlash                        -
+//   Start                   other               Start                          Symbol(char)
+//   InNumber                digit               InNumber                       -
+//   InNumber                other               Done                           Number
+//   InIdent                 letter, digit or _  InIdent                        -
+//   InIdent                 other               Done                           Ident
+//   InString                \                   InStringEscape                 -
+//   InString                "                   Start                          Str
+//   InString                other               InString                       -
+//   InStringEscape          any                 InString                       -
+//   InSlash                 /                   InLineComment                  -
+//   InSlash                 *                   InBlockComment(1)              -
+//   InSlash                 other               Done                           Symbol('/')
+//   InLineComment           newline             Start                          -
+//   InLineComment           other               InLineComment                  -
+//   InBlockComment(d)       *                   InBlockCommentStar(d)          -
+//   InBlockComment(d)       /                   InBlockCommentSlash(d)         -
+//   InBlockComment(d)       other               InBlockComment(d)              -
+//   InBlockCommentStar(d)   / when d = 1        Start                          -
+//   InBlockCommentStar(d)   / when d > 1        InBlockComment(d - 1)          -
+//   InBlockCommentStar(d)   *                   InBlockCommentStar(d)          -
+//   InBlockCommentStar(d)   other               InBlockComment(d)              -
+//   InBlockCommentSlash(d)  *                   InBlockComment(d + 1)          -
+//   InBlockCommentSlash(d)  /                   InBlockCommentSlash(d)         -
+//   InBlockCommentSlash(d)  other               InBlockComment(d)              -
+fn step(state: State, ch: char) -> (State, Option<Token>) {
+    use State::*;
+    match state {
+        Start => match ch {
+            c if c.is_whitespace() => (Start, None),
+            c if c.is_ascii_digit() => (InNumber, None),
+            c if c.is_alphabetic() || c == '_' => (InIdent, None),
+            '"' => (InString, None),
+            '/' => (InSlash, None),
+            c => (Start, Some(Token::Symbol(c))),
+        },
+        InNumber => match ch {
+            c if c.is_ascii_digit() => (InNumber, None),
+            _ => (Done, Some(Token::Number)),
+        },
+        InIdent => match ch {
+            c if c.is_alphanumeric() || c == '_' => (InIdent, None),
+            _ => (Done, Some(Token::Ident)),
+        },
+        InString => match ch {
+            '\\' => (InStringEscape, None),
+            '"' => (Start, Some(Token::Str)),
+            _ => (InString, None),
+        },
+        InStringEscape => (InString, None),
+        InSlash => match ch {
+            '/' => (InLineComment, None),
+            '*' => (InBlockComment { depth: 1 }, None),
+            _ => (Done, Some(Token::Symbol('/'))),
+        },
+        InLineComment => match ch {
+            '\n' => (Start, None),
+            _ => (InLineComment, None),
+        },
+        InBlockComment { depth } => match ch {
+            '*' => (InBlockCommentStar { depth }, None),
+            '/' => (InBlockCommentSlash { depth }, None),
+            _ => (InBlockComment { depth }, None),
+        },
+        InBlockCommentStar { depth } => match ch {
+            '/' if depth == 1 => (Start, None),
+            '/' => (InBlockComment { depth: depth - 1 }, None),
+            '*' => (InBlockCommentStar { depth }, None),
+            _ => (InBlockComment { depth }, None),
+        },
+        InBlockCommentSlash { depth } => match ch {
+            '*' => (InBlockComment { depth: depth + 1 }, None),
+            '/' => (InBlockCommentSlash { depth }, None),
+            _ => (InBlockComment { depth }, None),
+        },
+        Done => unreachable!("the driver restarts from Start after Done"),
+    }
+}
+
+// What the end of the input means in each state
+fn finish(state: State) -> Option<Token> {
+    use State::*;
+    match state {
+        InNumber => Some(Token::Number),
+        InIdent => Some(Token::Ident),
+        InSlash => Some(Token::Symbol('/')),
+        InString | InStringEscape => Some(Token::Unterminated),
+        InBlockComment { .. } | InBlockCommentStar { .. } | InBlockCommentSlash { .. } => {
+            Some(Token::Unterminated)
+        }
+        Start | InLineComment | Done => None,
+    }
+}
+
+// The driver: feed characters to `step` one by one
+// The only bookkeeping is where the current token started, to report its text
+fn scan(input: &str) -> Vec<(Token, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut state = State::Start;
+    let mut start = 0;
+    for (i, ch) in input.char_indices() {
+        if state == State::Start {
+            start = i;
+        }
+        match step(state, ch) {
+            // The token ended just before `ch`, so `ch` is read again from Start
+            (State::Done, Some(token)) => {
+                tokens.push((token, start..i));
+                start = i;
+                state = match step(State::Start, ch) {
+                    (next, Some(token)) => {
+                        tokens.push((token, i..i + ch.len_utf8()));
+                        next
+                    }
+                    (next, None) => next,
+                };
+            }
+            (next, Some(token)) => {
+                tokens.push((token, start..i + ch.len_utf8()));
+                state = next;
+            }
+            (next, None) => state = next,
+        }
+    }
+    if let Some(token) = finish(state) {
+        tokens.push((token, start..input.len()));
+    }
+    tokens
+}
+
+fn tokenize(input: &str) -> Vec<Token> {
+    scan(input).into_iter().map(|(token, _)| token).collect()
+}
+
+// The source text of every token, for printing
+fn lexemes(input: &str) -> Vec<(Token, &str)> {
+    scan(input)
+        .into_iter()
+        .map(|(token, span)| (token, &input[span]))
+        .collect()
+}
+
+fn main() {
+    use Token::*;
+
+    // Adjacent tokens with no whitespace between them
+    assert_eq!(
+        tokenize("x=42+y1;"),
+        [Ident, Symbol('='), Number, Symbol('+'), Ident, Symbol(';')]
+    );
+    assert_eq!(
+        lexemes("foo(12,bar)"),
+        [
+            (Ident, "foo"),
+            (Symbol('('), "("),
+            (Number, "12"),
+            (Symbol(','), ","),
+            (Ident, "bar"),
+            (Symbol(')'), ")"),
+        ]
+    );
+
+    // Escapes: \" doesn't end the string, and \\ doesn't escape the closing quote
+    let strings = r#"say("a \"quoted\" word") "ends with \\" 7"#;
+    assert_eq!(
+        lexemes(strings),
+        [
+            (Ident, "say"),
+            (Symbol('('), "("),
+            (Str, r#""a \"quoted\" word""#),
+            (Symbol(')'), ")"),
+            (Str, r#""ends with \\""#),
+            (Number, "7"),
+        ]
+    );
+
+    // Nested block comments only end when every level is closed
+    let nested = "a /* outer /* inner */ still a comment */ b";
+    assert_eq!(lexemes(nested), [(Ident, "a"), (Ident, "b")]);
+    assert_eq!(tokenize("1/*/**/*/2"), [Number, Number]);
+    assert_eq!(tokenize("/* **/ x"), [Ident]);
+    assert_eq!(tokenize("/* /* never closed */"), [Unterminated]);
+
+    // A lone slash is division, a double slash runs to the end of the line
+    assert_eq!(
+        tokenize("a/b // c d e\nf"),
+        [Ident, Symbol('/'), Ident, Ident]
+    );
+    assert_eq!(tokenize("\"open"), [Unterminated]);
+    assert_eq!(tokenize("x/"), [Ident, Symbol('/')]);
+    assert!(tokenize("  // only a comment").is_empty());
+
+    let source = "let total = price*2; /* tax /* later */ */ print(\"total: \\\"ok\\\"\")";
+    println!("{source}");
+    for (token, text) in lexemes(source) {
+        println!("  {:<12} {text}", format!("{token:?}"));
+    }
+}
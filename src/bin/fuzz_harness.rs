@@ -0,0 +1,127 @@
+// Fuzzing: throw huge amounts of random and half-broken input at a parser and
+// check that it never panics. A wrong answer is fine here (it should be an
+// Err), a crash is not
+//
+// cargo-fuzz with libfuzzer-sys is the usual tool; its targets look like
+//     fuzz_target!(|data: &[u8]| { let _ = parse(data); });
+// and are run with `cargo fuzz run fuzz_json -- -max_len=1024`
+// libfuzzer watches which branches each input reaches, which this can't do,
+// so instead we mutate the seed files in corpus/ at random, which still finds
+// most crashes in a parser this size
+//
+// Run from the repository root, so the seeds in corpus/ are found:
+//     cargo run --profile fuzz --bin fuzz_harness -- [iterations]
+use std::fs;
+use std::panic;
+use std::path::Path;
+
+use learning_rust::config::Config;
+use learning_rust::json;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Longer inputs mostly repeat what shorter ones already test, like libfuzzer's -max_len
+const MAX_LEN: usize = 1024;
+
+// A fuzz target takes raw bytes; it decides for itself what to do with invalid UTF-8
+fn fuzz_json(data: &[u8]) {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = json::parse(s);
+    }
+}
+
+// The command line is split into words, then handed to the flag parser
+fn fuzz_args(data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    let _ = Config::from_args(text.split_whitespace().map(str::to_string));
+}
+
+// Mutations borrowed from libfuzzer: flip a bit, overwrite, insert, delete,
+// or duplicate a slice of the input
+fn mutate(rng: &mut StdRng, input: &mut Vec<u8>) {
+    // Interesting bytes for these parsers, more likely to reach new branches than random ones
+    const TOKENS: &[u8] = b"{}[]\":,\\-+.eE0123456789tfnu ";
+    for _ in 0..rng.random_range(1..=4) {
+        let len = input.len();
+        match rng.random_range(0..5) {
+            0 if len > 0 => input[rng.random_range(0..len)] ^= 1 << rng.random_range(0..8),
+            1 if len > 0 => {
+                input[rng.random_range(0..len)] = TOKENS[rng.random_range(0..TOKENS.len())]
+            }
+            2 => input.insert(
+                rng.random_range(0..=len),
+                TOKENS[rng.random_range(0..TOKENS.len())],
+            ),
+            3 if len > 0 => {
+                input.remove(rng.random_range(0..len));
+            }
+            4 if len > 0 => {
+                let start = rng.random_range(0..len);
+                let end = rng.random_range(start..=len);
+                let copy = input[start..end].to_vec();
+                let at = rng.random_range(0..=input.len());
+                input.splice(at..at, copy);
+            }
+            _ => input.push(rng.random()),
+        }
+    }
+    input.truncate(MAX_LEN);
+}
+
+fn load_corpus(dir: &Path) -> Vec<Vec<u8>> {
+    let mut seeds: Vec<Vec<u8>> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| fs::read(entry.ok()?.path()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    // An empty input is always a worthwhile seed
+    seeds.push(Vec::new());
+    seeds
+}
+
+// A fuzz target, like the body of fuzz_target!
+type Target = fn(&[u8]);
+
+// Returns the first input that made the target panic, if any
+fn run(name: &str, target: Target, iterations: u32, rng: &mut StdRng) -> Option<Vec<u8>> {
+    let corpus = load_corpus(&Path::new("corpus").join(name));
+    for seed in &corpus {
+        target(seed);
+    }
+    for _ in 0..iterations {
+        let mut input = corpus[rng.random_range(0..corpus.len())].clone();
+        mutate(rng, &mut input);
+        // catch_unwind turns a panic back into a value, so we can report the input
+        if panic::catch_unwind(|| target(&input)).is_err() {
+            return Some(input);
+        }
+    }
+    println!(
+        "{name}: {iterations} inputs from {} seeds, no panics",
+        corpus.len()
+    );
+    None
+}
+
+fn main() {
+    let iterations = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(100_000);
+    let mut rng = StdRng::seed_from_u64(rand::random());
+
+    let targets: [(&str, Target); 2] = [("fuzz_json", fuzz_json), ("fuzz_args", fuzz_args)];
+    for (name, target) in targets {
+        if let Some(input) = run(name, target, iterations, &mut rng) {
+            let path = format!("crash-{name}");
+            fs::write(&path, &input).expect("write crash file");
+            eprintln!(
+                "{name} panicked on {:?}; saved to {path}",
+                String::from_utf8_lossy(&input)
+            );
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,290 @@
+// A typed arena: allocate lots of values of one type, free them all at once
+// Compilers and parsers build trees and graphs full of nodes that all die
+// together at the end of a pass. Giving each node its own Box means one
+// allocator call per node to create it and another to free it; an arena hands
+// out slots from a few big chunks instead, and dropping the arena frees them
+// in one go. Nodes refer to each other with plain references, so there is no
+// need for Rc or its reference counts
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// ---- Counting allocator calls ----
+// (allocator_aware.rs covers this properly; here it only counts)
+
+struct CountingAllocator {
+    calls: AtomicUsize,
+}
+
+// SAFETY: every call is forwarded to System unchanged, which upholds the contract
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    calls: AtomicUsize::new(0),
+};
+
+// How many times `f` asked the allocator for memory
+fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATOR.calls.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATOR.calls.load(Ordering::Relaxed) - before)
+}
+
+// ---- The arena ----
+
+const FIRST_CHUNK: usize = 16;
+
+// Each chunk is a Vec that is never allowed to grow past the capacity it was
+// created with: a Vec only moves its elements when it reallocates, so as long
+// as there is spare capacity, pushing leaves every earlier element where it is.
+// A full chunk is left alone and a new one twice the size is started, so the
+// number of chunks (and allocator calls) grows with log(n), not n
+//
+// The chunks sit in a RefCell so alloc can take &self. With `&mut self` the
+// returned reference would keep the arena mutably borrowed for as long as it
+// lived, and a tree could never hold a second node
+struct Arena<T> {
+    chunks: RefCell<Vec<Vec<T>>>,
+}
+
+impl<T> Arena<T> {
+    fn new() -> Arena<T> {
+        Arena {
+            chunks: RefCell::new(Vec::new()),
+        }
+    }
+
+    // Why the reference may live as long as `&self`, and no longer:
+    // the signature is `fn alloc<'a>(&'a self, value: T) -> &'a T`, so every
+    // value handed out borrows the arena itself. The borrow checker then refuses
+    // to drop or move the arena while any of them is alive, which is exactly the
+    // condition for them to be valid, entirely at compile time. Nodes that hold
+    // such references are written Node<'arena>, naming that one shared borrow
+    fn alloc(&self, value: T) -> &T {
+        let mut chunks = self.chunks.borrow_mut();
+        if chunks
+            .last()
+            .is_none_or(|chunk| chunk.len() == chunk.capacity())
+        {
+            let capacity = chunks
+                .last()
+                .map_or(FIRST_CHUNK, |chunk| chunk.capacity() * 2);
+            chunks.push(Vec::with_capacity(capacity));
+        }
+        let chunk = chunks.last_mut().expect("a chunk was just made");
+        chunk.push(value);
+        let slot: *const T = chunk.last().expect("a value was just pushed");
+        // SAFETY: the value lives in a chunk's heap buffer, which never
+        // reallocates (its length stays within its capacity) and is only freed
+        // when the arena is dropped. Pushing more chunks moves the Vec headers
+        // in `chunks`, not the buffers they point to. So the slot stays valid
+        // and unaliased by any &mut for as long as `&self` is borrowed, which
+        // is the lifetime the signature gives it
+        unsafe { &*slot }
+    }
+
+    fn len(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::len).sum()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.chunks.borrow().len()
+    }
+}
+
+// ---- A binary search tree in the arena ----
+
+// Children are shared references into the same arena. They sit in Cells
+// because a node is only reachable through &Node once allocated, and inserting
+// has to fill in a child of a node that already exists; Cell allows that for
+// Copy values like references without any runtime borrow tracking
+struct Node<'arena> {
+    val: i32,
+    left: Cell<Option<&'arena Node<'arena>>>,
+    right: Cell<Option<&'arena Node<'arena>>>,
+}
+
+fn leaf<'arena>(arena: &'arena Arena<Node<'arena>>, val: i32) -> &'arena Node<'arena> {
+    arena.alloc(Node {
+        val,
+        left: Cell::new(None),
+        right: Cell::new(None),
+    })
+}
+
+// Equal values go right, so duplicates are kept
+fn insert<'arena>(arena: &'arena Arena<Node<'arena>>, root: &'arena Node<'arena>, val: i32) {
+    let mut node = root;
+    loop {
+        let side = if val < node.val {
+            &node.left
+        } else {
+            &node.right
+        };
+        match side.get() {
+            Some(child) => node = child,
+            None => {
+                side.set(Some(leaf(arena, val)));
+                return;
+            }
+        }
+    }
+}
+
+fn build_arena_tree<'arena>(
+    arena: &'arena Arena<Node<'arena>>,
+    values: &[i32],
+) -> &'arena Node<'arena> {
+    let root = leaf(arena, values[0]);
+    for &val in &values[1..] {
+        insert(arena, root, val);
+    }
+    root
+}
+
+fn in_order(node: Option<&Node>, out: &mut Vec<i32>) {
+    if let Some(node) = node {
+        in_order(node.left.get(), out);
+        out.push(node.val);
+        in_order(node.right.get(), out);
+    }
+}
+
+fn depth(node: Option<&Node>) -> usize {
+    node.map_or(0, |node| {
+        1 + depth(node.left.get()).max(depth(node.right.get()))
+    })
+}
+
+// ---- The same tree with a Box per node ----
+
+struct BoxNode {
+    val: i32,
+    left: Option<Box<BoxNode>>,
+    right: Option<Box<BoxNode>>,
+}
+
+fn build_box_tree(values: &[i32]) -> Option<Box<BoxNode>> {
+    let mut root: Option<Box<BoxNode>> = None;
+    for &val in values {
+        // Walk down with a &mut to the empty slot where the value belongs
+        let mut slot = &mut root;
+        while let Some(node) = slot {
+            slot = if val < node.val {
+                &mut node.left
+            } else {
+                &mut node.right
+            };
+        }
+        *slot = Some(Box::new(BoxNode {
+            val,
+            left: None,
+            right: None,
+        }));
+    }
+    root
+}
+
+fn box_in_order(node: &Option<Box<BoxNode>>, out: &mut Vec<i32>) {
+    if let Some(node) = node {
+        box_in_order(&node.left, out);
+        out.push(node.val);
+        box_in_order(&node.right, out);
+    }
+}
+
+const NODES: usize = 10_000;
+const ROUNDS: u32 = 20;
+
+fn main() {
+    // Random values make a reasonably balanced tree; sorted ones would make a
+    // 10 000-deep linked list and overflow the recursive traversals
+    let mut rng = StdRng::seed_from_u64(129);
+    let values: Vec<i32> = (0..NODES)
+        .map(|_| rng.random_range(-50_000..50_000))
+        .collect();
+    let mut sorted = values.clone();
+    sorted.sort();
+
+    // 1. Both trees hold the same values in the same order
+    let arena = Arena::new();
+    let (root, arena_calls) = allocations(|| build_arena_tree(&arena, &values));
+    let mut walked = Vec::new();
+    in_order(Some(root), &mut walked);
+    assert_eq!(walked, sorted);
+    assert_eq!(arena.len(), NODES);
+
+    let (box_root, box_calls) = allocations(|| build_box_tree(&values));
+    let mut box_walked = Vec::new();
+    box_in_order(&box_root, &mut box_walked);
+    assert_eq!(box_walked, sorted);
+    println!(
+        "{NODES} nodes, tree depth {}: {} chunks in the arena",
+        depth(Some(root)),
+        arena.chunk_count()
+    );
+
+    // 2. Allocator calls: one per node for Box, a handful for the arena
+    // (16 + 32 + ... doubling reaches 10 000 after 10 chunks)
+    assert_eq!(box_calls, NODES);
+    assert_eq!(arena.chunk_count(), 10);
+    assert!(arena_calls < 20, "{arena_calls} allocator calls");
+    println!("Allocator calls to build it: Box {box_calls}, arena {arena_calls}");
+
+    // 3. Time: build and drop the tree ROUNDS times each way
+    // Dropping the Box tree visits and frees every node; dropping the arena frees
+    // its chunks (Node has no Drop of its own, so nothing runs per node). Each
+    // arena lives in a block: the Cells make Node<'arena> borrow its arena for the
+    // arena's whole life, so it can only go out of scope, not be passed to drop()
+    let mut arena_time = Duration::ZERO;
+    let mut box_time = Duration::ZERO;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        {
+            let arena = Arena::new();
+            let root = build_arena_tree(&arena, &values);
+            assert_eq!(root.val, values[0]);
+        }
+        arena_time += start.elapsed();
+
+        let start = Instant::now();
+        {
+            let root = build_box_tree(&values);
+            assert!(root.is_some());
+        }
+        box_time += start.elapsed();
+    }
+    // Not asserted: timings depend on the machine and the system allocator, and
+    // the tree walk itself (cache misses) is much of the cost either way
+    println!(
+        "Build and drop, {ROUNDS} rounds: Box {box_time:.1?}, arena {arena_time:.1?} ({:.2}x)",
+        box_time.as_secs_f64() / arena_time.as_secs_f64()
+    );
+
+    // The borrow checker at work: this doesn't compile, since `root` borrows
+    // `arena` and would outlive it
+    //
+    //     let root = {
+    //         let arena = Arena::new();
+    //         leaf(&arena, 1)
+    //     };              // error[E0597]: `arena` does not live long enough
+}
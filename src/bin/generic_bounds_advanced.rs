@@ -0,0 +1,184 @@
+// Two advanced corners of generics: higher-ranked trait bounds (for<'a>)
+// and generic associated types (GATs)
+use std::fmt::Display;
+
+// 1. Higher-ranked trait bounds
+// `F: Fn(&'a str) -> &'a str` for one particular 'a chosen by the caller wouldn't
+// work here: the strings we pass in are local to this function, and the caller
+// can't name their lifetime. for<'a> says "for every lifetime 'a", i.e. the
+// closure must work for whatever borrow it is handed
+fn apply_to_words<F>(text: &str, f: F) -> Vec<String>
+where
+    F: for<'a> Fn(&'a str) -> &'a str,
+{
+    text.split_whitespace()
+        .map(|word| {
+            let owned = word.to_lowercase();
+            // `owned` only lives inside this closure, yet f can still borrow from it
+            f(&owned).to_string()
+        })
+        .collect()
+}
+
+// Plain `Fn(&str) -> &str` is sugar for exactly this HRTB; writing it out just
+// makes the hidden for<'a> visible
+fn first_half(s: &str) -> &str {
+    &s[..s.len() / 2]
+}
+
+// 2. Generic associated types
+// Output<U> is an associated type that takes its own type parameter, so one
+// impl can describe "the same container, holding a U instead" for every U
+trait Mappable {
+    type Item;
+    type Output<U>;
+
+    fn map<U, F: Fn(Self::Item) -> U>(self, f: F) -> Self::Output<U>;
+}
+
+impl<T> Mappable for Option<T> {
+    type Item = T;
+    type Output<U> = Option<U>;
+
+    fn map<U, F: Fn(T) -> U>(self, f: F) -> Option<U> {
+        // Option already has an inherent map method, which does exactly this
+        Option::map(self, f)
+    }
+}
+
+// B = A is a default type parameter: Pair<i32> means Pair<i32, i32>
+#[derive(Debug, PartialEq)]
+struct Pair<A, B = A> {
+    first: A,
+    second: B,
+}
+
+// One closure can only map pairs whose two halves have the same type
+impl<A> Mappable for Pair<A> {
+    type Item = A;
+    type Output<U> = Pair<U>;
+
+    fn map<U, F: Fn(A) -> U>(self, f: F) -> Pair<U> {
+        Pair {
+            first: f(self.first),
+            second: f(self.second),
+        }
+    }
+}
+
+// Without GATs, the target type has to move into the trait itself
+// That works, but now Option<i32> implements MapTo<String>, MapTo<f64>, ...
+// as separate traits, and generic code has to name the U up front: `T: MapTo<U>`
+// The other option, a plain `type Output;`, can only ever map to one fixed type
+trait MapTo<U> {
+    type Item;
+    type Output;
+
+    fn map_to<F: Fn(Self::Item) -> U>(self, f: F) -> Self::Output;
+}
+
+impl<T, U> MapTo<U> for Option<T> {
+    type Item = T;
+    type Output = Option<U>;
+
+    fn map_to<F: Fn(T) -> U>(self, f: F) -> Option<U> {
+        Mappable::map(self, f)
+    }
+}
+
+// With the GAT the bound is just `M: Mappable`, and U is picked per call
+fn describe_all<M: Mappable>(container: M) -> M::Output<String>
+where
+    M::Item: Display,
+{
+    container.map(|item| format!("<{item}>"))
+}
+
+// 3. impl Trait in argument position vs return position
+// Argument position: the caller picks the type; this is shorthand for a generic
+// parameter, fn total<I: Iterator<Item = u32>>(items: I)
+fn total(items: impl Iterator<Item = u32>) -> u32 {
+    items.sum()
+}
+
+// Return position: the function picks one concrete type and hides it. The
+// caller only knows it's "some iterator of u32s" (here a Filter<Range<u32>, ..>,
+// a type involving a closure that can't even be written down)
+fn evens(limit: u32) -> impl Iterator<Item = u32> {
+    (0..limit).filter(|n| n.is_multiple_of(2))
+}
+
+// 4. impl Trait returned from a trait method
+// Before Rust 1.75 this was an error without #![feature(return_position_impl_trait_in_trait)]
+// (and async fn in traits needed #![feature(async_fn_in_trait)]); people boxed
+// the result instead: fn numbers(&self) -> Box<dyn Iterator<Item = u32> + '_>
+// Both features were stabilised in 1.75, so on this edition it just works
+trait Source {
+    fn numbers(&self) -> impl Iterator<Item = u32>;
+}
+
+struct Countdown(u32);
+
+impl Source for Countdown {
+    fn numbers(&self) -> impl Iterator<Item = u32> {
+        (1..=self.0).rev()
+    }
+}
+
+fn main() {
+    let shortened = apply_to_words("Hello Wonderful World", first_half);
+    println!("first halves: {shortened:?}");
+    assert_eq!(shortened, ["he", "wond", "wo"]);
+    let trimmed = apply_to_words("--rust-- --gats--", |s| s.trim_matches('-'));
+    assert_eq!(trimmed, ["rust", "gats"]);
+
+    // The same map, on two different containers, changing the element type
+    // Option's own map method would win over the trait's, so call the trait explicitly
+    let length = Mappable::map(Some("hello"), str::len);
+    assert_eq!(length, Some(5));
+    let none: Option<&str> = None;
+    assert_eq!(Mappable::map(none, str::len), None);
+
+    let pair = Pair {
+        first: 3,
+        second: 4,
+    };
+    let squared = pair.map(|n| n * n);
+    println!("squared pair: {squared:?}");
+    assert_eq!(
+        squared,
+        Pair {
+            first: 9,
+            second: 16
+        }
+    );
+
+    // A mixed Pair is fine to build, it just isn't Mappable
+    let mixed: Pair<&str, f64> = Pair {
+        first: "half",
+        second: 0.5,
+    };
+    println!("mixed pair: {} and {}", mixed.first, mixed.second);
+
+    assert_eq!(describe_all(Some(7)), Some("<7>".to_string()));
+    let described = describe_all(Pair {
+        first: 'a',
+        second: 'b',
+    });
+    assert_eq!(described.first, "<a>");
+    println!("described: {described:?}");
+
+    let via_second_parameter: Option<String> = Some(1).map_to(|n: i32| n.to_string());
+    assert_eq!(via_second_parameter.as_deref(), Some("1"));
+
+    // Any iterator works as the argument...
+    assert_eq!(total([1, 2, 3].into_iter()), 6);
+    assert_eq!(total(evens(10)), 20);
+    // ...but the returned one can only be used through the Iterator trait
+    let first_evens: Vec<u32> = evens(100).take(4).collect();
+    assert_eq!(first_evens, [0, 2, 4, 6]);
+
+    let countdown: Vec<u32> = Countdown(3).numbers().collect();
+    println!("countdown: {countdown:?}");
+    assert_eq!(countdown, [3, 2, 1]);
+}
@@ -0,0 +1,120 @@
+// Finding the n-th smallest (or largest) element without sorting everything
+// Sorting a million numbers to read off the 10th smallest does far more work
+// than needed. A heap holding only the best n seen so far costs O(len * log n)
+// time and O(n) memory, which matters when n is small and the input is huge
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+mod arrays {
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    // BinaryHeap is a max-heap: peek() is the largest element
+    // To keep the n smallest, hold them in a max-heap of size n: its top is the
+    // largest of the n, exactly the one to throw out when something smaller comes
+    // along. When all elements are seen, that top is the n-th smallest
+    pub fn nth_smallest<T: Ord + Copy>(slice: &[T], n: usize) -> Option<T> {
+        if n == 0 || n > slice.len() {
+            return None;
+        }
+        let mut heap = BinaryHeap::with_capacity(n);
+        for &x in slice {
+            if heap.len() < n {
+                heap.push(x);
+            } else if let Some(&top) = heap.peek()
+                && x < top
+            {
+                heap.pop();
+                heap.push(x);
+            }
+        }
+        heap.peek().copied()
+    }
+
+    // The mirror image needs a min-heap, and Reverse turns BinaryHeap into one:
+    // Reverse(a) < Reverse(b) exactly when a > b, so the top is the smallest
+    pub fn nth_largest<T: Ord + Copy>(slice: &[T], n: usize) -> Option<T> {
+        if n == 0 || n > slice.len() {
+            return None;
+        }
+        let mut heap = BinaryHeap::with_capacity(n);
+        for &x in slice {
+            if heap.len() < n {
+                heap.push(Reverse(x));
+            } else if let Some(&Reverse(top)) = heap.peek()
+                && x > top
+            {
+                heap.pop();
+                heap.push(Reverse(x));
+            }
+        }
+        heap.peek().map(|&Reverse(x)| x)
+    }
+}
+
+// The obvious way, used to check the heap versions
+fn nth_smallest_by_sorting<T: Ord + Copy>(slice: &[T], n: usize) -> Option<T> {
+    let mut sorted = slice.to_vec();
+    sorted.sort();
+    n.checked_sub(1).and_then(|i| sorted.get(i).copied())
+}
+
+fn main() {
+    let data = [7, 2, 9, 4, 4, 1, 8];
+    // Sorted: 1 2 4 4 7 8 9
+    assert_eq!(arrays::nth_smallest(&data, 1), Some(1));
+    assert_eq!(arrays::nth_smallest(&data, 3), Some(4));
+    assert_eq!(arrays::nth_smallest(&data, 4), Some(4)); // duplicates count twice
+    assert_eq!(arrays::nth_smallest(&data, 7), Some(9));
+    assert_eq!(arrays::nth_largest(&data, 1), Some(9));
+    assert_eq!(arrays::nth_largest(&data, 2), Some(8));
+    println!(
+        "{data:?}: 3rd smallest {:?}, 2nd largest {:?}",
+        arrays::nth_smallest(&data, 3),
+        arrays::nth_largest(&data, 2)
+    );
+
+    // Out of range: there is no 0th element, and no 8th of 7
+    assert_eq!(arrays::nth_smallest(&data, 0), None);
+    assert_eq!(arrays::nth_smallest(&data, 8), None);
+    assert_eq!(arrays::nth_largest(&data, 0), None);
+    assert_eq!(arrays::nth_smallest::<i32>(&[], 1), None);
+
+    // Works for anything Ord + Copy, like chars
+    assert_eq!(arrays::nth_smallest(&['q', 'a', 'z', 'm'], 2), Some('m'));
+
+    // Against the sorting version on random slices, for every n from 0 to len + 1
+    let mut rng = StdRng::seed_from_u64(126);
+    for _ in 0..200 {
+        let len = rng.random_range(0..50);
+        let slice: Vec<i32> = (0..len).map(|_| rng.random_range(-20..=20)).collect();
+        for n in 0..=len + 1 {
+            assert_eq!(
+                arrays::nth_smallest(&slice, n),
+                nth_smallest_by_sorting(&slice, n),
+                "{n}-th smallest of {slice:?}"
+            );
+            // The n-th largest is the (len - n + 1)-th smallest
+            let mirrored = (1..=len)
+                .contains(&n)
+                .then(|| nth_smallest_by_sorting(&slice, len - n + 1))
+                .flatten();
+            assert_eq!(arrays::nth_largest(&slice, n), mirrored);
+        }
+    }
+    println!("Heap and sorting agree on 200 random slices.");
+
+    // The reason to bother: the 10 smallest of a million numbers need a heap of 10
+    let big: Vec<u64> = (0..1_000_000).map(|_| rng.random()).collect();
+    let tenth = arrays::nth_smallest(&big, 10);
+    assert_eq!(tenth, nth_smallest_by_sorting(&big, 10));
+    println!("10th smallest of 1,000,000 random u64s: {}", tenth.unwrap());
+
+    // Reverse on its own, outside a heap: it flips any ordering
+    assert!(Reverse(1) > Reverse(2));
+    let mut min_heap: BinaryHeap<Reverse<i32>> = [5, 1, 3].into_iter().map(Reverse).collect();
+    assert_eq!(min_heap.pop(), Some(Reverse(1)));
+}
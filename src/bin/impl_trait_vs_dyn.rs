@@ -0,0 +1,135 @@
+// Three ways to take a function as an argument: a generic parameter, impl Trait,
+// and a &dyn trait object. The first two are the same thing; the third is a
+// different trade: one compiled copy and a call through a pointer, in exchange
+// for being able to mix different closures in one collection
+use std::hint::black_box;
+use std::mem::size_of_val;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 10_000_000;
+
+// Any function from i32 to i32, boxed, for the list of steps at the end
+type Step = Box<dyn Fn(i32) -> i32>;
+// transform_generic or transform_impl, made for fn pointers
+type Transform = fn(&[i32], fn(i32) -> i32) -> Vec<i32>;
+
+// (1) A generic: the compiler makes a copy of this function for every closure
+// type it's called with (monomorphization), with f's body inlined into the loop
+fn transform_generic<F: Fn(i32) -> i32>(v: &[i32], f: F) -> Vec<i32> {
+    v.iter().map(|&x| f(x)).collect()
+}
+
+// (2) A trait object: a single copy of this function, shared by every closure,
+// which calls f through the vtable pointer that sits next to the data pointer in &dyn
+fn transform_dyn(v: &[i32], f: &dyn Fn(i32) -> i32) -> Vec<i32> {
+    v.iter().map(|&x| f(x)).collect()
+}
+
+// (3) impl Trait in argument position: shorthand for (1), with a type parameter
+// that has no name. The one thing lost is the turbofish: callers can't write
+// transform_impl::<SomeType>(...), since there is no parameter to fill in
+fn transform_impl(v: &[i32], f: impl Fn(i32) -> i32) -> Vec<i32> {
+    v.iter().map(|&x| f(x)).collect()
+}
+
+// Runs `f` once, returning its result and how long it took
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+fn double_plus_one(x: i32) -> i32 {
+    x.wrapping_mul(2).wrapping_add(1)
+}
+
+fn main() {
+    let data: Vec<i32> = (0..LEN as i32).collect();
+    let offset = 7;
+    let shift = move |x: i32| x.wrapping_add(offset);
+
+    // ---- Timing ----
+    let (generic, generic_time) = time(|| transform_generic(black_box(&data), shift));
+    // black_box hides which closure the &dyn points to, as when it comes from
+    // somewhere else at run time; otherwise the optimizer can see through it
+    let (dynamic, dyn_time) = time(|| transform_dyn(black_box(&data), black_box(&shift)));
+    let (implicit, impl_time) = time(|| transform_impl(black_box(&data), shift));
+    assert_eq!(generic, dynamic);
+    assert_eq!(generic, implicit);
+    assert_eq!(generic[3], 10);
+    println!("{LEN} elements, x + {offset}:");
+    println!("  generic <F: Fn>  {generic_time:>12?}");
+    println!("  &dyn Fn          {dyn_time:>12?}");
+    println!("  impl Fn          {impl_time:>12?}");
+    // With optimizations the generic and impl versions inline the closure and
+    // can vectorize the loop; the dyn version makes a real call per element.
+    // Filling the new Vec costs the same in all three, which narrows the gap,
+    // and in a debug build nothing is inlined at all, so the numbers are close;
+    // try `cargo run --release --bin impl_trait_vs_dyn` to see the difference
+
+    // ---- (1) and (3) are the same function ----
+    // Each can be turned into a plain fn pointer by naming the closure type it
+    // should be made for. For the generic that's the <F>; for impl Trait it can
+    // only be inferred, here from the fn pointer type we ask for
+    let generic_for_fn: Transform = transform_generic;
+    let impl_for_fn: Transform = transform_impl;
+    let small = [1, 2, 3];
+    assert_eq!(generic_for_fn(&small, double_plus_one), [3, 5, 7]);
+    assert_eq!(impl_for_fn(&small, double_plus_one), [3, 5, 7]);
+    // Both accept exactly the same arguments, any closure, fn item or fn
+    // pointer, and each call with a new type makes one more copy in the binary
+    assert_eq!(
+        transform_impl(&small, shift),
+        transform_generic(&small, shift)
+    );
+    assert_eq!(
+        transform_impl(&small, double_plus_one),
+        transform_generic(&small, double_plus_one)
+    );
+    // Only the generic can be called with the type spelled out
+    let by_name = transform_generic::<fn(i32) -> i32>(&small, double_plus_one);
+    assert_eq!(by_name, [3, 5, 7]);
+
+    // ---- What a closure costs to pass ----
+    // A generic closure is passed as itself: just its captures, here one i32
+    // (and nothing at all for a closure that captures nothing). A &dyn is two
+    // pointers, the data and the vtable
+    let no_captures = |x: i32| x;
+    assert_eq!(size_of_val(&shift), size_of_val(&offset));
+    assert_eq!(size_of_val(&no_captures), 0);
+    let dyn_size = size_of::<&dyn Fn(i32) -> i32>();
+    assert_eq!(dyn_size, 2 * size_of::<usize>());
+    println!(
+        "\nPassed by value: shift closure {} bytes, capture-free closure {} bytes; &dyn Fn {} bytes",
+        size_of_val(&shift),
+        size_of_val(&no_captures),
+        dyn_size
+    );
+
+    // ---- Where only dyn works ----
+    // Every closure has its own type, so a Vec<F> can only hold one of them:
+    //
+    //     let steps = vec![|x| x + 1, |x| x * 2];
+    //     error[E0308]: mismatched types ... no two closures, even if identical, have the same type
+    //
+    // Boxing them as dyn Fn erases the types, so closures with different
+    // captures, and plain functions, can share the Vec
+    let factor = 3;
+    let label = String::from("negate");
+    let steps: Vec<(String, Step)> = vec![
+        ("add one".to_string(), Box::new(|x| x + 1)),
+        (format!("times {factor}"), Box::new(move |x| x * factor)),
+        (label, Box::new(|x: i32| -x)),
+        ("double plus one".to_string(), Box::new(double_plus_one)),
+    ];
+    let mut value = 4;
+    println!("\nA Vec<Box<dyn Fn(i32) -> i32>>, applied in turn to {value}:");
+    for (name, step) in &steps {
+        value = step(value);
+        println!("  {name:<16}-> {value}");
+    }
+    // ((4 + 1) * 3) negated, then doubled plus one
+    assert_eq!(value, -29);
+    // And each boxed step still works with transform_dyn, which takes it as is
+    assert_eq!(transform_dyn(&small, steps[1].1.as_ref()), [3, 6, 9]);
+}
@@ -0,0 +1,278 @@
+// Interval arithmetic: compute with ranges instead of single numbers
+// A measurement like "2.0 ± 0.1" becomes the interval [1.9, 2.1], and every
+// operation returns an interval guaranteed to contain every possible result,
+// so the error bound of a whole formula falls out of the calculation
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// Invariant: lo <= hi, checked by the only constructors below
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+#[derive(Debug, PartialEq)]
+struct NegativeError(Interval);
+
+impl fmt::Display for NegativeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} contains negative numbers", self.0)
+    }
+}
+
+impl Interval {
+    fn new(lo: f64, hi: f64) -> Interval {
+        assert!(lo <= hi, "interval [{lo}, {hi}] is empty");
+        Interval { lo, hi }
+    }
+
+    // A value known to within ± error
+    fn around(value: f64, error: f64) -> Interval {
+        Interval::new(value - error, value + error)
+    }
+
+    // Results of + - * / are rounded to the nearest f64, which may land just
+    // inside the true bound. Nudging lo down and hi up by one step each keeps
+    // the guarantee: the true result is always inside the interval
+    fn outward(lo: f64, hi: f64) -> Interval {
+        Interval::new(lo.next_down(), hi.next_up())
+    }
+
+    // next_down() of 0.0 is a tiny negative number; squares and roots can't be negative
+    fn clamp_at_zero(self) -> Interval {
+        Interval::new(self.lo.max(0.0), self.hi)
+    }
+
+    fn contains(&self, x: f64) -> bool {
+        self.lo <= x && x <= self.hi
+    }
+
+    fn overlaps(&self, other: &Interval) -> bool {
+        self.lo <= other.hi && other.lo <= self.hi
+    }
+
+    fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    fn midpoint(&self) -> f64 {
+        (self.lo + self.hi) / 2.0
+    }
+}
+
+// Which side of zero an interval is on; multiplication picks its bounds from this
+enum Sign {
+    Positive, // lo >= 0
+    Negative, // hi <= 0
+    Mixed,    // lo < 0 < hi
+}
+
+fn sign(i: Interval) -> Sign {
+    if i.lo >= 0.0 {
+        Sign::Positive
+    } else if i.hi <= 0.0 {
+        Sign::Negative
+    } else {
+        Sign::Mixed
+    }
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, other: Interval) -> Interval {
+        Interval::outward(self.lo + other.lo, self.hi + other.hi)
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    // The smallest difference is the smallest self minus the largest other
+    fn sub(self, other: Interval) -> Interval {
+        Interval::outward(self.lo - other.hi, self.hi - other.lo)
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    // Exact in floating point, so no widening needed
+    fn neg(self) -> Interval {
+        Interval::new(-self.hi, -self.lo)
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    // The extremes are always products of endpoints, but which ones depends on
+    // the signs. Working it out per case avoids computing all four products,
+    // and only the Mixed x Mixed case has to compare two candidates per bound
+    fn mul(self, other: Interval) -> Interval {
+        let (a, b) = (self, other);
+        let (lo, hi) = match (sign(a), sign(b)) {
+            (Sign::Positive, Sign::Positive) => (a.lo * b.lo, a.hi * b.hi),
+            (Sign::Positive, Sign::Negative) => (a.hi * b.lo, a.lo * b.hi),
+            (Sign::Positive, Sign::Mixed) => (a.hi * b.lo, a.hi * b.hi),
+            (Sign::Negative, Sign::Positive) => (a.lo * b.hi, a.hi * b.lo),
+            (Sign::Negative, Sign::Negative) => (a.hi * b.hi, a.lo * b.lo),
+            (Sign::Negative, Sign::Mixed) => (a.lo * b.hi, a.lo * b.lo),
+            (Sign::Mixed, Sign::Positive) => (a.lo * b.hi, a.hi * b.hi),
+            (Sign::Mixed, Sign::Negative) => (a.hi * b.lo, a.lo * b.lo),
+            (Sign::Mixed, Sign::Mixed) => (
+                (a.lo * b.hi).min(a.hi * b.lo),
+                (a.lo * b.lo).max(a.hi * b.hi),
+            ),
+        };
+        Interval::outward(lo, hi)
+    }
+}
+
+impl Div for Interval {
+    type Output = Interval;
+
+    // a / b = a * (1 / b), and 1/b flips the bounds
+    // If b contains zero the quotient is unbounded, so that is treated as a bug
+    fn div(self, other: Interval) -> Interval {
+        assert!(
+            !other.contains(0.0),
+            "division by {other}, which contains zero"
+        );
+        self * Interval::outward(1.0 / other.hi, 1.0 / other.lo)
+    }
+}
+
+// Honors a precision like {:.3} for both ends
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(p) => write!(f, "[{:.p$}, {:.p$}]", self.lo, self.hi),
+            None => write!(f, "[{}, {}]", self.lo, self.hi),
+        }
+    }
+}
+
+// sqrt is increasing, so the bounds are just the square roots of the ends
+fn sqrt_interval(i: Interval) -> Result<Interval, NegativeError> {
+    if i.lo < 0.0 {
+        return Err(NegativeError(i));
+    }
+    Ok(Interval::outward(i.lo.sqrt(), i.hi.sqrt()).clamp_at_zero())
+}
+
+// Not the same as multiplying i by itself n times: x * x treats the two x's as
+// independent, so [-1, 2] * [-1, 2] = [-2, 4], while x² is really [0, 4]
+fn pow_interval(i: Interval, n: u32) -> Interval {
+    let (lo, hi) = (i.lo.powi(n as i32), i.hi.powi(n as i32));
+    match n {
+        0 => Interval::new(1.0, 1.0),
+        // Odd powers keep the order of the ends
+        n if n % 2 == 1 => Interval::outward(lo, hi),
+        // Even powers of a range around zero bottom out at zero
+        _ => match sign(i) {
+            Sign::Positive => Interval::outward(lo, hi).clamp_at_zero(),
+            Sign::Negative => Interval::outward(hi, lo).clamp_at_zero(),
+            Sign::Mixed => Interval::new(0.0, lo.max(hi).next_up()),
+        },
+    }
+}
+
+// The tightest product bounds, found the slow way for comparison
+fn mul_by_all_products(a: Interval, b: Interval) -> (f64, f64) {
+    let products = [a.lo * b.lo, a.lo * b.hi, a.hi * b.lo, a.hi * b.hi];
+    let lo = products.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = products.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    (lo, hi)
+}
+
+fn main() {
+    let x = Interval::new(1.9, 2.1);
+    let y = Interval::new(0.9, 1.1);
+    println!("x = {x}, y = {y}");
+    println!("x + y = {:.4}", x + y);
+    println!("x - y = {:.4}", x - y);
+    println!("x * y = {:.4}", x * y);
+    println!("x / y = {:.4}", x / y);
+    println!("-x    = {}", -x);
+    assert!((x + y).contains(3.0) && (x - y).contains(1.0));
+    assert_eq!(-x, Interval::new(-2.1, -1.9));
+
+    // 1. Every sign combination gives the same bounds as trying all four products
+    // (our result is one step wider on each side from the outward rounding)
+    let samples = [
+        Interval::new(1.0, 3.0),
+        Interval::new(-4.0, -2.0),
+        Interval::new(-1.0, 5.0),
+        Interval::new(-6.0, 2.0),
+        Interval::new(0.0, 2.0),
+        Interval::new(-3.0, 0.0),
+    ];
+    for a in samples {
+        for b in samples {
+            let (lo, hi) = mul_by_all_products(a, b);
+            assert_eq!(a * b, Interval::outward(lo, hi), "{a} * {b}");
+        }
+    }
+    println!("[-1, 5] * [-6, 2] = {:.4}", samples[2] * samples[3]);
+
+    // 2. The same value written two ways gives two different intervals
+    // Each formula uses x and y twice, and interval arithmetic treats every use as
+    // if it could vary on its own (the dependency problem). In (x + y) * (x - y)
+    // the largest x + y and the largest x - y need opposite y's, so its bound is
+    // looser than the true range, [2.4, 3.6]. Both are still valid: they overlap,
+    // and both contain the exact result for any x and y in range
+    let factored = (x + y) * (x - y);
+    let expanded = x * x - y * y;
+    let squared = pow_interval(x, 2) - pow_interval(y, 2);
+    println!(
+        "(x + y) * (x - y) = {factored:.4}, width {:.4}",
+        factored.width()
+    );
+    println!(
+        "x*x - y*y         = {expanded:.4}, width {:.4}",
+        expanded.width()
+    );
+    println!(
+        "x^2 - y^2         = {squared:.4}, width {:.4}",
+        squared.width()
+    );
+    assert!(factored.overlaps(&expanded) && factored.overlaps(&squared));
+    for (xv, yv) in [(1.9, 0.9), (2.0, 1.0), (2.1, 1.1), (1.9, 1.1), (2.1, 0.9)] {
+        let exact = xv * xv - yv * yv;
+        assert!(factored.contains(exact) && expanded.contains(exact));
+    }
+
+    // 3. Powers and square roots
+    let around_zero = Interval::new(-1.0, 2.0);
+    println!(
+        "[-1, 2]^2 = {:.4}, but [-1, 2] * [-1, 2] = {:.4}",
+        pow_interval(around_zero, 2),
+        around_zero * around_zero
+    );
+    assert_eq!(pow_interval(around_zero, 2).lo, 0.0);
+    assert!(pow_interval(Interval::new(-3.0, -2.0), 2).contains(4.0));
+    assert!(pow_interval(Interval::new(-3.0, -2.0), 3).contains(-27.0));
+    assert_eq!(pow_interval(x, 0), Interval::new(1.0, 1.0));
+    let root = sqrt_interval(Interval::new(4.0, 9.0)).unwrap();
+    println!("sqrt([4, 9]) = {root:.4}");
+    assert!(root.contains(2.0) && root.contains(3.0));
+    let negative = sqrt_interval(Interval::new(-1.0, 4.0));
+    println!("sqrt([-1, 4]) = {}", negative.as_ref().unwrap_err());
+    assert!(negative.is_err());
+
+    // 4. Error bounds for a formula: the period of a pendulum, T = 2π √(L / g)
+    // Length measured as 1.00 m ± 1 cm, g known as 9.81 ± 0.02 m/s²
+    let length = Interval::around(1.00, 0.01);
+    let gravity = Interval::around(9.81, 0.02);
+    let two_pi = Interval::new(2.0 * std::f64::consts::PI, 2.0 * std::f64::consts::PI);
+    let period = two_pi * sqrt_interval(length / gravity).unwrap();
+    println!(
+        "Pendulum: L = {length:.2} m, g = {gravity:.2} m/s², T = {period:.4} s ({:.4} ± {:.4} s)",
+        period.midpoint(),
+        period.width() / 2.0
+    );
+    let nominal = 2.0 * std::f64::consts::PI * (1.00f64 / 9.81).sqrt();
+    assert!(period.contains(nominal));
+}
@@ -0,0 +1,278 @@
+// The four I/O traits: Read, Write, BufRead and Seek
+// Read and Write move bytes a buffer at a time and say how many they moved;
+// BufRead adds an internal buffer so lines can be read without a system call
+// per byte; Seek moves the position a reader or writer is at. Because each is
+// a small trait, wrappers that implement one around another compose freely:
+// the three below are stacked onto a file at the end
+use std::fs;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::process;
+
+// ---- LimitedReader: at most `remaining` more bytes, then end of file ----
+// std's Read::take does this job; it is written out here to show how little
+// a Read impl needs. Returning Ok(0) is how a reader says it has ended
+struct LimitedReader<R: Read> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R: Read> LimitedReader<R> {
+    fn new(inner: R, limit: u64) -> LimitedReader<R> {
+        LimitedReader {
+            inner,
+            remaining: limit,
+        }
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining == 0 {
+            return Ok(0);
+        }
+        // Only offer the inner reader as much room as is left, so it can't
+        // hand over bytes past the limit
+        let max = buf
+            .len()
+            .min(self.remaining.try_into().unwrap_or(usize::MAX));
+        let n = self.inner.read(&mut buf[..max])?;
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+// ---- Tee: every byte read is also written somewhere else ----
+struct Tee<R: Read, W: Write> {
+    reader: R,
+    writer: W,
+}
+
+impl<R: Read, W: Write> Tee<R, W> {
+    fn new(reader: R, writer: W) -> Tee<R, W> {
+        Tee { reader, writer }
+    }
+}
+
+impl<R: Read, W: Write> Read for Tee<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        // write() may take only part of what it is given; write_all keeps
+        // going until all of it is written or there is an error
+        self.writer.write_all(&buf[..n])?;
+        Ok(n)
+    }
+}
+
+// ---- LineCounter: passes writes through and counts the newlines ----
+struct LineCounter<W: Write> {
+    inner: W,
+    count: usize,
+}
+
+impl<W: Write> LineCounter<W> {
+    fn new(inner: W) -> LineCounter<W> {
+        LineCounter { inner, count: 0 }
+    }
+}
+
+impl<W: Write> Write for LineCounter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Count only what the inner writer took; the caller passes the rest
+        // again, and counting it now would count it twice
+        let n = self.inner.write(buf)?;
+        self.count += buf[..n].iter().filter(|&&b| b == b'\n').count();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// A reader that never ends: 0, 1, 2, ..., 255, 0, 1, ... Unlike
+// io::repeat its bytes differ, so a test can see which ones it got
+struct Counting(u8);
+
+impl Read for Counting {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        for b in buf.iter_mut() {
+            *b = self.0;
+            self.0 = self.0.wrapping_add(1);
+        }
+        Ok(buf.len())
+    }
+}
+
+// A reader that hands out at most `chunk` bytes per call, the way a pipe or
+// socket may, to show that callers must loop rather than expect a full buffer
+struct Trickle<R: Read> {
+    inner: R,
+    chunk: usize,
+}
+
+impl<R: Read> Read for Trickle<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let max = buf.len().min(self.chunk);
+        self.inner.read(&mut buf[..max])
+    }
+}
+
+fn main() -> io::Result<()> {
+    // ---- LimitedReader on a source that never ends ----
+    for limit in [0, 1, 7, 1000, 65_536] {
+        let mut bytes = Vec::new();
+        LimitedReader::new(Counting(0), limit).read_to_end(&mut bytes)?;
+        assert_eq!(bytes.len() as u64, limit);
+        assert!(bytes.iter().enumerate().all(|(i, &b)| b == i as u8));
+        // The same as std's own
+        let mut theirs = Vec::new();
+        Counting(0).take(limit).read_to_end(&mut theirs)?;
+        assert_eq!(bytes, theirs);
+    }
+    // With a buffer bigger than the limit, one read returns what's left
+    let mut limited = LimitedReader::new(io::repeat(b'x'), 5);
+    let mut buf = [0; 16];
+    assert_eq!(limited.read(&mut buf)?, 5);
+    assert_eq!(&buf[..5], b"xxxxx");
+    assert_eq!(limited.read(&mut buf)?, 0);
+    // Through a source that trickles 3 bytes at a time it still stops at the limit
+    let trickle = Trickle {
+        inner: Counting(0),
+        chunk: 3,
+    };
+    let mut bytes = Vec::new();
+    LimitedReader::new(trickle, 10).read_to_end(&mut bytes)?;
+    assert_eq!(bytes, (0..10).collect::<Vec<u8>>());
+    println!("LimitedReader stops at exactly N bytes of an endless source");
+
+    // ---- Tee and LineCounter ----
+    let mut copy = Vec::new();
+    let mut text = String::new();
+    Tee::new(&b"one\ntwo\n"[..], &mut copy).read_to_string(&mut text)?;
+    assert_eq!(text, "one\ntwo\n");
+    assert_eq!(copy, text.as_bytes());
+    let mut counter = LineCounter::new(Vec::new());
+    write!(counter, "a\nb\n")?;
+    writeln!(counter, "c")?;
+    assert_eq!(counter.count, 3);
+    assert_eq!(counter.inner, b"a\nb\nc\n");
+    println!(
+        "Tee copied {} bytes; LineCounter counted {} lines",
+        copy.len(),
+        counter.count
+    );
+
+    // ---- Cursor: a Vec<u8> that is both Read and Write, with a position ----
+    let mut cursor = Cursor::new(Vec::new());
+    writeln!(cursor, "first line")?;
+    writeln!(cursor, "second line")?;
+    // Writing left the position at the end, so reading now finds nothing
+    let mut rest = String::new();
+    cursor.read_to_string(&mut rest)?;
+    assert_eq!(rest, "");
+    // Seek back to the start to read it all again
+    cursor.seek(SeekFrom::Start(0))?;
+    cursor.read_to_string(&mut rest)?;
+    assert_eq!(rest, "first line\nsecond line\n");
+    // From the end, or from where it is now
+    cursor.seek(SeekFrom::End(-5))?;
+    let mut last = String::new();
+    cursor.read_to_string(&mut last)?;
+    assert_eq!(last, "line\n");
+    cursor.seek(SeekFrom::Start(6))?;
+    cursor.seek(SeekFrom::Current(-6))?;
+    assert_eq!(cursor.stream_position()?, 0);
+    // Writing in the middle overwrites rather than inserting
+    cursor.seek(SeekFrom::Start(0))?;
+    cursor.write_all(b"FIRST")?;
+    assert_eq!(cursor.get_ref().as_slice(), b"FIRST line\nsecond line\n");
+    // Seeking before the start is an error rather than a wrap around
+    assert!(cursor.seek(SeekFrom::Current(-100)).is_err());
+    println!("Cursor wrote two lines, sought back and read them again");
+
+    // ---- BufRead: lines, read_line, read_until, and the buffer itself ----
+    // Cursor is BufRead already, since all of its bytes are in memory
+    let mut input = Cursor::new("alpha\nbeta\r\ngamma");
+    let mut line = String::new();
+    // read_line keeps the newline and appends, so the String is cleared each time
+    input.read_line(&mut line)?;
+    assert_eq!(line, "alpha\n");
+    line.clear();
+    input.read_line(&mut line)?;
+    assert_eq!(line, "beta\r\n");
+    // lines() drops the "\n" or "\r\n", and the last line needn't have one
+    let lines: Vec<String> = Cursor::new("alpha\nbeta\r\ngamma")
+        .lines()
+        .collect::<io::Result<_>>()?;
+    assert_eq!(lines, ["alpha", "beta", "gamma"]);
+    // read_until splits on any byte, here the commas of a record
+    let mut fields = Vec::new();
+    let mut record = Cursor::new("a,bb,ccc");
+    loop {
+        let mut field = Vec::new();
+        if record.read_until(b',', &mut field)? == 0 {
+            break;
+        }
+        fields.push(
+            String::from_utf8_lossy(&field)
+                .trim_end_matches(',')
+                .to_string(),
+        );
+    }
+    assert_eq!(fields, ["a", "bb", "ccc"]);
+    // fill_buf shows what is buffered without using it up; consume uses it up
+    let mut reader = BufReader::with_capacity(4, &b"abcdefgh"[..]);
+    assert_eq!(reader.fill_buf()?, b"abcd");
+    reader.consume(2);
+    assert_eq!(reader.fill_buf()?, b"cd");
+    reader.consume(2);
+    assert_eq!(reader.fill_buf()?, b"efgh");
+    println!(
+        "BufRead read {} lines and {} fields",
+        lines.len(),
+        fields.len()
+    );
+
+    // ---- All of them at once, on a real file ----
+    let path = std::env::temp_dir().join(format!("io-traits-{}.txt", process::id()));
+    let mut file = fs::File::create(&path)?;
+    for n in 1..=200 {
+        writeln!(file, "line {n:03}: the quick brown fox")?;
+    }
+    drop(file);
+    let file = fs::File::open(&path)?;
+
+    // file -> Tee (copies into the LineCounter) -> LimitedReader (1024 bytes)
+    // -> BufReader (finds the lines). Each layer only knows it has a Read
+    let mut reader = BufReader::new(LimitedReader::new(
+        Tee::new(file, LineCounter::new(io::sink())),
+        1024,
+    ));
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        lines.push(std::mem::take(&mut line));
+    }
+    fs::remove_file(&path)?;
+
+    // Each line is 30 bytes, so 1024 bytes is 34 whole lines and 4 bytes of
+    // the 35th. The limit knows nothing of lines, so the last one is cut off
+    let bytes: usize = lines.iter().map(String::len).sum();
+    assert_eq!(bytes, 1024);
+    assert_eq!(lines.len(), 35);
+    assert_eq!(lines[0], "line 001: the quick brown fox\n");
+    assert_eq!(lines[34], "line");
+    // Taking the layers apart again to reach the counter at the bottom. It saw
+    // exactly what came through the limit, since LimitedReader never asks
+    // the Tee for more than it will pass on
+    let limited = reader.into_inner();
+    assert_eq!(limited.remaining, 0);
+    let counter = limited.inner.writer;
+    assert_eq!(counter.count, 34);
+    println!(
+        "Read {} lines ({bytes} bytes) through the stack; the counter saw {} newlines",
+        lines.len(),
+        counter.count
+    );
+    Ok(())
+}
@@ -0,0 +1,288 @@
+// Iterator adaptors written out by hand: chain, zip, enumerate, flat_map, step_by
+// An adaptor is a struct that owns the iterator (or iterators) it wraps and
+// implements Iterator by calling their next(). Nothing happens until someone
+// calls next() on the outside, which is why adaptors are lazy. Each one below
+// does the job of the std adaptor of the same name, and main checks that
+// they agree on random inputs
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CASES: usize = 100;
+
+// ---- Chain: all of `a`, then all of `b` ----
+// `a` is set to None once it runs out, so it is never asked again; an
+// iterator that has returned None may start returning items again unless it
+// is fused, and Chain shouldn't depend on that
+struct Chain<A, B> {
+    a: Option<A>,
+    b: B,
+}
+
+impl<A, B> Iterator for Chain<A, B>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<A::Item> {
+        if let Some(a) = &mut self.a {
+            match a.next() {
+                Some(item) => return Some(item),
+                None => self.a = None,
+            }
+        }
+        self.b.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_low, a_high) = self.a.as_ref().map_or((0, Some(0)), |a| a.size_hint());
+        let (b_low, b_high) = self.b.size_hint();
+        let high = match (a_high, b_high) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None,
+        };
+        (a_low.saturating_add(b_low), high)
+    }
+}
+
+// len() comes from size_hint, which is exact when both sides' are. std's
+// Chain leaves this out, since the sum could overflow usize; here that would
+// make len() panic, which is acceptable for a lesson
+impl<A, B> ExactSizeIterator for Chain<A, B>
+where
+    A: ExactSizeIterator,
+    B: ExactSizeIterator<Item = A::Item>,
+{
+}
+
+// ---- Zip: pairs, until either side runs out ----
+struct Zip<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Iterator, B: Iterator> Iterator for Zip<A, B> {
+    type Item = (A::Item, B::Item);
+
+    fn next(&mut self) -> Option<(A::Item, B::Item)> {
+        // `a` is asked first, so when it is the shorter one `b` loses nothing
+        let a = self.a.next()?;
+        let b = self.b.next()?;
+        Some((a, b))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (a_low, a_high) = self.a.size_hint();
+        let (b_low, b_high) = self.b.size_hint();
+        let high = match (a_high, b_high) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (Some(n), None) | (None, Some(n)) => Some(n),
+            (None, None) => None,
+        };
+        (a_low.min(b_low), high)
+    }
+}
+
+impl<A: ExactSizeIterator, B: ExactSizeIterator> ExactSizeIterator for Zip<A, B> {}
+
+// ---- Enumerate: each item with its position ----
+struct Enumerate<I> {
+    iter: I,
+    count: usize,
+}
+
+impl<I: Iterator> Iterator for Enumerate<I> {
+    type Item = (usize, I::Item);
+
+    fn next(&mut self) -> Option<(usize, I::Item)> {
+        let item = self.iter.next()?;
+        let index = self.count;
+        self.count += 1;
+        Some((index, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+// ---- FlatMap: map each item to an iterable, then walk through each ----
+// `front` is the inner iterator in progress; when it runs out, the next item
+// of the outer one is mapped to a new inner one
+struct FlatMap<I, F, U: IntoIterator> {
+    iter: I,
+    f: F,
+    front: Option<U::IntoIter>,
+}
+
+impl<I, F, U> Iterator for FlatMap<I, F, U>
+where
+    I: Iterator,
+    F: Fn(I::Item) -> U,
+    U: IntoIterator,
+{
+    type Item = U::Item;
+
+    fn next(&mut self) -> Option<U::Item> {
+        loop {
+            if let Some(front) = &mut self.front
+                && let Some(item) = front.next()
+            {
+                return Some(item);
+            }
+            // A loop, not recursion: many empty inner iterators in a row
+            // would otherwise mean as many nested calls
+            let next = self.iter.next()?;
+            self.front = Some((self.f)(next).into_iter());
+        }
+    }
+}
+
+// ---- StepBy: the first item, then every `step`-th after it ----
+struct StepBy<I> {
+    iter: I,
+    // How many items to skip before the next one; 0 only before the first
+    skip: usize,
+    step: usize,
+}
+
+impl<I: Iterator> Iterator for StepBy<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        for _ in 0..self.skip {
+            self.iter.next()?;
+        }
+        self.skip = self.step - 1;
+        self.iter.next()
+    }
+}
+
+// The constructors, as methods on every iterator the way std's are. The my_
+// prefix keeps them from clashing with the std methods of the same names.
+// Like std's, chain and zip take anything iterable as the second argument
+trait MyIteratorExt: Iterator + Sized {
+    fn my_chain<B: IntoIterator<Item = Self::Item>>(self, other: B) -> Chain<Self, B::IntoIter> {
+        Chain {
+            a: Some(self),
+            b: other.into_iter(),
+        }
+    }
+
+    fn my_zip<B: IntoIterator>(self, other: B) -> Zip<Self, B::IntoIter> {
+        Zip {
+            a: self,
+            b: other.into_iter(),
+        }
+    }
+
+    fn my_enumerate(self) -> Enumerate<Self> {
+        Enumerate {
+            iter: self,
+            count: 0,
+        }
+    }
+
+    fn my_flat_map<U: IntoIterator, F: Fn(Self::Item) -> U>(self, f: F) -> FlatMap<Self, F, U> {
+        FlatMap {
+            iter: self,
+            f,
+            front: None,
+        }
+    }
+
+    // Panics on a step of 0, like std's, which would never move on
+    fn my_step_by(self, step: usize) -> StepBy<Self> {
+        assert!(step > 0, "step_by needs a step of at least 1");
+        StepBy {
+            iter: self,
+            skip: 0,
+            step,
+        }
+    }
+}
+
+impl<I: Iterator> MyIteratorExt for I {}
+
+// A list of random length (empty now and then) and random contents
+fn random_vec(rng: &mut StdRng) -> Vec<i32> {
+    let len = rng.random_range(0..=20);
+    (0..len).map(|_| rng.random_range(-50..=50)).collect()
+}
+
+fn main() {
+    // ---- Worked examples ----
+    let chained: Vec<i32> = [1, 2].into_iter().my_chain([3]).collect();
+    assert_eq!(chained, [1, 2, 3]);
+    let zipped: Vec<(char, i32)> = "abc".chars().my_zip(1..).collect();
+    assert_eq!(zipped, [('a', 1), ('b', 2), ('c', 3)]);
+    let numbered: Vec<(usize, &str)> = ["x", "y"].into_iter().my_enumerate().collect();
+    assert_eq!(numbered, [(0, "x"), (1, "y")]);
+    let words: Vec<char> = ["hi", "", "yo"]
+        .into_iter()
+        .my_flat_map(|word| word.chars())
+        .collect();
+    assert_eq!(words, ['h', 'i', 'y', 'o']);
+    let stepped: Vec<i32> = (0..10).my_step_by(3).collect();
+    assert_eq!(stepped, [0, 3, 6, 9]);
+    println!("chain {chained:?}, zip {zipped:?}, enumerate {numbered:?}");
+    println!("flat_map {words:?}, step_by(3) {stepped:?}");
+
+    // The adaptors nest like the std ones, since each is just an Iterator
+    let nested: Vec<(usize, i32)> = (1..=3)
+        .my_flat_map(|n| (0..n).my_chain([n * 10]))
+        .my_step_by(2)
+        .my_enumerate()
+        .collect();
+    let std_nested: Vec<(usize, i32)> = (1..=3)
+        .flat_map(|n| (0..n).chain([n * 10]))
+        .step_by(2)
+        .enumerate()
+        .collect();
+    assert_eq!(nested, std_nested);
+
+    // ---- ExactSizeIterator ----
+    let both = vec![1, 2, 3].into_iter().my_chain(vec![4, 5]);
+    assert_eq!(both.len(), 5);
+    let pairs = [1, 2, 3].iter().my_zip(["a", "b"].iter());
+    assert_eq!(pairs.len(), 2);
+    // A Chain over an iterator with no exact length has no len(); this
+    // doesn't compile, because Filter isn't ExactSizeIterator:
+    //
+    //     (0..5).filter(|n| n % 2 == 0).my_chain(0..2).len();
+    //     error[E0599]: the method `len` exists for struct `Chain<...>`, but its trait bounds were not satisfied
+
+    // ---- The same output as std, on random inputs ----
+    // A fixed seed, so a failure shows up on every run
+    let mut rng = StdRng::seed_from_u64(142);
+    for _ in 0..CASES {
+        let (a, b) = (random_vec(&mut rng), random_vec(&mut rng));
+
+        let mine: Vec<i32> = a.iter().copied().my_chain(b.iter().copied()).collect();
+        let theirs: Vec<i32> = a.iter().copied().chain(b.iter().copied()).collect();
+        assert_eq!(mine, theirs);
+        assert_eq!(a.iter().my_chain(b.iter()).len(), a.len() + b.len());
+
+        let mine: Vec<(i32, i32)> = a.iter().copied().my_zip(b.iter().copied()).collect();
+        let theirs: Vec<(i32, i32)> = a.iter().copied().zip(b.iter().copied()).collect();
+        assert_eq!(mine, theirs);
+        assert_eq!(a.iter().my_zip(b.iter()).len(), a.len().min(b.len()));
+
+        let mine: Vec<(usize, &i32)> = a.iter().my_enumerate().collect();
+        let theirs: Vec<(usize, &i32)> = a.iter().enumerate().collect();
+        assert_eq!(mine, theirs);
+
+        // Each number n becomes |n| % 4 copies of itself, so some become none
+        let copies = |n: &i32| vec![*n; (n.unsigned_abs() % 4) as usize];
+        let mine: Vec<i32> = a.iter().my_flat_map(copies).collect();
+        let theirs: Vec<i32> = a.iter().flat_map(copies).collect();
+        assert_eq!(mine, theirs);
+
+        let step = rng.random_range(1..=5);
+        let mine: Vec<&i32> = a.iter().my_step_by(step).collect();
+        let theirs: Vec<&i32> = a.iter().step_by(step).collect();
+        assert_eq!(mine, theirs);
+    }
+    println!("All five agree with std on {CASES} random inputs");
+}
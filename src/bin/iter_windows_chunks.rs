@@ -0,0 +1,151 @@
+// Looking at a slice a few elements at a time: windows, chunks, and friends
+// All of these borrow from the original slice; nothing is copied
+
+// Groups runs of neighbouring elements that share a key
+// Equal keys that aren't next to each other end up in separate groups, like
+// the Unix `uniq` command (the standard library's version is chunk_by)
+fn group_by_key<T, K, F>(v: &[T], key: F) -> Vec<Vec<&T>>
+where
+    F: Fn(&T) -> K,
+    K: Eq,
+{
+    let mut groups: Vec<Vec<&T>> = Vec::new();
+    let mut last_key: Option<K> = None;
+    for item in v {
+        let k = key(item);
+        match groups.last_mut() {
+            Some(group) if last_key.as_ref() == Some(&k) => group.push(item),
+            _ => groups.push(vec![item]),
+        }
+        last_key = Some(k);
+    }
+    groups
+}
+
+// Each window is one overlapping slice of `window` elements
+// A window larger than the data gives no averages at all
+fn sliding_average(data: &[f64], window: usize) -> Vec<f64> {
+    if window == 0 {
+        return Vec::new();
+    }
+    data.windows(window)
+        .map(|w| w.iter().sum::<f64>() / window as f64)
+        .collect()
+}
+
+// Rotate left by k: the first k elements move to the back
+// The standard library has rotate_left, which does this in place
+fn rotate_slice<T: Clone>(v: &[T], k: usize) -> Vec<T> {
+    if v.is_empty() {
+        return Vec::new();
+    }
+    let (front, back) = v.split_at(k % v.len());
+    [back, front].concat()
+}
+
+fn main() {
+    let v = vec![1, 2, 3, 4, 5, 6, 7];
+
+    // windows(n): every run of n neighbours, overlapping
+    let windows: Vec<&[i32]> = v.windows(3).collect();
+    println!("windows(3):      {windows:?}");
+    assert_eq!(
+        windows,
+        [
+            &[1, 2, 3][..],
+            &[2, 3, 4],
+            &[3, 4, 5],
+            &[4, 5, 6],
+            &[5, 6, 7]
+        ]
+    );
+    assert_eq!(v.windows(8).count(), 0);
+
+    // chunks(n): non-overlapping pieces; the last one may be shorter
+    let chunks: Vec<&[i32]> = v.chunks(3).collect();
+    println!("chunks(3):       {chunks:?}");
+    assert_eq!(chunks, [&[1, 2, 3][..], &[4, 5, 6], &[7]]);
+
+    // chunks_exact(n): only full pieces, and the leftovers are kept separately
+    let mut exact = v.chunks_exact(3);
+    let full: Vec<&[i32]> = exact.by_ref().collect();
+    println!(
+        "chunks_exact(3): {full:?}, remainder {:?}",
+        exact.remainder()
+    );
+    assert_eq!(full, [&[1, 2, 3][..], &[4, 5, 6]]);
+    assert_eq!(exact.remainder(), &[7]);
+
+    // rchunks(n): chunks counted from the end, so the short one is at the front
+    let rchunks: Vec<&[i32]> = v.rchunks(3).collect();
+    println!("rchunks(3):      {rchunks:?}");
+    assert_eq!(rchunks, [&[5, 6, 7][..], &[2, 3, 4], &[1]]);
+
+    // group_by_key
+    let numbers = [1, 3, 5, 2, 4, 7, 9, 6];
+    let by_parity = group_by_key(&numbers, |n| n % 2);
+    println!("grouped by parity: {by_parity:?}");
+    assert_eq!(
+        by_parity,
+        [vec![&1, &3, &5], vec![&2, &4], vec![&7, &9], vec![&6]]
+    );
+    let words = [
+        "apple",
+        "avocado",
+        "banana",
+        "blueberry",
+        "cherry",
+        "apricot",
+    ];
+    let by_letter = group_by_key(&words, |w| w.chars().next());
+    assert_eq!(
+        by_letter,
+        [
+            vec![&"apple", &"avocado"],
+            vec![&"banana", &"blueberry"],
+            vec![&"cherry"],
+            vec![&"apricot"],
+        ]
+    );
+    assert!(group_by_key(&[] as &[i32], |n| *n).is_empty());
+
+    // sliding_average
+    let temperatures = [20.0, 22.0, 24.0, 23.0, 21.0];
+    let smoothed = sliding_average(&temperatures, 3);
+    println!("3-day averages: {smoothed:?}");
+    assert_eq!(smoothed, [22.0, 23.0, 22.666666666666668]);
+    assert_eq!(sliding_average(&[1.0, 2.0], 1), [1.0, 2.0]);
+    assert!(sliding_average(&[1.0, 2.0], 3).is_empty());
+
+    // split_at: two slices, before and from the index
+    let (left, right) = v.split_at(2);
+    assert_eq!((left, right), (&[1, 2][..], &[3, 4, 5, 6, 7][..]));
+
+    // split_at_mut: two &mut halves at once, which two separate &mut v[..] borrows
+    // couldn't give us, because the borrow checker can't tell they don't overlap
+    let mut scores = [10, 20, 30, 40];
+    let (first_half, second_half) = scores.split_at_mut(2);
+    first_half[0] += second_half[1];
+    second_half[0] = 0;
+    println!("after split_at_mut: {scores:?}");
+    assert_eq!(scores, [50, 20, 0, 40]);
+
+    // split_first / split_last: one element and the rest, or None when empty
+    assert_eq!(v.split_first(), Some((&1, &[2, 3, 4, 5, 6, 7][..])));
+    assert_eq!(v.split_last(), Some((&7, &[1, 2, 3, 4, 5, 6][..])));
+    let empty: [i32; 0] = [];
+    assert_eq!(empty.split_first(), None);
+
+    // starts_with / ends_with compare against another slice
+    assert!(v.starts_with(&[1, 2]));
+    assert!(!v.starts_with(&[2]));
+    assert!(v.ends_with(&[6, 7]));
+    assert!(v.ends_with(&[]));
+
+    // rotate_slice
+    assert_eq!(rotate_slice(&v, 2), [3, 4, 5, 6, 7, 1, 2]);
+    assert_eq!(rotate_slice(&["a", "b", "c"], 4), ["b", "c", "a"]);
+    assert_eq!(rotate_slice(&v, 0), v);
+    assert!(rotate_slice::<i32>(&[], 3).is_empty());
+    println!("rotated by 2: {:?}", rotate_slice(&v, 2));
+}
@@ -0,0 +1,118 @@
+// Changing a Vec in place: drain, split_off, retain, dedup and friends
+// Each of these works on the Vec's own buffer, moving elements around inside
+// it rather than building a new Vec from an iterator
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// Keeps the first copy of every element, in the order they first appeared
+// HashSet::insert returns false for a value already in the set, which marks an
+// element to drop. The set can't hold references while retain runs, since retain
+// borrows the Vec mutably, and taking copies would need T: Clone; so decide first,
+// with the set borrowing the elements, and let that borrow end before retain
+fn remove_duplicates_preserving_order<T: Eq + Hash>(mut v: Vec<T>) -> Vec<T> {
+    let keep: Vec<bool> = {
+        let mut seen = HashSet::new();
+        v.iter().map(|x| seen.insert(x)).collect()
+    };
+    // retain visits the elements in order, so the flags line up with them
+    let mut keep = keep.into_iter();
+    v.retain(|_| keep.next().expect("one flag per element"));
+    v
+}
+
+fn main() {
+    // drain(range) takes a sub-slice out and hands it over as an iterator;
+    // everything after it shifts down to close the gap
+    let mut v: Vec<i32> = (1..=10).collect();
+    let middle: Vec<i32> = v.drain(3..7).collect();
+    assert_eq!(middle, [4, 5, 6, 7]);
+    assert_eq!(v, [1, 2, 3, 8, 9, 10]);
+    println!("drain(3..7) took {middle:?}, leaving {v:?}");
+
+    // drain(..) empties the Vec but keeps its buffer, ready to be refilled
+    // (to move everything into a new Vec instead, std::mem::take(&mut v) does it
+    // without copying; clippy suggests that for drain(..).collect())
+    let capacity = v.capacity();
+    let total: i32 = v.drain(..).sum();
+    assert!(v.is_empty());
+    assert_eq!(v.capacity(), capacity);
+    assert_eq!(total, 33);
+
+    // split_off(at) moves everything from `at` on into a new Vec with its own
+    // buffer: two owners where there was one
+    let mut front = vec!["a", "b", "c", "d", "e"];
+    let back = front.split_off(2);
+    assert_eq!(front, ["a", "b"]);
+    assert_eq!(back, ["c", "d", "e"]);
+    println!("split_off(2): {front:?} and {back:?}");
+
+    // retain keeps what the closure says yes to, shuffling the kept elements
+    // down in one pass; nothing is allocated
+    let mut numbers: Vec<i32> = (1..=20).collect();
+    let buffer = numbers.as_ptr();
+    numbers.retain(|&n| n % 3 == 0);
+    assert_eq!(numbers, [3, 6, 9, 12, 15, 18]);
+    assert_eq!(numbers.as_ptr(), buffer);
+    println!("retain(multiples of 3): {numbers:?}");
+
+    // dedup drops consecutive repeats only, like the Unix `uniq`
+    let mut runs = vec![1, 1, 2, 2, 2, 3, 1, 1];
+    runs.dedup();
+    assert_eq!(runs, [1, 2, 3, 1]);
+    println!("dedup: {runs:?} (the second run of 1s stays, it isn't next to the first)");
+
+    // dedup_by_key compares a key instead of the whole element
+    let mut words = vec!["Apple", "apple", "APPLE", "banana", "Banana", "cherry"];
+    words.dedup_by_key(|w| w.to_lowercase());
+    assert_eq!(words, ["Apple", "banana", "cherry"]);
+    println!("dedup_by_key(lowercase): {words:?}");
+
+    // extend_from_slice clones from a slice, and knows the length up front, so
+    // it reserves once. extend takes any iterator, including ones that move
+    // their elements in and ones whose length isn't known
+    let mut letters = vec!['a', 'b'];
+    letters.extend_from_slice(&['c', 'd']);
+    letters.extend("ef".chars());
+    letters.extend(['g', 'h'].iter().filter(|&&c| c != 'h'));
+    assert_eq!(letters, ['a', 'b', 'c', 'd', 'e', 'f', 'g']);
+    let mut owned = vec![String::from("x")];
+    // Moved in, not cloned: extend_from_slice would need &[String] and Clone
+    owned.extend(vec![String::from("y"), String::from("z")]);
+    assert_eq!(owned, ["x", "y", "z"]);
+    println!("extend_from_slice and extend: {letters:?}, {owned:?}");
+
+    // truncate drops everything past a length; resize grows with copies of a
+    // value (or shrinks, like truncate)
+    let mut sizes = vec![1, 2, 3, 4, 5];
+    sizes.truncate(3);
+    assert_eq!(sizes, [1, 2, 3]);
+    sizes.truncate(10);
+    assert_eq!(sizes, [1, 2, 3], "truncating past the end does nothing");
+    sizes.resize(6, 0);
+    assert_eq!(sizes, [1, 2, 3, 0, 0, 0]);
+    sizes.resize(2, 0);
+    assert_eq!(sizes, [1, 2]);
+    println!("truncate and resize: {sizes:?}");
+
+    // Removing duplicates everywhere, not just in runs
+    // sort + dedup is simple and needs no extra memory, but sorting loses the
+    // original order; the HashSet version keeps it
+    let visits = vec!["home", "search", "cart", "home", "search", "checkout"];
+    let first_visits = remove_duplicates_preserving_order(visits.clone());
+    assert_eq!(first_visits, ["home", "search", "cart", "checkout"]);
+
+    let mut sorted = visits.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(sorted, ["cart", "checkout", "home", "search"]);
+    // Same elements either way; only the order differs
+    assert_ne!(first_visits, sorted);
+    let mut resorted = first_visits.clone();
+    resorted.sort();
+    assert_eq!(resorted, sorted);
+    println!("Pages in the order first visited: {first_visits:?}");
+    println!("sort + dedup gives the same pages:  {sorted:?}");
+
+    assert!(remove_duplicates_preserving_order(Vec::<i32>::new()).is_empty());
+    assert_eq!(remove_duplicates_preserving_order(vec![7, 7, 7]), [7]);
+}
@@ -0,0 +1,183 @@
+// Lazy evaluation: wrap an expensive computation in a thunk, a closure that
+// runs only when its value is first needed, then remember the result
+// The standard library has this built in as std::cell::LazyCell (and LazyLock
+// for sharing between threads); this builds the same thing from OnceCell
+use std::cell::{Cell, OnceCell};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+type Thunk<T> = Box<dyn FnOnce() -> T>;
+
+// OnceCell holds the value once there is one, and get_or_init fills it at most once
+// The initializer is FnOnce, and get only has &self, so it can't be called
+// straight out of a plain field: calling a FnOnce consumes it. Cell<Option<..>>
+// lets get take it out through a shared reference, leaving None behind
+struct Lazy<T> {
+    value: OnceCell<T>,
+    init: Cell<Option<Thunk<T>>>,
+}
+
+impl<T> Lazy<T> {
+    // 'static because the closure is stored, and may run long after this call
+    fn new(f: impl FnOnce() -> T + 'static) -> Lazy<T> {
+        Lazy {
+            value: OnceCell::new(),
+            init: Cell::new(Some(Box::new(f))),
+        }
+    }
+
+    fn get(&self) -> &T {
+        self.value.get_or_init(|| {
+            // Only reachable once: after this the OnceCell is full. (A thunk that
+            // asks for its own value would make OnceCell panic, not loop forever)
+            let init = self.init.take().expect("the initializer runs only once");
+            init()
+        })
+    }
+
+    fn is_evaluated(&self) -> bool {
+        self.value.get().is_some()
+    }
+}
+
+// A map whose values are worked out on first lookup
+struct LazyMap<K, V> {
+    entries: HashMap<K, Lazy<V>>,
+}
+
+impl<K: Eq + Hash, V> LazyMap<K, V> {
+    fn new() -> LazyMap<K, V> {
+        LazyMap {
+            entries: HashMap::new(),
+        }
+    }
+
+    fn insert(&mut self, key: K, f: impl FnOnce() -> V + 'static) {
+        self.entries.insert(key, Lazy::new(f));
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.entries.get(key).map(Lazy::get)
+    }
+
+    fn evaluated(&self) -> usize {
+        self.entries
+            .values()
+            .filter(|lazy| lazy.is_evaluated())
+            .count()
+    }
+}
+
+// Wraps a thunk so every run of it is counted
+// Arc<AtomicUsize> rather than Rc<Cell<usize>> only because it reads the same
+// way in threaded code; nothing here crosses threads
+fn counted<T>(counter: Arc<AtomicUsize>, f: impl FnOnce() -> T + 'static) -> impl FnOnce() -> T {
+    move || {
+        counter.fetch_add(1, Ordering::SeqCst);
+        f()
+    }
+}
+
+fn main() {
+    // 1. Building a Lazy costs nothing; the first get pays, later ones don't
+    let start = Instant::now();
+    let slow = Lazy::new(|| {
+        thread::sleep(Duration::from_millis(500));
+        42
+    });
+    let built = start.elapsed();
+    assert!(built < Duration::from_millis(100), "new() took {built:?}");
+    assert!(!slow.is_evaluated());
+
+    let start = Instant::now();
+    assert_eq!(*slow.get(), 42);
+    let first = start.elapsed();
+    let start = Instant::now();
+    assert_eq!(*slow.get(), 42);
+    let second = start.elapsed();
+    assert!(first >= Duration::from_millis(500));
+    assert!(second < Duration::from_millis(100));
+    println!("new: {built:?}, first get: {first:.0?}, second get: {second:?}");
+
+    // 2. A LazyMap computes only what is looked up
+    let squares = Arc::new(AtomicUsize::new(0));
+    let mut map = LazyMap::new();
+    for n in 1..=100u64 {
+        map.insert(n, counted(Arc::clone(&squares), move || n * n));
+    }
+    assert_eq!(map.get(&7), Some(&49));
+    assert_eq!(map.get(&7), Some(&49));
+    assert_eq!(map.get(&12), Some(&144));
+    assert_eq!(map.get(&1000), None);
+    assert_eq!(map.evaluated(), 2);
+    assert_eq!(squares.load(Ordering::SeqCst), 2);
+    println!(
+        "LazyMap of 100 squares: looked up 7 twice and 12 once, computed {} of them",
+        map.evaluated()
+    );
+
+    // 3. A dependency graph: each node's thunk gets the values it depends on
+    //      config
+    //      /    \
+    //  parsed   schema
+    //      \    /
+    //     report          audit (depends on nothing, never asked for)
+    // Rc, because several nodes hold on to the same dependency
+    let counters: Vec<Arc<AtomicUsize>> = (0..5).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+    let config = Rc::new(Lazy::new(counted(Arc::clone(&counters[0]), || {
+        "size=3".to_string()
+    })));
+    let parsed = {
+        let config = Rc::clone(&config);
+        Rc::new(Lazy::new(counted(Arc::clone(&counters[1]), move || {
+            config
+                .get()
+                .split_once('=')
+                .and_then(|(_, size)| size.parse::<u32>().ok())
+                .unwrap_or(0)
+        })))
+    };
+    let schema = {
+        let config = Rc::clone(&config);
+        Rc::new(Lazy::new(counted(Arc::clone(&counters[2]), move || {
+            format!("schema for {}", config.get())
+        })))
+    };
+    let report = {
+        let (parsed, schema) = (Rc::clone(&parsed), Rc::clone(&schema));
+        Lazy::new(counted(Arc::clone(&counters[3]), move || {
+            format!("{} with {} rows", schema.get(), parsed.get())
+        }))
+    };
+    let audit = Lazy::new(counted(Arc::clone(&counters[4]), || {
+        thread::sleep(Duration::from_secs(10));
+        "a very slow audit".to_string()
+    }));
+
+    assert!(!config.is_evaluated());
+    println!("report: {}", report.get());
+    report.get();
+    assert_eq!(report.get(), "schema for size=3 with 3 rows");
+
+    // config is shared by two nodes but ran once; audit isn't reachable from report
+    for (name, runs) in ["config", "parsed", "schema", "report"]
+        .iter()
+        .zip(&counters)
+    {
+        assert_eq!(runs.load(Ordering::SeqCst), 1, "{name}");
+    }
+    assert_eq!(counters[4].load(Ordering::SeqCst), 0);
+    assert!(!audit.is_evaluated());
+    println!(
+        "Runs per node: {:?} (config, parsed, schema, report, audit)",
+        counters
+            .iter()
+            .map(|c| c.load(Ordering::SeqCst))
+            .collect::<Vec<_>>()
+    );
+}
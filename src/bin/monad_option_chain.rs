@@ -0,0 +1,271 @@
+// Chaining steps that can fail, with Option and Result
+// In Haskell, >>= ("bind") feeds the value inside a Maybe to the next step and
+// stops at the first Nothing. Option::and_then is the same operation, and
+// Result::and_then does it for Ok/Err. The ? operator is that chain again,
+// written as ordinary statements
+use std::collections::HashMap;
+use std::fmt;
+
+// and_then, written out: apply f to the value if there is one
+fn maybe<T, U, F: Fn(T) -> Option<U>>(opt: Option<T>, f: F) -> Option<U> {
+    match opt {
+        Some(value) => f(value),
+        None => None,
+    }
+}
+
+struct User {
+    name: &'static str,
+    active: bool,
+    account: u32,
+}
+
+struct Bank {
+    users: HashMap<u32, User>,
+    // Account number to balance, in cents
+    balances: HashMap<u32, u64>,
+}
+
+fn bank() -> Bank {
+    let users = HashMap::from([
+        (
+            1,
+            User {
+                name: "ana",
+                active: true,
+                account: 100,
+            },
+        ),
+        (
+            2,
+            User {
+                name: "ben",
+                active: false,
+                account: 200,
+            },
+        ),
+        (
+            3,
+            User {
+                name: "cy",
+                active: true,
+                account: 300,
+            },
+        ),
+        // An active user whose account number points nowhere
+        (
+            4,
+            User {
+                name: "dee",
+                active: true,
+                account: 999,
+            },
+        ),
+    ]);
+    let balances = HashMap::from([(100, 5_000), (200, 9_000), (300, 250)]);
+    Bank { users, balances }
+}
+
+// ---- 1. The pipeline as an and_then chain ----
+// Each step takes the previous step's value and may give up; filter is a step
+// that keeps the value or turns it into None. A step only sees the value just
+// before it, so a later step needing an earlier value would have to nest closures
+fn withdraw_chain(bank: &Bank, id: &str, amount: u64) -> Option<u64> {
+    id.trim()
+        .parse::<u32>()
+        .ok()
+        .and_then(|id| bank.users.get(&id))
+        .filter(|user| user.active)
+        .and_then(|user| bank.balances.get(&user.account))
+        .filter(|&&balance| balance >= amount)
+        .map(|_| amount)
+}
+
+// The same steps through `maybe`, to show it really is and_then
+fn withdraw_maybe(bank: &Bank, id: &str, amount: u64) -> Option<u64> {
+    let id = id.trim().parse::<u32>().ok();
+    let user = maybe(id, |id| bank.users.get(&id));
+    let active = maybe(user, |user| user.active.then_some(user));
+    let balance = maybe(active, |user| bank.balances.get(&user.account));
+    maybe(balance, |&balance| (balance >= amount).then_some(amount))
+}
+
+// ---- 2. The same with ? ----
+// Each ? is one and_then: a None returns from the function on the spot. Every
+// step's value gets a name, and they all stay in scope, which is easier to
+// read once the chain gets long or a step needs more than one earlier value
+fn withdraw_question_mark(bank: &Bank, id: &str, amount: u64) -> Option<u64> {
+    let id: u32 = id.trim().parse().ok()?;
+    let user = bank.users.get(&id)?;
+    if !user.active {
+        return None;
+    }
+    let balance = bank.balances.get(&user.account)?;
+    (*balance >= amount).then_some(amount)
+}
+
+// ---- 3. The same with Result ----
+// None only says that something went wrong; an error says what. Each step turns
+// its None into an error with ok_or/ok_or_else, and the rest is the same chain
+#[derive(Debug, PartialEq)]
+enum WithdrawError {
+    BadId(String),
+    NoSuchUser(u32),
+    Inactive(&'static str),
+    NoAccount { user: &'static str, account: u32 },
+    Insufficient { balance: u64, amount: u64 },
+}
+
+impl fmt::Display for WithdrawError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WithdrawError::BadId(id) => write!(f, "'{id}' is not a user id"),
+            WithdrawError::NoSuchUser(id) => write!(f, "there is no user {id}"),
+            WithdrawError::Inactive(name) => write!(f, "{name}'s account is closed"),
+            WithdrawError::NoAccount { user, account } => {
+                write!(f, "account {account} of {user} doesn't exist")
+            }
+            WithdrawError::Insufficient { balance, amount } => {
+                write!(f, "a balance of {balance} can't cover {amount}")
+            }
+        }
+    }
+}
+
+fn withdraw_result(bank: &Bank, id: &str, amount: u64) -> Result<u64, WithdrawError> {
+    let id: u32 = id
+        .trim()
+        .parse()
+        .map_err(|_| WithdrawError::BadId(id.to_string()))?;
+    let user = bank.users.get(&id).ok_or(WithdrawError::NoSuchUser(id))?;
+    if !user.active {
+        return Err(WithdrawError::Inactive(user.name));
+    }
+    let &balance = bank
+        .balances
+        .get(&user.account)
+        .ok_or(WithdrawError::NoAccount {
+            user: user.name,
+            account: user.account,
+        })?;
+    if balance < amount {
+        return Err(WithdrawError::Insufficient { balance, amount });
+    }
+    Ok(amount)
+}
+
+// The Result version as one chain, for comparison: it works, but the error for
+// a step has to be built inside the closure of that step, and the nesting grows
+fn withdraw_result_chain(bank: &Bank, id: &str, amount: u64) -> Result<u64, WithdrawError> {
+    id.trim()
+        .parse::<u32>()
+        .map_err(|_| WithdrawError::BadId(id.to_string()))
+        .and_then(|id| bank.users.get(&id).ok_or(WithdrawError::NoSuchUser(id)))
+        .and_then(|user| {
+            if user.active {
+                Ok(user)
+            } else {
+                Err(WithdrawError::Inactive(user.name))
+            }
+        })
+        .and_then(|user| {
+            bank.balances
+                .get(&user.account)
+                .ok_or(WithdrawError::NoAccount {
+                    user: user.name,
+                    account: user.account,
+                })
+        })
+        .and_then(|&balance| {
+            if balance >= amount {
+                Ok(amount)
+            } else {
+                Err(WithdrawError::Insufficient { balance, amount })
+            }
+        })
+}
+
+// Which to use:
+// - A short chain of small steps reads well as and_then/map/filter, especially
+//   inline where a whole function would be too much (a closure, an argument)
+// - Once there are several steps, or one needs a value from two steps back, ?
+//   in a small function is clearer: names instead of nested closures, and
+//   plain if statements for the checks
+// - Option when the caller only needs to know that it failed (a lookup that
+//   may find nothing); Result when the caller or the user needs to know why,
+//   as here, where "closed account" and "not enough money" call for different
+//   responses
+
+fn main() {
+    let bank = bank();
+    let cases: [(&str, u64); 7] = [
+        ("1", 1_000),
+        (" 3 ", 250),
+        ("one", 10),
+        ("7", 10),
+        ("2", 10),
+        ("4", 10),
+        ("3", 251),
+    ];
+
+    println!("{:<8}{:>7}  result", "id", "amount");
+    for (id, amount) in cases {
+        let chain = withdraw_chain(&bank, id, amount);
+        // All four Option/Result versions agree on every input
+        assert_eq!(withdraw_maybe(&bank, id, amount), chain);
+        assert_eq!(withdraw_question_mark(&bank, id, amount), chain);
+        let result = withdraw_result(&bank, id, amount);
+        assert_eq!(result, withdraw_result_chain(&bank, id, amount));
+        assert_eq!(result.as_ref().ok().copied(), chain);
+
+        let explained = match &result {
+            Ok(amount) => format!("withdrew {amount}"),
+            Err(err) => format!("refused: {err}"),
+        };
+        println!(
+            "{:<8}{amount:>7}  {chain:?} / {explained}",
+            format!("{id:?}")
+        );
+    }
+
+    assert_eq!(withdraw_chain(&bank, "1", 1_000), Some(1_000));
+    assert_eq!(withdraw_chain(&bank, "2", 10), None);
+    assert_eq!(
+        withdraw_result(&bank, "2", 10),
+        Err(WithdrawError::Inactive("ben"))
+    );
+    assert_eq!(
+        withdraw_result(&bank, "4", 10),
+        Err(WithdrawError::NoAccount {
+            user: "dee",
+            account: 999
+        })
+    );
+    assert_eq!(
+        withdraw_result(&bank, "3", 251),
+        Err(WithdrawError::Insufficient {
+            balance: 250,
+            amount: 251
+        })
+    );
+
+    // maybe is and_then: the same answers on Some and None, whatever the function
+    let halve = |n: i32| (n % 2 == 0).then_some(n / 2);
+    for opt in [Some(8), Some(3), None] {
+        assert_eq!(maybe(opt, halve), opt.and_then(halve));
+    }
+    // The monad laws, for and_then: Some is a neutral start and end, and grouping
+    // the steps differently changes nothing
+    let quarter = |n: i32| maybe(halve(n), halve);
+    assert_eq!(Some(12).and_then(halve), halve(12));
+    let unit = |n: i32| Some(n);
+    assert_eq!(Some(12).and_then(unit), Some(12));
+    assert_eq!(
+        Some(12).and_then(halve).and_then(halve),
+        Some(12).and_then(quarter)
+    );
+    println!(
+        "\nmaybe(Some(12), quarter) = {:?}",
+        maybe(Some(12), quarter)
+    );
+}
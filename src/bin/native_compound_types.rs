@@ -1,8 +1,21 @@
+// (types_repl.rs explores the same tuples and arrays interactively)
+// Pair up two slices element by element into a Vec of tuples
+// Stops at the end of the shorter slice, just like Iterator::zip
+fn zip_pairs<A: Clone, B: Clone>(a: &[A], b: &[B]) -> Vec<(A, B)> {
+    let len = a.len().min(b.len());
+    let mut pairs = Vec::with_capacity(len);
+    for i in 0..len {
+        // (x, y) builds a tuple; clone because the slices only lend us their elements
+        pairs.push((a[i].clone(), b[i].clone()));
+    }
+    pairs
+}
+
 fn main() {
     // Type annotation explicit: (i32, f64, u8)
     let tup: (i32, f64, u8) = (500, 6.4, 1);
     let (x, y, z) = tup;
-    println!("The value of y is: {}", y);
+    println!("The values are: {}, {}, {}", x, y, z);
 
     // Without type annotation - Rust infers the types automatically!
     // Rust will infer: (i32, f64, i32) based on the literal values
@@ -19,4 +32,48 @@ fn main() {
     // It knows there are 5 elements and they're all i32 (default integer type)
     let lst2 = [1, 2, 3, 4, 5];
     println!("The second element of lst2 is: {}", lst2[1]);
-}
\ No newline at end of file
+
+    // Tuples built from two parallel slices
+    let names = ["x", "y", "z"];
+    let values = [1.5, 2.5, 3.5];
+    let pairs = zip_pairs(&names, &values);
+    println!("Zipped pairs: {:?}", pairs);
+
+    // Unequal lengths: the extra elements of the longer slice are dropped
+    println!(
+        "Zipped unevenly: {:?}",
+        zip_pairs(&[1, 2, 3, 4], &['a', 'b'])
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_lengths_pair_every_element() {
+        let pairs = zip_pairs(&["x", "y", "z"], &[1.5, 2.5, 3.5]);
+        assert_eq!(pairs, [("x", 1.5), ("y", 2.5), ("z", 3.5)]);
+    }
+
+    #[test]
+    fn unequal_lengths_stop_at_the_shorter_slice() {
+        assert_eq!(zip_pairs(&[1, 2, 3, 4], &['a', 'b']), [(1, 'a'), (2, 'b')]);
+        assert_eq!(zip_pairs(&['a'], &[1, 2, 3]), [('a', 1)]);
+    }
+
+    #[test]
+    fn an_empty_slice_gives_no_pairs() {
+        assert!(zip_pairs::<i32, char>(&[], &['a', 'b']).is_empty());
+        assert!(zip_pairs::<i32, char>(&[1, 2], &[]).is_empty());
+        assert!(zip_pairs::<i32, char>(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn it_matches_iterator_zip() {
+        let a = [3, 1, 4, 1, 5];
+        let b = ["three", "one", "four"];
+        let zipped: Vec<(i32, &str)> = a.iter().copied().zip(b.iter().copied()).collect();
+        assert_eq!(zip_pairs(&a, &b), zipped);
+    }
+}
@@ -0,0 +1,565 @@
+// Generic numeric code: write gcd, abs or average once and use it for every number type
+// The num-traits / num-integer / num-bigint crates provide traits like Integer,
+// Signed and Float that describe "what a number can do". They aren't available
+// offline here, so the two modules below re-create the small slice of their API
+// this example uses, with the same names; the generic functions further down are
+// written exactly as they would be against the real crates
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+mod num_traits {
+    use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+    // The additive and multiplicative identities: 0 and 1 for whatever type this is
+    pub trait Zero: Sized {
+        fn zero() -> Self;
+        fn is_zero(&self) -> bool;
+    }
+
+    pub trait One: Sized {
+        fn one() -> Self;
+    }
+
+    // "Behaves like a number": has 0 and 1 and the four basic operations plus %
+    pub trait Num:
+        PartialEq
+        + Zero
+        + One
+        + Add<Output = Self>
+        + Sub<Output = Self>
+        + Mul<Output = Self>
+        + Div<Output = Self>
+        + Rem<Output = Self>
+    {
+    }
+
+    // A blanket impl: anything with those operations is a Num automatically
+    impl<T> Num for T where
+        T: PartialEq
+            + Zero
+            + One
+            + Add<Output = T>
+            + Sub<Output = T>
+            + Mul<Output = T>
+            + Div<Output = T>
+            + Rem<Output = T>
+    {
+    }
+
+    // Numbers that can be negative; u32 and u64 deliberately don't implement this
+    pub trait Signed: Num + Neg<Output = Self> {
+        fn abs(&self) -> Self;
+    }
+
+    // Lossy conversions go through Option, because not every value fits:
+    // -1 has no u32, and 1e300 has no i64
+    pub trait ToPrimitive {
+        fn to_i64(&self) -> Option<i64>;
+        fn to_f64(&self) -> Option<f64>;
+    }
+
+    pub trait NumCast: Sized + ToPrimitive {
+        fn from<T: ToPrimitive>(n: T) -> Option<Self>;
+    }
+
+    pub trait FromPrimitive: Sized {
+        fn from_f64(n: f64) -> Option<Self>;
+    }
+
+    // Floating point only: the things integers can't do
+    pub trait Float: Num + Copy + PartialOrd + Neg<Output = Self> {
+        fn sqrt(self) -> Self;
+        fn sin(self) -> Self;
+        fn cos(self) -> Self;
+        fn abs(self) -> Self;
+        fn epsilon() -> Self;
+    }
+
+    // Exponentiation by squaring: O(log exp) multiplications instead of exp of them
+    pub fn pow<T: Clone + One + Mul<Output = T>>(mut base: T, mut exp: usize) -> T {
+        let mut result = T::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base.clone();
+            }
+            exp >>= 1;
+            // Skipping the final squaring avoids overflow when the result itself fits
+            if exp > 0 {
+                base = base.clone() * base;
+            }
+        }
+        result
+    }
+
+    // One macro invocation per family of types instead of a hand-written impl each
+    macro_rules! int_impls {
+        ($($t:ty),*) => {$(
+            impl Zero for $t {
+                fn zero() -> $t { 0 }
+                fn is_zero(&self) -> bool { *self == 0 }
+            }
+            impl One for $t {
+                fn one() -> $t { 1 }
+            }
+            impl ToPrimitive for $t {
+                fn to_i64(&self) -> Option<i64> { i64::try_from(*self).ok() }
+                fn to_f64(&self) -> Option<f64> { Some(*self as f64) }
+            }
+            impl NumCast for $t {
+                fn from<T: ToPrimitive>(n: T) -> Option<$t> {
+                    n.to_i64().and_then(|n| <$t>::try_from(n).ok())
+                }
+            }
+            impl FromPrimitive for $t {
+                fn from_f64(n: f64) -> Option<$t> {
+                    // `as` saturates instead of failing, so check the range first
+                    let in_range = n >= <$t>::MIN as f64 && n <= <$t>::MAX as f64;
+                    in_range.then(|| n as $t)
+                }
+            }
+        )*};
+    }
+    int_impls!(i32, i64, u32, u64);
+
+    macro_rules! signed_impls {
+        ($($t:ty),*) => {$(
+            impl Signed for $t {
+                fn abs(&self) -> $t { <$t>::abs(*self) }
+            }
+        )*};
+    }
+    signed_impls!(i32, i64);
+
+    macro_rules! float_impls {
+        ($($t:ty),*) => {$(
+            impl Zero for $t {
+                fn zero() -> $t { 0.0 }
+                fn is_zero(&self) -> bool { *self == 0.0 }
+            }
+            impl One for $t {
+                fn one() -> $t { 1.0 }
+            }
+            impl Signed for $t {
+                fn abs(&self) -> $t { <$t>::abs(*self) }
+            }
+            impl ToPrimitive for $t {
+                fn to_i64(&self) -> Option<i64> {
+                    let in_range = *self >= i64::MIN as $t && *self <= i64::MAX as $t;
+                    in_range.then(|| *self as i64)
+                }
+                fn to_f64(&self) -> Option<f64> { Some(*self as f64) }
+            }
+            impl NumCast for $t {
+                fn from<T: ToPrimitive>(n: T) -> Option<$t> { n.to_f64().map(|n| n as $t) }
+            }
+            impl FromPrimitive for $t {
+                fn from_f64(n: f64) -> Option<$t> { Some(n as $t) }
+            }
+            impl Float for $t {
+                fn sqrt(self) -> $t { <$t>::sqrt(self) }
+                fn sin(self) -> $t { <$t>::sin(self) }
+                fn cos(self) -> $t { <$t>::cos(self) }
+                fn abs(self) -> $t { <$t>::abs(self) }
+                fn epsilon() -> $t { <$t>::EPSILON }
+            }
+        )*};
+    }
+    float_impls!(f32, f64);
+}
+
+mod num_integer {
+    use super::num_traits::Num;
+
+    // Integer-only operations, with default methods built on Num
+    // so an impl for a new type can be empty
+    pub trait Integer: Num + Clone + PartialOrd {
+        // Euclid's algorithm: gcd(a, b) = gcd(b, a % b), and gcd(a, 0) = a
+        fn gcd(&self, other: &Self) -> Self {
+            let mut a = self.clone();
+            let mut b = other.clone();
+            while !b.is_zero() {
+                let r = a % b.clone();
+                a = b;
+                b = r;
+            }
+            // % keeps the sign of the dividend, so a signed input can leave a negative
+            // result; the gcd is always reported as non-negative
+            non_negative(a)
+        }
+
+        // Divide before multiplying so the intermediate value stays small
+        fn lcm(&self, other: &Self) -> Self {
+            if self.is_zero() || other.is_zero() {
+                return Self::zero();
+            }
+            let gcd = self.gcd(other);
+            non_negative(self.clone() / gcd * other.clone())
+        }
+
+        fn is_even(&self) -> bool {
+            (self.clone() % (Self::one() + Self::one())).is_zero()
+        }
+    }
+
+    fn non_negative<T: Num + PartialOrd>(n: T) -> T {
+        if n < T::zero() { T::zero() - n } else { n }
+    }
+
+    impl Integer for i32 {}
+    impl Integer for i64 {}
+    impl Integer for u32 {}
+    impl Integer for u64 {}
+}
+
+use num_integer::Integer;
+use num_traits::{Float, FromPrimitive, NumCast, One, Zero};
+
+// ---- The generic functions: one body each, every numeric type ----
+
+fn gcd<T: num_integer::Integer>(a: T, b: T) -> T {
+    a.gcd(&b)
+}
+
+fn lcm<T: num_integer::Integer>(a: T, b: T) -> T {
+    a.lcm(&b)
+}
+
+fn abs_generic<T: num_traits::Signed>(x: T) -> T {
+    x.abs()
+}
+
+// Needs nothing numeric at all, so it also works for strings
+fn clamp<T: PartialOrd>(x: T, lo: T, hi: T) -> T {
+    if x < lo {
+        lo
+    } else if x > hi {
+        hi
+    } else {
+        x
+    }
+}
+
+// Summing in f64 avoids overflowing small integer types;
+// FromPrimitive lets the caller turn the mean back into T, as main does below
+fn average<T: num_traits::NumCast + num_traits::FromPrimitive>(data: &[T]) -> Option<f64> {
+    if data.is_empty() {
+        return None;
+    }
+    let mut sum = 0.0;
+    for x in data {
+        sum += x.to_f64()?;
+    }
+    Some(sum / data.len() as f64)
+}
+
+fn hypotenuse<T: Float>(a: T, b: T) -> T {
+    (a * a + b * b).sqrt()
+}
+
+// sin² + cos² = 1 for every angle, up to rounding
+fn is_on_unit_circle<T: Float>(angle: T) -> bool {
+    let (s, c) = (angle.sin(), angle.cos());
+    Float::abs(s * s + c * c - T::one()) < T::epsilon() * (T::one() + T::one() + T::one())
+}
+
+// ---- A tiny num_bigint::BigInt ----
+// Sign plus magnitude; the magnitude is base-2^32 limbs, least significant first,
+// with no trailing zero limbs (so zero is an empty Vec and never negative)
+// Only what Integer needs is implemented: + - * / % and comparisons
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut limbs: Vec<u32>) -> BigInt {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        let negative = negative && !limbs.is_empty();
+        BigInt { negative, limbs }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> BigInt {
+        let m = n.unsigned_abs();
+        BigInt::new(n < 0, vec![m as u32, (m >> 32) as u32])
+    }
+}
+
+fn cmp_mag(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        out.push(sum as u32);
+        carry = sum >> 32;
+    }
+    out.push(carry as u32);
+    out
+}
+
+// Requires a >= b
+fn sub_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &limb) in a.iter().enumerate() {
+        let mut diff = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = (diff < 0) as i64;
+        if diff < 0 {
+            diff += 1 << 32;
+        }
+        out.push(diff as u32);
+    }
+    while out.last() == Some(&0) {
+        out.pop();
+    }
+    out
+}
+
+fn mul_mag(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let cur = x as u64 * y as u64 + out[i + j] as u64 + carry;
+            out[i + j] = cur as u32;
+            carry = cur >> 32;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    out
+}
+
+// Long division one bit at a time: slow, but short and obviously correct
+fn div_rem_mag(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    assert!(!b.is_empty(), "attempt to divide by zero");
+    let mut quotient = vec![0u32; a.len()];
+    let mut rem: Vec<u32> = Vec::new();
+    for bit in (0..a.len() * 32).rev() {
+        // rem = rem * 2 + next bit of a
+        rem = add_mag(&rem, &rem);
+        rem[0] |= (a[bit / 32] >> (bit % 32)) & 1;
+        while rem.last() == Some(&0) {
+            rem.pop();
+        }
+        if cmp_mag(&rem, b) != Ordering::Less {
+            rem = sub_mag(&rem, b);
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quotient, rem)
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.limbs, &other.limbs),
+            (true, true) => cmp_mag(&other.limbs, &self.limbs),
+        })
+    }
+}
+
+impl Zero for BigInt {
+    fn zero() -> BigInt {
+        BigInt::new(false, Vec::new())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+}
+
+impl One for BigInt {
+    fn one() -> BigInt {
+        BigInt::new(false, vec![1])
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.limbs)
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_mag(&self.limbs, &other.limbs));
+        }
+        // Different signs: the larger magnitude wins and keeps its sign
+        match cmp_mag(&self.limbs, &other.limbs) {
+            Ordering::Less => BigInt::new(other.negative, sub_mag(&other.limbs, &self.limbs)),
+            _ => BigInt::new(self.negative, sub_mag(&self.limbs, &other.limbs)),
+        }
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: BigInt) -> BigInt {
+        self + -other
+    }
+}
+
+impl Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_mag(&self.limbs, &other.limbs),
+        )
+    }
+}
+
+// Division truncates towards zero and the remainder takes the dividend's sign,
+// the same rules as i32 and i64, so Integer's default methods behave identically
+impl Div for BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: BigInt) -> BigInt {
+        let (q, _) = div_rem_mag(&self.limbs, &other.limbs);
+        BigInt::new(self.negative != other.negative, q)
+    }
+}
+
+impl Rem for BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: BigInt) -> BigInt {
+        let (_, r) = div_rem_mag(&self.limbs, &other.limbs);
+        BigInt::new(self.negative, r)
+    }
+}
+
+// With those in place BigInt is a Num (via the blanket impl) and gets gcd and lcm for free
+impl Integer for BigInt {}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Peel off 9 decimal digits at a time, least significant first
+        let billion = [1_000_000_000];
+        let mut rest = self.limbs.clone();
+        let mut chunks = Vec::new();
+        while !rest.is_empty() {
+            let (q, r) = div_rem_mag(&rest, &billion);
+            chunks.push(r.first().copied().unwrap_or(0));
+            rest = q;
+            while rest.last() == Some(&0) {
+                rest.pop();
+            }
+        }
+        if chunks.is_empty() {
+            return write!(f, "0");
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", chunks.last().unwrap())?;
+        for chunk in chunks.iter().rev().skip(1) {
+            write!(f, "{chunk:09}")?;
+        }
+        Ok(())
+    }
+}
+
+fn main() {
+    // gcd and lcm: the same two functions for i32, i64 and u64
+    assert_eq!(gcd(48i32, 18), 6);
+    assert_eq!(gcd(-48i32, 18), 6);
+    assert_eq!(gcd(1_000_000_007i64 * 6, 1_000_000_007 * 4), 2_000_000_014);
+    assert_eq!(gcd(0u64, 5), 5);
+    assert_eq!(lcm(4i32, 6), 12);
+    assert_eq!(lcm(-4i64, 6), 12);
+    assert_eq!(lcm(u64::MAX / 3, 3), u64::MAX);
+    assert_eq!(lcm(0u64, 5), 0);
+    assert!(12u64.is_even() && !7i32.is_even());
+    println!("gcd(48, 18) = {}, lcm(4, 6) = {}", gcd(48, 18), lcm(4, 6));
+
+    // abs_generic accepts signed types only; abs_generic(5u32) doesn't compile
+    assert_eq!(abs_generic(-5i32), 5);
+    assert_eq!(abs_generic(i64::MIN + 1), i64::MAX);
+    assert_eq!(abs_generic(-2.5f64), 2.5);
+
+    // clamp only needs PartialOrd, so it works for numbers and strings alike
+    assert_eq!(clamp(15i32, 0, 10), 10);
+    assert_eq!(clamp(-0.5f64, 0.0, 1.0), 0.0);
+    assert_eq!(clamp(7u64, 1, 100), 7);
+    assert_eq!(clamp("kiwi", "apple", "banana"), "banana");
+
+    // average: summing u64::MAX twice in u64 would overflow, in f64 it doesn't
+    assert_eq!(average(&[1i32, 2, 3, 4]), Some(2.5));
+    assert_eq!(average(&[10i64, -10, 30]), Some(10.0));
+    assert_eq!(average(&[u64::MAX, u64::MAX]), Some(u64::MAX as f64));
+    assert_eq!(average(&[0.5f64, 1.5]), Some(1.0));
+    assert_eq!(average::<i32>(&[]), None);
+    // ...and back into the data's own type, which may not be possible
+    let mean = average(&[3i32, 4]).unwrap();
+    assert_eq!(i32::from_f64(mean.round()), Some(4));
+    assert_eq!(u32::from_f64(-1.0), None);
+    assert_eq!(<u32 as NumCast>::from(-1i64), None);
+    assert_eq!(<f64 as NumCast>::from(7u64), Some(7.0));
+    println!("average of [1, 2, 3, 4] = {:?}", average(&[1, 2, 3, 4]));
+
+    // pow works for anything with One and Mul, including Float types
+    assert_eq!(num_traits::pow(3i32, 4), 81);
+    assert_eq!(num_traits::pow(2u64, 63), 1 << 63);
+    assert_eq!(num_traits::pow(1.5f64, 2), 2.25);
+    assert_eq!(num_traits::pow(7i64, 0), 1);
+
+    // Float methods, generically over f32 and f64
+    assert_eq!(hypotenuse(3.0f64, 4.0), 5.0);
+    assert_eq!(hypotenuse(5.0f32, 12.0), 13.0);
+    for angle in [0.0f64, 0.5, 1.0, 2.0, -3.0] {
+        assert!(is_on_unit_circle(angle as f32));
+        assert!(is_on_unit_circle(angle));
+    }
+    println!("hypotenuse(3, 4) = {}", hypotenuse(3.0, 4.0));
+
+    // BigInt: the very same gcd and lcm, now on numbers far beyond u64
+    let two = BigInt::from(2);
+    let three = BigInt::from(3);
+    let a = num_traits::pow(two.clone(), 100) * three.clone();
+    let b = num_traits::pow(two.clone(), 70) * num_traits::pow(three.clone(), 5);
+    let g = gcd(a.clone(), b.clone());
+    assert_eq!(g, num_traits::pow(two.clone(), 70) * three.clone());
+    assert_eq!(g.to_string(), "3541774862152233910272");
+    assert_eq!(
+        gcd(-a.clone(), b.clone()),
+        g,
+        "gcd is non-negative for BigInt too"
+    );
+    let l = lcm(a.clone(), b.clone());
+    assert_eq!(l, num_traits::pow(two, 100) * num_traits::pow(three, 5));
+    // gcd * lcm = a * b for positive numbers
+    assert_eq!(g.clone() * l.clone(), a.clone() * b.clone());
+    assert_eq!(a.to_string(), "3802951800684688204490109616128");
+    assert_eq!(BigInt::from(-42).to_string(), "-42");
+    assert_eq!(BigInt::zero().to_string(), "0");
+    assert_eq!(
+        (
+            BigInt::from(-7) / BigInt::from(2),
+            BigInt::from(-7) % BigInt::from(2)
+        ),
+        (BigInt::from(-3), BigInt::from(-1))
+    );
+    println!("gcd(2^100 * 3, 2^70 * 3^5) = {g}");
+    println!("lcm(2^100 * 3, 2^70 * 3^5) = {l}");
+}
@@ -0,0 +1,189 @@
+// Rust's numeric types, side by side: their limits, converting between them,
+// what happens on overflow, and what floats look like in memory
+use std::hint::black_box;
+use std::panic;
+
+// Prints the limits of each integer type; a macro since there's no trait in std
+// that has MIN, MAX and BITS for every one of them
+macro_rules! integer_limits {
+    ($($t:ty),*) => {
+        $(println!("{:<6}{:>4} bits  {:>41} ..= {}", stringify!($t), <$t>::BITS, <$t>::MIN, <$t>::MAX);)*
+    };
+}
+
+// MIN is the most negative finite value, MIN_POSITIVE the smallest positive
+// normal one; DIGITS is how many decimal digits always survive a round trip
+macro_rules! float_limits {
+    ($($t:ident),*) => {
+        $(
+            println!("{}: {} bits, {} decimal digits, EPSILON {:e}", stringify!($t), size_of::<$t>() * 8, $t::DIGITS, $t::EPSILON);
+            println!("     MIN {:e}, MAX {:e}, MIN_POSITIVE {:e}", $t::MIN, $t::MAX, $t::MIN_POSITIVE);
+            println!("     INFINITY {}, NEG_INFINITY {}, NAN {}", $t::INFINITY, $t::NEG_INFINITY, $t::NAN);
+        )*
+    };
+}
+
+// A checked conversion to any type that has one: TryFrom is implemented between
+// every pair of integer types, failing when the value doesn't fit
+fn safe_cast<From: Copy, To: TryFrom<From>>(x: From) -> Result<To, <To as TryFrom<From>>::Error> {
+    To::try_from(x)
+}
+
+// `a + b` on u8, run where the compiler can't see the values; black_box stops it
+// from working out the overflow at compile time (which is a compile error)
+fn plain_add(a: u8, b: u8) -> u8 {
+    black_box(a) + black_box(b)
+}
+
+fn main() {
+    // ---- Limits ----
+    println!("Integers:");
+    integer_limits!(
+        i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+    );
+    println!("\nFloats:");
+    float_limits!(f32, f64);
+    // A signed type gives one bit to the sign: i8 spans -2^7 ..= 2^7 - 1
+    assert_eq!(i8::MIN as i16, -(1 << (i8::BITS - 1)));
+    assert_eq!(u16::MAX as u32, (1 << u16::BITS) - 1);
+    // EPSILON is the gap between 1.0 and the next float up; half of it is lost
+    // when added to 1.0 (black_box so the sum happens at run time)
+    let one = black_box(1.0f64);
+    assert_eq!(one + f64::EPSILON, f64::from_bits(one.to_bits() + 1));
+    assert_eq!(one + f64::EPSILON / 2.0, one);
+
+    // ---- Checked conversions ----
+    println!("\nsafe_cast (TryFrom):");
+    let fits: Result<u8, _> = safe_cast(200i32);
+    let too_big: Result<u8, _> = safe_cast(300i32);
+    let negative: Result<u32, _> = safe_cast(-1i64);
+    let wide: Result<i16, _> = safe_cast(40_000u32);
+    println!("  200i32 as u8: {fits:?}");
+    println!("  300i32 as u8: {too_big:?}");
+    println!("  -1i64 as u32: {negative:?}");
+    println!("  40000u32 as i16: {wide:?}");
+    assert_eq!(fits, Ok(200));
+    assert!(too_big.is_err() && negative.is_err() && wide.is_err());
+    // The error's Display says what went wrong, not which value
+    println!("  error message: {}", too_big.unwrap_err());
+    // Widening conversions can't fail, so their error type is Infallible
+    let widened: Result<i64, std::convert::Infallible> = safe_cast(i32::MIN);
+    assert_eq!(widened, Ok(-2_147_483_648));
+
+    // `as` never fails: it keeps the low bits, and floats saturate
+    assert_eq!(300i32 as u8, 44);
+    assert_eq!(-1i64 as u32, u32::MAX);
+    assert_eq!(1e10f64 as i32, i32::MAX);
+    assert_eq!(black_box(f64::NAN) as i32, 0);
+    assert_eq!(-3.99f64 as i32, -3);
+    println!(
+        "  `as` instead: 300i32 as u8 = {}, -1i64 as u32 = {}",
+        300i32 as u8, -1i64 as u32
+    );
+
+    // ---- Integers to floats ----
+    // An f64 has 53 bits of mantissa, so every u32 fits exactly: f64::from exists
+    let lossless = f64::from(u32::MAX);
+    assert_eq!(lossless as u32, u32::MAX);
+    println!("\nf64::from(u32::MAX) = {lossless} (exact)");
+    // A u64 has 64 significant bits, more than 53, so there is no f64::from(u64)
+    // (it would not compile) and `as` has to round to the nearest f64
+    let lossy = u64::MAX as f64;
+    println!(
+        "u64::MAX as f64 = {lossy} (u64::MAX is {}); back as u64: {}",
+        u64::MAX,
+        lossy as u64
+    );
+    assert_eq!(lossy, 18_446_744_073_709_551_616.0);
+    // The nearest f64 to u64::MAX is 2^64, one past it
+    assert_eq!(lossy, 2f64.powi(64));
+    // Above 2^53, neighbouring integers share a float
+    let big = 1u64 << 53;
+    assert_eq!(big as f64, (big + 1) as f64);
+    println!("2^53 and 2^53 + 1 are the same f64: {}", big as f64);
+
+    // ---- Overflow ----
+    // Each method says what to do when the answer doesn't fit
+    let x: u8 = 250;
+    println!("\n250u8 + 10:");
+    println!("  wrapping_add:    {}", x.wrapping_add(10));
+    println!("  checked_add:     {:?}", x.checked_add(10));
+    println!("  saturating_add:  {}", x.saturating_add(10));
+    println!("  overflowing_add: {:?}", x.overflowing_add(10));
+    assert_eq!(x.wrapping_add(10), 4);
+    assert_eq!(x.checked_add(10), None);
+    assert_eq!(x.checked_add(5), Some(255));
+    assert_eq!(x.saturating_add(10), 255);
+    assert_eq!(x.overflowing_add(10), (4, true));
+    assert_eq!(i8::MIN.saturating_sub(1), i8::MIN);
+    assert_eq!(i8::MIN.wrapping_abs(), i8::MIN);
+
+    // Plain + is the one that depends on the build: debug builds check and
+    // panic, release builds wrap (unless overflow-checks is turned on)
+    // The default panic message is silenced, since the panic is expected here
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let plain = panic::catch_unwind(|| plain_add(250, 10));
+    panic::set_hook(hook);
+    let mode = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    match plain {
+        Ok(sum) => println!("  plain +:         {sum} (wrapped, this is a {mode} build)"),
+        Err(_) => println!("  plain +:         panicked (this is a {mode} build)"),
+    }
+    if cfg!(debug_assertions) {
+        assert!(plain.is_err());
+    } else {
+        assert_eq!(plain.ok(), Some(4));
+    }
+
+    // ---- Floats in memory ----
+    // sign (1 bit) | exponent (8 bits) | mantissa (23 bits)
+    println!("\nf32 bit patterns (sign | exponent | mantissa):");
+    for (name, value) in [
+        ("1.0", 1.0f32),
+        ("-0.0", -0.0),
+        ("INFINITY", f32::INFINITY),
+        ("NEG_INFINITY", f32::NEG_INFINITY),
+        ("NAN", f32::NAN),
+    ] {
+        let bits = value.to_bits();
+        println!(
+            "  {name:<13} {:01b} {:08b} {:023b}  ({bits:#010x})",
+            bits >> 31,
+            (bits >> 23) & 0xff,
+            bits & 0x7f_ffff
+        );
+    }
+    assert_eq!(f32::INFINITY.to_bits(), 0x7f80_0000);
+    assert_eq!((-0.0f32).to_bits(), 0x8000_0000);
+    // An all-ones exponent is infinity with a zero mantissa, NaN with any other
+    assert_eq!(f32::NAN.to_bits() & 0x7f80_0000, 0x7f80_0000);
+    assert_ne!(f32::NAN.to_bits() & 0x7f_ffff, 0);
+
+    // -0.0 equals 0.0, though the bits differ, and it remembers its sign
+    assert_eq!(-0.0f32, 0.0);
+    assert_ne!((-0.0f32).to_bits(), 0.0f32.to_bits());
+    assert_eq!(1.0 / black_box(-0.0f32), f32::NEG_INFINITY);
+
+    // NaN is not equal to anything, itself included, which is why floats are
+    // PartialEq but not Eq (and can't be HashMap keys or sorted with sort())
+    let nan = f32::NAN;
+    let same_nan = nan;
+    assert!(nan != same_nan);
+    assert_eq!(nan.to_bits(), same_nan.to_bits());
+    assert!(nan.is_nan());
+    assert_eq!(nan.partial_cmp(&1.0), None);
+    // total_cmp gives every float, NaN included, a place in one order
+    let mut values = [2.0, f32::NAN, -0.0, 0.0, f32::NEG_INFINITY];
+    values.sort_by(f32::total_cmp);
+    println!(
+        "\nNaN == NaN is {}; sorted with total_cmp: {values:?}",
+        nan == same_nan
+    );
+    assert!(values[4].is_nan());
+    assert!(values[1].is_sign_negative() && values[1] == 0.0);
+}
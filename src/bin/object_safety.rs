@@ -0,0 +1,224 @@
+// Which traits can be trait objects (dyn Trait), and why some can't
+// A Box<dyn Trait> is a data pointer plus a vtable: one function pointer per
+// method. So every method has to be callable without knowing the concrete
+// type, through &self, with a signature that doesn't mention that type.
+// The rules used to be called "object safety"; the compiler now says
+// "dyn compatible", which is the same thing
+use std::fmt::Display;
+
+// ---- 1. Returning Self: not dyn compatible ----
+// The caller of clone() on a dyn Cloneable couldn't know how big the returned
+// value is, or even which type it is
+trait Cloneable {
+    fn clone(&self) -> Self;
+}
+//
+//     let b: Box<dyn Cloneable> = Box::new(Dot);
+//     error[E0038]: the trait `Cloneable` is not dyn compatible
+//       ...because method `clone` references the `Self` type in its return type
+//
+// It is still a fine trait for generics, where the type is known
+fn clone_twice<T: Cloneable>(value: &T) -> (T, T) {
+    (value.clone(), value.clone())
+}
+
+// ---- 2. Only &self and known types: dyn compatible ----
+trait Printable {
+    fn print(&self) -> String;
+}
+
+// ---- 3. Requiring Sized: not dyn compatible ----
+// (Called NeedsSized rather than Sized, which would shadow the std trait of
+// that name for the rest of the file.) dyn Trait is unsized, so a trait that
+// demands Sized of every implementor rules itself out of being one
+trait NeedsSized: Sized {
+    fn size(&self) -> usize {
+        size_of::<Self>()
+    }
+}
+//
+//     let b: Box<dyn NeedsSized> = Box::new(Dot);
+//     error[E0038]: the trait `NeedsSized` is not dyn compatible
+//       ...because it requires `Self: Sized`
+
+// ---- 4. Generic methods: not dyn compatible ----
+// process::<T> is a different function for every T, and a vtable has a fixed
+// number of slots, made before anyone knows which T will be called
+trait Generic {
+    fn process<T: Display>(&self, t: T) -> String;
+}
+//
+//     let b: Box<dyn Generic> = Box::new(Dot);
+//     error[E0038]: the trait `Generic` is not dyn compatible
+//       ...because method `process` has generic type parameters
+//
+// The usual way out is to take the argument as a trait object too: one
+// function that works for every T, so it fits in one vtable slot
+trait DynGeneric {
+    fn process(&self, t: &dyn Display) -> String;
+}
+
+// ---- 5. Associated types: dyn compatible once the type is fixed ----
+trait WithAssoc {
+    type Item;
+    fn item(&self) -> &Self::Item;
+}
+// dyn WithAssoc alone says nothing about what item() returns, so it has to be
+// pinned down in the type: dyn WithAssoc<Item = i32>
+//
+//     let b: Box<dyn WithAssoc> = Box::new(Dot);
+//     error[E0191]: the value of the associated type `Item` in `WithAssoc` must be specified
+
+// ---- 6. The `where Self: Sized` escape hatch ----
+// A method marked `where Self: Sized` can't be called on a dyn value, so it is
+// left out of the vtable and the rest of the trait stays dyn compatible
+trait Shape {
+    fn area(&self) -> f64;
+    fn name(&self) -> String;
+    fn duplicate(&self) -> Self
+    where
+        Self: Sized;
+}
+
+#[derive(Debug, PartialEq)]
+struct Dot;
+
+#[derive(Debug, PartialEq)]
+struct Square(f64);
+
+#[derive(Debug, PartialEq)]
+struct Circle(f64);
+
+impl Cloneable for Dot {
+    fn clone(&self) -> Dot {
+        Dot
+    }
+}
+
+impl Printable for Dot {
+    fn print(&self) -> String {
+        "a dot".to_string()
+    }
+}
+
+impl Printable for Square {
+    fn print(&self) -> String {
+        format!("a square of side {}", self.0)
+    }
+}
+
+impl NeedsSized for Square {}
+
+impl Generic for Dot {
+    fn process<T: Display>(&self, t: T) -> String {
+        format!("dot got {t}")
+    }
+}
+
+impl DynGeneric for Dot {
+    fn process(&self, t: &dyn Display) -> String {
+        format!("dot got {t}")
+    }
+}
+
+impl WithAssoc for Dot {
+    type Item = i32;
+    fn item(&self) -> &i32 {
+        &0
+    }
+}
+
+impl WithAssoc for Square {
+    type Item = f64;
+    fn item(&self) -> &f64 {
+        &self.0
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+    fn name(&self) -> String {
+        "square".to_string()
+    }
+    fn duplicate(&self) -> Square {
+        Square(self.0)
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.0 * self.0
+    }
+    fn name(&self) -> String {
+        "circle".to_string()
+    }
+    fn duplicate(&self) -> Circle {
+        Circle(self.0)
+    }
+}
+
+fn main() {
+    // 1. Cloneable works through generics only
+    assert_eq!(clone_twice(&Dot), (Dot, Dot));
+
+    // 2. Printable is dyn compatible: different types behind one Box type
+    let printables: Vec<Box<dyn Printable>> = vec![Box::new(Dot), Box::new(Square(2.0))];
+    for p in &printables {
+        println!("Printable: {}", p.print());
+    }
+    assert_eq!(printables[1].print(), "a square of side 2");
+
+    // 3. NeedsSized only as a generic bound, or on a concrete type
+    assert_eq!(Square(1.0).size(), size_of::<f64>());
+
+    // 4. Generic as a bound, DynGeneric as an object
+    assert_eq!(Generic::process(&Dot, 5), "dot got 5");
+    let dyn_generic: Box<dyn DynGeneric> = Box::new(Dot);
+    assert_eq!(dyn_generic.process(&"text"), "dot got text");
+    assert_eq!(dyn_generic.process(&1.5), "dot got 1.5");
+
+    // 5. WithAssoc with Item fixed: only implementors with that Item fit
+    let ints: Vec<Box<dyn WithAssoc<Item = i32>>> = vec![Box::new(Dot)];
+    let floats: Box<dyn WithAssoc<Item = f64>> = Box::new(Square(3.0));
+    assert_eq!(*ints[0].item(), 0);
+    assert_eq!(*floats.item(), 3.0);
+    // Box::new(Square(3.0)) in `ints` would not compile: its Item is f64
+    println!("dyn WithAssoc<Item = f64> gave {}", floats.item());
+
+    // 6. Shape is dyn compatible despite duplicate(), which returns Self
+    let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square(2.0)), Box::new(Circle(1.0))];
+    for shape in &shapes {
+        println!("{} with area {:.2}", shape.name(), shape.area());
+    }
+    // duplicate() is there on the concrete types...
+    assert_eq!(Square(2.0).duplicate(), Square(2.0));
+    // ...but not on the objects:
+    //
+    //     shapes[0].duplicate();
+    //     error: the `duplicate` method cannot be invoked on a trait object
+    let total: f64 = shapes.iter().map(|shape| shape.area()).sum();
+    assert!((total - (4.0 + std::f64::consts::PI)).abs() < 1e-9);
+
+    // ---- Combining traits in one object ----
+    // A trait object has one vtable, so it can name only one trait with methods.
+    // Writing `dyn` twice is a syntax error, and naming the trait twice (or two
+    // different traits) is rejected too:
+    //
+    //     let b: Box<dyn Printable + dyn Printable> = Box::new(Dot);
+    //     error: invalid `dyn` keyword
+    //     let b: Box<dyn Printable + Printable> = Box::new(Dot);
+    //     error[E0225]: only auto traits can be used as additional traits in a trait object
+    //
+    // Auto traits like Send and Sync have no methods, so they can be added
+    let sendable: Box<dyn Printable + Send + Sync> = Box::new(Square(1.0));
+    let printed = std::thread::spawn(move || sendable.print()).join().unwrap();
+    assert_eq!(printed, "a square of side 1");
+    // For two traits with methods, make a third trait that has both as supertraits
+    trait PrintableShape: Printable + Shape {}
+    impl<T: Printable + Shape> PrintableShape for T {}
+    let both: Box<dyn PrintableShape> = Box::new(Square(3.0));
+    println!("{}, area {}", both.print(), both.area());
+    assert_eq!(both.area(), 9.0);
+}
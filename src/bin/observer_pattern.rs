@@ -0,0 +1,114 @@
+// The observer pattern: a value that tells its subscribers whenever it changes
+// UI frameworks call this a signal or a reactive value
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// A handle for unsubscribing later; a newtype so it can't be mixed up with other numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SubscriptionId(u64);
+
+// Listeners are boxed closures, so each subscriber can capture whatever it needs
+// The id sits next to each closure so unsubscribe can find it again
+type Listener<T> = Box<dyn Fn(&T, &T)>;
+
+struct Observable<T: Clone + PartialEq> {
+    value: T,
+    listeners: Vec<(SubscriptionId, Listener<T>)>,
+    next_id: u64,
+}
+
+impl<T: Clone + PartialEq> Observable<T> {
+    fn new(value: T) -> Observable<T> {
+        Observable {
+            value,
+            listeners: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    fn get(&self) -> &T {
+        &self.value
+    }
+
+    // Setting the same value again is not a change, so nobody is notified
+    fn set(&mut self, new_val: T) {
+        if new_val == self.value {
+            return;
+        }
+        // Keep the old value around just long enough to show it to the listeners
+        let old = std::mem::replace(&mut self.value, new_val);
+        // A Vec keeps subscription order, so listeners fire oldest first
+        for (_, listener) in &self.listeners {
+            listener(&old, &self.value);
+        }
+    }
+
+    fn subscribe(&mut self, f: impl Fn(&T, &T) + 'static) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id);
+        self.next_id += 1;
+        self.listeners.push((id, Box::new(f)));
+        id
+    }
+
+    fn unsubscribe(&mut self, id: SubscriptionId) {
+        self.listeners.retain(|(listener_id, _)| *listener_id != id);
+    }
+}
+
+fn main() {
+    // Listeners can't hold &mut to our log while the Observable owns them,
+    // so the log is shared through Rc<RefCell<..>>
+    let log: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let mut counter = Observable::new(0);
+    let first = {
+        let log = Rc::clone(&log);
+        counter.subscribe(move |old, new| log.borrow_mut().push(format!("first: {old} -> {new}")))
+    };
+    {
+        let log = Rc::clone(&log);
+        counter.subscribe(move |old, new| log.borrow_mut().push(format!("second: {old} -> {new}")));
+    }
+
+    counter.set(1);
+    // Same value: no notification
+    counter.set(1);
+    assert_eq!(*counter.get(), 1);
+    assert_eq!(
+        *log.borrow(),
+        ["first: 0 -> 1", "second: 0 -> 1"],
+        "listeners fire once per change, in subscription order"
+    );
+
+    // After unsubscribing, only the second listener is left
+    counter.unsubscribe(first);
+    counter.set(2);
+    assert_eq!(log.borrow().last().unwrap(), "second: 1 -> 2");
+    assert_eq!(log.borrow().len(), 3);
+    for line in log.borrow().iter() {
+        println!("{line}");
+    }
+
+    // A derived value: a label that always shows the current temperature
+    let temperature = Rc::new(RefCell::new(Observable::new(20)));
+    let label = Rc::new(RefCell::new(Observable::new(format!("{}°C", 20))));
+    {
+        let label = Rc::clone(&label);
+        temperature
+            .borrow_mut()
+            .subscribe(move |_, new| label.borrow_mut().set(format!("{new}°C")));
+    }
+    // The label has its own subscribers, just like any other Observable
+    label
+        .borrow_mut()
+        .subscribe(|old, new| println!("label changed from {old:?} to {new:?}"));
+
+    temperature.borrow_mut().set(21);
+    temperature.borrow_mut().set(25);
+    assert_eq!(label.borrow().get(), "25°C");
+    println!(
+        "temperature is {}, label says {}",
+        temperature.borrow().get(),
+        label.borrow().get()
+    );
+}
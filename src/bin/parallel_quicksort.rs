@@ -0,0 +1,241 @@
+// Quicksort with the two halves sorted in parallel
+// After a partition the two sides have nothing in common, so they can be
+// sorted at the same time on different cores. Handing a tiny slice to
+// another thread costs more than sorting it, so below a threshold the
+// recursion stops and sort_unstable finishes the job. rayon::join is the
+// usual way to run the two sides; rayon isn't available offline, so
+// `mod rayon_lite` below is a small stand-in with the same signature
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 10_000_000;
+const THRESHOLD: usize = 2048;
+const THRESHOLDS: [usize; 4] = [256, 1024, 4096, 16384];
+
+mod rayon_lite {
+    use std::panic;
+    use std::sync::OnceLock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    // Threads started by join and not yet finished
+    static BUSY: AtomicUsize = AtomicUsize::new(0);
+    static CORES: OnceLock<usize> = OnceLock::new();
+
+    // Runs both closures and returns both results, `a` on a thread of its own
+    // while there are cores to spare. rayon keeps one pool of threads that
+    // steal work from each other instead, so it never starts a thread per call
+    pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+    where
+        A: FnOnce() -> RA + Send,
+        B: FnOnce() -> RB + Send,
+        RA: Send,
+        RB: Send,
+    {
+        let cores = *CORES.get_or_init(|| thread::available_parallelism().map_or(1, |n| n.get()));
+        // The calling thread is busy too, so one core is already taken
+        if BUSY.fetch_add(1, Ordering::SeqCst) + 1 < cores {
+            let results = thread::scope(|s| {
+                let handle = s.spawn(a);
+                let rb = b();
+                let ra = handle.join().unwrap_or_else(|p| panic::resume_unwind(p));
+                (ra, rb)
+            });
+            BUSY.fetch_sub(1, Ordering::SeqCst);
+            results
+        } else {
+            BUSY.fetch_sub(1, Ordering::SeqCst);
+            (a(), b())
+        }
+    }
+}
+
+// Puts the median of the first, middle and last elements at `to`, so sorted
+// or reversed input doesn't pick the smallest or largest as the pivot
+fn median_of_three<T: Ord>(v: &mut [T], to: usize) {
+    let (a, b, c) = (0, v.len() / 2, v.len() - 1);
+    let median = if v[a] < v[b] {
+        if v[b] < v[c] {
+            b
+        } else if v[a] < v[c] {
+            c
+        } else {
+            a
+        }
+    } else if v[a] < v[c] {
+        a
+    } else if v[b] < v[c] {
+        c
+    } else {
+        b
+    };
+    v.swap(median, to);
+}
+
+// Both partitions return the pivot's final index p: everything in v[..p] is
+// <= v[p] and everything in v[p + 1..] is >= v[p]
+//
+// Lomuto: the pivot goes last and one index walks forward, swapping each
+// smaller element down to the end of the "small" part. Both the reading and
+// the writing position move forward only, so it streams through memory; but
+// it swaps nearly every small element, and when many elements are equal to
+// the pivot they all land on one side, so the split becomes lopsided
+fn lomuto<T: Ord>(v: &mut [T]) -> usize {
+    let last = v.len() - 1;
+    median_of_three(v, last);
+    let (pivot, rest) = v.split_last_mut().expect("partition of an empty slice");
+    let mut store = 0;
+    for i in 0..rest.len() {
+        if rest[i] < *pivot {
+            rest.swap(i, store);
+            store += 1;
+        }
+    }
+    v.swap(store, last);
+    store
+}
+
+// Hoare: the pivot goes first and two indices walk in from the ends, each
+// stopping at an element on the wrong side; those two are swapped. Each index
+// still moves through memory in order, so it is as cache friendly as Lomuto,
+// but it swaps only misplaced pairs (about a third as many swaps on random
+// input) and stops on equal elements from both sides, which keeps runs of
+// duplicates evenly split
+fn hoare<T: Ord>(v: &mut [T]) -> usize {
+    median_of_three(v, 0);
+    let (pivot, rest) = v.split_first_mut().expect("partition of an empty slice");
+    // rest[..i] <= pivot and rest[j..] >= pivot throughout
+    let (mut i, mut j) = (0, rest.len());
+    loop {
+        while i < j && rest[i] < *pivot {
+            i += 1;
+        }
+        while i < j && rest[j - 1] > *pivot {
+            j -= 1;
+        }
+        if i >= j {
+            break;
+        }
+        j -= 1;
+        rest.swap(i, j);
+        i += 1;
+    }
+    // rest[i - 1] is the last element <= pivot, which is v[i]; the pivot
+    // swaps places with it. With i == 0 the pivot is already in place
+    v.swap(0, i);
+    i
+}
+
+fn par_quicksort_with<T: Ord + Send>(
+    v: &mut [T],
+    threshold: usize,
+    partition: fn(&mut [T]) -> usize,
+) {
+    if v.len() <= threshold.max(1) {
+        v.sort_unstable();
+        return;
+    }
+    let p = partition(v);
+    let (left, right) = v.split_at_mut(p);
+    // right[0] is the pivot, already where it belongs
+    rayon_lite::join(
+        || par_quicksort_with(left, threshold, partition),
+        || par_quicksort_with(&mut right[1..], threshold, partition),
+    );
+}
+
+fn par_quicksort<T: Ord + Send>(v: &mut [T]) {
+    par_quicksort_with(v, THRESHOLD, hoare);
+}
+
+fn is_sorted<T: Ord>(v: &[T]) -> bool {
+    v.windows(2).all(|pair| pair[0] <= pair[1])
+}
+
+// Sorts a copy of `input` and checks it against the expected answer
+fn time_sort(input: &[u64], expected: &[u64], sort: impl Fn(&mut [u64])) -> Duration {
+    let mut v = input.to_vec();
+    let start = Instant::now();
+    sort(black_box(&mut v));
+    let elapsed = start.elapsed();
+    assert!(is_sorted(&v));
+    assert_eq!(v, expected);
+    elapsed
+}
+
+fn main() {
+    // ---- Small cases, including the awkward ones ----
+    assert!(is_sorted::<u32>(&[]));
+    assert!(is_sorted(&[1, 2, 2, 3]));
+    assert!(!is_sorted(&[2, 1]));
+    let cases: [Vec<i32>; 6] = [
+        vec![],
+        vec![1],
+        vec![5, 4, 3, 2, 1],
+        (0..5000).collect(),
+        (0..5000).rev().collect(),
+        // Duplicates everywhere; Lomuto is slow on these but still correct
+        (0..5000).map(|n| n % 3).collect(),
+    ];
+    for case in &cases {
+        let mut expected = case.clone();
+        expected.sort_unstable();
+        for partition in [lomuto, hoare] {
+            // Threshold 1 partitions all the way down, so the partitions do
+            // all of the work
+            let mut v = case.clone();
+            par_quicksort_with(&mut v, 1, partition);
+            assert_eq!(v, expected);
+        }
+        let mut v = case.clone();
+        par_quicksort(&mut v);
+        assert_eq!(v, expected);
+    }
+    let mut words = vec!["pear", "fig", "apple", "kiwi", "date"];
+    par_quicksort_with(&mut words, 1, hoare);
+    assert_eq!(words, ["apple", "date", "fig", "kiwi", "pear"]);
+    println!("Both partition schemes sort every small case correctly");
+
+    // ---- 10 million numbers ----
+    // From a small xorshift generator, so every run sorts the same numbers
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let input: Vec<u64> = (0..LEN)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect();
+    let mut expected = input.clone();
+    let start = Instant::now();
+    expected.sort_unstable();
+    let baseline = start.elapsed();
+
+    let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!("\nSorting {LEN} numbers on {cores} core(s):");
+    println!("  sort_unstable        {baseline:>12.2?}");
+    for threshold in THRESHOLDS {
+        for (name, partition) in [
+            ("Hoare", hoare as fn(&mut [u64]) -> usize),
+            ("Lomuto", lomuto),
+        ] {
+            let elapsed = time_sort(&input, &expected, |v| {
+                par_quicksort_with(v, threshold, partition)
+            });
+            println!(
+                "  {threshold:>5}, {name:<6}        {elapsed:>12.2?}  {:.2}x",
+                baseline.as_secs_f64() / elapsed.as_secs_f64()
+            );
+        }
+    }
+    // With one core there is nothing to gain and the partitioning is pure
+    // overhead. With more, the speedup grows with the cores but stays below
+    // their number: the first partition runs on one core alone and touches
+    // all 10 million elements. Small thresholds mean more hand-offs, large
+    // ones leave big pieces to a single thread; a few thousand is about right.
+    // Lomuto can come out ahead on random numbers despite its extra swaps:
+    // its loop has one unpredictable branch where Hoare's two inner loops
+    // each stop at random places. Its weakness only shows with duplicates
+    println!("\nTry `cargo run --release --bin parallel_quicksort` for real timings");
+}
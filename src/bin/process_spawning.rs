@@ -0,0 +1,220 @@
+// Running other programs with std::process::Command
+// Most examples use common Unix tools (echo, cat, tr, sort, sleep)
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::process::{self, Child, Command, ExitStatus, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+enum TimeoutError {
+    // The program couldn't be started (not installed, no permission, ...)
+    Io(io::Error),
+    // The deadline passed, so the child was killed
+    TimedOut(Duration),
+    // The program ran but reported failure through its exit code
+    Failed(ExitStatus, String),
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeoutError::Io(err) => write!(f, "could not run command: {err}"),
+            TimeoutError::TimedOut(after) => write!(f, "killed after {after:?}"),
+            TimeoutError::Failed(status, stderr) => {
+                write!(f, "exited with {status}: {}", stderr.trim())
+            }
+        }
+    }
+}
+
+impl From<io::Error> for TimeoutError {
+    fn from(err: io::Error) -> TimeoutError {
+        TimeoutError::Io(err)
+    }
+}
+
+// Read a pipe to the end on its own thread
+// If nobody drains stdout/stderr, a chatty child fills the OS pipe buffer
+// (often 64 KiB) and blocks forever, and so would we while waiting for it
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            // A read error just means we keep what we got so far
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+fn run_with_timeout(cmd: &mut Command, duration: Duration) -> Result<Output, TimeoutError> {
+    let mut child = cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    // The watchdog and this thread both need the child, so share it behind a Mutex
+    // The watchdog only holds the lock for a moment at a time, so it never blocks us for long
+    let child: Arc<Mutex<Child>> = Arc::new(Mutex::new(child));
+    let watchdog = {
+        let child = Arc::clone(&child);
+        let deadline = Instant::now() + duration;
+        thread::spawn(move || -> io::Result<bool> {
+            loop {
+                let mut child = child.lock().unwrap();
+                // try_wait never blocks: Some(status) once the child has exited
+                if child.try_wait()?.is_some() {
+                    return Ok(false);
+                }
+                if Instant::now() >= deadline {
+                    child.kill()?;
+                    return Ok(true);
+                }
+                drop(child);
+                thread::sleep(Duration::from_millis(10));
+            }
+        })
+    };
+
+    // The pipes close when the child exits (or is killed), which ends both drains
+    let stdout = stdout.join().expect("stdout reader panicked");
+    let stderr = stderr.join().expect("stderr reader panicked");
+    let killed = watchdog.join().expect("watchdog panicked")?;
+
+    // wait() reaps the process so it doesn't linger as a zombie
+    let status = child.lock().unwrap().wait()?;
+    if killed {
+        return Err(TimeoutError::TimedOut(duration));
+    }
+    if !status.success() {
+        return Err(TimeoutError::Failed(
+            status,
+            String::from_utf8_lossy(&stderr).into_owned(),
+        ));
+    }
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+struct NoisyDrop(&'static str);
+
+impl Drop for NoisyDrop {
+    fn drop(&mut self) {
+        println!("Dropping {}", self.0);
+    }
+}
+
+fn main() -> io::Result<()> {
+    // 1. Capture stdout and split it into lines
+    // `dir` is a cmd.exe builtin rather than a program, so it has to go through cmd /C
+    let listing = if cfg!(windows) {
+        Command::new("cmd").args(["/C", "dir", "/B"]).output()?
+    } else {
+        Command::new("ls").output()?
+    };
+    let files: Vec<String> = String::from_utf8_lossy(&listing.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    println!("ls found {} entries: {:?}", files.len(), files);
+
+    if cfg!(windows) {
+        println!("The rest of this demo uses Unix tools, so it stops here on Windows.");
+        return Ok(());
+    }
+
+    // 2. Writing to a child's stdin
+    // `echo` ignores stdin, so use `cat`, which copies stdin to stdout
+    let mut cat = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // take() moves the handle out of the Child; dropping it closes the pipe,
+    // which is how cat learns that the input is finished
+    let mut stdin = cat.stdin.take().expect("stdin was piped");
+    stdin.write_all(b"hello")?;
+    drop(stdin);
+    let output = cat.wait_with_output()?;
+    println!(
+        "cat echoed back: {:?}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    assert_eq!(output.stdout, b"hello");
+
+    // 3. A pipeline: echo "foo bar baz" | tr ' ' '\n' | sort
+    // Each child's stdout becomes the next child's stdin through Stdio::from
+    let echo = Command::new("echo")
+        .arg("foo bar baz")
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let tr = Command::new("tr")
+        .args([" ", "\n"])
+        .stdin(Stdio::from(echo.stdout.expect("stdout was piped")))
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let sort = Command::new("sort")
+        .stdin(Stdio::from(tr.stdout.expect("stdout was piped")))
+        .output()?;
+    let sorted = String::from_utf8_lossy(&sort.stdout);
+    println!("pipeline output: {:?}", sorted);
+    assert_eq!(sorted, "bar\nbaz\nfoo\n");
+
+    // 4. wait() vs wait_with_output()
+    // wait() only returns the exit status; the child's output goes wherever its
+    // stdout points (here: inherited, so straight to our terminal)
+    let status = Command::new("echo")
+        .arg("  (printed by the child itself)")
+        .spawn()?
+        .wait()?;
+    println!("wait() returned {status}");
+    // wait_with_output() also collects piped stdout/stderr into memory
+    let output = Command::new("echo")
+        .arg("captured")
+        .stdout(Stdio::piped())
+        .spawn()?
+        .wait_with_output()?;
+    println!(
+        "wait_with_output() returned {} and {:?}",
+        output.status,
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    // 5. Timeouts and exit codes
+    let quick = run_with_timeout(Command::new("echo").arg("fast"), Duration::from_secs(2));
+    println!(
+        "echo with a 2s timeout: {:?}",
+        quick.map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+    );
+
+    let slow = run_with_timeout(Command::new("sleep").arg("5"), Duration::from_millis(200));
+    println!(
+        "sleep 5 with a 200ms timeout: {}",
+        slow.as_ref().unwrap_err()
+    );
+    assert!(matches!(slow, Err(TimeoutError::TimedOut(_))));
+
+    let failing = run_with_timeout(
+        Command::new("ls").arg("/definitely/not/here"),
+        Duration::from_secs(2),
+    );
+    println!("ls on a missing path: {}", failing.as_ref().unwrap_err());
+    assert!(matches!(failing, Err(TimeoutError::Failed(..))));
+
+    let missing = run_with_timeout(&mut Command::new("no-such-program"), Duration::from_secs(1));
+    println!("a program that doesn't exist: {}", missing.unwrap_err());
+
+    // 6. process::exit ends the process immediately
+    // Destructors of values still on the stack never run, so buffered data or
+    // cleanup in Drop is lost. Prefer returning from main when you can
+    let _first = NoisyDrop("a value declared at the top of main");
+    {
+        let _inner = NoisyDrop("a value from an inner scope");
+    }
+    let _skipped = NoisyDrop("a value that is never dropped");
+    println!("Calling process::exit(0); neither remaining value prints its Drop message");
+    process::exit(0);
+}
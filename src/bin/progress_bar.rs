@@ -0,0 +1,198 @@
+// A terminal progress bar, the kind indicatif draws, built from scratch
+// The trick is "\r": a carriage return moves the cursor back to the start of the
+// line without starting a new one, so the next print overwrites the bar in place
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use learning_rust::game::Game;
+use learning_rust::strategy::{self, Midpoint};
+
+#[derive(Debug, Clone, Copy)]
+struct BarStyle {
+    fill: char,
+    empty: char,
+    show_percent: bool,
+    show_eta: bool,
+}
+
+impl BarStyle {
+    // Block characters look best, but not every terminal font has them
+    const UNICODE: BarStyle = BarStyle {
+        fill: '█',
+        empty: ' ',
+        show_percent: true,
+        show_eta: true,
+    };
+    const ASCII: BarStyle = BarStyle {
+        fill: '#',
+        empty: '-',
+        show_percent: true,
+        show_eta: false,
+    };
+    const BARE: BarStyle = BarStyle {
+        fill: '#',
+        empty: ' ',
+        show_percent: false,
+        show_eta: false,
+    };
+}
+
+struct ProgressBar {
+    total: u64,
+    current: u64,
+    width: usize,
+    style: BarStyle,
+}
+
+impl ProgressBar {
+    fn new(total: u64, width: usize, style: BarStyle) -> ProgressBar {
+        ProgressBar {
+            total,
+            current: 0,
+            width,
+            style,
+        }
+    }
+
+    // Going past the total would draw a bar longer than its frame
+    fn tick(&mut self, n: u64) {
+        self.current = (self.current + n).min(self.total);
+    }
+
+    fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.current as f64 / self.total as f64
+        }
+    }
+
+    // Every part has a fixed width, so the line never changes length and a
+    // shorter redraw can't leave stray characters from the previous one behind
+    //   "[" + bar + "]"      2
+    //   " 100%"              5
+    //   " ETA 01:23"        10
+    fn overhead(&self) -> usize {
+        2 + if self.style.show_percent { 5 } else { 0 } + if self.style.show_eta { 10 } else { 0 }
+    }
+
+    fn render(&self, elapsed: Duration) -> String {
+        let filled = (self.fraction() * self.width as f64).round() as usize;
+        let mut line = String::from("[");
+        line.extend(std::iter::repeat_n(self.style.fill, filled));
+        line.extend(std::iter::repeat_n(self.style.empty, self.width - filled));
+        line.push(']');
+        if self.style.show_percent {
+            line.push_str(&format!(
+                " {:>3}%",
+                (self.fraction() * 100.0).round() as u32
+            ));
+        }
+        if self.style.show_eta {
+            line.push_str(&format!(" ETA {}", self.eta(elapsed)));
+        }
+        line
+    }
+
+    // Assume the remaining work goes at the same pace as the work done so far
+    fn eta(&self, elapsed: Duration) -> String {
+        if self.current == 0 {
+            return "--:--".to_string();
+        }
+        let remaining =
+            elapsed.as_secs_f64() * (self.total - self.current) as f64 / self.current as f64;
+        let secs = (remaining.round() as u64).min(99 * 60 + 59);
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+
+    fn print_progress(&self, elapsed: Duration) {
+        print!("\r{}", self.render(elapsed));
+        // stdout is line-buffered, and a progress bar never ends its line
+        io::stdout().flush().expect("flush stdout");
+    }
+}
+
+// For work whose size isn't known up front: something moves, but promises nothing
+struct Spinner {
+    frames: &'static [char],
+    index: usize,
+}
+
+impl Spinner {
+    fn new() -> Spinner {
+        Spinner {
+            frames: &['|', '/', '-', '\\'],
+            index: 0,
+        }
+    }
+
+    fn tick(&mut self) {
+        self.index = (self.index + 1) % self.frames.len();
+    }
+
+    fn render(&self, message: &str) -> String {
+        format!("{} {message}", self.frames[self.index])
+    }
+}
+
+fn main() {
+    // render always has the same visible length: width + overhead
+    let elapsed = Duration::from_secs(3);
+    for style in [BarStyle::UNICODE, BarStyle::ASCII, BarStyle::BARE] {
+        for width in [0, 1, 10, 37, 80] {
+            let mut bar = ProgressBar::new(50, width, style);
+            for step in [0, 1, 24, 25] {
+                bar.tick(step);
+                let line = bar.render(elapsed);
+                // chars, not len: '█' takes 3 bytes in UTF-8
+                assert_eq!(line.chars().count(), width + bar.overhead(), "{line:?}");
+            }
+        }
+    }
+    let mut bar = ProgressBar::new(4, 8, BarStyle::ASCII);
+    bar.tick(1);
+    assert_eq!(bar.render(elapsed), "[##------]  25%");
+    bar.tick(10);
+    assert_eq!(bar.render(elapsed), "[########] 100%");
+    // A quarter done after 3 seconds leaves 9 more
+    let mut bar = ProgressBar::new(4, 4, BarStyle::UNICODE);
+    bar.tick(1);
+    assert_eq!(bar.render(elapsed), "[█   ]  25% ETA 00:09");
+
+    // The computer solves round after round of the guessing game
+    let rounds = 40;
+    let mut bar = ProgressBar::new(rounds, 30, BarStyle::UNICODE);
+    let mut total_guesses = 0;
+    let start = Instant::now();
+    println!("The computer plays {rounds} rounds on 1..=1000 with binary search:");
+    for seed in 0..rounds {
+        let mut game = Game::seeded(1..=1000, seed);
+        let (guesses, won) = strategy::play(&mut Midpoint, &mut game, 20);
+        assert!(
+            won,
+            "binary search always wins within 20 guesses of 1..=1000"
+        );
+        total_guesses += guesses;
+        // Real work would take its own time; slow down so the bar can be seen
+        thread::sleep(Duration::from_millis(25));
+        bar.tick(1);
+        bar.print_progress(start.elapsed());
+    }
+    println!();
+    println!(
+        "Done: {:.2} guesses per round on average.",
+        total_guesses as f64 / rounds as f64
+    );
+
+    // A spinner while waiting for something of unknown length
+    let mut spinner = Spinner::new();
+    for _ in 0..12 {
+        print!("\r{}", spinner.render("thinking..."));
+        io::stdout().flush().expect("flush stdout");
+        spinner.tick();
+        thread::sleep(Duration::from_millis(50));
+    }
+    // Overwrite the spinner with spaces, or its last frame stays on screen
+    println!("\r{}\rThought about it.", " ".repeat(20));
+}
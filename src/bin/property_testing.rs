@@ -0,0 +1,456 @@
+// Property-based testing: instead of hand-picking examples, generate hundreds
+// of random inputs and check that a property holds for all of them
+// proptest and quickcheck do this with macros; here the moving parts are
+// written out by hand, on top of rand:
+//   generate a random input -> check the property -> on failure, shrink the input
+use std::fmt::Debug;
+use std::path::PathBuf;
+
+use learning_rust::config::Config;
+use learning_rust::daily::NaiveDate;
+use learning_rust::game::TempPolicy;
+use learning_rust::input_policy::InvalidInputPolicy;
+use learning_rust::progress::Difficulty;
+use learning_rust::radix;
+use learning_rust::recent_secrets::MAX_WINDOW;
+use learning_rust::season::{MAX_SEASON_MONTHS, Season, SeasonView, Seasons};
+use learning_rust::strategy;
+use learning_rust::terminal::SymbolSet;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CASES: usize = 256;
+
+// A fixed seed makes every run check the same inputs, so a failure can be reproduced
+const SEED: u64 = 2024;
+
+// Shrinking turns a big, noisy failing input into a small one that is easy to debug
+// Each implementation lists "simpler" versions of a value; the checker keeps
+// whichever one still fails and repeats until nothing simpler fails
+trait Shrink: Clone + Debug {
+    fn shrink(&self) -> Vec<Self>;
+}
+
+impl Shrink for i32 {
+    // Move towards zero: 0 first, then halfway, then one step
+    fn shrink(&self) -> Vec<i32> {
+        match *self {
+            0 => vec![],
+            n => {
+                let mut candidates = vec![0, n / 2, n - n.signum()];
+                candidates.dedup();
+                candidates.retain(|&c| c != n);
+                candidates
+            }
+        }
+    }
+}
+
+impl Shrink for u8 {
+    fn shrink(&self) -> Vec<u8> {
+        match *self {
+            0 => vec![],
+            n => vec![0, n / 2, n - 1],
+        }
+    }
+}
+
+impl<T: Shrink> Shrink for Vec<T> {
+    // Try dropping each element, then try shrinking each element in place
+    fn shrink(&self) -> Vec<Vec<T>> {
+        let mut candidates = Vec::new();
+        for i in 0..self.len() {
+            let mut smaller = self.clone();
+            smaller.remove(i);
+            candidates.push(smaller);
+        }
+        for (i, item) in self.iter().enumerate() {
+            for simpler in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = simpler;
+                candidates.push(candidate);
+            }
+        }
+        candidates
+    }
+}
+
+impl<A: Shrink, B: Shrink> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<(A, B)> {
+        let mut candidates: Vec<(A, B)> = self
+            .0
+            .shrink()
+            .into_iter()
+            .map(|a| (a, self.1.clone()))
+            .collect();
+        candidates.extend(self.1.shrink().into_iter().map(|b| (self.0.clone(), b)));
+        candidates
+    }
+}
+
+// Configs are built to be valid, so there is nothing simpler worth trying
+impl Shrink for Config {
+    fn shrink(&self) -> Vec<Config> {
+        vec![]
+    }
+}
+
+// Runs `property` on CASES generated inputs
+// On failure, returns the smallest failing input that shrinking could find
+fn check<T: Shrink>(
+    name: &str,
+    generate: impl Fn(&mut StdRng) -> T,
+    property: impl Fn(&T) -> bool,
+) -> Result<(), T> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    for _ in 0..CASES {
+        let input = generate(&mut rng);
+        if !property(&input) {
+            let original = input.clone();
+            let minimal = shrink_failure(input, &property);
+            println!("✗ {name}\n    failed for {original:?}\n    shrunk to  {minimal:?}");
+            return Err(minimal);
+        }
+    }
+    println!("✓ {name} ({CASES} cases)");
+    Ok(())
+}
+
+fn shrink_failure<T: Shrink>(mut failing: T, property: &impl Fn(&T) -> bool) -> T {
+    // Every accepted step makes the input strictly simpler, so this ends
+    while let Some(simpler) = failing.shrink().into_iter().find(|c| !property(c)) {
+        failing = simpler;
+    }
+    failing
+}
+
+// Generators, the equivalent of proptest's strategies
+fn small_vec(rng: &mut StdRng) -> Vec<i32> {
+    let len = rng.random_range(0..20);
+    // A narrow value range makes duplicates likely, which is where sorting bugs hide
+    (0..len).map(|_| rng.random_range(-50..=50)).collect()
+}
+
+fn bytes(rng: &mut StdRng) -> Vec<u8> {
+    let len = rng.random_range(0..40);
+    (0..len).map(|_| rng.random()).collect()
+}
+
+// Like prop_compose!: build a whole Config out of smaller generated parts,
+// keeping only combinations the program would accept
+fn valid_config(rng: &mut StdRng) -> Config {
+    let path = |rng: &mut StdRng| {
+        rng.random_bool(0.3)
+            .then(|| PathBuf::from(format!("save-{}.json", rng.random_range(0..100))))
+    };
+    Config {
+        difficulty: Difficulty::ALL[rng.random_range(0..Difficulty::ALL.len())],
+        center: rng.random_bool(0.5).then(|| rng.random()),
+        spread: rng.random_bool(0.5).then(|| rng.random()),
+        tolerance: rng.random_range(0..=10),
+        tutorial: rng.random(),
+        accessible: rng.random(),
+        warmup: rng.random(),
+        words: rng.random(),
+        blind: rng.random(),
+        base: radix::BASES[rng.random_range(0..radix::BASES.len())],
+        show_range: rng.random(),
+        symbols: match rng.random_range(0..3) {
+            0 => None,
+            1 => Some(SymbolSet::Unicode),
+            _ => Some(SymbolSet::Ascii),
+        },
+        echo: rng.random(),
+        terse: rng.random(),
+        max_line_width: rng.random_bool(0.5).then(|| rng.random_range(1..=200)),
+        bell: rng.random(),
+        celebrate: rng.random(),
+        warmer_colder: rng.random(),
+        temp_policy: TempPolicy::ALL[rng.random_range(0..TempPolicy::ALL.len())],
+        invalid_input: match rng.random_range(0..3) {
+            0 => InvalidInputPolicy::Lenient,
+            1 => InvalidInputPolicy::Counted,
+            _ => InvalidInputPolicy::Strict {
+                strikes: rng.random_range(1..=10),
+            },
+        },
+        entropy: rng.random(),
+        influence: rng.random(),
+        coach: rng.random(),
+        auto: rng.random(),
+        auto_quiet: rng.random(),
+        evaluate: rng
+            .random_bool(0.5)
+            .then(|| strategy::NAMES[rng.random_range(0..strategy::NAMES.len())].to_string()),
+        tournament: rng.random(),
+        games: rng.random_range(1..=5000),
+        grade: path(rng),
+        report: path(rng),
+        rubric: path(rng),
+        intervals: rng.random(),
+        save: path(rng),
+        resume: path(rng),
+        history: path(rng),
+        leaderboard: path(rng).unwrap_or_else(|| PathBuf::from("leaderboard.json")),
+        practice: rng.random(),
+        season: match rng.random_range(0..3) {
+            0 => None,
+            1 => Some(SeasonView::AllTime),
+            _ => Some(SeasonView::Season(Season::Starting {
+                year: rng.random_range(1970..=9999),
+                month: rng.random_range(1..=12),
+            })),
+        },
+        seasons: Seasons::new(rng.random_range(1..=MAX_SEASON_MONTHS)).unwrap(),
+        all_time: rng.random(),
+        aliases: path(rng),
+        spectator_log: path(rng),
+        auto_hints: rng.random(),
+        earn_hints: rng.random(),
+        free_first_hint: rng.random(),
+        timing: rng.random(),
+        seed: rng.random_bool(0.5).then(|| rng.random()),
+        no_repeat: rng
+            .random_bool(0.5)
+            .then(|| rng.random_range(1..=MAX_WINDOW)),
+        daily: rng.random(),
+        date: rng
+            .random_bool(0.5)
+            .then(|| NaiveDate::from_days_since_epoch(rng.random_range(0..40_000))),
+        show_seed: rng.random(),
+        commit: rng.random(),
+        calibrate: rng.random(),
+        capabilities: rng.random(),
+        tty: rng.random_bool(0.5).then(|| rng.random()),
+        utf8: rng.random_bool(0.5).then(|| rng.random()),
+        term_width: rng.random_bool(0.5).then(|| rng.random_range(1..=300)),
+    }
+}
+
+// The inverse of Config::from_args; the property below checks they agree
+fn to_args(config: &Config) -> Vec<String> {
+    let mut args = vec![
+        "--difficulty".to_string(),
+        config.difficulty.name().to_string(),
+        "--tolerance".to_string(),
+        config.tolerance.to_string(),
+        "--temp-policy".to_string(),
+        config.temp_policy.name().to_string(),
+        "--invalid-input".to_string(),
+        config.invalid_input.to_string(),
+        "--base".to_string(),
+        config.base.to_string(),
+    ];
+    let flags = [
+        ("--tutorial", config.tutorial),
+        ("--accessible", config.accessible),
+        ("--warmup", config.warmup),
+        ("--words", config.words),
+        ("--blind", config.blind),
+        ("--show-range", config.show_range),
+        ("--symbols", config.symbols == Some(SymbolSet::Unicode)),
+        ("--ascii-symbols", config.symbols == Some(SymbolSet::Ascii)),
+        ("--no-echo", !config.echo),
+        ("--terse", config.terse),
+        ("--bell", config.bell),
+        ("--no-celebration", !config.celebrate),
+        ("--warmer-colder", config.warmer_colder),
+        ("--entropy", config.entropy),
+        ("--influence", config.influence),
+        ("--coach", config.coach),
+        ("--auto-hints", config.auto_hints),
+        ("--earn-hints", config.earn_hints),
+        ("--free-first-hint", config.free_first_hint),
+        ("--timing", config.timing),
+        ("--daily", config.daily),
+        ("--show-seed", config.show_seed),
+        ("--commit", config.commit),
+        ("--calibrate", config.calibrate),
+        ("--tournament", config.tournament),
+        ("--intervals", config.intervals),
+        ("--auto", config.auto),
+        ("--auto-quiet", config.auto_quiet),
+        ("--capabilities", config.capabilities),
+        ("--practice", config.practice),
+        ("--all-time", config.all_time),
+    ];
+    for (flag, on) in flags {
+        if on {
+            args.push(flag.to_string());
+        }
+    }
+    if let Some(name) = &config.evaluate {
+        args.extend(["--evaluate".to_string(), name.clone()]);
+    }
+    if let Some(center) = config.center {
+        args.extend(["--center".to_string(), center.to_string()]);
+    }
+    if let Some(spread) = config.spread {
+        args.extend(["--spread".to_string(), format!("{spread}%")]);
+    }
+    if let Some(width) = config.max_line_width {
+        args.extend(["--max-line-width".to_string(), width.to_string()]);
+    }
+    if let Some(tty) = config.tty {
+        args.extend(["--tty".to_string(), tty.to_string()]);
+    }
+    if let Some(utf8) = config.utf8 {
+        args.extend(["--utf8".to_string(), utf8.to_string()]);
+    }
+    if let Some(width) = config.term_width {
+        args.extend(["--term-width".to_string(), width.to_string()]);
+    }
+    if let Some(seed) = config.seed {
+        args.extend(["--seed".to_string(), seed.to_string()]);
+    }
+    if let Some(window) = config.no_repeat {
+        args.extend(["--no-repeat".to_string(), window.to_string()]);
+    }
+    if let Some(date) = config.date {
+        args.extend(["--date".to_string(), date.to_string()]);
+    }
+    if let Some(season) = config.season {
+        args.extend(["--season".to_string(), season.to_string()]);
+    }
+    args.extend([
+        "--season-months".to_string(),
+        config.seasons.months().to_string(),
+    ]);
+    args.extend(["--games".to_string(), config.games.to_string()]);
+    args.extend([
+        "--leaderboard".to_string(),
+        config.leaderboard.display().to_string(),
+    ]);
+    for (flag, path) in [
+        ("--save", &config.save),
+        ("--resume", &config.resume),
+        ("--history", &config.history),
+        ("--grade", &config.grade),
+        ("--report", &config.report),
+        ("--rubric", &config.rubric),
+        ("--aliases", &config.aliases),
+        ("--spectator-log", &config.spectator_log),
+    ] {
+        if let Some(path) = path {
+            args.extend([flag.to_string(), path.display().to_string()]);
+        }
+    }
+    args
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Every 3 bytes become 4 characters of 6 bits each; '=' pads the last group
+fn base64_encode(data: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    for group in text.as_bytes().chunks(4) {
+        if group.len() != 4 {
+            return None;
+        }
+        let mut n = 0u32;
+        let mut padding = 0;
+        for &c in group {
+            let value = match c {
+                b'=' => {
+                    padding += 1;
+                    0
+                }
+                _ => BASE64.iter().position(|&b| b == c)? as u32,
+            };
+            n = n << 6 | value;
+        }
+        let bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        out.extend_from_slice(&bytes[..3 - padding.min(3)]);
+    }
+    Some(out)
+}
+
+fn sorted(v: &[i32]) -> Vec<i32> {
+    let mut v = v.to_vec();
+    v.sort();
+    v
+}
+
+fn main() {
+    // 1. Sorting twice changes nothing
+    let idempotent = check("sort is idempotent", small_vec, |v| {
+        sorted(&sorted(v)) == sorted(v)
+    });
+
+    // 2. Reversing twice gives back the original
+    let involution = check("reverse(reverse(v)) == v", small_vec, |v| {
+        let mut twice = v.clone();
+        twice.reverse();
+        twice.reverse();
+        twice == *v
+    });
+
+    // 3. Addition is commutative; wrapping_add so huge values can't overflow-panic
+    let commutative = check(
+        "a + b == b + a",
+        |rng| (rng.random::<i32>(), rng.random::<i32>()),
+        |&(a, b)| a.wrapping_add(b) == b.wrapping_add(a),
+    );
+
+    // 4. Decoding undoes encoding
+    let round_trip = check("base64 round trip", bytes, |data| {
+        base64_decode(&base64_encode(data)).as_deref() == Some(data.as_slice())
+    });
+
+    // 5. Inserting and re-sorting is the same as inserting at the right spot
+    let insertion = check(
+        "insert + sort == insert at position",
+        |rng| (small_vec(rng), rng.random_range(-50..=50)),
+        |(v, x)| {
+            let mut resorted = sorted(v);
+            resorted.push(*x);
+            resorted.sort();
+            let mut placed = sorted(v);
+            let position = placed.partition_point(|y| y < x);
+            placed.insert(position, *x);
+            resorted == placed
+        },
+    );
+
+    // 6. A generated Config survives being turned back into arguments
+    let config = check("Config -> args -> Config", valid_config, |config| {
+        Config::from_args(to_args(config)) == Ok(config.clone())
+    });
+
+    assert!(idempotent.is_ok() && involution.is_ok() && commutative.is_ok());
+    assert!(round_trip.is_ok() && insertion.is_ok() && config.is_ok());
+    assert_eq!(base64_encode(b"Man"), "TWFu");
+    assert_eq!(base64_encode(b"hi"), "aGk=");
+
+    // 7. A property that is wrong: "reversing a vector leaves it unchanged"
+    // The first failure is some random mess; shrinking boils it down to the
+    // smallest counterexample, two different elements
+    let wrong = check("reverse(v) == v (wrong on purpose)", small_vec, |v| {
+        let mut reversed = v.clone();
+        reversed.reverse();
+        reversed == *v
+    });
+    let minimal = wrong.unwrap_err();
+    assert_eq!(minimal.len(), 2);
+    assert_ne!(minimal[0], minimal[1]);
+}
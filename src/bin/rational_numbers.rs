@@ -0,0 +1,262 @@
+// Exact rational arithmetic: fractions that never lose precision
+// With f64, 0.1 + 0.2 is only *approximately* 0.3
+// With a Rational, 1/10 + 2/10 is exactly 3/10
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// A fraction num/den, always kept in reduced form:
+//   - gcd(|num|, den) == 1
+//   - the sign lives in the numerator, so the denominator is always positive
+//   - zero is stored as 0/1
+// Because each value has exactly one representation, derived equality would be correct,
+// but we still write PartialEq by hand to make that reasoning explicit
+#[derive(Debug, Clone, Copy)]
+struct Rational {
+    num: i64,
+    den: u64,
+}
+
+#[derive(Debug, PartialEq)]
+struct DivByZeroError;
+
+impl fmt::Display for DivByZeroError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "denominator must not be zero")
+    }
+}
+
+// Error returned when an f64 can't be turned into an i64/u64 fraction
+// (NaN, infinity, or a value whose exact fraction needs more than 64 bits)
+#[derive(Debug, PartialEq)]
+struct NotRepresentable(f64);
+
+// Euclid's algorithm: gcd(a, b) == gcd(b, a % b)
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Rational {
+    fn new(num: i64, den: i64) -> Result<Rational, DivByZeroError> {
+        if den == 0 {
+            return Err(DivByZeroError);
+        }
+        Ok(Rational::reduce(num as i128, den as i128))
+    }
+
+    // Build a reduced Rational from wide intermediate values
+    // The arithmetic operators compute in i128 so that products like
+    // a.num * b.den can't overflow before we get the chance to reduce them
+    fn reduce(num: i128, den: i128) -> Rational {
+        // Move the sign into the numerator
+        let (num, den) = if den < 0 { (-num, -den) } else { (num, den) };
+        let divisor = gcd(num.unsigned_abs(), den as u128).max(1) as i128;
+        Rational {
+            num: i64::try_from(num / divisor).expect("numerator overflowed i64"),
+            den: u64::try_from(den / divisor).expect("denominator overflowed u64"),
+        }
+    }
+
+    fn to_f64(self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Rational {
+        Rational { num: n, den: 1 }
+    }
+}
+
+impl TryFrom<f64> for Rational {
+    type Error = NotRepresentable;
+
+    // Every finite f64 is exactly mantissa * 2^exponent, i.e. a fraction whose
+    // denominator is a power of two. 0.1 is therefore NOT 1/10 but the nearest
+    // such fraction: 3602879701896397/36028797018963968
+    fn try_from(x: f64) -> Result<Rational, NotRepresentable> {
+        if !x.is_finite() {
+            return Err(NotRepresentable(x));
+        }
+        if x == 0.0 {
+            return Ok(Rational::from(0));
+        }
+
+        // Pull the IEEE 754 fields apart: 1 sign bit, 11 exponent bits, 52 fraction bits
+        let bits = x.to_bits();
+        let sign: i128 = if bits >> 63 == 1 { -1 } else { 1 };
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = (bits & ((1 << 52) - 1)) as i128;
+
+        // Normal numbers have an implicit leading 1 bit, subnormals do not
+        let (mantissa, exponent) = if biased_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exponent - 1075)
+        };
+
+        // Strip factors of two shared by the mantissa and the power of two so the
+        // shifts below stay as small as possible
+        let shift = mantissa.trailing_zeros() as i32;
+        let mantissa = mantissa >> shift;
+        let exponent = exponent + shift;
+
+        if exponent >= 0 {
+            let num = mantissa
+                .checked_shl(exponent as u32)
+                .filter(|n| n >> exponent == mantissa)
+                .and_then(|n| i64::try_from(sign * n).ok())
+                .ok_or(NotRepresentable(x))?;
+            Ok(Rational::from(num))
+        } else if -exponent < 64 {
+            let num = i64::try_from(sign * mantissa).map_err(|_| NotRepresentable(x))?;
+            Ok(Rational::reduce(num as i128, 1i128 << -exponent))
+        } else {
+            // The denominator would need 64 or more bits
+            Err(NotRepresentable(x))
+        }
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl PartialEq for Rational {
+    fn eq(&self, other: &Rational) -> bool {
+        // Reduced form means equal values have identical fields
+        self.num == other.num && self.den == other.den
+    }
+}
+
+impl Eq for Rational {}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    // a/b < c/d  <=>  a*d < c*b  (valid because both denominators are positive)
+    fn cmp(&self, other: &Rational) -> Ordering {
+        let left = self.num as i128 * other.den as i128;
+        let right = other.num as i128 * self.den as i128;
+        left.cmp(&right)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational::reduce(-(self.num as i128), self.den as i128)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    // a/b + c/d = (a*d + c*b) / (b*d)
+    fn add(self, other: Rational) -> Rational {
+        Rational::reduce(
+            self.num as i128 * other.den as i128 + other.num as i128 * self.den as i128,
+            self.den as i128 * other.den as i128,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Rational) -> Rational {
+        self + (-other)
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    // a/b * c/d = (a*c) / (b*d)
+    fn mul(self, other: Rational) -> Rational {
+        Rational::reduce(
+            self.num as i128 * other.num as i128,
+            self.den as i128 * other.den as i128,
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    // a/b / c/d = (a*d) / (b*c)
+    // Like integer division, dividing by zero panics; use Rational::new for a checked version
+    fn div(self, other: Rational) -> Rational {
+        assert!(other.num != 0, "attempt to divide by zero");
+        Rational::reduce(
+            self.num as i128 * other.den as i128,
+            self.den as i128 * other.num as i128,
+        )
+    }
+}
+
+fn r(num: i64, den: i64) -> Rational {
+    Rational::new(num, den).expect("non-zero denominator")
+}
+
+fn main() {
+    // Construction always reduces and normalizes the sign
+    assert_eq!(r(6, 8), r(3, 4));
+    assert_eq!(r(5, -2).to_string(), "-5/2");
+    assert_eq!(r(14, 2).to_string(), "7");
+    assert_eq!(Rational::new(1, 0), Err(DivByZeroError));
+    println!(
+        "6/8 = {}, 5/-2 = {}, 14/2 = {}",
+        r(6, 8),
+        r(5, -2),
+        r(14, 2)
+    );
+
+    // The classic: exact where floating point is not
+    let exact = r(1, 3) + r(1, 6);
+    println!("1/3 + 1/6 = {}", exact);
+    assert_eq!(exact, r(1, 2));
+    // The same kind of sum in floating point picks up rounding error
+    println!("0.1 + 0.2 = {:.20} (f64)", 0.1 + 0.2);
+    println!("1/10 + 2/10 = {} (Rational)", r(1, 10) + r(2, 10));
+    assert_eq!(r(1, 10) + r(2, 10), r(3, 10));
+
+    // The other operators
+    assert_eq!(r(3, 4) - r(1, 4), r(1, 2));
+    assert_eq!(r(2, 3) * r(3, 4), r(1, 2));
+    assert_eq!(r(1, 2) / r(1, 4), Rational::from(2));
+    assert_eq!(-r(1, 2), r(-1, 2));
+    assert!(r(1, 3) < r(1, 2));
+    assert!(r(-1, 2) < r(-1, 3));
+
+    // f64 -> Rational is exact, which shows what a float really stores
+    assert_eq!(Rational::try_from(0.75), Ok(r(3, 4)));
+    assert_eq!(Rational::try_from(-2.5), Ok(r(-5, 2)));
+    assert_eq!(Rational::try_from(8.0), Ok(Rational::from(8)));
+    assert!(Rational::try_from(f64::NAN).is_err());
+    assert!(Rational::try_from(f64::INFINITY).is_err());
+    println!("0.1 as f64 is really {}", Rational::try_from(0.1).unwrap());
+
+    // Harmonic numbers H_n = 1/1 + 1/2 + ... + 1/n
+    println!("\nHarmonic numbers:");
+    let mut h = Rational::from(0);
+    for k in 1..=10 {
+        h = h + r(1, k);
+        println!("H_{:<2} = {:>10} ~ {:.6}", k, h.to_string(), h.to_f64());
+    }
+    assert_eq!(h, r(7381, 2520));
+}
@@ -0,0 +1,150 @@
+// Handling Unix signals (Ctrl-C / SIGINT and SIGTERM) without dying mid-write
+// Run it, then press Ctrl-C, or from another terminal: kill -TERM <pid>
+// `cargo run --bin signal_handling -- --demo` sends itself SIGINT twice
+//
+// Crates like signal-hook wrap all of this; here we use the raw libc calls to
+// see what such a crate does under the hood. The moving parts are:
+//   signal handler --(write 1 byte)--> pipe --(read)--> watcher thread --(channel)--> main loop
+// Signals only exist on Unix, so on other platforms the program just says so
+
+#[cfg(unix)]
+mod unix {
+    use std::fs;
+    use std::process;
+    use std::sync::atomic::{AtomicI32, Ordering};
+    use std::sync::mpsc::{self, Receiver, TryRecvError};
+    use std::thread;
+    use std::time::Duration;
+
+    // The write end of the self-pipe, shared with the signal handler
+    // A handler can't capture variables like a closure, so it has to be a static
+    static PIPE_WRITE_FD: AtomicI32 = AtomicI32::new(-1);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Signal {
+        Interrupt,
+        Terminate,
+    }
+
+    // The signal handler interrupts whatever the program was doing, at any instruction
+    // It may only call "async-signal-safe" functions: no allocation, no locks, no println!
+    // Writing one byte to a pipe is safe, so that is all it does
+    extern "C" fn on_signal(signum: libc::c_int) {
+        let byte = signum as u8;
+        let fd = PIPE_WRITE_FD.load(Ordering::Relaxed);
+        // SAFETY: write(2) is async-signal-safe and `byte` outlives the call
+        // If the pipe is full the byte is dropped, which is fine: a signal is already pending
+        unsafe {
+            libc::write(fd, &byte as *const u8 as *const libc::c_void, 1);
+        }
+    }
+
+    // Install the handler and return a channel that yields each received signal,
+    // i.e. a tiny version of signal_hook::iterator::Signals feeding a channel
+    fn subscribe_to_signals() -> Receiver<Signal> {
+        let mut fds = [0 as libc::c_int; 2];
+        // SAFETY: fds points at two writable c_ints, as pipe(2) requires
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            panic!("pipe() failed");
+        }
+        let [read_fd, write_fd] = fds;
+        PIPE_WRITE_FD.store(write_fd, Ordering::Relaxed);
+
+        for signum in [libc::SIGINT, libc::SIGTERM] {
+            // SAFETY: a zeroed sigaction is a valid "no flags, empty mask" starting point,
+            // and on_signal has the signature the kernel expects for sa_sigaction
+            unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = on_signal as *const () as libc::sighandler_t;
+                // SA_RESTART makes interrupted system calls (like our read) resume
+                action.sa_flags = libc::SA_RESTART;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(signum, &action, std::ptr::null_mut());
+            }
+        }
+
+        // The watcher thread is free to do anything slow, since it runs outside the handler
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            loop {
+                let mut byte = 0u8;
+                // SAFETY: reading one byte into a valid, writable u8
+                let n =
+                    unsafe { libc::read(read_fd, &mut byte as *mut u8 as *mut libc::c_void, 1) };
+                if n != 1 {
+                    break;
+                }
+                let signal = match byte as libc::c_int {
+                    libc::SIGINT => Signal::Interrupt,
+                    libc::SIGTERM => Signal::Terminate,
+                    _ => continue,
+                };
+                // The main loop is gone once it has decided to exit; nothing left to do
+                if tx.send(signal).is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+
+    fn send_to_self(signum: libc::c_int) {
+        // SAFETY: kill(2) with our own pid only raises a signal
+        unsafe {
+            libc::kill(libc::getpid(), signum);
+        }
+    }
+
+    pub fn main() {
+        let demo = std::env::args().any(|arg| arg == "--demo");
+        let signals = subscribe_to_signals();
+        println!("Running as pid {}. Press Ctrl-C to stop.", process::id());
+
+        let mut counter: u64 = 0;
+        loop {
+            counter += 1;
+            println!("counter = {counter}");
+
+            if demo && counter == 3 {
+                // An impatient user hammering Ctrl-C: two signals back to back
+                send_to_self(libc::SIGINT);
+                send_to_self(libc::SIGINT);
+            }
+
+            thread::sleep(Duration::from_millis(500));
+
+            // Poll instead of blocking, so the loop keeps ticking between signals
+            match signals.try_recv() {
+                Ok(Signal::Interrupt) => {
+                    println!("Received CTRL-C, saving state…");
+                    fs::write("state.txt", format!("{counter}\n")).expect("write state.txt");
+                    // Any further Ctrl-C just queues up another message in the channel;
+                    // our handler stays installed, so the default "die immediately"
+                    // behaviour can't cut the save short
+                    let extra = signals.try_iter().count();
+                    if extra > 0 {
+                        println!("(ignored {extra} more signal(s) while saving)");
+                    }
+                    println!("Saved counter {counter} to state.txt");
+                    process::exit(0);
+                }
+                Ok(Signal::Terminate) => {
+                    println!("Received SIGTERM, shutting down…");
+                    process::exit(0);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => panic!("signal watcher thread stopped"),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn main() {
+    unix::main();
+}
+
+#[cfg(not(unix))]
+fn main() {
+    println!("This example uses Unix signals and only runs on Linux and macOS.");
+}
@@ -0,0 +1,250 @@
+// SIMD: one instruction works on several numbers at once
+// An AVX2 register holds 8 f32s, so adding two registers does 8 additions in one step
+// std::simd (f32x8 and friends) is still nightly-only and the wide crate isn't
+// available offline, so this example shows the two stable routes instead:
+//   1. portable: process [f32; 8] chunks with 8 separate accumulators and let the
+//      compiler turn them into vector instructions
+//   2. explicit: std::arch intrinsics inside #[target_feature(enable = "avx2")]
+//      functions, chosen at runtime with is_x86_feature_detected!
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LANES: usize = 8;
+const LEN: usize = 1_000_000;
+const RUNS: u32 = 20;
+
+// The plain loops everything is checked against
+fn sum_scalar(data: &[f32]) -> f32 {
+    data.iter().sum()
+}
+
+fn dot_scalar(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn clamp_scalar(data: &mut [f32], lo: f32, hi: f32) {
+    for x in data {
+        *x = x.clamp(lo, hi);
+    }
+}
+
+// Route 1: eight independent running sums, one per lane
+// A single accumulator forces every addition to wait for the one before it;
+// eight of them have no dependency on each other, which is exactly the shape
+// of one vector add. The leftover (< 8) elements at the end are done one by one
+mod portable {
+    use super::LANES;
+
+    pub fn sum(data: &[f32]) -> f32 {
+        let chunks = data.chunks_exact(LANES);
+        let tail: f32 = chunks.remainder().iter().sum();
+        let mut acc = [0.0f32; LANES];
+        for chunk in chunks {
+            for (lane, x) in acc.iter_mut().zip(chunk) {
+                *lane += x;
+            }
+        }
+        acc.iter().sum::<f32>() + tail
+    }
+
+    pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+        let (a_chunks, b_chunks) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+        let tail: f32 = a_chunks
+            .remainder()
+            .iter()
+            .zip(b_chunks.remainder())
+            .map(|(x, y)| x * y)
+            .sum();
+        let mut acc = [0.0f32; LANES];
+        for (x, y) in a_chunks.zip(b_chunks) {
+            for lane in 0..LANES {
+                acc[lane] += x[lane] * y[lane];
+            }
+        }
+        acc.iter().sum::<f32>() + tail
+    }
+
+    pub fn clamp(data: &mut [f32], lo: f32, hi: f32) {
+        // Elementwise with no running state, so the plain loop already vectorizes
+        for x in data {
+            *x = x.max(lo).min(hi);
+        }
+    }
+}
+
+// Route 2: the same three functions written with AVX2 intrinsics
+// #[target_feature] lets the compiler use AVX2 inside these functions even though
+// the rest of the program is built for plain x86_64. Calling them on a CPU
+// without AVX2 is undefined behaviour, which is why they are `unsafe fn`
+// and only reached after is_x86_feature_detected! says yes
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    use super::LANES;
+
+    // Adds the 8 lanes of a register together
+    #[target_feature(enable = "avx2")]
+    fn horizontal_sum(v: __m256) -> f32 {
+        let mut lanes = [0.0f32; LANES];
+        // SAFETY: `lanes` is exactly 8 f32s, and storeu doesn't need alignment
+        unsafe { _mm256_storeu_ps(lanes.as_mut_ptr(), v) };
+        lanes.iter().sum()
+    }
+
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn sum(data: &[f32]) -> f32 {
+        let chunks = data.chunks_exact(LANES);
+        let tail: f32 = chunks.remainder().iter().sum();
+        let mut acc = _mm256_setzero_ps();
+        for chunk in chunks {
+            // SAFETY: every chunk is 8 f32s long; loadu accepts any alignment
+            let v = unsafe { _mm256_loadu_ps(chunk.as_ptr()) };
+            acc = _mm256_add_ps(acc, v);
+        }
+        horizontal_sum(acc) + tail
+    }
+
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn dot(a: &[f32], b: &[f32]) -> f32 {
+        let (a_chunks, b_chunks) = (a.chunks_exact(LANES), b.chunks_exact(LANES));
+        let tail: f32 = a_chunks
+            .remainder()
+            .iter()
+            .zip(b_chunks.remainder())
+            .map(|(x, y)| x * y)
+            .sum();
+        let mut acc = _mm256_setzero_ps();
+        for (x, y) in a_chunks.zip(b_chunks) {
+            // SAFETY: both chunks are 8 f32s long
+            let (x, y) = unsafe { (_mm256_loadu_ps(x.as_ptr()), _mm256_loadu_ps(y.as_ptr())) };
+            acc = _mm256_add_ps(acc, _mm256_mul_ps(x, y));
+        }
+        horizontal_sum(acc) + tail
+    }
+
+    /// # Safety
+    /// The CPU must support AVX2.
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn clamp(data: &mut [f32], lo: f32, hi: f32) {
+        let (lo_v, hi_v) = (_mm256_set1_ps(lo), _mm256_set1_ps(hi));
+        let mut chunks = data.chunks_exact_mut(LANES);
+        for chunk in &mut chunks {
+            // SAFETY: the chunk is 8 f32s long and we hold the only reference to it
+            unsafe {
+                let v = _mm256_loadu_ps(chunk.as_ptr());
+                let v = _mm256_min_ps(_mm256_max_ps(v, lo_v), hi_v);
+                _mm256_storeu_ps(chunk.as_mut_ptr(), v);
+            }
+        }
+        for x in chunks.into_remainder() {
+            *x = x.max(lo).min(hi);
+        }
+    }
+}
+
+// The public functions check the CPU once per call and pick a route
+// Real code would usually detect once and store a function pointer
+fn sum_f32_simd(data: &[f32]) -> f32 {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: we just checked that the CPU has AVX2
+        return unsafe { avx2::sum(data) };
+    }
+    portable::sum(data)
+}
+
+fn dot_product_simd(a: &[f32], b: &[f32]) -> f32 {
+    assert_eq!(a.len(), b.len(), "dot product of different lengths");
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: we just checked that the CPU has AVX2
+        return unsafe { avx2::dot(a, b) };
+    }
+    portable::dot(a, b)
+}
+
+fn clamp_simd(data: &mut [f32], lo: f32, hi: f32) {
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: we just checked that the CPU has AVX2
+        unsafe { avx2::clamp(data, lo, hi) };
+        return;
+    }
+    portable::clamp(data, lo, hi);
+}
+
+// Adding in a different order rounds differently, so SIMD and scalar sums of a
+// million f32s agree only approximately; the allowed error grows with the sum of
+// the magnitudes involved
+fn close(a: f32, b: f32, scale: f32) -> bool {
+    (a - b).abs() <= 1e-4 * scale.max(1.0)
+}
+
+// Runs `f` RUNS times and returns the result of the last run with the average time
+fn time<T>(mut f: impl FnMut() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let mut result = f();
+    for _ in 1..RUNS {
+        result = black_box(f());
+    }
+    (result, start.elapsed() / RUNS)
+}
+
+fn report(name: &str, scalar: Duration, simd: Duration) {
+    let speedup = scalar.as_secs_f64() / simd.as_secs_f64().max(f64::MIN_POSITIVE);
+    println!("{name:<6} scalar {scalar:>10.2?}  simd {simd:>10.2?}  speedup {speedup:.1}x");
+}
+
+fn main() {
+    #[cfg(target_arch = "x86_64")]
+    println!("AVX2 available: {}", is_x86_feature_detected!("avx2"));
+
+    // Deterministic data in [-0.5, 0.5), plus an odd length tail to exercise the remainders
+    let a: Vec<f32> = (0..LEN + 3)
+        .map(|i| (i * 7919 % 1000) as f32 / 1000.0 - 0.5)
+        .collect();
+    let b: Vec<f32> = (0..LEN + 3)
+        .map(|i| (i * 104_729 % 1000) as f32 / 1000.0 - 0.5)
+        .collect();
+    let magnitude: f32 = a.iter().map(|x| x.abs()).sum();
+
+    // 1. Sum
+    let (scalar, scalar_time) = time(|| sum_scalar(black_box(&a)));
+    let (simd, simd_time) = time(|| sum_f32_simd(black_box(&a)));
+    assert!(close(scalar, simd, magnitude), "sum: {scalar} vs {simd}");
+    assert!(close(portable::sum(&a), scalar, magnitude));
+    report("sum", scalar_time, simd_time);
+
+    // 2. Dot product
+    let products: f32 = a.iter().zip(&b).map(|(x, y)| (x * y).abs()).sum();
+    let (scalar, scalar_time) = time(|| dot_scalar(black_box(&a), black_box(&b)));
+    let (simd, simd_time) = time(|| dot_product_simd(black_box(&a), black_box(&b)));
+    assert!(close(scalar, simd, products), "dot: {scalar} vs {simd}");
+    assert!(close(portable::dot(&a, &b), scalar, products));
+    report("dot", scalar_time, simd_time);
+
+    // 3. Clamp in place: no additions, so the results must match exactly
+    let mut by_scalar = a.clone();
+    let mut by_simd = a.clone();
+    let (_, scalar_time) = time(|| clamp_scalar(black_box(&mut by_scalar), -0.25, 0.25));
+    let (_, simd_time) = time(|| clamp_simd(black_box(&mut by_simd), -0.25, 0.25));
+    assert_eq!(by_scalar, by_simd);
+    assert!(by_simd.iter().all(|x| (-0.25..=0.25).contains(x)));
+    let mut by_portable = a.clone();
+    portable::clamp(&mut by_portable, -0.25, 0.25);
+    assert_eq!(by_portable, by_scalar);
+    report("clamp", scalar_time, simd_time);
+
+    // Small inputs that are all tail
+    assert_eq!(sum_f32_simd(&[]), 0.0);
+    assert_eq!(sum_f32_simd(&[1.0, 2.0, 3.0]), 6.0);
+    assert_eq!(dot_product_simd(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]), 32.0);
+
+    // In a debug build the scalar loops aren't optimized either; try
+    // `cargo run --release --bin simd_intro` for realistic numbers
+}
@@ -0,0 +1,239 @@
+// A tiny in-memory "ORM": typed tables plus a fluent query builder
+// Real ORMs (Diesel, SeaORM) generate SQL; here the "database" is a HashMap
+// and the queries are closures, but the shape of the API is the same
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// Anything stored in a Table needs an id the table can assign
+trait Record {
+    fn id(&self) -> u64;
+    fn set_id(&mut self, id: u64);
+}
+
+struct Table<T: Record> {
+    rows: HashMap<u64, T>,
+    next_id: u64,
+}
+
+impl<T: Record> Table<T> {
+    fn new() -> Table<T> {
+        Table {
+            rows: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    // Like an AUTO_INCREMENT column: ids are never reused, even after a delete
+    fn insert(&mut self, mut record: T) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        record.set_id(id);
+        self.rows.insert(id, record);
+        id
+    }
+
+    fn find(&self, id: u64) -> Option<&T> {
+        self.rows.get(&id)
+    }
+
+    // A HashMap has no order, so sort by id to make results predictable
+    fn find_all(&self) -> Vec<&T> {
+        let mut all: Vec<&T> = self.rows.values().collect();
+        all.sort_by_key(|r| r.id());
+        all
+    }
+
+    // Replaces the row with the same id; returns false if there is no such row
+    fn update(&mut self, record: T) -> bool {
+        match self.rows.get_mut(&record.id()) {
+            Some(row) => {
+                *row = record;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn delete(&mut self, id: u64) -> Option<T> {
+        self.rows.remove(&id)
+    }
+}
+
+// The builder stores the query as boxed closures and only runs it in execute()
+// Each method takes self by value and returns it, which is what makes
+// `.filter(..).order_by(..).limit(..)` chain
+type Filter<T> = Box<dyn Fn(&T) -> bool>;
+type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering>;
+
+struct QueryBuilder<T> {
+    filters: Vec<Filter<T>>,
+    order: Option<Comparator<T>>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl<T: Record> QueryBuilder<T> {
+    fn new() -> QueryBuilder<T> {
+        QueryBuilder {
+            filters: Vec::new(),
+            order: None,
+            limit: None,
+            offset: 0,
+        }
+    }
+
+    // Several filters are combined with AND, like WHERE a AND b
+    fn filter(mut self, predicate: impl Fn(&T) -> bool + 'static) -> QueryBuilder<T> {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    fn order_by(mut self, compare: impl Fn(&T, &T) -> Ordering + 'static) -> QueryBuilder<T> {
+        self.order = Some(Box::new(compare));
+        self
+    }
+
+    fn limit(mut self, n: usize) -> QueryBuilder<T> {
+        self.limit = Some(n);
+        self
+    }
+
+    fn offset(mut self, n: usize) -> QueryBuilder<T> {
+        self.offset = n;
+        self
+    }
+
+    // Same order as SQL: WHERE, then ORDER BY, then OFFSET/LIMIT
+    fn execute<'a>(&self, table: &'a Table<T>) -> Vec<&'a T> {
+        let mut rows: Vec<&T> = table
+            .find_all()
+            .into_iter()
+            .filter(|row| self.filters.iter().all(|f| f(row)))
+            .collect();
+        if let Some(compare) = &self.order {
+            // sort_by is stable, so rows that compare equal stay in id order
+            rows.sort_by(|a, b| compare(a, b));
+        }
+        rows.into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct User {
+    id: u64,
+    name: String,
+    age: u32,
+    active: bool,
+}
+
+impl Record for User {
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn set_id(&mut self, id: u64) {
+        self.id = id;
+    }
+}
+
+fn user(name: &str, age: u32, active: bool) -> User {
+    // id 0 is a placeholder; insert() assigns the real one
+    User {
+        id: 0,
+        name: name.to_string(),
+        age,
+        active,
+    }
+}
+
+fn names(users: &[&User]) -> Vec<String> {
+    users.iter().map(|u| u.name.clone()).collect()
+}
+
+fn main() {
+    let mut users = Table::new();
+    let people = [
+        ("Mallory", 34, true),
+        ("Alice", 24, true),
+        ("Trent", 41, false),
+        ("Bob", 29, true),
+        ("Peggy", 22, false),
+        ("Carol", 19, true),
+        ("Victor", 55, true),
+        ("Dave", 27, true),
+        ("Walter", 31, true),
+        ("Eve", 25, false),
+        ("Frank", 28, true),
+        ("Oscar", 38, false),
+        ("Grace", 21, true),
+        ("Judy", 33, true),
+        ("Heidi", 26, true),
+        ("Niaj", 45, true),
+        ("Ivan", 23, true),
+        ("Sybil", 29, false),
+        ("Olivia", 18, true),
+        ("Rupert", 30, true),
+    ];
+    for (name, age, active) in people {
+        users.insert(user(name, age, active));
+    }
+    assert_eq!(users.find_all().len(), 20);
+    println!("Inserted {} users", users.find_all().len());
+
+    // find by id, update, delete
+    let alice = users.find(2).unwrap().clone();
+    println!("User #2 is {alice:?}");
+    assert_eq!(alice.name, "Alice");
+    assert!(users.update(User { age: 25, ..alice }));
+    assert_eq!(users.find(2).unwrap().age, 25);
+    assert!(!users.update(user("Nobody", 1, true)));
+
+    let trent = users.delete(3).unwrap();
+    println!("Deleted {}", trent.name);
+    assert_eq!(users.find(3), None);
+    // The next insert gets a fresh id, not the deleted one
+    assert_eq!(users.insert(user("Zoe", 20, true)), 21);
+
+    // SELECT * FROM users WHERE active AND age < 30 ORDER BY name
+    let young_active = QueryBuilder::new()
+        .filter(|u: &User| u.active)
+        .filter(|u: &User| u.age < 30)
+        .order_by(|a: &User, b: &User| a.name.cmp(&b.name));
+    let all = young_active.execute(&users);
+    println!("Active users under 30: {:?}", names(&all));
+    assert_eq!(
+        names(&all),
+        [
+            "Alice", "Bob", "Carol", "Dave", "Frank", "Grace", "Heidi", "Ivan", "Olivia", "Zoe"
+        ]
+    );
+
+    // Pagination: pages of 4, so the last page is only partly full
+    let page_size = 4;
+    for page in 0..3 {
+        let query = QueryBuilder::new()
+            .filter(|u: &User| u.active && u.age < 30)
+            .order_by(|a: &User, b: &User| a.name.cmp(&b.name))
+            .offset(page * page_size)
+            .limit(page_size);
+        let rows = query.execute(&users);
+        println!("Page {}: {:?}", page + 1, names(&rows));
+        // Each page should match the same chunk of the unpaginated result
+        assert_eq!(rows, all.chunks(page_size).nth(page).unwrap());
+    }
+
+    // Oldest three users: reverse the comparison to sort descending
+    let oldest = QueryBuilder::new()
+        .order_by(|a: &User, b: &User| b.age.cmp(&a.age))
+        .limit(3)
+        .execute(&users);
+    println!("Oldest: {:?}", names(&oldest));
+    assert_eq!(names(&oldest), ["Victor", "Niaj", "Oscar"]);
+
+    // An offset past the end is simply an empty page
+    let empty = QueryBuilder::new().offset(100).execute(&users);
+    assert!(empty.is_empty());
+}
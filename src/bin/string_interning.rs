@@ -0,0 +1,145 @@
+// String interning: store each distinct string once and hand out small integer ids
+// Compilers do this for identifiers: comparing two ids is one integer comparison,
+// and a million mentions of `count` cost a million usizes, not a million Strings
+use std::collections::HashMap;
+use std::fmt;
+use std::mem::size_of;
+
+#[derive(Default)]
+struct StringInterner {
+    // id -> string: the id is simply the position in this Vec
+    strings: Vec<String>,
+    // string -> id, so interning an existing string is one lookup
+    map: HashMap<String, usize>,
+}
+
+impl StringInterner {
+    fn new() -> StringInterner {
+        StringInterner::default()
+    }
+
+    fn intern(&mut self, s: &str) -> usize {
+        // get() with a &str works on a HashMap<String, _> because String: Borrow<str>,
+        // so no String is allocated when the string is already known
+        if let Some(&id) = self.map.get(s) {
+            return id;
+        }
+        let id = self.strings.len();
+        self.strings.push(s.to_string());
+        self.map.insert(s.to_string(), id);
+        id
+    }
+
+    fn get(&self, id: usize) -> Option<&str> {
+        self.strings.get(id).map(String::as_str)
+    }
+
+    fn resolve(&self, id: usize) -> InternedStr<'_> {
+        InternedStr(id, self)
+    }
+
+    // A rough estimate of the heap plus inline size
+    // - every string is stored twice, once in the Vec and once as a map key
+    // - each map slot holds a String and a usize, plus about one control byte
+    fn memory_bytes(&self) -> usize {
+        let text: usize = self.strings.iter().map(String::capacity).sum();
+        let vec = self.strings.capacity() * size_of::<String>();
+        let map = self.map.capacity() * (size_of::<String>() + size_of::<usize>() + 1);
+        size_of::<StringInterner>() + 2 * text + vec + map
+    }
+}
+
+// An id together with the interner it came from, so it can be printed
+// The lifetime 'a stops it from outliving the interner
+#[derive(Clone, Copy)]
+struct InternedStr<'a>(usize, &'a StringInterner);
+
+impl fmt::Display for InternedStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.1.get(self.0).unwrap_or("<unknown>"))
+    }
+}
+
+impl PartialEq for InternedStr<'_> {
+    fn eq(&self, other: &InternedStr) -> bool {
+        // Ids from the same interner compare as plain integers; ids from two
+        // different interners mean nothing to each other, so fall back to the text
+        if std::ptr::eq(self.1, other.1) {
+            self.0 == other.0
+        } else {
+            self.1.get(self.0) == other.1.get(other.0)
+        }
+    }
+}
+
+const TEXT: &str = "the quick brown fox jumps over the lazy dog while the cat \
+                    watches the fox and the dog from the top of the old brown fence";
+
+fn main() {
+    let mut interner = StringInterner::new();
+    let fox = interner.intern("fox");
+    let dog = interner.intern("dog");
+    // The same string always gets the same id
+    assert_eq!(interner.intern("fox"), fox);
+    assert_ne!(fox, dog);
+    assert_eq!(interner.get(fox), Some("fox"));
+    assert_eq!(interner.get(99), None);
+    println!("'fox' is #{fox}, 'dog' is #{dog}");
+
+    // InternedStr prints like the string it stands for
+    // Intern first: an InternedStr borrows the interner, so interning (which
+    // needs &mut) isn't allowed while one is alive
+    let fox_again = interner.intern("fox");
+    let a = interner.resolve(fox);
+    let b = interner.resolve(fox_again);
+    println!("resolved: {a} and {b}, equal: {}", a == b);
+    assert!(a == b);
+    assert!(a != interner.resolve(dog));
+
+    // Two interners give different ids to the same word, but the text still matches
+    let mut other = StringInterner::new();
+    other.intern("cat");
+    let other_fox = other.intern("fox");
+    assert_ne!(other_fox, fox);
+    assert!(other.resolve(other_fox) == interner.resolve(fox));
+
+    // Word frequencies keyed by id: only the first sighting of a word allocates
+    let mut counts: HashMap<usize, u32> = HashMap::new();
+    for word in TEXT.split_whitespace() {
+        *counts.entry(interner.intern(word)).or_insert(0) += 1;
+    }
+    let mut top: Vec<(usize, u32)> = counts.into_iter().collect();
+    // Most frequent first; ties in alphabetical order
+    top.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then_with(|| interner.get(a.0).cmp(&interner.get(b.0)))
+    });
+    print!("most common words:");
+    for &(id, count) in top.iter().take(4) {
+        print!(" {}×{count}", interner.resolve(id));
+    }
+    println!();
+    assert_eq!(top[0], (interner.intern("the"), 7));
+
+    // 10 000 words: owned Strings vs ids into an interner
+    let words: Vec<&str> = TEXT.split_whitespace().cycle().take(10_000).collect();
+    let owned: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+    let owned_bytes =
+        owned.capacity() * size_of::<String>() + owned.iter().map(String::capacity).sum::<usize>();
+
+    let mut shared = StringInterner::new();
+    let ids: Vec<usize> = words.iter().map(|w| shared.intern(w)).collect();
+    let interned_bytes = ids.capacity() * size_of::<usize>() + shared.memory_bytes();
+
+    println!(
+        "10 000 words as Vec<String>: ~{owned_bytes} bytes; as ids + interner: ~{interned_bytes} bytes ({} distinct words)",
+        shared.strings.len()
+    );
+    assert!(interned_bytes < owned_bytes);
+    // Nothing is lost: every id turns back into its word
+    assert!(
+        ids.iter()
+            .zip(&words)
+            .all(|(&id, w)| shared.get(id) == Some(w))
+    );
+}
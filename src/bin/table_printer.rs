@@ -0,0 +1,313 @@
+// A generic table formatter: give it headers and rows of strings, get back a
+// box-drawn table for the terminal or a Markdown table for a README
+// Usage: cargo run --bin table_printer [data.csv]
+// With a CSV file (first line = headers), that file is printed as a third table
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::process;
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    // One per column; columns without an entry are left-aligned
+    alignment: Vec<Align>,
+}
+
+impl Table {
+    fn new(headers: &[&str]) -> Table {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            ..Table::default()
+        }
+    }
+
+    fn align(mut self, alignment: &[Align]) -> Table {
+        self.alignment = alignment.to_vec();
+        self
+    }
+
+    // `impl ToString` accepts numbers, &str and String alike, so callers
+    // don't have to convert every cell themselves
+    fn add_row(&mut self, row: Vec<impl ToString>) {
+        assert_eq!(
+            row.len(),
+            self.headers.len(),
+            "a row needs one cell per column"
+        );
+        self.rows
+            .push(row.iter().map(|cell| cell.to_string()).collect());
+    }
+
+    fn alignment_of(&self, column: usize) -> Align {
+        self.alignment.get(column).copied().unwrap_or(Align::Left)
+    }
+
+    // Each column is as wide as its widest cell, header included
+    // chars().count() rather than len(), so "é" counts as one column, not two bytes
+    fn widths(&self) -> Vec<usize> {
+        (0..self.headers.len())
+            .map(|column| {
+                std::iter::once(&self.headers[column])
+                    .chain(self.rows.iter().map(|row| &row[column]))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    fn render(&self) -> String {
+        let widths = self.widths();
+        // A horizontal line: left corner, ─ under every column, joins between them
+        let line = |left: char, join: char, right: char| {
+            let segments: Vec<String> = widths.iter().map(|&w| "─".repeat(w + 2)).collect();
+            format!("{left}{}{right}\n", segments.join(&join.to_string()))
+        };
+        let row = |cells: &[String]| {
+            let mut out = String::from("│");
+            for (column, (cell, &width)) in cells.iter().zip(&widths).enumerate() {
+                // Padding is counted in chars too, for the same reason as in widths()
+                let pad = width - cell.chars().count();
+                let (before, after) = match self.alignment_of(column) {
+                    Align::Left => (0, pad),
+                    Align::Right => (pad, 0),
+                    Align::Center => (pad / 2, pad - pad / 2),
+                };
+                write!(out, " {}{cell}{} │", " ".repeat(before), " ".repeat(after)).unwrap();
+            }
+            out.push('\n');
+            out
+        };
+
+        let mut out = line('╔', '╦', '╗');
+        out.push_str(&row(&self.headers));
+        out.push_str(&line('╠', '┼', '╣'));
+        for cells in &self.rows {
+            out.push_str(&row(cells));
+        }
+        out.push_str(&line('╚', '╩', '╝'));
+        out
+    }
+
+    // GitHub-flavored Markdown: alignment goes in the separator row as colons,
+    // and a | inside a cell has to be escaped or it would start a new column
+    fn render_markdown(&self) -> String {
+        let escape = |cell: &String| cell.replace('|', "\\|");
+        let row = |cells: &[String]| {
+            let cells: Vec<String> = cells.iter().map(escape).collect();
+            format!("| {} |\n", cells.join(" | "))
+        };
+        let separators: Vec<&str> = (0..self.headers.len())
+            .map(|column| match self.alignment_of(column) {
+                Align::Left => ":---",
+                Align::Right => "---:",
+                Align::Center => ":---:",
+            })
+            .collect();
+
+        let mut out = row(&self.headers);
+        out.push_str(&format!("| {} |\n", separators.join(" | ")));
+        for cells in &self.rows {
+            out.push_str(&row(cells));
+        }
+        out
+    }
+}
+
+// The standard SHA-256 test vectors from FIPS 180-2
+fn sha256_table() -> Table {
+    let mut table =
+        Table::new(&["input", "bytes", "SHA-256"]).align(&[Align::Left, Align::Right, Align::Left]);
+    let vectors = [
+        (
+            "",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        ),
+        (
+            "abc",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        ),
+    ];
+    for (input, digest) in vectors {
+        let shown = if input.is_empty() {
+            "(empty)".to_string()
+        } else if input.len() > 20 {
+            format!("{}...", &input[..17])
+        } else {
+            input.to_string()
+        };
+        table.add_row(vec![shown, input.len().to_string(), digest.to_string()]);
+    }
+    table
+}
+
+type SortFn = fn(&mut [u32]);
+
+fn insertion_sort(v: &mut [u32]) {
+    for i in 1..v.len() {
+        let mut j = i;
+        while j > 0 && v[j - 1] > v[j] {
+            v.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+// Times a few sorting algorithms on the same pseudo-random input
+fn sorting_table() -> Table {
+    let mut table = Table::new(&["algorithm", "n", "time (µs)", "sorted"]).align(&[
+        Align::Left,
+        Align::Right,
+        Align::Right,
+        Align::Center,
+    ]);
+    let sorts: [(&str, SortFn); 3] = [
+        ("slice::sort", |v| v.sort()),
+        ("slice::sort_unstable", |v| v.sort_unstable()),
+        ("insertion sort", insertion_sort),
+    ];
+    for n in [100, 2_000] {
+        // A linear congruential generator: cheap, repeatable "random" numbers
+        let data: Vec<u32> = (0..n as u32)
+            .map(|i| i.wrapping_mul(1_103_515_245).wrapping_add(12_345) % 10_000)
+            .collect();
+        for (name, sort) in sorts {
+            let mut v = data.clone();
+            let start = Instant::now();
+            sort(&mut v);
+            let micros = start.elapsed().as_micros();
+            let sorted = v.is_sorted();
+            table.add_row(vec![
+                name.to_string(),
+                n.to_string(),
+                micros.to_string(),
+                if sorted { "yes" } else { "NO" }.to_string(),
+            ]);
+        }
+    }
+    table
+}
+
+// The first line is the headers; numbers are right-aligned like a spreadsheet would
+fn csv_table(text: &str) -> Result<Table, String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let headers: Vec<&str> = lines
+        .next()
+        .ok_or("the file is empty")?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let mut table = Table::new(&headers);
+    for (number, line) in lines.enumerate() {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        if cells.len() != headers.len() {
+            return Err(format!(
+                "row {} has {} cells, expected {}",
+                number + 1,
+                cells.len(),
+                headers.len()
+            ));
+        }
+        table.add_row(cells);
+    }
+    let numeric = |column: usize| {
+        !table.rows.is_empty()
+            && table
+                .rows
+                .iter()
+                .all(|row| row[column].parse::<f64>().is_ok())
+    };
+    table.alignment = (0..headers.len())
+        .map(|column| {
+            if numeric(column) {
+                Align::Right
+            } else {
+                Align::Left
+            }
+        })
+        .collect();
+    Ok(table)
+}
+
+const SAMPLE_CSV: &str = "city, country, population (millions)
+Tokyo, Japan, 37.4
+Delhi, India, 31.0
+São Paulo, Brazil, 22.4
+Zürich, Switzerland, 0.4";
+
+fn main() {
+    // 1. A small table, checked character for character
+    let mut small =
+        Table::new(&["name", "score", "rank"]).align(&[Align::Left, Align::Right, Align::Center]);
+    small.add_row(vec!["ada", "9", "1"]);
+    small.add_row(vec!["grace", "10", "2"]);
+    let expected = "\
+╔───────╦───────╦──────╗
+│ name  │ score │ rank │
+╠───────┼───────┼──────╣
+│ ada   │     9 │  1   │
+│ grace │    10 │  2   │
+╚───────╩───────╩──────╝
+";
+    print!("{}", small.render());
+    assert_eq!(small.render(), expected);
+
+    let markdown = small.render_markdown();
+    println!("\n{markdown}");
+    assert_eq!(
+        markdown,
+        "| name | score | rank |\n| :--- | ---: | :---: |\n| ada | 9 | 1 |\n| grace | 10 | 2 |\n"
+    );
+
+    // add_row takes anything printable, numbers included
+    let mut numbers = Table::new(&["n", "n²"]);
+    numbers.add_row(vec![3, 9]);
+    assert_eq!(numbers.rows[0], ["3", "9"]);
+
+    // 2. SHA-256 test vectors
+    println!("SHA-256 test vectors:");
+    print!("{}", sha256_table().render());
+
+    // 3. Sorting timings
+    println!("\nSorting timings:");
+    let sorting = sorting_table();
+    print!("{}", sorting.render());
+    assert!(sorting.rows.iter().all(|row| row[3] == "yes"));
+
+    // 4. Your own data: a CSV file given on the command line, or the sample
+    let text = match env::args().nth(1) {
+        Some(path) => match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("Could not read {path}: {err}");
+                process::exit(1);
+            }
+        },
+        None => SAMPLE_CSV.to_string(),
+    };
+    match csv_table(&text) {
+        Ok(table) => {
+            println!("\nYour data:");
+            print!("{}", table.render());
+            println!("\nThe same as Markdown:\n{}", table.render_markdown());
+        }
+        Err(err) => {
+            eprintln!("Could not read the CSV: {err}");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,379 @@
+// Organising tests: fixtures, helpers and custom assertions
+// `cargo run --bin test_fixtures` shows the helpers at work;
+// `cargo test --bin test_fixtures` runs the test suite at the bottom of this file
+//
+// The fakes in learning_rust::testing (CapturedOutput, ScriptedInput, fake
+// clocks) are fixtures too, for code that writes, reads or looks at the time;
+// the tests build with them, as Cargo.toml turns on test-util for tests
+//
+// A fixture is whatever a test needs set up before it runs and torn down after:
+// a directory with some files in it, a server to talk to. In Rust the tear-down
+// goes in Drop, so it happens even when the test panics halfway through
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+
+// ---- Temporary directories ----
+
+// The tempfile crate's TempDir, in miniature: a fresh directory that deletes
+// itself (and everything in it) when dropped
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new() -> TempDir {
+        // Tests run in parallel threads, so the name needs the process id and a
+        // per-process counter to be unique
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("fixture-{}-{n}", process::id()));
+        fs::create_dir_all(&path).expect("create temp dir");
+        TempDir { path }
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Creates parent directories as needed, so "a/b/c.txt" just works
+    fn write(&self, name: &str, contents: &str) {
+        let file = self.path.join(name);
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent).expect("create parent dir");
+        }
+        fs::write(file, contents).expect("write fixture file");
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // Ignore errors: a failing clean-up shouldn't hide the real test failure
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+// fixture!({ "name" => "contents", ... }) gives a TempDir holding those files
+macro_rules! fixture {
+    ({ $($name:expr => $contents:expr),* $(,)? }) => {{
+        let dir = TempDir::new();
+        $(dir.write($name, $contents);)*
+        dir
+    }};
+}
+
+// ---- A throwaway server ----
+
+// Binds to port 0, which asks the OS for any free port, so tests running at
+// the same time never fight over one. Each connection sends one request line
+// ("GET /path") and gets back the canned response for that path
+struct TestServer {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl TestServer {
+    fn start(responses: HashMap<String, String>) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let addr = listener.local_addr().expect("local addr");
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopping = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopping.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(mut stream) = stream else { continue };
+                let mut line = String::new();
+                if BufReader::new(&stream).read_line(&mut line).is_err() {
+                    continue;
+                }
+                let path = line.split_whitespace().nth(1).unwrap_or("/");
+                let reply = match responses.get(path) {
+                    Some(body) => format!("200 {body}\n"),
+                    None => "404 not found\n".to_string(),
+                };
+                let _ = stream.write_all(reply.as_bytes());
+            }
+        });
+        TestServer {
+            addr,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        // accept() blocks, so after raising the flag, connect once to wake it up
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// A minimal client for the server above
+fn get(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).expect("connect to test server");
+    writeln!(stream, "GET {path}").expect("send request");
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .expect("read reply");
+    reply.trim_end().to_string()
+}
+
+// ---- Assertions ----
+
+// Floating point results are rarely exactly equal; compare within a tolerance
+macro_rules! assert_approx_eq {
+    ($left:expr, $right:expr) => {
+        assert_approx_eq!($left, $right, 1e-9)
+    };
+    ($left:expr, $right:expr, $tolerance:expr) => {{
+        let (left, right, tolerance): (f64, f64, f64) = ($left, $right, $tolerance);
+        assert!(
+            (left - right).abs() <= tolerance,
+            "{left} and {right} are not approximately equal (tolerance {tolerance})"
+        );
+    }};
+}
+
+// Says where the order breaks, not just that it does
+macro_rules! assert_sorted {
+    ($slice:expr) => {{
+        let slice = &$slice;
+        if let Some(i) = (1..slice.len()).find(|&i| slice[i - 1] > slice[i]) {
+            panic!(
+                "slice is not sorted: {:?} comes before {:?} at index {}",
+                slice[i - 1],
+                slice[i],
+                i
+            );
+        }
+    }};
+}
+
+// Table-driven tests: each case is (name, input, expected)
+// Every case runs even after one fails, and the panic names all the failures
+fn run_cases<T, F>(cases: &[(&str, T, T)], f: F)
+where
+    T: PartialEq + Debug,
+    F: Fn(&T) -> T,
+{
+    let failures: Vec<String> = cases
+        .iter()
+        .filter_map(|(name, input, expected)| {
+            let actual = f(input);
+            (actual != *expected).then(|| format!("{name}: expected {expected:?}, got {actual:?}"))
+        })
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "{} of {} cases failed:\n{}",
+        failures.len(),
+        cases.len(),
+        failures.join("\n")
+    );
+}
+
+// ---- Code under test ----
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    assert!(!values.is_empty(), "mean of an empty slice");
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Reads a tiny "key = value" config file, skipping blank lines and # comments
+fn read_settings(path: &Path) -> HashMap<String, String> {
+    let text = fs::read_to_string(path).expect("read settings");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+// Prints the settings one per line, sorted so the output is the same every run
+fn report(settings: &HashMap<String, String>, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut keys: Vec<&String> = settings.keys().collect();
+    keys.sort();
+    for key in keys {
+        writeln!(out, "{key} = {}", settings[key])?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let dir = fixture!({
+        "settings.conf" => "# defaults\nname = ferris\n\nlevel = 3\n",
+        "notes/todo.txt" => "write more tests",
+    });
+    let settings = read_settings(&dir.path().join("settings.conf"));
+    println!("Fixture directory {} holds:", dir.path().display());
+    report(&settings, &mut std::io::stdout()).expect("print the settings");
+    assert_eq!(settings["name"], "ferris");
+    assert!(dir.path().join("notes/todo.txt").exists());
+    let kept = dir.path().to_path_buf();
+    drop(dir);
+    assert!(!kept.exists(), "dropping the TempDir deletes it");
+
+    let server = TestServer::start(HashMap::from([("/hello".to_string(), "world".to_string())]));
+    println!("Test server listening on {}", server.addr());
+    assert_eq!(get(server.addr(), "/hello"), "200 world");
+    assert_eq!(get(server.addr(), "/missing"), "404 not found");
+    drop(server);
+
+    assert_approx_eq!(0.1 + 0.2, 0.3);
+    assert_approx_eq!(mean(&[1.0, 2.0, 4.0]), 2.333, 0.001);
+    assert_sorted!([1, 2, 2, 5]);
+    run_cases(
+        &[
+            ("empty", "", 0),
+            ("one", "word", 1),
+            ("spaces", "  a  b ", 2),
+        ]
+        .map(|(name, input, expected)| (name, input.to_string(), expected.to_string())),
+        |input| word_count(input).to_string(),
+    );
+    println!("All helpers behave; run `cargo test --bin test_fixtures` for the suite.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Small and fast: one function, no files, no network
+    mod unit {
+        use super::*;
+
+        #[test]
+        fn word_count_cases() {
+            run_cases(
+                &[("empty", 0, 0), ("single", 1, 1), ("several", 3, 3)],
+                |&n| word_count(&vec!["word"; n].join(" ")),
+            );
+        }
+
+        #[test]
+        fn mean_is_close_enough() {
+            assert_approx_eq!(mean(&[0.1, 0.2, 0.3]), 0.2);
+        }
+
+        #[test]
+        #[should_panic(expected = "mean of an empty slice")]
+        fn mean_of_nothing_panics() {
+            mean(&[]);
+        }
+
+        #[test]
+        #[should_panic(expected = "not approximately equal")]
+        fn approx_eq_rejects_big_gaps() {
+            assert_approx_eq!(1.0, 1.1, 0.01);
+        }
+
+        #[test]
+        #[should_panic(expected = "3 comes before 1 at index 2")]
+        fn assert_sorted_names_the_break() {
+            assert_sorted!([2, 3, 1]);
+        }
+
+        #[test]
+        #[should_panic(expected = "1 of 2 cases failed")]
+        fn run_cases_reports_failures() {
+            run_cases(&[("right", 2, 4), ("wrong", 3, 10)], |&n| n * 2);
+        }
+    }
+
+    // Real files and sockets, set up and torn down by fixtures
+    mod integration {
+        use super::*;
+        use learning_rust::testing::CapturedOutput;
+
+        // The report goes into a CapturedOutput rather than stdout, so the
+        // test can read back exactly what a user would see
+        #[test]
+        fn settings_are_read_from_a_fixture() {
+            let dir = fixture!({
+                "app.conf" => "# comment\n  colour = blue \nbroken line\nsize=10\n",
+            });
+            let settings = read_settings(&dir.path().join("app.conf"));
+            let mut out = CapturedOutput::new();
+            report(&settings, &mut out).unwrap();
+            assert_eq!(out.lines(), ["colour = blue", "size = 10"]);
+            out.assert_contains_line("size = 10");
+        }
+
+        #[test]
+        fn fixtures_are_separate_and_cleaned_up() {
+            let a = fixture!({ "x.txt" => "a" });
+            let b = fixture!({ "x.txt" => "b" });
+            assert_ne!(a.path(), b.path());
+            let path = a.path().to_path_buf();
+            drop(a);
+            assert!(!path.exists());
+            assert_eq!(fs::read_to_string(b.path().join("x.txt")).unwrap(), "b");
+        }
+
+        #[test]
+        fn server_serves_canned_responses() {
+            let server = TestServer::start(HashMap::from([
+                ("/a".to_string(), "first".to_string()),
+                ("/b".to_string(), "second".to_string()),
+            ]));
+            assert_eq!(get(server.addr(), "/b"), "200 second");
+            assert_eq!(get(server.addr(), "/a"), "200 first");
+            assert_eq!(get(server.addr(), "/c"), "404 not found");
+        }
+
+        #[test]
+        fn dropping_the_server_frees_the_port() {
+            let addr = TestServer::start(HashMap::new()).addr();
+            // The server was dropped at the end of the line above, so nobody answers
+            assert!(TcpStream::connect(addr).is_err());
+        }
+    }
+
+    // One test per bug that was fixed, named after the bug, so it can't come back
+    mod regression {
+        use super::*;
+
+        #[test]
+        fn tabs_and_newlines_separate_words() {
+            assert_eq!(word_count("a\tb\nc"), 3);
+        }
+
+        #[test]
+        fn values_containing_equals_signs_are_kept_whole() {
+            let dir = fixture!({ "eq.conf" => "query = a=b\n" });
+            let settings = read_settings(&dir.path().join("eq.conf"));
+            assert_eq!(settings["query"], "a=b");
+        }
+
+        #[test]
+        fn already_sorted_input_with_duplicates_passes() {
+            assert_sorted!([1, 1, 1]);
+            assert_sorted!(Vec::<i32>::new());
+        }
+    }
+}
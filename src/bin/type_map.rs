@@ -0,0 +1,181 @@
+// A "type map": a collection that holds at most one value of each type
+// Web frameworks use this for request extensions: middleware can attach a User,
+// a RequestId, a Session... and handlers fetch them back by type, with no
+// central struct listing every possible field
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+// TypeId is a unique, comparable id for every 'static type, so it works as a HashMap key
+// Box<dyn Any> can hold a value of any 'static type; downcast gets it back out,
+// but only if we ask for the exact type that was put in
+#[derive(Default)]
+struct TypeMap {
+    inner: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl TypeMap {
+    fn new() -> TypeMap {
+        TypeMap::default()
+    }
+
+    // Inserting a second value of the same type replaces the first
+    fn insert<T: Any>(&mut self, value: T) {
+        self.inner.insert(TypeId::of::<T>(), Box::new(value));
+    }
+
+    fn get<T: Any>(&self) -> Option<&T> {
+        // downcast_ref can't fail for a value stored under TypeId::of::<T>(),
+        // but it returns an Option anyway, which fits our return type
+        self.inner.get(&TypeId::of::<T>())?.downcast_ref::<T>()
+    }
+
+    fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.inner.get_mut(&TypeId::of::<T>())?.downcast_mut::<T>()
+    }
+
+    fn remove<T: Any>(&mut self) -> Option<T> {
+        // Box<dyn Any>::downcast turns the box back into Box<T>; * moves the T out
+        let boxed = self.inner.remove(&TypeId::of::<T>())?;
+        boxed.downcast::<T>().ok().map(|b| *b)
+    }
+
+    fn contains<T: Any>(&self) -> bool {
+        self.inner.contains_key(&TypeId::of::<T>())
+    }
+}
+
+// The same idea, shareable between threads
+// - every stored value must itself be Send + Sync
+// - RwLock lets many readers in at once, or one writer
+// Because a read guard can't outlive the lock, get() hands out a clone instead of a reference
+#[derive(Default)]
+struct SyncTypeMap {
+    inner: RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+impl SyncTypeMap {
+    fn insert<T: Any + Send + Sync>(&self, value: T) {
+        self.inner
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<T>(), Box::new(value));
+    }
+
+    fn get<T: Any + Send + Sync + Clone>(&self) -> Option<T> {
+        let map = self.inner.read().unwrap();
+        map.get(&TypeId::of::<T>())?.downcast_ref::<T>().cloned()
+    }
+
+    fn contains<T: Any + Send + Sync>(&self) -> bool {
+        self.inner.read().unwrap().contains_key(&TypeId::of::<T>())
+    }
+}
+
+// Types a web server might attach to a request
+#[derive(Debug, Clone, PartialEq)]
+struct User {
+    name: String,
+    admin: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RequestId(u64);
+
+// "Middleware": each one adds what it knows, without knowing about the others
+fn authenticate(extensions: &mut TypeMap) {
+    extensions.insert(User {
+        name: "ferris".to_string(),
+        admin: true,
+    });
+}
+
+fn assign_request_id(extensions: &mut TypeMap) {
+    extensions.insert(RequestId(42));
+}
+
+// A "handler" asks only for the types it cares about
+fn handle(extensions: &TypeMap) -> String {
+    let id = extensions.get::<RequestId>().map_or(0, |id| id.0);
+    match extensions.get::<User>() {
+        Some(user) => format!("request #{id}: hello {} (admin: {})", user.name, user.admin),
+        None => format!("request #{id}: hello stranger"),
+    }
+}
+
+fn main() {
+    let mut map = TypeMap::new();
+    map.insert(42u32);
+    map.insert("hello");
+    map.insert(String::from("an owned string"));
+
+    // Each type gets its own slot, even though all three are "numbers or strings"
+    assert_eq!(map.get::<u32>(), Some(&42));
+    assert_eq!(map.get::<&str>(), Some(&"hello"));
+    assert_eq!(
+        map.get::<String>().map(String::as_str),
+        Some("an owned string")
+    );
+    println!("u32: {:?}, &str: {:?}", map.get::<u32>(), map.get::<&str>());
+
+    // u64 is a different type from u32, so it's simply not there
+    assert_eq!(map.get::<u64>(), None);
+    assert!(!map.contains::<u64>());
+
+    // Values can be changed in place
+    if let Some(n) = map.get_mut::<u32>() {
+        *n += 1;
+    }
+    assert_eq!(map.get::<u32>(), Some(&43));
+
+    // A second insert of the same type replaces the old value
+    map.insert(7u32);
+    assert_eq!(map.get::<u32>(), Some(&7));
+
+    // remove hands back ownership of the value
+    assert_eq!(map.remove::<u32>(), Some(7));
+    assert!(!map.contains::<u32>());
+    assert_eq!(map.remove::<u32>(), None);
+
+    // Request extensions, Axum/Actix style
+    let mut extensions = TypeMap::new();
+    println!("{}", handle(&extensions));
+    assign_request_id(&mut extensions);
+    authenticate(&mut extensions);
+    println!("{}", handle(&extensions));
+    assert_eq!(extensions.get::<RequestId>(), Some(&RequestId(42)));
+    assert_eq!(
+        extensions.get::<User>().map(|u| u.name.as_str()),
+        Some("ferris")
+    );
+
+    // The thread-safe version can be shared through an Arc
+    let shared = Arc::new(SyncTypeMap::default());
+    let writers: Vec<_> = [0u8, 1]
+        .into_iter()
+        .map(|i| {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                if i == 0 {
+                    shared.insert(RequestId(7));
+                } else {
+                    shared.insert(User {
+                        name: "from another thread".to_string(),
+                        admin: false,
+                    });
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+    assert!(shared.contains::<User>());
+    assert_eq!(shared.get::<RequestId>(), Some(RequestId(7)));
+    println!(
+        "shared map: {:?} and {:?}",
+        shared.get::<RequestId>(),
+        shared.get::<User>()
+    );
+}
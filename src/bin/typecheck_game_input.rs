@@ -0,0 +1,147 @@
+// guess_with_random again, with the input parsed once into a type
+// There the loop compares the raw string with "quit", then tries to parse it,
+// and every new command would mean another `if` in the middle of the game.
+// Here parse_action turns a line into a PlayerAction or an ActionError, and
+// the loop only matches on the result. A new command is a new variant and a
+// new arm in parse_action; the compiler then points at the one match in the
+// loop that has to handle it
+use std::cmp::Ordering;
+use std::fmt;
+use std::io;
+use std::num::NonZeroU32;
+use std::ops::RangeInclusive;
+
+use rand::Rng;
+
+const RANGE: RangeInclusive<u32> = 1..=100;
+
+// Everything the player can do. A guess is NonZeroU32 because the range
+// starts at 1, so a Guess(0) can't even be built
+enum PlayerAction {
+    Guess(NonZeroU32),
+    Quit,
+    Help,
+}
+
+// Everything that can be wrong with a line, each with what is needed to say so
+#[derive(Debug, PartialEq)]
+enum ActionError {
+    EmptyInput,
+    OutOfRange(u32),
+    NotANumber(String),
+    UnknownCommand(String),
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ActionError::EmptyInput => write!(f, "Please type a guess, 'help' or 'quit'."),
+            ActionError::OutOfRange(n) => write!(
+                f,
+                "{n} is outside the range; guess from {} to {}.",
+                RANGE.start(),
+                RANGE.end()
+            ),
+            ActionError::NotANumber(text) => write!(
+                f,
+                "'{text}' can't be read as a guess; guesses are whole numbers from {} to {}.",
+                RANGE.start(),
+                RANGE.end()
+            ),
+            ActionError::UnknownCommand(word) => {
+                write!(f, "There's no command '{word}'; try 'help' or 'quit'.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+fn parse_action(raw: &str) -> Result<PlayerAction, ActionError> {
+    let text = raw.trim();
+    if text.is_empty() {
+        return Err(ActionError::EmptyInput);
+    }
+    // A line that starts like a number is a guess, right or wrong; anything
+    // else is a command
+    if text.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+        // Too many digits for a u32 lands here too: it can't be held, let
+        // alone checked against the range
+        let n: u32 = text
+            .parse()
+            .map_err(|_| ActionError::NotANumber(text.to_string()))?;
+        return match NonZeroU32::new(n) {
+            Some(guess) if RANGE.contains(&n) => Ok(PlayerAction::Guess(guess)),
+            _ => Err(ActionError::OutOfRange(n)),
+        };
+    }
+    match text.to_lowercase().as_str() {
+        "quit" | "q" => Ok(PlayerAction::Quit),
+        "help" | "h" | "?" => Ok(PlayerAction::Help),
+        _ => Err(ActionError::UnknownCommand(text.to_string())),
+    }
+}
+
+fn main() {
+    println!("Guess the number!");
+    println!("Type 'help' to see where you are, or 'quit' to exit.");
+
+    let secret_number = rand::rng().random_range(RANGE);
+    // What the answers so far have narrowed it down to, for 'help'
+    let (mut low, mut high) = (*RANGE.start(), *RANGE.end());
+    let mut attempts = 0;
+
+    loop {
+        println!("Please input your guess:");
+
+        let mut line = String::new();
+        let read = io::stdin()
+            .read_line(&mut line)
+            .expect("Failed to read line");
+        // End of input (Ctrl-D, or a closed pipe) ends the game like 'quit'
+        if read == 0 {
+            println!("Goodbye!");
+            break;
+        }
+
+        let guess = match parse_action(&line) {
+            Ok(PlayerAction::Guess(guess)) => guess.get(),
+            Ok(PlayerAction::Quit) => {
+                println!("Goodbye!");
+                break;
+            }
+            Ok(PlayerAction::Help) => {
+                println!(
+                    "The number is between {low} and {high}. You've made {attempts} guess{}.",
+                    if attempts == 1 { "" } else { "es" }
+                );
+                continue;
+            }
+            Err(err) => {
+                println!("{err}");
+                continue;
+            }
+        };
+
+        println!("You guessed: {guess}");
+        attempts += 1;
+
+        match guess.cmp(&secret_number) {
+            Ordering::Less => {
+                println!("Too small!");
+                low = low.max(guess + 1);
+            }
+            Ordering::Greater => {
+                println!("Too big!");
+                high = high.min(guess - 1);
+            }
+            Ordering::Equal => {
+                println!(
+                    "You win! That took {attempts} guess{}.",
+                    if attempts == 1 { "" } else { "es" }
+                );
+                break;
+            }
+        }
+    }
+}
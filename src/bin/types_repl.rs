@@ -0,0 +1,132 @@
+// An interactive follow-up to native_compound_types.rs: build tuples and
+// arrays, then index, destructure and slice them, and see the type Rust gives
+// every piece. The parsing and the rules live in learning_rust::types_repl;
+// this file is only the loop around them
+//
+//   cargo run --bin types_repl            type commands (try "help")
+//   cargo run --bin types_repl -- --demo  play a fixed session and check its output
+use std::io::{self, Write};
+
+use learning_rust::input::{InputSource, ScriptedInput, StdinInput};
+use learning_rust::types_repl::{Command, Session, parse_command};
+
+// Reads commands until quit or the end of input. With `echo`, each line read is
+// printed after the prompt, so a scripted session reads like a typed one
+fn repl(input: &mut dyn InputSource, out: &mut impl Write, echo: bool) -> io::Result<()> {
+    let mut session = Session::new();
+    loop {
+        write!(out, "> ")?;
+        out.flush()?;
+        let Some(line) = input.read_line()? else {
+            writeln!(out)?;
+            return Ok(());
+        };
+        if echo {
+            writeln!(out, "{line}")?;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        // Errors are printed and the loop goes on: a bad index is something to
+        // learn from here, not a reason to stop
+        match parse_command(&line).and_then(|command| match command {
+            Command::Quit => Ok(None),
+            command => session.execute(command).map(Some),
+        }) {
+            Ok(Some(text)) => writeln!(out, "{text}")?,
+            Ok(None) => return Ok(()),
+            Err(e) => writeln!(out, "error: {e}")?,
+        }
+    }
+}
+
+const DEMO: [&str; 14] = [
+    "tuple 500 6.4 1",
+    "index 2",
+    "index 3",
+    "destructure (a, _, c)",
+    "destructure (a, b)",
+    "slice 0..2",
+    "array 1 2 3 4 5",
+    "slice 1..3",
+    "slice 3..9",
+    "destructure [first, .., last]",
+    "array 1 2u8 300",
+    "array 1 2.5",
+    "tuple 'x' \"two words\" true 1i64",
+    "quit",
+];
+
+// The whole demo session, prompts and all, exactly as it should print
+const GOLDEN: &str = "\
+> tuple 500 6.4 1
+value = (500, 6.4, 1): (i32, f64, i32)
+> index 2
+value.2 = 1: i32
+> index 3
+error: the tuple has 3 elements, so .3 doesn't exist; in Rust that is a compile error (no field `3`)
+> destructure (a, _, c)
+a = 500: i32
+_ skips 6.4
+c = 1: i32
+> destructure (a, b)
+error: the pattern has 2 elements but the tuple has 3; add .. to skip the rest
+> slice 0..2
+error: a tuple can't be sliced: its elements may differ in type, so they can't form a [T]
+> array 1 2 3 4 5
+value = [1, 2, 3, 4, 5]: [i32; 5]
+> slice 1..3
+&value[1..3] = [2, 3]: &[i32] of length 2
+> slice 3..9
+error: range end 9 is out of range for an array of length 5; Rust would panic here, while .get(..) returns None
+> destructure [first, .., last]
+first = 1: i32
+.. skips 3 elements
+last = 5: i32
+> array 1 2u8 300
+error: 300 doesn't fit in u8; Rust rejects the literal (a suffix like 1i64 picks a wider type)
+> array 1 2.5
+error: element 1 is f64 but the array holds i32; every element of an array has one type
+> tuple 'x' \"two words\" true 1i64
+value = ('x', \"two words\", true, 1): (char, &str, bool, i64)
+> quit
+";
+
+fn main() -> io::Result<()> {
+    if std::env::args().any(|arg| arg == "--demo") {
+        let mut transcript = Vec::new();
+        repl(&mut ScriptedInput::new(DEMO), &mut transcript, true)?;
+        let transcript = String::from_utf8(transcript).expect("the REPL writes UTF-8");
+        print!("{transcript}");
+        assert_eq!(transcript, GOLDEN);
+        return Ok(());
+    }
+    println!("Explore tuples and arrays; 'help' lists the commands.");
+    repl(&mut StdinInput, &mut io::stdout().lock(), false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_demo_session_matches_the_golden_transcript() {
+        let mut transcript = Vec::new();
+        repl(&mut ScriptedInput::new(DEMO), &mut transcript, true).unwrap();
+        assert_eq!(String::from_utf8(transcript).unwrap(), GOLDEN);
+    }
+
+    #[test]
+    fn errors_keep_the_session_going_until_the_input_ends() {
+        let mut transcript = Vec::new();
+        let lines = ["index 0", "", "tuple 1", "index 0"];
+        repl(&mut ScriptedInput::new(lines), &mut transcript, false).unwrap();
+        assert_eq!(
+            String::from_utf8(transcript).unwrap(),
+            "> error: nothing to work on yet: build a tuple or an array first\n\
+             > > value = (1,): (i32,)\n\
+             > value.0 = 1: i32\n\
+             > \n"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+// Skipping bounds checks with unsafe, and keeping it sound
+// slice[i] checks i < len on every access and panics if not. get_unchecked
+// doesn't check, and reading past the end with it is undefined behaviour, so
+// each unsafe block below says why its indices are in bounds (a `// SAFETY:`
+// comment) and wraps the unsafe operation in a function that can't be misused
+use std::hint::black_box;
+use std::panic;
+use std::ptr;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 1_000_000;
+const SEARCHES: usize = 10_000_000;
+
+// The checked get, built on the unchecked one: the comparison is the proof
+fn get_unchecked_safe<T>(slice: &[T], idx: usize) -> Option<&T> {
+    if idx < slice.len() {
+        // SAFETY: idx < slice.len() was checked just above
+        Some(unsafe { slice.get_unchecked(idx) })
+    } else {
+        None
+    }
+}
+
+// The index of the first element that is not less than `target`, which is
+// where it is if present and where it would go if not (like partition_point)
+//
+// Loop invariant: lo <= hi <= sorted.len(), everything before lo is < target
+// and everything from hi on is >= target. Inside the loop lo < hi, so
+// mid = lo + (hi - lo) / 2 satisfies lo <= mid < hi <= len: always in bounds.
+// Both updates keep the invariant: lo = mid + 1 <= hi, and hi = mid >= lo
+fn binary_search_unchecked<T: Ord>(sorted: &[T], target: &T) -> usize {
+    let (mut lo, mut hi) = (0, sorted.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        // SAFETY: mid < hi <= sorted.len() by the loop invariant above
+        if unsafe { sorted.get_unchecked(mid) } < target {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+// The same search with get(), which checks the index again; the check can
+// never fail, but the compiler can't always prove that and remove it
+fn binary_search_checked<T: Ord>(sorted: &[T], target: &T) -> usize {
+    let (mut lo, mut hi) = (0, sorted.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if sorted.get(mid).expect("mid < hi <= len") < target {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+// A memcpy for slices of the same length, like copy_from_slice. A length
+// mismatch panics here instead of reading or writing past either slice
+fn copy_nonoverlapping_safe(src: &[u8], dst: &mut [u8]) {
+    assert_eq!(
+        src.len(),
+        dst.len(),
+        "source and destination lengths differ"
+    );
+    // SAFETY: both pointers come from live slices, so each is valid for
+    // src.len() bytes (dst.len() is the same, checked above), and u8 has no
+    // alignment to get wrong. They can't overlap: dst is a &mut, which the
+    // borrow checker guarantees no other reference, src included, points into
+    unsafe { ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), src.len()) }
+}
+
+// Runs every search and returns the sum of the answers, so none can be skipped
+fn time_searches(
+    search: impl Fn(&[u64], &u64) -> usize,
+    sorted: &[u64],
+    targets: &[u64],
+) -> (usize, Duration) {
+    let start = Instant::now();
+    let total = targets
+        .iter()
+        .map(|target| search(black_box(sorted), black_box(target)))
+        .fold(0usize, usize::wrapping_add);
+    (total, start.elapsed())
+}
+
+fn main() {
+    // ---- get_unchecked_safe ----
+    let letters = ['a', 'b', 'c'];
+    assert_eq!(get_unchecked_safe(&letters, 0), Some(&'a'));
+    assert_eq!(get_unchecked_safe(&letters, 2), Some(&'c'));
+    assert_eq!(get_unchecked_safe(&letters, 3), None);
+    assert_eq!(get_unchecked_safe::<char>(&[], 0), None);
+    for idx in 0..5 {
+        assert_eq!(get_unchecked_safe(&letters, idx), letters.get(idx));
+    }
+    println!("get_unchecked_safe agrees with get on every index, in bounds or not");
+
+    // ---- Binary search ----
+    // Even numbers only, so half of the targets below are missing
+    let sorted: Vec<u64> = (0..LEN as u64).map(|n| n * 2).collect();
+    for target in [0, 1, 2, 999, 1_000, 1_999_998, 1_999_999, 5_000_000] {
+        let expected = sorted.partition_point(|&x| x < target);
+        assert_eq!(binary_search_unchecked(&sorted, &target), expected);
+        assert_eq!(binary_search_checked(&sorted, &target), expected);
+    }
+    assert_eq!(binary_search_unchecked(&sorted, &1_000), 500);
+    assert_eq!(binary_search_unchecked::<u64>(&[], &7), 0);
+    // With duplicates it finds the first of them
+    assert_eq!(binary_search_unchecked(&[1, 3, 3, 3, 5], &3), 1);
+
+    // The targets come from a small xorshift generator, so every run searches
+    // for the same numbers and the two versions see the same work
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let targets: Vec<u64> = (0..SEARCHES)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % (2 * LEN as u64)
+        })
+        .collect();
+    let (unchecked_total, unchecked_time) =
+        time_searches(binary_search_unchecked, &sorted, &targets);
+    let (checked_total, checked_time) = time_searches(binary_search_checked, &sorted, &targets);
+    assert_eq!(unchecked_total, checked_total);
+    println!("\n{SEARCHES} searches in {LEN} sorted elements:");
+    println!("  get_unchecked  {unchecked_time:>12?}");
+    println!("  get            {checked_time:>12?}");
+    println!(
+        "  speedup        {:.2}x",
+        checked_time.as_secs_f64() / unchecked_time.as_secs_f64()
+    );
+    // Expect a small difference at best: the check is one comparison that is
+    // always true, which the CPU predicts perfectly, and the cache misses of
+    // jumping around a 1 million element slice cost far more. Measure before
+    // reaching for unsafe; try `cargo run --release --bin unsafe_slice` too
+
+    // ---- copy_nonoverlapping_safe ----
+    let src = *b"unsafe, carefully";
+    let mut dst = [0u8; 17];
+    copy_nonoverlapping_safe(&src, &mut dst);
+    assert_eq!(dst, src);
+    println!("\ncopied {:?}", std::str::from_utf8(&dst).unwrap());
+    // Overlapping copies can't even be written: one slice can't be borrowed
+    // as both &[u8] and &mut [u8] at once
+    //
+    //     copy_nonoverlapping_safe(&dst[..4], &mut dst[4..8]);
+    //     error[E0502]: cannot borrow `dst` as mutable because it is also borrowed as immutable
+    //
+    // Copying between two parts of one buffer goes through split_at_mut, which
+    // hands out two slices that are known not to overlap
+    let (front, back) = dst.split_at_mut(8);
+    copy_nonoverlapping_safe(&front[..6], &mut back[..6]);
+    assert_eq!(&dst[8..14], b"unsafe");
+
+    // A length mismatch is a panic, not undefined behaviour. The default panic
+    // message is silenced, since the panic is expected here
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mismatch = panic::catch_unwind(|| {
+        let mut short = [0u8; 4];
+        copy_nonoverlapping_safe(&src, &mut short);
+    });
+    panic::set_hook(hook);
+    assert!(mismatch.is_err());
+    println!("copying 17 bytes into 4 panicked instead of writing past the end");
+}
@@ -0,0 +1,248 @@
+// Vec and slice methods that are easy to miss, each with a job it suits
+// Most of them replace a few lines of index juggling with one call, and some
+// are faster than the obvious approach too: those are timed against it.
+// slice::array_chunks is still unstable, so as_chunks stands in for it; the
+// nightly-only Vec::drain_filter became extract_if
+use std::cell::Cell;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const LEN: usize = 1_000_000;
+
+// Numbers from a small xorshift generator, the same on every run
+fn numbers(len: usize, mut state: u64) -> Vec<u64> {
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect()
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = black_box(f());
+    (result, start.elapsed())
+}
+
+fn speedup(slow: Duration, fast: Duration) -> f64 {
+    slow.as_secs_f64() / fast.as_secs_f64().max(1e-9)
+}
+
+// ---- extend_from_within: decoding back-references ----
+// LZ77-style compression writes "copy `len` bytes from `distance` back"
+// instead of repeating them. A copy may overlap what it is writing (distance
+// 1, len 5 repeats one byte five times), so it goes one distance's worth at a
+// time, each part already in the Vec
+enum Token {
+    Literal(u8),
+    Copy { distance: usize, len: usize },
+}
+
+fn decode(tokens: &[Token]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for token in tokens {
+        match *token {
+            Token::Literal(byte) => out.push(byte),
+            Token::Copy { distance, mut len } => {
+                while len > 0 {
+                    let start = out.len() - distance;
+                    let part = len.min(distance);
+                    out.extend_from_within(start..start + part);
+                    len -= part;
+                }
+            }
+        }
+    }
+    out
+}
+
+// ---- sort_by_cached_key: a key that is slow to work out ----
+// Counts how often the key function runs
+fn slow_key(word: &str, calls: &Cell<usize>) -> String {
+    calls.set(calls.get() + 1);
+    // Lower-casing allocates a new String every time
+    word.to_lowercase()
+}
+
+fn main() {
+    // ---- extend_from_within ----
+    use Token::{Copy, Literal};
+    let tokens = [
+        Literal(b'a'),
+        Literal(b'b'),
+        Copy {
+            distance: 2,
+            len: 4,
+        },
+        Literal(b'!'),
+        Copy {
+            distance: 1,
+            len: 3,
+        },
+    ];
+    assert_eq!(decode(&tokens), b"ababab!!!!");
+    println!(
+        "extend_from_within: decoded {:?}",
+        String::from_utf8_lossy(&decode(&tokens))
+    );
+
+    // ---- splice: replace a range with something of a different length ----
+    // Expanding a template's {name} in place; splice hands back what it took out
+    let mut text: Vec<char> = "Hello, {name}!".chars().collect();
+    let start = text.iter().position(|&c| c == '{').unwrap();
+    let end = text.iter().position(|&c| c == '}').unwrap();
+    let removed: String = text.splice(start..=end, "Ferris".chars()).collect();
+    assert_eq!(removed, "{name}");
+    assert_eq!(text.iter().collect::<String>(), "Hello, Ferris!");
+    // An empty range inserts, an empty replacement deletes
+    text.splice(0..0, ">> ".chars());
+    text.splice(3..10, []);
+    assert_eq!(text.iter().collect::<String>(), ">> Ferris!");
+    println!("splice: {:?}", text.iter().collect::<String>());
+
+    // ---- extract_if and retain_mut: take some out, change the rest ----
+    // Sessions with a time-to-live: expired ones are moved out (to be logged,
+    // say) rather than just dropped, and the others count down
+    let mut sessions = vec![("ann", 3), ("bob", 0), ("cy", 1), ("dee", 0)];
+    let expired: Vec<(&str, i32)> = sessions.extract_if(.., |(_, ttl)| *ttl == 0).collect();
+    assert_eq!(expired, [("bob", 0), ("dee", 0)]);
+    // retain_mut can change what it keeps while deciding
+    sessions.retain_mut(|(_, ttl)| {
+        *ttl -= 1;
+        *ttl > 0
+    });
+    assert_eq!(sessions, [("ann", 2)]);
+    println!("extract_if: expired {expired:?}; retain_mut left {sessions:?}");
+
+    // ---- flatten: a Vec of Vecs into one ----
+    // Rows of different lengths, as a parser might collect them line by line
+    let mut rows = vec![vec![1, 2], vec![], vec![3]];
+    rows.push(vec![4, 5, 6]);
+    let flat: Vec<i32> = rows.iter().flatten().copied().collect();
+    assert_eq!(flat, [1, 2, 3, 4, 5, 6]);
+    // concat does the same when the whole Vec is wanted, with one allocation
+    assert_eq!(rows.concat(), flat);
+    // Option is iterable too, so flatten skips the Nones
+    let found: Vec<u32> = [Some(1), None, Some(3)].into_iter().flatten().collect();
+    assert_eq!(found, [1, 3]);
+    println!("flatten: {flat:?}");
+
+    // ---- sort_by_cached_key ----
+    // sort_by_key works the key out at every comparison, about n log n times;
+    // sort_by_cached_key works each out once and sorts the results
+    let words: Vec<String> = numbers(100_000, 7)
+        .iter()
+        .map(|n| format!("Word{:X}", n % 1_000_000))
+        .collect();
+    let calls = Cell::new(0);
+    let (by_key, slow) = time(|| {
+        let mut v = words.clone();
+        v.sort_by_key(|w| slow_key(w, &calls));
+        v
+    });
+    let key_calls = calls.replace(0);
+    let (cached, fast) = time(|| {
+        let mut v = words.clone();
+        v.sort_by_cached_key(|w| slow_key(w, &calls));
+        v
+    });
+    // Both sorts are stable, so even ties come out the same
+    assert_eq!(by_key, cached);
+    assert_eq!(calls.get(), words.len());
+    println!(
+        "sort_by_cached_key: {} key calls instead of {key_calls}; {fast:.2?} vs {slow:.2?} ({:.1}x)",
+        calls.get(),
+        speedup(slow, fast)
+    );
+
+    // ---- binary_search_by_key: look up a sorted list by one field ----
+    let users = [(3, "ann"), (8, "bob"), (21, "cy"), (34, "dee")];
+    assert_eq!(users.binary_search_by_key(&21, |&(id, _)| id), Ok(2));
+    // Err is where it would go, ready for an insert that keeps the order
+    assert_eq!(users.binary_search_by_key(&10, |&(id, _)| id), Err(2));
+    println!("binary_search_by_key: id 21 is {}", users[2].1);
+
+    // ---- partition_point: where a sorted slice stops matching ----
+    // The first index where the predicate is false; everything before it is
+    // true. How many scores are under 50, in log n comparisons
+    let mut scores: Vec<u64> = numbers(LEN, 11).iter().map(|n| n % 100).collect();
+    scores.sort_unstable();
+    let (below, fast) = time(|| scores.partition_point(|&s| s < 50));
+    let (counted, slow) = time(|| scores.iter().filter(|&&s| s < 50).count());
+    assert_eq!(below, counted);
+    // All the 50s, between two partition points
+    let fifties = scores.partition_point(|&s| s <= 50) - below;
+    assert!(scores[below..below + fifties].iter().all(|&s| s == 50));
+    println!(
+        "partition_point: {below} of {LEN} scores under 50; {fast:.2?} vs counting {slow:.2?} ({:.0}x)",
+        speedup(slow, fast)
+    );
+
+    // ---- select_nth_unstable: the median without sorting ----
+    // Quickselect puts the nth element where sorting would, with the smaller
+    // ones before it and the larger after, in O(n) time on average rather than
+    // sorting's O(n log n)
+    let data = numbers(LEN, 13);
+    let (median, fast) = time(|| {
+        let mut v = data.clone();
+        *v.select_nth_unstable(LEN / 2).1
+    });
+    let (sorted_median, slow) = time(|| {
+        let mut v = data.clone();
+        v.sort_unstable();
+        v[LEN / 2]
+    });
+    assert_eq!(median, sorted_median);
+    // The same finds the top 10 without sorting the rest: the 10th largest,
+    // and after it the 9 above it, in no particular order
+    let mut v = data.clone();
+    let (_, tenth, above) = v.select_nth_unstable(LEN - 10);
+    assert!(above.len() == 9 && above.iter().all(|n| n >= tenth));
+    println!(
+        "select_nth_unstable: median in {fast:.2?}, sorting took {slow:.2?} ({:.1}x)",
+        speedup(slow, fast)
+    );
+
+    // ---- as_chunks: fixed-size records ----
+    // Each chunk is a [u8; 3], not a &[u8], so indexing it can't go out of
+    // bounds and the compiler can drop the checks. What doesn't fill a whole
+    // chunk comes back separately
+    let bytes = [255, 0, 0, 0, 255, 0, 0, 0, 255, 7];
+    let (pixels, rest) = bytes.as_chunks::<3>();
+    let brightness: Vec<u32> = pixels
+        .iter()
+        .map(|&[r, g, b]| r as u32 + g as u32 + b as u32)
+        .collect();
+    assert_eq!(brightness, [255, 255, 255]);
+    assert_eq!(rest, [7]);
+    // Four bytes at a time into numbers
+    let words: Vec<u32> = [1, 0, 0, 0, 0, 1, 0, 0]
+        .as_chunks::<4>()
+        .0
+        .iter()
+        .map(|&chunk| u32::from_le_bytes(chunk))
+        .collect();
+    assert_eq!(words, [1, 256]);
+    println!(
+        "as_chunks: {} pixels and {} byte left over",
+        pixels.len(),
+        rest.len()
+    );
+
+    // ---- array_windows: neighbours as arrays ----
+    // Like windows(N), but each window is a &[T; N] that destructures
+    let temps = [12, 14, 13, 17, 19, 18];
+    let changes: Vec<i32> = temps.array_windows().map(|[a, b]| b - a).collect();
+    assert_eq!(changes, [2, -1, 4, 2, -1]);
+    let rising = temps
+        .array_windows::<3>()
+        .filter(|[a, b, c]| a < b && b < c)
+        .count();
+    assert_eq!(rising, 1);
+    println!("array_windows: changes {changes:?}, {rising} run of three rising");
+
+    println!("\nTry `cargo run --release --bin vec_tricks` for real timings");
+}
@@ -0,0 +1,245 @@
+// What the terminal the game runs in can do, worked out once at startup
+// Features that need more than plain text (the animation, Unicode glyphs,
+// charts sized to the window) ask this instead of probing on their own, so they
+// all agree, and --capabilities can say what was turned off and why
+use std::env;
+use std::fmt::Write;
+use std::io::{self, IsTerminal};
+
+/// A feature that only works on some terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// The fireworks drawn in place on a win, which move the cursor.
+    Celebration,
+    /// ▲ ▼ ★ from --symbols; without them the ASCII set is used instead.
+    UnicodeSymbols,
+    /// Charts as wide as the terminal rather than a fixed width.
+    FittedCharts,
+}
+
+/// Every feature, in the order --capabilities lists them.
+pub const FEATURES: [Feature; 3] = [
+    Feature::Celebration,
+    Feature::UnicodeSymbols,
+    Feature::FittedCharts,
+];
+
+impl Feature {
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Celebration => "celebration",
+            Feature::UnicodeSymbols => "unicode symbols",
+            Feature::FittedCharts => "fitted charts",
+        }
+    }
+}
+
+/// What was detected about stdin, stdout and the locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub stdin_tty: bool,
+    pub stdout_tty: bool,
+    /// Whether the locale says the terminal shows UTF-8.
+    pub utf8: bool,
+    /// The terminal's width in columns, if it would say.
+    pub width: Option<usize>,
+}
+
+impl Capabilities {
+    /// Asks the environment; call once and pass the result around.
+    pub fn detect() -> Capabilities {
+        Capabilities {
+            stdin_tty: io::stdin().is_terminal(),
+            stdout_tty: io::stdout().is_terminal(),
+            utf8: cfg!(windows) || locale_is_utf8(locale().as_deref()),
+            width: terminal_width(),
+        }
+    }
+
+    /// Replaces whatever detection found with the given answers. Saying that
+    /// there is no terminal also forgets its width, unless one is given too.
+    pub fn overridden(
+        mut self,
+        tty: Option<bool>,
+        utf8: Option<bool>,
+        width: Option<usize>,
+    ) -> Capabilities {
+        if let Some(tty) = tty {
+            self.stdin_tty = tty;
+            self.stdout_tty = tty;
+            if !tty {
+                self.width = None;
+            }
+        }
+        if let Some(utf8) = utf8 {
+            self.utf8 = utf8;
+        }
+        if width.is_some() {
+            self.width = width;
+        }
+        self
+    }
+
+    /// Why `feature` is turned off here, or None if it can be used.
+    pub fn missing_for(&self, feature: Feature) -> Option<&'static str> {
+        match feature {
+            Feature::Celebration if !self.stdout_tty => Some("stdout is not a terminal"),
+            Feature::Celebration | Feature::FittedCharts if self.width.is_none() => {
+                Some("the terminal's width is unknown")
+            }
+            Feature::UnicodeSymbols if !self.utf8 => Some("the locale is not UTF-8"),
+            _ => None,
+        }
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.missing_for(feature).is_none()
+    }
+
+    /// The table printed by --capabilities: what was detected, then every
+    /// feature and, if it's off, the reason.
+    pub fn report(&self) -> String {
+        let yes_no = |b: bool| if b { "yes" } else { "no" }.to_string();
+        let detected = [
+            ("stdin is a terminal", yes_no(self.stdin_tty)),
+            ("stdout is a terminal", yes_no(self.stdout_tty)),
+            ("UTF-8 locale", yes_no(self.utf8)),
+            (
+                "width",
+                self.width
+                    .map_or("unknown".to_string(), |width| format!("{width} columns")),
+            ),
+        ];
+        let mut report = "Terminal:\n".to_string();
+        for (name, value) in detected {
+            writeln!(report, "  {name:<22}{value}").unwrap();
+        }
+        report.push_str("Features:\n");
+        for feature in FEATURES {
+            let state = match self.missing_for(feature) {
+                Some(reason) => format!("off: {reason}"),
+                None => "on".to_string(),
+            };
+            writeln!(report, "  {:<22}{state}", feature.name()).unwrap();
+        }
+        report
+    }
+}
+
+// The variable that decides the character set: LC_ALL beats LC_CTYPE beats LANG,
+// and an empty one counts as unset
+fn locale() -> Option<String> {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Whether a locale name like "en_US.UTF-8" asks for UTF-8; no locale at all
+/// means the C locale, which is ASCII.
+pub fn locale_is_utf8(locale: Option<&str>) -> bool {
+    locale.is_some_and(|locale| {
+        let lower = locale.to_ascii_lowercase();
+        lower.contains("utf-8") || lower.contains("utf8")
+    })
+}
+
+// Asks the terminal how many columns it has; None when stdout isn't a terminal
+#[cfg(unix)]
+fn terminal_width() -> Option<usize> {
+    // SAFETY: TIOCGWINSZ only fills in the winsize struct we pass it
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+    (ok && size.ws_col > 0).then_some(size.ws_col as usize)
+}
+
+#[cfg(not(unix))]
+fn terminal_width() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TERMINAL: Capabilities = Capabilities {
+        stdin_tty: true,
+        stdout_tty: true,
+        utf8: true,
+        width: Some(80),
+    };
+
+    // Which features are on, in FEATURES order
+    fn enabled(caps: Capabilities) -> Vec<Feature> {
+        FEATURES.into_iter().filter(|&f| caps.supports(f)).collect()
+    }
+
+    #[test]
+    fn overrides_decide_which_features_are_on() {
+        use Feature::*;
+        // (--tty, --utf8, --width) and the features left on
+        let cases = [
+            (
+                (None, None, None),
+                vec![Celebration, UnicodeSymbols, FittedCharts],
+            ),
+            ((Some(false), None, None), vec![UnicodeSymbols]),
+            (
+                (Some(false), None, Some(40)),
+                vec![UnicodeSymbols, FittedCharts],
+            ),
+            ((None, Some(false), None), vec![Celebration, FittedCharts]),
+            ((Some(false), Some(false), None), vec![]),
+            (
+                (Some(true), None, Some(120)),
+                vec![Celebration, UnicodeSymbols, FittedCharts],
+            ),
+        ];
+        for ((tty, utf8, width), expected) in cases {
+            let caps = TERMINAL.overridden(tty, utf8, width);
+            assert_eq!(enabled(caps), expected, "{tty:?} {utf8:?} {width:?}");
+        }
+    }
+
+    #[test]
+    fn each_missing_feature_says_why() {
+        let piped = TERMINAL.overridden(Some(false), Some(false), None);
+        assert_eq!(
+            piped.missing_for(Feature::Celebration),
+            Some("stdout is not a terminal")
+        );
+        assert_eq!(
+            piped.missing_for(Feature::FittedCharts),
+            Some("the terminal's width is unknown")
+        );
+        assert_eq!(
+            piped.missing_for(Feature::UnicodeSymbols),
+            Some("the locale is not UTF-8")
+        );
+        // A terminal that won't give its width can't animate either
+        let no_width = Capabilities {
+            width: None,
+            ..TERMINAL
+        };
+        assert_eq!(
+            no_width.missing_for(Feature::Celebration),
+            Some("the terminal's width is unknown")
+        );
+    }
+
+    #[test]
+    fn the_report_lists_every_feature() {
+        let report = TERMINAL.overridden(None, Some(false), None).report();
+        assert!(report.contains("  width                 80 columns\n"));
+        assert!(report.contains("  celebration           on\n"));
+        assert!(report.contains("  unicode symbols       off: the locale is not UTF-8\n"));
+    }
+
+    #[test]
+    fn utf8_locales_are_recognised() {
+        assert!(locale_is_utf8(Some("en_US.UTF-8")));
+        assert!(locale_is_utf8(Some("C.utf8")));
+        assert!(!locale_is_utf8(Some("C")));
+        assert!(!locale_is_utf8(None));
+    }
+}
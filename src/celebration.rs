@@ -0,0 +1,109 @@
+// A little fireworks show for a won round
+// Building the frames is kept apart from showing them, so the drawing doesn't
+// depend on a terminal or on sleeping between frames
+use crate::analysis;
+use crate::game::Game;
+
+const FIREWORKS: [[&str; 3]; 3] = [
+    ["     .     ", "     |     ", "     |     "],
+    ["   \\ | /   ", "  -- * --  ", "   / | \\   "],
+    [" *  .  * . ", ".  * . *  *", " * .  *  . "],
+];
+
+/// The frames of the celebration for a won `game`, each a few lines of text:
+/// a burst of fireworks over a banner with the secret, the attempts and the
+/// efficiency grade.
+///
+/// Every line is at most `width` characters. When the banner doesn't fit in
+/// `width` there are no frames at all.
+pub fn celebration_frames(game: &Game, width: usize) -> Vec<String> {
+    let grade = analysis::round_grade(game.range(), game.attempts());
+    let lines = [
+        format!("You found {}!", game.secret()),
+        format!("Attempts: {}   Grade: {grade}", game.attempts()),
+    ];
+    let inner = lines.iter().map(|line| line.len()).max().unwrap_or(0) + 2;
+    // "|" + inner + "|"
+    if inner + 2 > width {
+        return Vec::new();
+    }
+
+    let border = format!("+{}+", "-".repeat(inner));
+    let mut banner = vec![border.clone()];
+    for line in &lines {
+        banner.push(format!("| {line:<0$} |", inner - 2));
+    }
+    banner.push(border);
+
+    let indent = (inner + 2).saturating_sub(FIREWORKS[0][0].len()) / 2;
+    FIREWORKS
+        .iter()
+        .map(|burst| {
+            let mut frame = String::new();
+            for line in burst {
+                frame.push_str(format!("{}{line}", " ".repeat(indent)).trim_end());
+                frame.push('\n');
+            }
+            for line in &banner {
+                frame.push_str(line);
+                frame.push('\n');
+            }
+            frame
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Guess;
+
+    fn won(range: std::ops::RangeInclusive<u32>, secret: u32) -> Game {
+        let mut game = Game::with_secret(range.clone(), secret);
+        game.guess(Guess::new(secret, &range).unwrap());
+        game
+    }
+
+    // The banner is the boxed part of a frame, from the first border down
+    fn banner(frame: &str) -> Vec<&str> {
+        frame
+            .lines()
+            .skip_while(|line| !line.starts_with('+'))
+            .collect()
+    }
+
+    #[test]
+    fn every_line_fits_the_width() {
+        let game = won(1..=100, 42);
+        for width in [30, 40, 80] {
+            let frames = celebration_frames(&game, width);
+            assert_eq!(frames.len(), FIREWORKS.len());
+            for frame in &frames {
+                for line in frame.lines() {
+                    assert!(line.len() <= width, "{line:?} is wider than {width}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_long_secret_keeps_the_borders_lined_up() {
+        let game = won(1..=u32::MAX, u32::MAX);
+        let frames = celebration_frames(&game, 80);
+        let banner = banner(&frames[0]);
+        assert_eq!(banner.len(), 4);
+        assert!(banner[1].contains("You found 4294967295!"));
+        for line in &banner {
+            assert_eq!(line.len(), banner[0].len(), "{line:?}");
+            assert!(line.ends_with(['+', '|']), "{line:?}");
+        }
+    }
+
+    #[test]
+    fn a_too_narrow_width_has_no_frames() {
+        let game = won(1..=u32::MAX, u32::MAX);
+        let needed = banner(&celebration_frames(&game, 80)[0])[0].len();
+        assert!(celebration_frames(&game, needed - 1).is_empty());
+        assert!(!celebration_frames(&game, needed).is_empty());
+    }
+}
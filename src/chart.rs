@@ -0,0 +1,189 @@
+// Plain-text charts for the terminal
+// Every renderer takes the width to fit in, so the caller decides how wide the
+// screen is and the drawing code stays easy to check with fixed widths
+use crate::game::{Game, GuessOutcome};
+use crate::range_math;
+use crate::strategy::Summary;
+
+/// Width used when the terminal size can't be found, e.g. when output is piped.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Draws one row per turn of `game`: the guess, the answer, and a track
+/// across the range with the guess marked `*` and the numbers still possible
+/// after it as `=`.
+///
+/// Lines are at most `width` characters, as long as `width` leaves room for
+/// the labels and a one-cell track.
+pub fn render_history(game: &Game, width: usize) -> String {
+    let range = game.range();
+    let label_width = range.end().to_string().len();
+    // "{guess} {arrow} [" + track + "]"
+    let track = width.saturating_sub(label_width + 5).max(1);
+    let cell = |value: u32| range_math::scale(value, range, track);
+
+    let mut out = String::new();
+    for (turn, feasible) in game.history().iter().zip(game.feasible_history()) {
+        let arrow = match turn.outcome {
+            GuessOutcome::TooSmall => '^',
+            GuessOutcome::TooBig => 'v',
+            GuessOutcome::Correct => '=',
+        };
+        let mut cells = vec![' '; track];
+        if !feasible.is_empty() {
+            for c in &mut cells[cell(*feasible.start())..=cell(*feasible.end())] {
+                *c = '=';
+            }
+        }
+        cells[cell(turn.guess)] = '*';
+        let cells: String = cells.into_iter().collect();
+        out.push_str(&format!("{:>label_width$} {arrow} [{cells}]\n", turn.guess));
+    }
+    out
+}
+
+/// Draws a horizontal bar chart, one `(label, count)` per row, scaling the
+/// longest bar to the space left in `width`.
+pub fn render_histogram(rows: &[(String, u32)], width: usize) -> String {
+    let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let max = rows.iter().map(|&(_, count)| count).max().unwrap_or(0);
+    let count_width = max.to_string().len();
+    // "{label} |{bar} {count}"
+    let room = width.saturating_sub(label_width + count_width + 3).max(1);
+
+    let mut out = String::new();
+    for (label, count) in rows {
+        let bar = if max == 0 {
+            0
+        } else {
+            (*count as u64 * room as u64 / max as u64) as usize
+        };
+        out.push_str(&format!(
+            "{label:>label_width$} |{}{} {count:>count_width$}\n",
+            "#".repeat(bar),
+            " ".repeat(room - bar)
+        ));
+    }
+    out
+}
+
+/// Draws a tournament table: one row per strategy with its summary, then
+/// "wins-losses" against every other strategy in the columns (ties make up
+/// the rest of the secrets).
+pub fn render_tournament(names: &[&str], summaries: &[Summary], wins: &[Vec<u32>]) -> String {
+    let name_width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+    let mut out = format!("{:<name_width$}   mean  p95  worst |", "strategy");
+    for name in names {
+        out.push_str(&format!(" {name:>11}"));
+    }
+    out.push('\n');
+    for (i, (name, summary)) in names.iter().zip(summaries).enumerate() {
+        out.push_str(&format!(
+            "{name:<name_width$} {:>6.2} {:>4} {:>6} |",
+            summary.mean, summary.p95, summary.worst
+        ));
+        for (j, theirs) in wins.iter().enumerate() {
+            let cell = if i == j {
+                "-".to_string()
+            } else {
+                format!("{}-{}", wins[i][j], theirs[i])
+            };
+            out.push_str(&format!(" {cell:>11}"));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Guess;
+
+    fn played(range: std::ops::RangeInclusive<u32>, secret: u32, guesses: &[u32]) -> Game {
+        let mut game = Game::with_secret(range, secret);
+        for &value in guesses {
+            game.guess(Guess::new(value, game.range()).unwrap());
+        }
+        game
+    }
+
+    // The column of the `*` in each row's track
+    fn marks(chart: &str) -> Vec<usize> {
+        chart
+            .lines()
+            .map(|line| {
+                let track = &line[line.find('[').unwrap() + 1..line.rfind(']').unwrap()];
+                track.find('*').unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_million_wide_history_uses_distinct_columns() {
+        let game = played(
+            1..=1_000_000,
+            990_000,
+            &[1, 250_000, 500_000, 750_000, 999_999, 990_000],
+        );
+        let chart = render_history(&game, 60);
+        // A 48-cell track (60 less the labels), so each cell is 20833 numbers
+        // and guess g lands in cell (g - 1) * 48 / 1000000
+        assert_eq!(marks(&chart), [0, 11, 23, 35, 47, 47]);
+        let columns = &marks(&chart)[..5];
+        assert!(columns.windows(2).all(|pair| pair[0] < pair[1]));
+        for line in chart.lines() {
+            assert_eq!(line.chars().count(), 60);
+        }
+    }
+
+    #[test]
+    fn the_feasible_band_narrows_on_a_wide_range() {
+        let game = played(1..=1_000_000, 600_000, &[500_000, 750_000]);
+        let chart = render_history(&game, 60);
+        let bands: Vec<usize> = chart
+            .lines()
+            .map(|line| line.matches(['=', '*']).count())
+            .collect();
+        // After 500000 the upper half is left, after 750000 a quarter
+        assert!(bands[0] > bands[1]);
+        assert!(bands[1] > 1);
+    }
+
+    #[test]
+    fn history_lines_fit_narrow_and_wide_widths() {
+        let game = played(1..=100, 65, &[50, 75, 62, 68, 65]);
+        // "100 ^ [" and "]" leave a one-cell track at 9
+        for width in [9, 10, 20, 80, 200] {
+            let chart = render_history(&game, width);
+            assert_eq!(chart.lines().count(), 5);
+            for line in chart.lines() {
+                assert_eq!(line.chars().count(), width, "{line:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn a_too_narrow_history_still_has_one_cell() {
+        let game = played(1..=100, 65, &[50, 65]);
+        assert_eq!(render_history(&game, 3), " 50 ^ [*]\n 65 = [*]\n");
+    }
+
+    #[test]
+    fn histogram_lines_fit_narrow_and_wide_widths() {
+        let rows = [
+            ("1".to_string(), 2),
+            ("2".to_string(), 10),
+            ("10".to_string(), 0),
+        ];
+        // "10 |" + bar + " 10" leaves a one-cell bar at 8
+        for width in [8, 12, 40, 120] {
+            let chart = render_histogram(&rows, width);
+            for line in chart.lines() {
+                assert_eq!(line.chars().count(), width, "{line:?}");
+            }
+            // The longest count fills its row
+            let longest = chart.lines().nth(1).unwrap();
+            assert_eq!(longest.matches('#').count(), width - 7);
+        }
+    }
+}
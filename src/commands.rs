@@ -0,0 +1,401 @@
+// Working out which command a word at the prompt means
+// Besides the full name, a command can be typed as an alias from the player's
+// alias file or as any start of its name that fits no other command. When more
+// than one applies the order is: exact name, then alias, then unique prefix
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::number_words::parse_number_word;
+use crate::radix;
+
+/// How a word was matched to its command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Via {
+    /// The command's own name.
+    Exact,
+    /// An alias the player defined.
+    Alias,
+    /// The start of exactly one command's name.
+    Prefix,
+}
+
+/// The command a word stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolved {
+    pub command: &'static str,
+    pub via: Via,
+}
+
+/// A prefix that starts more than one command's name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguous {
+    pub prefix: String,
+    /// The commands it could mean, in alphabetical order.
+    pub matches: Vec<&'static str>,
+}
+
+impl fmt::Display for Ambiguous {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (last, rest) = self.matches.split_last().expect("at least two matches");
+        write!(
+            f,
+            "'{}' could be {} or {last}; type a little more of it.",
+            self.prefix,
+            rest.join(", ")
+        )
+    }
+}
+
+impl std::error::Error for Ambiguous {}
+
+/// Which command `word` means, or `None` if it isn't one (it may be a guess).
+pub fn resolve(
+    word: &str,
+    commands: &[&'static str],
+    aliases: &Aliases,
+) -> Result<Option<Resolved>, Ambiguous> {
+    if let Some(&command) = commands.iter().find(|&&name| name == word) {
+        return Ok(Some(Resolved {
+            command,
+            via: Via::Exact,
+        }));
+    }
+    if let Some(command) = aliases.get(word) {
+        return Ok(Some(Resolved {
+            command,
+            via: Via::Alias,
+        }));
+    }
+    // Every name starts with "", which would make an empty line ambiguous
+    if word.is_empty() {
+        return Ok(None);
+    }
+    let mut matches: Vec<&'static str> = commands
+        .iter()
+        .copied()
+        .filter(|name| name.starts_with(word))
+        .collect();
+    match matches[..] {
+        [] => Ok(None),
+        [command] => Ok(Some(Resolved {
+            command,
+            via: Via::Prefix,
+        })),
+        _ => {
+            matches.sort_unstable();
+            Err(Ambiguous {
+                prefix: word.to_string(),
+                matches,
+            })
+        }
+    }
+}
+
+/// A problem with the alias file, with the line it is on (counting from 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AliasError {
+    /// Not `name = command`, a [section] or a comment.
+    Syntax {
+        line: usize,
+    },
+    /// The name is already a command, which always wins.
+    ShadowsCommand {
+        line: usize,
+        alias: String,
+    },
+    /// The name reads as a number, which would stop it being guessed.
+    ShadowsNumber {
+        line: usize,
+        alias: String,
+    },
+    UnknownCommand {
+        line: usize,
+        alias: String,
+        command: String,
+    },
+    Duplicate {
+        line: usize,
+        alias: String,
+    },
+}
+
+impl fmt::Display for AliasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AliasError::Syntax { line } => {
+                write!(f, "line {line}: expected 'name = command'")
+            }
+            AliasError::ShadowsCommand { line, alias } => {
+                write!(f, "line {line}: '{alias}' is already a command")
+            }
+            AliasError::ShadowsNumber { line, alias } => {
+                write!(
+                    f,
+                    "line {line}: '{alias}' reads as a number, so it can't be an alias"
+                )
+            }
+            AliasError::UnknownCommand {
+                line,
+                alias,
+                command,
+            } => write!(
+                f,
+                "line {line}: '{alias}' points to '{command}', which isn't a command"
+            ),
+            AliasError::Duplicate { line, alias } => {
+                write!(f, "line {line}: '{alias}' is defined twice")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AliasError {}
+
+/// Short names for commands, from the `[aliases]` section of an alias file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Aliases {
+    // Alias and command, in the order the file gives them
+    entries: Vec<(String, &'static str)>,
+}
+
+impl Aliases {
+    pub fn new() -> Aliases {
+        Aliases::default()
+    }
+
+    /// Reads aliases written like
+    ///
+    /// ```text
+    /// [aliases]
+    /// h = "history"
+    /// q = quit
+    /// ```
+    ///
+    /// Blank lines and `#` comments are skipped, and so are other sections.
+    /// An alias has to be a single word that isn't a command, or a number in
+    /// any base or in words, and has to point to one of `commands`.
+    pub fn parse(text: &str, commands: &[&'static str]) -> Result<Aliases, AliasError> {
+        let mut aliases = Aliases::new();
+        let mut in_aliases = false;
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_aliases = section.trim() == "aliases";
+                continue;
+            }
+            if !in_aliases {
+                continue;
+            }
+            let syntax = AliasError::Syntax { line: line_number };
+            let (alias, command) = line.split_once('=').ok_or(syntax.clone())?;
+            let alias = alias.trim();
+            let command = command.trim();
+            let command = command
+                .strip_prefix('"')
+                .and_then(|c| c.strip_suffix('"'))
+                .unwrap_or(command);
+            if alias.is_empty() || alias.contains(char::is_whitespace) {
+                return Err(syntax);
+            }
+            aliases.add(line_number, alias, command, commands)?;
+        }
+        Ok(aliases)
+    }
+
+    /// Reads an alias file; see [`Aliases::parse`] for the layout.
+    pub fn load(path: &Path, commands: &[&'static str]) -> io::Result<Aliases> {
+        let text = fs::read_to_string(path)?;
+        Aliases::parse(&text, commands)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+
+    fn add(
+        &mut self,
+        line: usize,
+        alias: &str,
+        command: &str,
+        commands: &[&'static str],
+    ) -> Result<(), AliasError> {
+        let alias_owned = || alias.to_string();
+        if commands.contains(&alias) {
+            return Err(AliasError::ShadowsCommand {
+                line,
+                alias: alias_owned(),
+            });
+        }
+        let is_number = radix::BASES
+            .iter()
+            .any(|&base| radix::parse_in_base(alias, base).is_some())
+            || parse_number_word(alias).is_some();
+        if is_number {
+            return Err(AliasError::ShadowsNumber {
+                line,
+                alias: alias_owned(),
+            });
+        }
+        let Some(&command) = commands.iter().find(|&&name| name == command) else {
+            return Err(AliasError::UnknownCommand {
+                line,
+                alias: alias_owned(),
+                command: command.to_string(),
+            });
+        };
+        if self.get(alias).is_some() {
+            return Err(AliasError::Duplicate {
+                line,
+                alias: alias_owned(),
+            });
+        }
+        self.entries.push((alias_owned(), command));
+        Ok(())
+    }
+
+    /// The command `alias` stands for.
+    pub fn get(&self, alias: &str) -> Option<&'static str> {
+        self.entries
+            .iter()
+            .find(|(name, _)| name == alias)
+            .map(|&(_, command)| command)
+    }
+
+    /// Every alias for `command`, in the order they were defined.
+    pub fn for_command<'a>(&'a self, command: &'a str) -> impl Iterator<Item = &'a str> {
+        self.entries
+            .iter()
+            .filter(move |&&(_, c)| c == command)
+            .map(|(alias, _)| alias.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMANDS: [&str; 4] = ["hint", "history", "help", "quit"];
+
+    fn aliases(text: &str) -> Aliases {
+        Aliases::parse(text, &COMMANDS).unwrap()
+    }
+
+    #[test]
+    fn words_resolve_exact_then_alias_then_prefix() {
+        // "h" starts three names and "qu" starts quit, but an alias wins over
+        // a prefix. An alias can't be a command's name, so exact needs no alias
+        let aliases = aliases("[aliases]\nh = history\nqu = \"hint\"\n");
+        let cases: [(&str, Option<(&str, Via)>); 8] = [
+            ("hint", Some(("hint", Via::Exact))),
+            ("help", Some(("help", Via::Exact))),
+            ("h", Some(("history", Via::Alias))),
+            ("qu", Some(("hint", Via::Alias))),
+            ("q", Some(("quit", Via::Prefix))),
+            ("his", Some(("history", Via::Prefix))),
+            ("42", None),
+            ("", None),
+        ];
+        for (word, expected) in cases {
+            let resolved = resolve(word, &COMMANDS, &aliases).unwrap();
+            let expected = expected.map(|(command, via)| Resolved { command, via });
+            assert_eq!(resolved, expected, "resolving {word:?}");
+        }
+    }
+
+    #[test]
+    fn a_prefix_of_several_commands_is_ambiguous() {
+        let err = resolve("hi", &COMMANDS, &Aliases::new()).unwrap_err();
+        assert_eq!(
+            err,
+            Ambiguous {
+                prefix: "hi".to_string(),
+                matches: vec!["hint", "history"],
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "'hi' could be hint or history; type a little more of it."
+        );
+        let err = resolve("h", &COMMANDS, &Aliases::new()).unwrap_err();
+        assert_eq!(err.matches, ["help", "hint", "history"]);
+        assert_eq!(
+            err.to_string(),
+            "'h' could be help, hint or history; type a little more of it."
+        );
+    }
+
+    #[test]
+    fn other_sections_and_comments_are_skipped() {
+        let aliases = aliases("# mine\n[colours]\nh = red\n\n[aliases]\nx = quit\n");
+        assert_eq!(aliases.get("h"), None);
+        assert_eq!(aliases.get("x"), Some("quit"));
+        assert_eq!(aliases.for_command("quit").collect::<Vec<_>>(), ["x"]);
+    }
+
+    #[test]
+    fn every_alias_error_names_its_line() {
+        let alias = |name: &str| name.to_string();
+        let cases = [
+            (
+                "[aliases]\nh history",
+                AliasError::Syntax { line: 2 },
+                "line 2: expected 'name = command'",
+            ),
+            (
+                "[aliases]\nmy hint = hint",
+                AliasError::Syntax { line: 2 },
+                "line 2: expected 'name = command'",
+            ),
+            (
+                "[aliases]\n\nquit = hint",
+                AliasError::ShadowsCommand {
+                    line: 3,
+                    alias: alias("quit"),
+                },
+                "line 3: 'quit' is already a command",
+            ),
+            (
+                "[aliases]\n12 = quit",
+                AliasError::ShadowsNumber {
+                    line: 2,
+                    alias: alias("12"),
+                },
+                "line 2: '12' reads as a number, so it can't be an alias",
+            ),
+            (
+                "[aliases]\nten = quit",
+                AliasError::ShadowsNumber {
+                    line: 2,
+                    alias: alias("ten"),
+                },
+                "line 2: 'ten' reads as a number, so it can't be an alias",
+            ),
+            (
+                "[aliases]\nx = exit",
+                AliasError::UnknownCommand {
+                    line: 2,
+                    alias: alias("x"),
+                    command: "exit".to_string(),
+                },
+                "line 2: 'x' points to 'exit', which isn't a command",
+            ),
+            (
+                "[aliases]\nx = quit\nx = hint",
+                AliasError::Duplicate {
+                    line: 3,
+                    alias: alias("x"),
+                },
+                "line 3: 'x' is defined twice",
+            ),
+        ];
+        for (text, expected, message) in cases {
+            let err = Aliases::parse(text, &COMMANDS).unwrap_err();
+            assert_eq!(err, expected, "parsing {text:?}");
+            assert_eq!(err.to_string(), message);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// Commit to the secret before the round starts, so the player can check
+// afterwards that it wasn't changed mid-game
+// The commitment is SHA-256 of the secret and a random salt. It is shown up
+// front; the salt is revealed at the end. Without the salt the commitment
+// can't be brute-forced from the small range of possible secrets
+use rand::Rng;
+
+/// A fresh random salt, as 16 hex digits.
+pub fn new_salt() -> String {
+    format!("{:016x}", rand::rng().random::<u64>())
+}
+
+/// The commitment for a secret and salt: the hex SHA-256 of "secret:salt".
+pub fn commit(secret: u32, salt: &str) -> String {
+    hex(&sha256(format!("{secret}:{salt}").as_bytes()))
+}
+
+/// Whether `commitment` was made for exactly this secret and salt.
+pub fn verify(commitment: &str, secret: u32, salt: &str) -> bool {
+    commit(secret, salt).eq_ignore_ascii_case(commitment.trim())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+// SHA-256 as specified in FIPS 180-4, vendored to keep the crate dependency-free
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+fn sha256(message: &[u8]) -> [u8; 32] {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    // Padding: a 1 bit, zeros up to 56 mod 64 bytes, then the length in bits
+    let mut data = message.to_vec();
+    data.push(0x80);
+    while data.len() % 64 != 56 {
+        data.push(0);
+    }
+    data.extend_from_slice(&(message.len() as u64 * 8).to_be_bytes());
+
+    for block in data.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for (k, w) in K.iter().zip(w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(h) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The one- and two-block examples from FIPS 180-4, plus the empty message
+    #[test]
+    fn sha256_matches_the_standard_vectors() {
+        let cases: [(&str, &str); 3] = [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (message, digest) in cases {
+            assert_eq!(hex(&sha256(message.as_bytes())), digest, "{message:?}");
+        }
+    }
+
+    #[test]
+    fn a_commitment_verifies_for_its_own_secret_and_salt() {
+        let commitment = commit(42, "00000000deadbeef");
+        assert_eq!(
+            commitment,
+            "58845fde40fc3ea4085bea2c78c2d844bbe8a874a304f9a295ba1ecbcb7b7d5c"
+        );
+        assert!(verify(&commitment, 42, "00000000deadbeef"));
+        assert!(verify(&commitment.to_uppercase(), 42, "00000000deadbeef"));
+    }
+
+    #[test]
+    fn a_changed_secret_fails_verification() {
+        let commitment = commit(42, "00000000deadbeef");
+        assert!(!verify(&commitment, 43, "00000000deadbeef"));
+    }
+
+    #[test]
+    fn a_changed_salt_fails_verification() {
+        let commitment = commit(42, "00000000deadbeef");
+        assert!(!verify(&commitment, 42, "00000000deadbeee"));
+    }
+
+    #[test]
+    fn fresh_salts_are_sixteen_hex_digits() {
+        let salt = new_salt();
+        assert_eq!(salt.len(), 16);
+        assert!(salt.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
@@ -0,0 +1,497 @@
+// Command-line options for the game in main.rs and app.rs
+// Parsed by hand to keep the crate dependency-free; clap would be the usual choice
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+use crate::daily::NaiveDate;
+use crate::game::TempPolicy;
+use crate::input_policy::InvalidInputPolicy;
+use crate::progress::Difficulty;
+use crate::radix;
+use crate::range_math;
+use crate::recent_secrets::MAX_WINDOW;
+use crate::season::{SeasonView, Seasons};
+use crate::terminal::SymbolSet;
+
+pub const USAGE: &str = "Usage: learning-rust [options]
+
+Options:
+  --difficulty D  easy (1-10), medium (1-100, the default), hard (1-1000)
+                  or expert (1-10000); harder levels unlock as you win
+  --tutorial      a guided first round that explains every answer
+  --accessible    plain, complete sentences instead of charts, for screen readers
+  --warmup        work out the range from a few clues before playing
+  --words         also accept guesses written in words, like 'forty-two'
+  --blind         don't say what the range is; find its edges yourself
+  --base B        read and write numbers in base 2, 8, 10 (the default) or 16
+  --show-range    repeat the range in every prompt, like 'Guess (1-100):'
+                  (ignored with --blind)
+  --center N --spread P%
+                  play on the numbers within P percent of N instead of a
+                  difficulty, e.g. '--center 500 --spread 10%' is 450-550
+  --tolerance N   a guess within N of the secret also wins (default 0)
+  --symbols       mark each answer with a shape: ▲ too big, ▼ too small, ★ a win
+  --ascii-symbols like --symbols, with ^, v and * for terminals without Unicode
+  --no-echo       don't repeat each guess back before answering it
+  --terse         one short line per guess and no animations or charts,
+                  for slow terminals and serial consoles
+  --max-line-width N
+                  wrap lines longer than N columns (with --terse, cut them off)
+  --bell          ring the terminal bell when you win
+  --no-celebration
+                  skip the fireworks when you win
+  --warmer-colder say whether each guess is closer to the secret than the last
+  --temp-policy P what --warmer-colder says when two guesses are equally far away:
+                  same (the default) or crossover (notice jumping over it)
+  --invalid-input P
+                  what happens when a line isn't a number or command: lenient
+                  (the default), counted, or strict:N (N in a row end the round)
+  --entropy       show how many bits of information remain after each guess
+  --influence     say how many numbers each guess ruled out and how many remain
+  --coach         grade every guess against the binary search strategy
+  --auto-hints    get a free hint after 3, 5 and 7 wrong guesses
+  --earn-hints    pay for hints with hints earned by good guesses instead of points
+  --free-first-hint
+                  the first hint of each round costs no points
+  --timing        report your fastest and slowest guess when the round ends
+  --calibrate     rate your confidence before each answer and get a calibration score
+  --auto          watch binary search solve a round on the chosen difficulty
+  --auto-quiet    like --auto, but only say how many guesses it took
+  --evaluate NAME play many games with a built-in strategy and report how it did
+                  (midpoint, low-quarter, linear or random)
+  --tournament    play every built-in strategy on the same secrets and compare them
+  --games N       how many games --evaluate and --tournament play (default 1000)
+  --grade DIR     check and grade every replay file (as written by --history)
+                  in DIR, and print a CSV report of them
+  --report PATH   with --grade, write the report to PATH and a summary to the screen
+  --rubric PATH   with --grade, take the points for each grade from the [rubric]
+                  section of PATH, one 'A = 10' per line (default A 4 to D 1)
+  --intervals     guess a range each turn instead of a number; narrower hits score more
+  --seed N        pick the secret from seed N, to replay a round exactly
+  --no-repeat K   don't deal any of the range's last K secrets (1 to 100) again,
+                  unless the range is too small to avoid them; kept between runs
+  --daily         play today's puzzle: everyone gets the same secret on the same day
+  --date D        with --daily, play the puzzle of day D (YYYY-MM-DD) instead
+  --show-seed     say which seed the round used when it ends
+  --commit        show a hash of the secret up front and reveal it at the end,
+                  so you can check the secret never changed
+  --save PATH     save the round to PATH when you quit
+  --resume PATH   continue a round saved with --save
+  --history PATH  add every round you play to PATH, for --practice
+  --leaderboard PATH
+                  keep top scores and progress in PATH (default leaderboard.json)
+  --practice      lean the secret toward the part of the range where the
+                  rounds in --history went worst
+  --season S      print the standings of season S (YYYY-MM, 'legacy' for results
+                  from before seasons, or 'all-time') and exit
+  --season-months N
+                  make each ranked season N months long (1-12, default 1)
+  --all-time      show the all-time top scores after a win, not this season's
+  --aliases PATH  read short names for commands from the [aliases] section of
+                  PATH, one 'name = command' per line
+  --spectator-log PATH
+                  append each event of the round to PATH as it happens, for
+                  'tail -f'; the secret is only written once the round is over
+  --capabilities  list what the terminal was found to support, and which
+                  features were turned off because of it
+  --tty BOOL      take stdin and stdout to be terminals (true) or not (false)
+                  instead of detecting it; --utf8 BOOL does the same for the locale
+  --term-width N  take the terminal to be N columns wide";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// Which range to play on.
+    pub difficulty: Difficulty,
+    /// With `spread`, play on a range around this number instead of the difficulty's.
+    pub center: Option<u32>,
+    /// How far either side of `center` the range reaches, in percent of it.
+    pub spread: Option<u8>,
+    /// How far from the secret a guess may be and still win.
+    pub tolerance: u32,
+    /// Play the guided tutorial round.
+    pub tutorial: bool,
+    /// Describe every turn in plain sentences and draw no charts.
+    pub accessible: bool,
+    /// Start with the guess-the-range warm-up.
+    pub warmup: bool,
+    /// Accept number words ("forty-two") as well as digits.
+    pub words: bool,
+    /// Hide the range until the player runs into its ends.
+    pub blind: bool,
+    /// The base guesses are read in and numbers are written in; one of `radix::BASES`.
+    pub base: u32,
+    /// Put the range in every prompt; ignored when `blind` hides it.
+    pub show_range: bool,
+    /// Put a glyph in front of every answer, from this set.
+    pub symbols: Option<SymbolSet>,
+    /// Repeat each guess back ("You guessed: 42"); turned off by --no-echo.
+    pub echo: bool,
+    /// Answer each guess in one short line and skip the animation and charts.
+    pub terse: bool,
+    /// Wrap (or with `terse`, truncate) output lines to this many columns,
+    /// at least 1.
+    pub max_line_width: Option<usize>,
+    /// Ring the terminal bell on a win.
+    pub bell: bool,
+    /// Show the fireworks animation on a win (turned off by --no-celebration).
+    pub celebrate: bool,
+    /// Compare each guess's distance to the secret with the previous one.
+    pub warmer_colder: bool,
+    /// How --warmer-colder reports two guesses at the same distance.
+    pub temp_policy: TempPolicy,
+    /// How lines that are neither a guess nor a command are handled.
+    pub invalid_input: InvalidInputPolicy,
+    /// Print the remaining information (in bits) after each guess.
+    pub entropy: bool,
+    /// Say how many candidates each guess eliminated.
+    pub influence: bool,
+    /// Grade each guess by how many candidates it eliminated.
+    pub coach: bool,
+    /// Give hints away as wrong guesses pile up, following hints::Escalation.
+    pub auto_hints: bool,
+    /// Pay for hints from a HintBank instead of the points wallet.
+    pub earn_hints: bool,
+    /// Make the first hint of the round cost no points.
+    pub free_first_hint: bool,
+    /// Report the fastest and slowest guess at the end of the round.
+    pub timing: bool,
+    /// Ask for a confidence rating before each answer is revealed.
+    pub calibrate: bool,
+    /// Let binary search play the round instead of the player.
+    pub auto: bool,
+    /// Print only the final guess count of an --auto round (implies `auto`).
+    pub auto_quiet: bool,
+    /// Name of a strategy to evaluate instead of playing interactively.
+    pub evaluate: Option<String>,
+    /// Compare every built-in strategy instead of playing interactively.
+    pub tournament: bool,
+    /// Number of games played by `evaluate` and `tournament`.
+    pub games: u32,
+    /// A folder of replay files to grade instead of playing.
+    pub grade: Option<PathBuf>,
+    /// Where `grade` writes its CSV report, instead of the screen.
+    pub report: Option<PathBuf>,
+    /// Points for each grade when grading, instead of the default ones.
+    pub rubric: Option<PathBuf>,
+    /// Play interval mode: the player names ranges rather than single numbers.
+    pub intervals: bool,
+    /// Derive the secret from this seed instead of a random one.
+    pub seed: Option<u64>,
+    /// Redraw a secret that was one of the range's last this many.
+    pub no_repeat: Option<usize>,
+    /// Derive the secret from the date, the same for every player that day.
+    pub daily: bool,
+    /// The day whose puzzle --daily plays, instead of today (UTC).
+    pub date: Option<NaiveDate>,
+    /// Print the round's seed when it ends.
+    pub show_seed: bool,
+    /// Commit to the secret with a hash before the round and reveal it after.
+    pub commit: bool,
+    /// Where to save an unfinished round on quit.
+    pub save: Option<PathBuf>,
+    /// A saved round to continue instead of starting a new one.
+    pub resume: Option<PathBuf>,
+    /// A file every round is appended to, one record per line.
+    pub history: Option<PathBuf>,
+    /// Where the top scores, progress and recent secrets are kept.
+    pub leaderboard: PathBuf,
+    /// Draw the secret mostly from the zone `history` shows to be hardest.
+    pub practice: bool,
+    /// Print this season's (or all-time) standings instead of playing.
+    pub season: Option<SeasonView>,
+    /// How long ranked seasons last.
+    pub seasons: Seasons,
+    /// Show the all-time top scores after a win instead of the season's.
+    pub all_time: bool,
+    /// A file with an [aliases] section of short names for commands.
+    pub aliases: Option<PathBuf>,
+    /// A file to narrate the round into, one flushed line per event.
+    pub spectator_log: Option<PathBuf>,
+    /// Print the detected terminal capabilities instead of playing.
+    pub capabilities: bool,
+    /// Whether stdin and stdout are terminals, instead of detecting it.
+    pub tty: Option<bool>,
+    /// Whether the locale is UTF-8, instead of detecting it.
+    pub utf8: Option<bool>,
+    /// The terminal's width, instead of asking it.
+    pub term_width: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            difficulty: Difficulty::Medium,
+            center: None,
+            spread: None,
+            tolerance: 0,
+            tutorial: false,
+            accessible: false,
+            warmup: false,
+            words: false,
+            blind: false,
+            base: 10,
+            show_range: false,
+            symbols: None,
+            echo: true,
+            terse: false,
+            max_line_width: None,
+            bell: false,
+            celebrate: true,
+            warmer_colder: false,
+            temp_policy: TempPolicy::SameDistance,
+            invalid_input: InvalidInputPolicy::Lenient,
+            entropy: false,
+            influence: false,
+            coach: false,
+            auto_hints: false,
+            earn_hints: false,
+            free_first_hint: false,
+            timing: false,
+            calibrate: false,
+            auto: false,
+            auto_quiet: false,
+            evaluate: None,
+            tournament: false,
+            games: 1000,
+            grade: None,
+            report: None,
+            rubric: None,
+            intervals: false,
+            seed: None,
+            no_repeat: None,
+            daily: false,
+            date: None,
+            show_seed: false,
+            commit: false,
+            save: None,
+            resume: None,
+            history: None,
+            leaderboard: PathBuf::from("leaderboard.json"),
+            practice: false,
+            season: None,
+            seasons: Seasons::default(),
+            all_time: false,
+            aliases: None,
+            spectator_log: None,
+            capabilities: false,
+            tty: None,
+            utf8: None,
+            term_width: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArgError {
+    UnknownFlag(String),
+    MissingValue(&'static str),
+    InvalidValue { flag: &'static str, value: String },
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgError::UnknownFlag(flag) => write!(f, "unknown option '{flag}'"),
+            ArgError::MissingValue(flag) => write!(f, "{flag} needs a value"),
+            ArgError::InvalidValue { flag, value } => {
+                write!(f, "'{value}' is not a valid value for {flag}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+impl Config {
+    /// The range to play on: the one given by --center and --spread, or else the difficulty's.
+    pub fn range(&self) -> RangeInclusive<u32> {
+        match (self.center, self.spread) {
+            (Some(center), Some(spread)) => range_math::range_from_center(center, spread),
+            _ => self.difficulty.range(),
+        }
+    }
+
+    /// Builds a Config from the program arguments (without the program name).
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Config, ArgError> {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--difficulty" => config.difficulty = parse_value(&mut args, "--difficulty")?,
+                "--center" => config.center = Some(parse_value(&mut args, "--center")?),
+                "--spread" => config.spread = Some(parse_percent(&mut args, "--spread")?),
+                "--tolerance" => config.tolerance = parse_value(&mut args, "--tolerance")?,
+                "--tutorial" => config.tutorial = true,
+                "--accessible" => config.accessible = true,
+                "--warmup" => config.warmup = true,
+                "--words" => config.words = true,
+                "--blind" => config.blind = true,
+                "--base" => config.base = parse_base(&mut args)?,
+                "--show-range" => config.show_range = true,
+                // --ascii-symbols wins whichever order the two come in
+                "--symbols" => {
+                    config.symbols.get_or_insert(SymbolSet::Unicode);
+                }
+                "--ascii-symbols" => config.symbols = Some(SymbolSet::Ascii),
+                "--no-echo" => config.echo = false,
+                "--terse" => config.terse = true,
+                "--max-line-width" => config.max_line_width = Some(parse_line_width(&mut args)?),
+                "--bell" => config.bell = true,
+                "--no-celebration" => config.celebrate = false,
+                "--warmer-colder" => config.warmer_colder = true,
+                "--temp-policy" => config.temp_policy = parse_value(&mut args, "--temp-policy")?,
+                "--invalid-input" => {
+                    config.invalid_input = parse_value(&mut args, "--invalid-input")?
+                }
+                "--entropy" => config.entropy = true,
+                "--influence" => config.influence = true,
+                "--coach" => config.coach = true,
+                "--auto-hints" => config.auto_hints = true,
+                "--earn-hints" => config.earn_hints = true,
+                "--free-first-hint" => config.free_first_hint = true,
+                "--timing" => config.timing = true,
+                "--calibrate" => config.calibrate = true,
+                "--auto" => config.auto = true,
+                "--auto-quiet" => config.auto_quiet = true,
+                "--evaluate" => config.evaluate = Some(parse_value(&mut args, "--evaluate")?),
+                "--tournament" => config.tournament = true,
+                "--games" => config.games = parse_value(&mut args, "--games")?,
+                "--grade" => config.grade = Some(parse_value(&mut args, "--grade")?),
+                "--report" => config.report = Some(parse_value(&mut args, "--report")?),
+                "--rubric" => config.rubric = Some(parse_value(&mut args, "--rubric")?),
+                "--intervals" => config.intervals = true,
+                "--seed" => config.seed = Some(parse_value(&mut args, "--seed")?),
+                "--no-repeat" => config.no_repeat = Some(parse_no_repeat(&mut args)?),
+                "--daily" => config.daily = true,
+                "--date" => config.date = Some(parse_value(&mut args, "--date")?),
+                "--show-seed" => config.show_seed = true,
+                "--commit" => config.commit = true,
+                "--save" => config.save = Some(parse_value(&mut args, "--save")?),
+                "--resume" => config.resume = Some(parse_value(&mut args, "--resume")?),
+                "--history" => config.history = Some(parse_value(&mut args, "--history")?),
+                "--leaderboard" => config.leaderboard = parse_value(&mut args, "--leaderboard")?,
+                "--practice" => config.practice = true,
+                "--season" => config.season = Some(parse_value(&mut args, "--season")?),
+                "--season-months" => config.seasons = parse_season_months(&mut args)?,
+                "--all-time" => config.all_time = true,
+                "--aliases" => config.aliases = Some(parse_value(&mut args, "--aliases")?),
+                "--spectator-log" => {
+                    config.spectator_log = Some(parse_value(&mut args, "--spectator-log")?)
+                }
+                "--capabilities" => config.capabilities = true,
+                "--tty" => config.tty = Some(parse_value(&mut args, "--tty")?),
+                "--utf8" => config.utf8 = Some(parse_value(&mut args, "--utf8")?),
+                "--term-width" => config.term_width = Some(parse_value(&mut args, "--term-width")?),
+                _ => return Err(ArgError::UnknownFlag(arg)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// A percentage, with or without the % sign: "10%" and "10" are both 10
+fn parse_percent(
+    args: &mut impl Iterator<Item = String>,
+    flag: &'static str,
+) -> Result<u8, ArgError> {
+    let value = args.next().ok_or(ArgError::MissingValue(flag))?;
+    value
+        .strip_suffix('%')
+        .unwrap_or(&value)
+        .parse()
+        .map_err(|_| ArgError::InvalidValue { flag, value })
+}
+
+// A number base, and only one of the few the game can read and write
+fn parse_base(args: &mut impl Iterator<Item = String>) -> Result<u32, ArgError> {
+    let value = args.next().ok_or(ArgError::MissingValue("--base"))?;
+    match value.parse() {
+        Ok(base) if radix::BASES.contains(&base) => Ok(base),
+        _ => Err(ArgError::InvalidValue {
+            flag: "--base",
+            value,
+        }),
+    }
+}
+
+// A season length that Seasons accepts
+fn parse_season_months(args: &mut impl Iterator<Item = String>) -> Result<Seasons, ArgError> {
+    let value = args
+        .next()
+        .ok_or(ArgError::MissingValue("--season-months"))?;
+    match value.parse().ok().and_then(Seasons::new) {
+        Some(seasons) => Ok(seasons),
+        None => Err(ArgError::InvalidValue {
+            flag: "--season-months",
+            value,
+        }),
+    }
+}
+
+// A window of recent secrets between 1 and MAX_WINDOW
+fn parse_no_repeat(args: &mut impl Iterator<Item = String>) -> Result<usize, ArgError> {
+    let value = args.next().ok_or(ArgError::MissingValue("--no-repeat"))?;
+    match value.parse() {
+        Ok(window) if (1..=MAX_WINDOW).contains(&window) => Ok(window),
+        _ => Err(ArgError::InvalidValue {
+            flag: "--no-repeat",
+            value,
+        }),
+    }
+}
+
+// A line width of at least one column; nothing fits in 0
+fn parse_line_width(args: &mut impl Iterator<Item = String>) -> Result<usize, ArgError> {
+    let value = args
+        .next()
+        .ok_or(ArgError::MissingValue("--max-line-width"))?;
+    match value.parse() {
+        Ok(width) if width > 0 => Ok(width),
+        _ => Err(ArgError::InvalidValue {
+            flag: "--max-line-width",
+            value,
+        }),
+    }
+}
+
+// Takes the value following a flag and parses it into whatever type the caller needs
+fn parse_value<T: std::str::FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &'static str,
+) -> Result<T, ArgError> {
+    let value = args.next().ok_or(ArgError::MissingValue(flag))?;
+    value
+        .parse()
+        .map_err(|_| ArgError::InvalidValue { flag, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Config, ArgError> {
+        Config::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn max_line_width_must_be_at_least_one() {
+        assert_eq!(
+            parse(&["--max-line-width", "0"]),
+            Err(ArgError::InvalidValue {
+                flag: "--max-line-width",
+                value: "0".to_string(),
+            })
+        );
+        assert!(parse(&["--max-line-width", "-5"]).is_err());
+        assert_eq!(
+            parse(&["--max-line-width"]),
+            Err(ArgError::MissingValue("--max-line-width"))
+        );
+        assert_eq!(
+            parse(&["--max-line-width", "1"]).unwrap().max_line_width,
+            Some(1)
+        );
+    }
+}
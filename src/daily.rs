@@ -0,0 +1,157 @@
+// The daily puzzle: one secret per calendar day, the same for everyone
+// chrono's NaiveDate would be the usual date type; this module has just
+// enough of a date to turn "today in UTC" into a seed
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date with no time zone, like chrono's `NaiveDate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NaiveDate {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+fn is_leap(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl NaiveDate {
+    /// The date, or None if there is no such day (like February 30th).
+    pub fn from_ymd_opt(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+        ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day))
+            .then_some(NaiveDate { year, month, day })
+    }
+
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// The month, from 1 (January) to 12.
+    pub fn month(self) -> u32 {
+        self.month
+    }
+
+    /// Today's date in UTC, read from the system clock.
+    pub fn today_utc() -> NaiveDate {
+        let seconds = match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(since) => since.as_secs() as i64,
+            Err(before) => -(before.duration().as_secs() as i64),
+        };
+        NaiveDate::from_days_since_epoch(seconds.div_euclid(86_400))
+    }
+
+    // Both conversions follow Howard Hinnant's "days from civil" algorithms,
+    // which count in 400-year eras starting on the 1st of March,
+    // so the leap day falls at the end of each year
+
+    /// Days since 1970-01-01, negative before it.
+    pub fn days_since_epoch(self) -> i64 {
+        let year = self.year as i64 - (self.month <= 2) as i64;
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = (self.month as i64 + 9) % 12;
+        let day_of_year = (153 * month + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    pub fn from_days_since_epoch(days: i64) -> NaiveDate {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+        let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+        let year = (year_of_era + era * 400 + (month <= 2) as i64) as i32;
+        NaiveDate { year, month, day }
+    }
+}
+
+impl fmt::Display for NaiveDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parses `YYYY-MM-DD`.
+impl FromStr for NaiveDate {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<NaiveDate, ()> {
+        let mut parts = s.splitn(3, '-');
+        let mut next = || parts.next().ok_or(());
+        let (year, month, day) = (next()?, next()?, next()?);
+        NaiveDate::from_ymd_opt(
+            year.parse().map_err(|_| ())?,
+            month.parse().map_err(|_| ())?,
+            day.parse().map_err(|_| ())?,
+        )
+        .ok_or(())
+    }
+}
+
+/// The seed for `date`'s puzzle: the same on every machine and every run.
+///
+/// The day number is run through SplitMix64, so neighbouring days get
+/// unrelated seeds instead of seeds that differ by one.
+pub fn daily_seed(date: NaiveDate) -> u64 {
+    let mut z = (date.days_since_epoch() as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn the_same_date_always_has_the_same_seed() {
+        assert_eq!(
+            daily_seed(date("2026-10-14")),
+            daily_seed(date("2026-10-14"))
+        );
+        // SplitMix64's first output from a zero state, a published value
+        assert_eq!(daily_seed(date("1970-01-01")), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn different_dates_have_different_seeds() {
+        let days = [
+            "2026-10-13",
+            "2026-10-14",
+            "2026-10-15",
+            "2027-10-14",
+            "1969-12-31",
+        ];
+        let mut seeds: Vec<u64> = days.iter().map(|day| daily_seed(date(day))).collect();
+        seeds.sort();
+        seeds.dedup();
+        assert_eq!(seeds.len(), days.len());
+    }
+
+    #[test]
+    fn neighbouring_days_do_not_get_neighbouring_seeds() {
+        let today = daily_seed(date("2026-10-14"));
+        let tomorrow = daily_seed(date("2026-10-15"));
+        assert!(today.abs_diff(tomorrow) > 1 << 32);
+    }
+}
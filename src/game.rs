@@ -0,0 +1,499 @@
+// Core guessing game logic, kept free of any input/output so it can be reused
+// by every front end (the terminal game in main.rs, simulations, ...)
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// A guess that is known to lie inside the game's range.
+///
+/// The only way to build one is `Guess::new`, so an out-of-range number can
+/// never reach the comparison logic in `Game::guess`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Guess(u32);
+
+/// Error returned when a number falls outside the range of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub value: u32,
+    pub range: RangeInclusive<u32>,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} is not between {} and {}",
+            self.value,
+            self.range.start(),
+            self.range.end()
+        )
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+impl OutOfRange {
+    /// Which end of the range the number fell past.
+    pub fn bound(&self) -> Bound {
+        if self.value < *self.range.start() {
+            Bound::Low
+        } else {
+            Bound::High
+        }
+    }
+}
+
+/// One end of a game's range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Low,
+    High,
+}
+
+impl Guess {
+    pub fn new(value: u32, range: &RangeInclusive<u32>) -> Result<Guess, OutOfRange> {
+        if range.contains(&value) {
+            Ok(Guess(value))
+        } else {
+            Err(OutOfRange {
+                value,
+                range: range.clone(),
+            })
+        }
+    }
+
+    pub fn value(self) -> u32 {
+        self.0
+    }
+}
+
+/// What the game says about a single guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GuessOutcome {
+    TooSmall,
+    TooBig,
+    Correct,
+}
+
+/// How the latest guess compares with the one before it, by distance to the secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Temperature {
+    Warmer,
+    Colder,
+    Same,
+    /// As far away as last time, but on the other side of the secret.
+    JumpedOver,
+}
+
+/// What [`Game::temperature`] says when two guesses in a row are equally far
+/// from the secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TempPolicy {
+    /// Always `Same`.
+    #[default]
+    SameDistance,
+    /// `JumpedOver` when the guesses are on opposite sides, otherwise `Same`.
+    Crossover,
+}
+
+impl TempPolicy {
+    pub const ALL: [TempPolicy; 2] = [TempPolicy::SameDistance, TempPolicy::Crossover];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TempPolicy::SameDistance => "same",
+            TempPolicy::Crossover => "crossover",
+        }
+    }
+}
+
+impl FromStr for TempPolicy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<TempPolicy, ()> {
+        TempPolicy::ALL
+            .into_iter()
+            .find(|policy| policy.name() == s)
+            .ok_or(())
+    }
+}
+
+/// How far `guess` is from `secret`. Safe across the whole `u32` range, where
+/// `guess - secret` would overflow whenever the guess is the smaller one.
+pub fn distance(guess: u32, secret: u32) -> u32 {
+    guess.abs_diff(secret)
+}
+
+/// A guess that has been played, together with the game's answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Turn {
+    pub guess: u32,
+    pub outcome: GuessOutcome,
+}
+
+/// One round of the guessing game: a secret number hidden inside a range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    secret: u32,
+    range: RangeInclusive<u32>,
+    // The part of the range that is still consistent with every answer so far
+    feasible: RangeInclusive<u32>,
+    attempts: u32,
+    history: Vec<Turn>,
+    // A guess this close to the secret counts as correct; 0 means exact matches only
+    tolerance: u32,
+    // Which ends of the range the player has been told about; both, unless blind
+    revealed_low: bool,
+    revealed_high: bool,
+}
+
+/// Where a game's secret comes from. Every `rand` generator is one; tests can
+/// hand out numbers of their own instead.
+pub trait RandomSource {
+    /// A number in `range`, which is never empty.
+    fn pick(&mut self, range: &RangeInclusive<u32>) -> u32;
+}
+
+impl<R: Rng + ?Sized> RandomSource for R {
+    fn pick(&mut self, range: &RangeInclusive<u32>) -> u32 {
+        self.random_range(range.clone())
+    }
+}
+
+impl Game {
+    /// Starts a game with a random secret drawn from `range`.
+    pub fn new(range: RangeInclusive<u32>) -> Game {
+        Game::drawn(range, &mut rand::rng())
+    }
+
+    /// Starts a game whose secret is derived from `seed`, so the same seed
+    /// always hides the same number.
+    pub fn seeded(range: RangeInclusive<u32>, seed: u64) -> Game {
+        Game::drawn(range, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Starts a game whose secret is picked from `range` by `source`.
+    pub fn drawn(range: RangeInclusive<u32>, source: &mut dyn RandomSource) -> Game {
+        let secret = source.pick(&range);
+        Game::with_secret(range, secret)
+    }
+
+    /// Starts a game with a known secret.
+    ///
+    /// Panics if `secret` is outside `range`, since such a game could never be won.
+    pub fn with_secret(range: RangeInclusive<u32>, secret: u32) -> Game {
+        assert!(
+            range.contains(&secret),
+            "secret {secret} is outside the range {range:?}"
+        );
+        Game {
+            secret,
+            feasible: range.clone(),
+            range,
+            attempts: 0,
+            history: Vec::new(),
+            tolerance: 0,
+            revealed_low: true,
+            revealed_high: true,
+        }
+    }
+
+    /// Lets guesses within `tolerance` of the secret win ("close enough").
+    pub fn with_tolerance(mut self, tolerance: u32) -> Game {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Keeps the range a secret too: neither bound counts as revealed until
+    /// the player runs into it (see `reveal`).
+    pub fn blind(mut self) -> Game {
+        self.revealed_low = false;
+        self.revealed_high = false;
+        self
+    }
+
+    /// Whether the player has been told where this end of the range is.
+    pub fn is_revealed(&self, bound: Bound) -> bool {
+        match bound {
+            Bound::Low => self.revealed_low,
+            Bound::High => self.revealed_high,
+        }
+    }
+
+    /// Marks `bound` as known to the player. Returns true if it wasn't before.
+    pub fn reveal(&mut self, bound: Bound) -> bool {
+        let revealed = match bound {
+            Bound::Low => &mut self.revealed_low,
+            Bound::High => &mut self.revealed_high,
+        };
+        !std::mem::replace(revealed, true)
+    }
+
+    pub fn range(&self) -> &RangeInclusive<u32> {
+        &self.range
+    }
+
+    /// The numbers that could still be the secret given the answers so far.
+    pub fn feasible(&self) -> &RangeInclusive<u32> {
+        &self.feasible
+    }
+
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Every guess so far, oldest first.
+    pub fn history(&self) -> &[Turn] {
+        &self.history
+    }
+
+    pub fn tolerance(&self) -> u32 {
+        self.tolerance
+    }
+
+    /// Whether the latest guess got closer to the secret than the one before.
+    /// None until two guesses have been made.
+    pub fn temperature(&self, policy: TempPolicy) -> Option<Temperature> {
+        let [.., previous, latest] = self.history.as_slice() else {
+            return None;
+        };
+        let (now, before) = (
+            distance(latest.guess, self.secret),
+            distance(previous.guess, self.secret),
+        );
+        let temperature = match now.cmp(&before) {
+            std::cmp::Ordering::Less => Temperature::Warmer,
+            std::cmp::Ordering::Greater => Temperature::Colder,
+            // Equally far and not the same number means one is below and one above
+            std::cmp::Ordering::Equal
+                if policy == TempPolicy::Crossover && latest.guess != previous.guess =>
+            {
+                Temperature::JumpedOver
+            }
+            std::cmp::Ordering::Equal => Temperature::Same,
+        };
+        Some(temperature)
+    }
+
+    /// The feasible range as it stood after each turn in `history`.
+    pub fn feasible_history(&self) -> Vec<RangeInclusive<u32>> {
+        // Replaying on a fresh copy reuses the exact narrowing rules of `guess`
+        let mut replay =
+            Game::with_secret(self.range.clone(), self.secret).with_tolerance(self.tolerance);
+        self.history
+            .iter()
+            .map(|turn| {
+                replay.evaluate(turn.guess);
+                replay.feasible.clone()
+            })
+            .collect()
+    }
+
+    /// Compares a guess with the secret, counting it as one attempt.
+    ///
+    /// Any guess within the tolerance of the secret is `Correct`; outside of it
+    /// the usual too small / too big hints apply.
+    pub fn guess(&mut self, guess: Guess) -> GuessOutcome {
+        let outcome = self.evaluate(guess.value());
+        self.attempts += 1;
+        self.history.push(Turn {
+            guess: guess.value(),
+            outcome,
+        });
+        outcome
+    }
+
+    // Works out the answer and narrows the feasible range accordingly
+    fn evaluate(&mut self, value: u32) -> GuessOutcome {
+        let (low, high) = (*self.feasible.start(), *self.feasible.end());
+        if distance(value, self.secret) <= self.tolerance {
+            self.feasible = self.secret..=self.secret;
+            return GuessOutcome::Correct;
+        }
+        // Outside the tolerance the secret is more than `tolerance` away from the guess,
+        // so it must lie beyond value + tolerance (or below value - tolerance).
+        // That also means neither bound below can overflow
+        if value < self.secret {
+            self.feasible = low.max(value + self.tolerance + 1)..=high;
+            GuessOutcome::TooSmall
+        } else {
+            self.feasible = low..=high.min(value - self.tolerance - 1);
+            GuessOutcome::TooBig
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_is_zero_on_the_secret() {
+        assert_eq!(distance(0, 0), 0);
+        assert_eq!(distance(42, 42), 0);
+        assert_eq!(distance(u32::MAX, u32::MAX), 0);
+    }
+
+    #[test]
+    fn distance_is_the_same_either_way_round() {
+        assert_eq!(distance(50, 42), 8);
+        assert_eq!(distance(42, 50), 8);
+        assert_eq!(distance(u32::MAX, u32::MAX - 1), 1);
+        assert_eq!(distance(u32::MAX - 1, u32::MAX), 1);
+    }
+
+    #[test]
+    fn distance_spans_the_whole_range() {
+        assert_eq!(distance(0, u32::MAX), u32::MAX);
+        assert_eq!(distance(u32::MAX, 0), u32::MAX);
+        assert_eq!(distance(1, u32::MAX), u32::MAX - 1);
+        assert_eq!(distance(u32::MAX / 2, u32::MAX), u32::MAX / 2 + 1);
+    }
+
+    // A round on 1..=100 around 50 that lets guesses within 3 win
+    fn tolerant() -> Game {
+        Game::with_secret(1..=100, 50).with_tolerance(3)
+    }
+
+    fn guess(game: &mut Game, value: u32) -> GuessOutcome {
+        game.guess(Guess::new(value, game.range()).unwrap())
+    }
+
+    #[test]
+    fn the_secret_itself_is_correct() {
+        let mut game = tolerant();
+        assert_eq!(guess(&mut game, 50), GuessOutcome::Correct);
+        assert_eq!(*game.feasible(), 50..=50);
+    }
+
+    #[test]
+    fn guesses_within_the_tolerance_are_correct() {
+        for value in [47, 48, 52, 53] {
+            let mut game = tolerant();
+            assert_eq!(
+                guess(&mut game, value),
+                GuessOutcome::Correct,
+                "guessing {value}"
+            );
+            // Winning pins the feasible range to the secret, not the guess
+            assert_eq!(*game.feasible(), 50..=50);
+        }
+    }
+
+    #[test]
+    fn guesses_just_outside_narrow_past_the_tolerance() {
+        let mut game = tolerant();
+        // 46 is 4 away, so the secret is above 46 + 3
+        assert_eq!(guess(&mut game, 46), GuessOutcome::TooSmall);
+        assert_eq!(*game.feasible(), 50..=100);
+        let mut game = tolerant();
+        assert_eq!(guess(&mut game, 54), GuessOutcome::TooBig);
+        assert_eq!(*game.feasible(), 1..=50);
+        // Both sides together leave only the secret
+        assert_eq!(guess(&mut game, 46), GuessOutcome::TooSmall);
+        assert_eq!(*game.feasible(), 50..=50);
+        assert_eq!(game.attempts(), 2);
+    }
+
+    #[test]
+    fn without_a_tolerance_only_the_secret_wins() {
+        let mut game = Game::with_secret(1..=100, 50);
+        assert_eq!(guess(&mut game, 49), GuessOutcome::TooSmall);
+        assert_eq!(*game.feasible(), 50..=100);
+        assert_eq!(guess(&mut game, 51), GuessOutcome::TooBig);
+        assert_eq!(*game.feasible(), 50..=50);
+    }
+
+    #[test]
+    fn a_tolerance_near_the_ends_does_not_overflow() {
+        let mut game = Game::with_secret(0..=u32::MAX, u32::MAX).with_tolerance(5);
+        assert_eq!(guess(&mut game, 0), GuessOutcome::TooSmall);
+        assert_eq!(*game.feasible(), 6..=u32::MAX);
+        assert_eq!(guess(&mut game, u32::MAX - 5), GuessOutcome::Correct);
+    }
+
+    #[test]
+    fn a_blind_game_reveals_each_bound_once() {
+        let mut game = Game::with_secret(1..=100, 50).blind();
+        assert!(!game.is_revealed(Bound::Low));
+        assert!(!game.is_revealed(Bound::High));
+        let err = Guess::new(101, game.range()).unwrap_err();
+        assert_eq!(err.bound(), Bound::High);
+        assert!(game.reveal(err.bound()));
+        assert!(!game.reveal(Bound::High));
+        assert!(!game.is_revealed(Bound::Low));
+        assert_eq!(Guess::new(0, game.range()).unwrap_err().bound(), Bound::Low);
+        assert!(game.reveal(Bound::Low));
+        assert!(game.is_revealed(Bound::Low));
+    }
+
+    #[test]
+    fn a_game_that_is_not_blind_starts_with_both_bounds_known() {
+        let mut game = Game::with_secret(1..=100, 50);
+        assert!(game.is_revealed(Bound::Low) && game.is_revealed(Bound::High));
+        assert!(!game.reveal(Bound::Low));
+    }
+
+    fn temperature_after(guesses: &[u32], policy: TempPolicy) -> Option<Temperature> {
+        let mut game = Game::with_secret(1..=100, 50);
+        for &value in guesses {
+            guess(&mut game, value);
+        }
+        game.temperature(policy)
+    }
+
+    #[test]
+    fn temperature_under_each_policy() {
+        use Temperature::*;
+        // (guesses, under SameDistance, under Crossover)
+        let cases: [(&[u32], Option<Temperature>, Option<Temperature>); 7] = [
+            (&[], None, None),
+            (&[40], None, None),
+            // The same number twice is the same distance on the same side
+            (&[40, 40], Some(Same), Some(Same)),
+            (&[40, 60], Some(Same), Some(JumpedOver)),
+            (&[60, 40], Some(Same), Some(JumpedOver)),
+            (&[30, 40], Some(Warmer), Some(Warmer)),
+            (&[40, 30], Some(Colder), Some(Colder)),
+        ];
+        for (guesses, same, crossover) in cases {
+            assert_eq!(
+                temperature_after(guesses, TempPolicy::SameDistance),
+                same,
+                "{guesses:?}"
+            );
+            assert_eq!(
+                temperature_after(guesses, TempPolicy::Crossover),
+                crossover,
+                "{guesses:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn crossing_the_secret_is_still_warmer_or_colder_by_distance() {
+        for policy in TempPolicy::ALL {
+            assert_eq!(
+                temperature_after(&[30, 55], policy),
+                Some(Temperature::Warmer)
+            );
+            assert_eq!(
+                temperature_after(&[45, 70], policy),
+                Some(Temperature::Colder)
+            );
+            // Only the last two guesses count
+            assert_eq!(
+                temperature_after(&[49, 10, 20], policy),
+                Some(Temperature::Warmer)
+            );
+        }
+    }
+}
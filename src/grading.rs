@@ -0,0 +1,585 @@
+// Grading a folder of replay files in one go, for a teacher with a class
+// A replay file is what --history writes: one record per line, under a
+// persisted_file header. Each file is checked against its header, then every
+// round is replayed against the engine with replay_check before it is graded.
+// A bad file or round is written into the report rather than stopping the
+// run, so one broken file can't hide the rest of the class
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::analysis;
+use crate::game::GuessOutcome;
+use crate::persisted_file::{self, IntegrityError};
+use crate::record::{GameRecord, RecordOutcome};
+use crate::weakness::HISTORY_KIND;
+
+/// The efficiency grades, best first (see `analysis::efficiency_grade`).
+pub const GRADES: [char; 4] = ['A', 'B', 'C', 'D'];
+
+/// Points for each grade. A round that wasn't won scores nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rubric {
+    // One per grade in GRADES
+    points: [u32; GRADES.len()],
+}
+
+impl Default for Rubric {
+    /// 4 points for an A down to 1 for a D.
+    fn default() -> Rubric {
+        Rubric {
+            points: [4, 3, 2, 1],
+        }
+    }
+}
+
+/// A problem with the rubric file, with the line it is on (counting from 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RubricError {
+    /// Not `grade = points`, a [section] or a comment.
+    Syntax {
+        line: usize,
+    },
+    UnknownGrade {
+        line: usize,
+        grade: String,
+    },
+    Points {
+        line: usize,
+        value: String,
+    },
+}
+
+impl fmt::Display for RubricError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RubricError::Syntax { line } => write!(f, "line {line}: expected 'grade = points'"),
+            RubricError::UnknownGrade { line, grade } => write!(
+                f,
+                "line {line}: '{grade}' is not a grade (expected A, B, C or D)"
+            ),
+            RubricError::Points { line, value } => {
+                write!(f, "line {line}: '{value}' is not a whole number of points")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RubricError {}
+
+impl Rubric {
+    /// Reads points written like
+    ///
+    /// ```text
+    /// [rubric]
+    /// A = 10
+    /// B = 7
+    /// ```
+    ///
+    /// in the same subset of TOML as the alias file. Grades the file leaves
+    /// out are worth nothing.
+    pub fn parse(text: &str) -> Result<Rubric, RubricError> {
+        let mut points = [0; GRADES.len()];
+        let mut in_rubric = false;
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                in_rubric = section.trim() == "rubric";
+                continue;
+            }
+            if !in_rubric {
+                continue;
+            }
+            let (grade, value) = line
+                .split_once('=')
+                .ok_or(RubricError::Syntax { line: line_number })?;
+            let (grade, value) = (grade.trim().trim_matches('"'), value.trim());
+            let index = GRADES
+                .iter()
+                .position(|g| grade == g.to_string())
+                .ok_or_else(|| RubricError::UnknownGrade {
+                    line: line_number,
+                    grade: grade.to_string(),
+                })?;
+            points[index] = value.parse().map_err(|_| RubricError::Points {
+                line: line_number,
+                value: value.to_string(),
+            })?;
+        }
+        Ok(Rubric { points })
+    }
+
+    /// Reads a rubric file; see [`Rubric::parse`] for the layout.
+    pub fn load(path: &Path) -> io::Result<Rubric> {
+        let text = fs::read_to_string(path)?;
+        Rubric::parse(&text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+    }
+
+    /// The points `grade` is worth; 0 for anything that isn't a grade.
+    pub fn points(&self, grade: char) -> u32 {
+        GRADES
+            .iter()
+            .position(|&g| g == grade)
+            .map_or(0, |index| self.points[index])
+    }
+}
+
+/// One round that replayed cleanly, with its marks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GradedRound {
+    pub record: GameRecord,
+    /// Turns binary search would have needed for this secret.
+    pub optimal: u32,
+    /// The efficiency grade, for a won round.
+    pub grade: Option<char>,
+    pub points: u32,
+    /// Guesses at numbers that earlier answers had already ruled out.
+    pub wasted: u32,
+}
+
+impl GradedRound {
+    /// Total thinking time, when the record has one per turn.
+    pub fn total_time(&self) -> Option<Duration> {
+        (!self.record.durations.is_empty()).then(|| self.record.durations.iter().sum())
+    }
+
+    pub fn mean_time(&self) -> Option<Duration> {
+        self.total_time()
+            .map(|total| total / self.record.durations.len() as u32)
+    }
+
+    pub fn slowest_time(&self) -> Option<Duration> {
+        self.record.durations.iter().max().copied()
+    }
+}
+
+/// Replays `record` and grades it, or says why it doesn't replay.
+pub fn grade_record(record: GameRecord, rubric: &Rubric) -> Result<GradedRound, String> {
+    let game = record.replay_check().map_err(|err| err.to_string())?;
+    let optimal = analysis::optimal_guesses(&record.range, record.secret).len() as u32;
+    let grade = (record.outcome == RecordOutcome::Won)
+        .then(|| analysis::round_grade(&record.range, game.attempts()).letter());
+    // The candidates before each turn: the whole range, then what each answer left
+    let before = std::iter::once(record.range.clone()).chain(game.feasible_history());
+    let wasted = record
+        .turns
+        .iter()
+        .zip(before)
+        .filter(|(turn, feasible)| {
+            turn.outcome != GuessOutcome::Correct && !feasible.contains(&turn.guess)
+        })
+        .count() as u32;
+    Ok(GradedRound {
+        optimal,
+        points: grade.map_or(0, |grade| rubric.points(grade)),
+        grade,
+        wasted,
+        record,
+    })
+}
+
+/// Whether a whole file can be trusted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStatus {
+    /// Every round replayed cleanly.
+    Pass,
+    /// The file doesn't match its header, so none of it is graded.
+    Tampered(IntegrityError),
+    /// Some rounds didn't read or replay; the rest are still graded.
+    Invalid,
+    /// The file couldn't be read at all.
+    Unreadable(String),
+}
+
+impl FileStatus {
+    /// The status column of the report.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FileStatus::Pass => "pass",
+            FileStatus::Tampered(_) => "tampered",
+            FileStatus::Invalid => "invalid",
+            FileStatus::Unreadable(_) => "unreadable",
+        }
+    }
+}
+
+/// Everything found in one replay file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    /// Each round with its line in the file (counting from 1).
+    pub rounds: Vec<(usize, Result<GradedRound, String>)>,
+}
+
+impl FileReport {
+    pub fn points(&self) -> u32 {
+        self.rounds
+            .iter()
+            .filter_map(|(_, round)| round.as_ref().ok())
+            .map(|round| round.points)
+            .sum()
+    }
+}
+
+/// Checks and grades every round in one replay file.
+pub fn grade_file(path: &Path, rubric: &Rubric) -> FileReport {
+    let report = |status, rounds| FileReport {
+        path: path.to_path_buf(),
+        status,
+        rounds,
+    };
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) => return report(FileStatus::Unreadable(err.to_string()), Vec::new()),
+    };
+    let payload = match persisted_file::decode(HISTORY_KIND, &text) {
+        Ok(payload) => payload,
+        Err(err) => return report(FileStatus::Tampered(err), Vec::new()),
+    };
+    // The header is line 1 of the file when there is one
+    let header_lines = (payload.len() < text.len()) as usize;
+    let rounds: Vec<(usize, Result<GradedRound, String>)> = payload
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let round = GameRecord::from_json(line)
+                .map_err(|err| err.to_string())
+                .and_then(|record| grade_record(record, rubric));
+            (i + 1 + header_lines, round)
+        })
+        .collect();
+    let status = if rounds.iter().all(|(_, round)| round.is_ok()) {
+        FileStatus::Pass
+    } else {
+        FileStatus::Invalid
+    };
+    report(status, rounds)
+}
+
+/// Grades every file directly inside `dir`, in name order. Backups that
+/// persisted_file leaves next to the files are skipped. Only failing to read
+/// `dir` itself is an error; a bad file goes into its own report.
+pub fn grade_dir(dir: &Path, rubric: &Rubric) -> io::Result<Vec<FileReport>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let is_backup = path.extension().is_some_and(|ext| ext == "bak");
+        if entry.file_type()?.is_file() && !is_backup {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths.iter().map(|path| grade_file(path, rubric)).collect())
+}
+
+/// The report columns, in order.
+pub const CSV_HEADER: &str = "file,line,status,error,range,secret,outcome,attempts,optimal,grade,points,wasted,total_ms,mean_ms,slowest_ms";
+
+/// The reports as CSV: one row per round, or one row for a file with no
+/// rounds to show, with `CSV_HEADER` first.
+pub fn to_csv(reports: &[FileReport]) -> String {
+    let mut out = format!("{CSV_HEADER}\n");
+    for report in reports {
+        let file = report.path.display().to_string();
+        let file_error = match &report.status {
+            FileStatus::Tampered(err) => Some(err.to_string()),
+            FileStatus::Unreadable(err) => Some(err.clone()),
+            FileStatus::Pass | FileStatus::Invalid => None,
+        };
+        if report.rounds.is_empty() {
+            let status = report.status.name();
+            let error = file_error.unwrap_or_else(|| "no rounds".to_string());
+            push_row(&mut out, &[&file, "", status, &error]);
+            continue;
+        }
+        for (line, round) in &report.rounds {
+            let line = line.to_string();
+            match round {
+                Err(err) => push_row(&mut out, &[&file, &line, "invalid", err]),
+                Ok(round) => {
+                    let record = &round.record;
+                    let ms = |time: Option<Duration>| {
+                        time.map_or(String::new(), |time| time.as_millis().to_string())
+                    };
+                    let outcome = match record.outcome {
+                        RecordOutcome::Won => "won",
+                        RecordOutcome::Unfinished => "unfinished",
+                    };
+                    push_row(
+                        &mut out,
+                        &[
+                            &file,
+                            &line,
+                            "pass",
+                            "",
+                            &format!("{}-{}", record.range.start(), record.range.end()),
+                            &record.secret.to_string(),
+                            outcome,
+                            &record.turns.len().to_string(),
+                            &round.optimal.to_string(),
+                            &round.grade.map_or(String::new(), String::from),
+                            &round.points.to_string(),
+                            &round.wasted.to_string(),
+                            &ms(round.total_time()),
+                            &ms(round.mean_time()),
+                            &ms(round.slowest_time()),
+                        ],
+                    );
+                }
+            }
+        }
+    }
+    out
+}
+
+// Adds one row, padding short rows with empty fields to the header's width
+fn push_row(out: &mut String, fields: &[&str]) {
+    let columns = CSV_HEADER.split(',').count();
+    let row: Vec<String> = (0..columns)
+        .map(|i| csv_field(fields.get(i).copied().unwrap_or("")))
+        .collect();
+    out.push_str(&row.join(","));
+    out.push('\n');
+}
+
+// A field in quotes, with its own quotes doubled, if it needs them
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{Game, Guess};
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> TempDir {
+            let path = std::env::temp_dir().join(format!("grading-{}-{test}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        // A replay file as --history writes it, one record per line
+        fn write(&self, name: &str, records: &[GameRecord]) -> PathBuf {
+            let payload: String = records.iter().map(|r| r.to_json() + "\n").collect();
+            let path = self.0.join(name);
+            fs::write(&path, persisted_file::encode(HISTORY_KIND, &payload)).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn round(secret: u32, guesses: &[u32]) -> GameRecord {
+        let mut game = Game::with_secret(1..=100, secret);
+        for &value in guesses {
+            game.guess(Guess::new(value, game.range()).unwrap());
+        }
+        game.into_record()
+    }
+
+    // A round whose answers don't match its secret, as if edited by hand
+    fn lying_round() -> GameRecord {
+        let mut record = round(42, &[50, 25, 42]);
+        record.turns[0].outcome = GuessOutcome::TooSmall;
+        record
+    }
+
+    #[test]
+    fn the_default_rubric_is_four_down_to_one() {
+        let rubric = Rubric::default();
+        let points: Vec<u32> = GRADES.iter().map(|&g| rubric.points(g)).collect();
+        assert_eq!(points, [4, 3, 2, 1]);
+        assert_eq!(rubric.points('E'), 0);
+    }
+
+    #[test]
+    fn a_rubric_file_sets_points_and_leaves_others_at_zero() {
+        let rubric =
+            Rubric::parse("# marks\n[other]\nA = 99\n[rubric]\nA = 10\n\"B\" = 7\n").unwrap();
+        assert_eq!(rubric.points('A'), 10);
+        assert_eq!(rubric.points('B'), 7);
+        assert_eq!(rubric.points('C'), 0);
+        assert_eq!(rubric.points('D'), 0);
+    }
+
+    #[test]
+    fn rubric_errors_name_their_line() {
+        assert_eq!(
+            Rubric::parse("[rubric]\nA 10"),
+            Err(RubricError::Syntax { line: 2 })
+        );
+        assert_eq!(
+            Rubric::parse("[rubric]\n\nF = 0"),
+            Err(RubricError::UnknownGrade {
+                line: 3,
+                grade: "F".to_string()
+            })
+        );
+        let err = Rubric::parse("[rubric]\nA = ten").unwrap_err();
+        assert_eq!(
+            err,
+            RubricError::Points {
+                line: 2,
+                value: "ten".to_string()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "line 2: 'ten' is not a whole number of points"
+        );
+    }
+
+    #[test]
+    fn a_won_round_is_graded_with_the_rubric() {
+        let rubric = Rubric::parse("[rubric]\nA = 10\nC = 5").unwrap();
+        // Seven guesses is the most binary search needs on 1..=100
+        let graded = grade_record(round(42, &[50, 25, 42]), &rubric).unwrap();
+        assert_eq!(
+            (graded.grade, graded.points, graded.wasted),
+            (Some('A'), 10, 0)
+        );
+        assert_eq!(graded.optimal, 7);
+
+        let slow = round(42, &(31..=42).collect::<Vec<_>>());
+        let graded = grade_record(slow, &rubric).unwrap();
+        assert_eq!((graded.grade, graded.points), (Some('C'), 5));
+    }
+
+    #[test]
+    fn guesses_already_ruled_out_are_wasted() {
+        // After "50 is too big", 60 and 70 can't be it
+        let graded = grade_record(round(42, &[50, 60, 70, 42]), &Rubric::default()).unwrap();
+        assert_eq!(graded.wasted, 2);
+    }
+
+    #[test]
+    fn an_unfinished_round_scores_nothing() {
+        let graded = grade_record(round(42, &[50, 25]), &Rubric::default()).unwrap();
+        assert_eq!((graded.grade, graded.points), (None, 0));
+    }
+
+    #[test]
+    fn a_round_that_does_not_replay_is_rejected() {
+        assert!(grade_record(lying_round(), &Rubric::default()).is_err());
+    }
+
+    #[test]
+    fn a_clean_file_passes_with_line_numbers_after_the_header() {
+        let dir = TempDir::new("clean");
+        let path = dir.write("ann.hist", &[round(42, &[50, 25, 42]), round(7, &[50, 7])]);
+        let report = grade_file(&path, &Rubric::default());
+        assert_eq!(report.status, FileStatus::Pass);
+        let lines: Vec<usize> = report.rounds.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [2, 3]);
+        assert_eq!(report.points(), 8);
+    }
+
+    #[test]
+    fn an_edited_replay_file_is_tampered_and_not_graded() {
+        let dir = TempDir::new("tampered");
+        let path = dir.write("bob.hist", &[round(42, &[50, 25, 42])]);
+        // Change the secret after the checksum was taken
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, text.replace("\"secret\":42", "\"secret\":43")).unwrap();
+        let report = grade_file(&path, &Rubric::default());
+        assert_eq!(
+            report.status,
+            FileStatus::Tampered(IntegrityError::Checksum)
+        );
+        assert!(report.rounds.is_empty());
+    }
+
+    #[test]
+    fn bad_rounds_make_a_file_invalid_but_the_rest_are_graded() {
+        let dir = TempDir::new("invalid");
+        let path = dir.write("cat.hist", &[round(42, &[50, 25, 42]), lying_round()]);
+        let report = grade_file(&path, &Rubric::default());
+        assert_eq!(report.status, FileStatus::Invalid);
+        assert!(report.rounds[0].1.is_ok());
+        assert!(report.rounds[1].1.is_err());
+        assert_eq!(report.points(), 4);
+    }
+
+    #[test]
+    fn a_missing_file_is_unreadable() {
+        let dir = TempDir::new("missing");
+        let report = grade_file(&dir.0.join("nobody.hist"), &Rubric::default());
+        assert_eq!(report.status.name(), "unreadable");
+    }
+
+    #[test]
+    fn a_folder_is_graded_in_name_order_without_backups_or_subfolders() {
+        let dir = TempDir::new("walk");
+        dir.write("b.hist", &[round(42, &[42])]);
+        dir.write("a.hist", &[round(42, &[42])]);
+        dir.write("a.hist.bak", &[round(42, &[42])]);
+        fs::create_dir(dir.0.join("c")).unwrap();
+        let reports = grade_dir(&dir.0, &Rubric::default()).unwrap();
+        let names: Vec<_> = reports
+            .iter()
+            .map(|report| report.path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["a.hist", "b.hist"]);
+    }
+
+    #[test]
+    fn a_missing_folder_is_an_error() {
+        let dir = TempDir::new("no-folder");
+        assert!(grade_dir(&dir.0.join("absent"), &Rubric::default()).is_err());
+    }
+
+    #[test]
+    fn the_report_has_a_row_per_round_and_per_bad_file() {
+        let dir = TempDir::new("csv");
+        let good = dir.write("a.hist", &[round(42, &[50, 25, 42])]);
+        let bad = dir.write("b.hist", &[round(42, &[42])]);
+        let text = fs::read_to_string(&bad).unwrap();
+        fs::write(&bad, text.replace("\"secret\":42", "\"secret\":43")).unwrap();
+        let csv = to_csv(&grade_dir(&dir.0, &Rubric::default()).unwrap());
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows[0], CSV_HEADER);
+        assert_eq!(
+            rows[1],
+            format!("{},2,pass,,1-100,42,won,3,7,A,4,0,,,", good.display())
+        );
+        assert_eq!(
+            rows[2],
+            format!(
+                "{},,tampered,its contents don't match their checksum,,,,,,,,,,,",
+                bad.display()
+            )
+        );
+        assert_eq!(rows.len(), 3);
+        let columns = CSV_HEADER.split(',').count();
+        assert!(rows.iter().all(|row| row.split(',').count() == columns));
+    }
+
+    #[test]
+    fn fields_with_commas_or_quotes_are_quoted() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,362 @@
+// Hints the player can buy with points from their Wallet, or spend from a
+// HintBank earned by guessing well
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::analysis;
+use crate::game::Game;
+use crate::property_hints::PropertyHints;
+use crate::range_math;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HintKind {
+    /// Whether the secret is even or odd.
+    Parity,
+    /// Which third of the full range the secret is in.
+    Zone,
+    /// The middle of the numbers that are still possible.
+    Midpoint,
+    /// A property of the secret, such as its digit sum (see property_hints).
+    Property,
+}
+
+impl HintKind {
+    pub const ALL: [HintKind; 4] = [
+        HintKind::Parity,
+        HintKind::Zone,
+        HintKind::Midpoint,
+        HintKind::Property,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HintKind::Parity => "parity",
+            HintKind::Zone => "zone",
+            HintKind::Midpoint => "midpoint",
+            HintKind::Property => "property",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<HintKind> {
+        HintKind::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// Points charged for this hint; more useful hints cost more.
+    pub fn cost(self) -> u32 {
+        match self {
+            HintKind::Parity | HintKind::Property => 2,
+            HintKind::Zone => 3,
+            HintKind::Midpoint => 5,
+        }
+    }
+
+    /// What the hint says. A Property hint here is the first one a round would
+    /// get; to avoid repeating one, choose it through a round's PropertyHints.
+    pub fn text(self, game: &Game) -> String {
+        match self {
+            HintKind::Parity => {
+                if game.secret().is_multiple_of(2) {
+                    "The number is even.".to_string()
+                } else {
+                    "The number is odd.".to_string()
+                }
+            }
+            HintKind::Zone => {
+                // offset * 3 / width splits any width into three near-equal
+                // parts, even one too narrow for a whole third
+                let width = range_math::width(game.range());
+                let offset = (game.secret() - game.range().start()) as u64;
+                let zone = match offset * 3 / width {
+                    0 => "lower",
+                    1 => "middle",
+                    _ => "upper",
+                };
+                format!("The number is in the {zone} third of the range.")
+            }
+            HintKind::Midpoint => {
+                let feasible = game.feasible();
+                let (low, high) = (*feasible.start(), *feasible.end());
+                format!(
+                    "Try {}, halfway between {low} and {high}.",
+                    range_math::midpoint(feasible)
+                )
+            }
+            HintKind::Property => match PropertyHints::new().choose(game.feasible(), game.secret())
+            {
+                Ok(hint) => hint.to_string(),
+                Err(err) => format!("There is no property hint: {err}."),
+            },
+        }
+    }
+}
+
+/// After how many wrong guesses --auto-hints gives each hint away for free.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escalation {
+    steps: Vec<(u32, HintKind)>,
+}
+
+impl Default for Escalation {
+    /// Parity after 3 wrong guesses, the zone after 5, the midpoint after 7.
+    fn default() -> Escalation {
+        Escalation::new([
+            (3, HintKind::Parity),
+            (5, HintKind::Zone),
+            (7, HintKind::Midpoint),
+        ])
+    }
+}
+
+impl Escalation {
+    /// Gives each hint once its number of wrong guesses is reached.
+    pub fn new(steps: impl IntoIterator<Item = (u32, HintKind)>) -> Escalation {
+        Escalation {
+            steps: steps.into_iter().collect(),
+        }
+    }
+
+    /// The hint due right after the `attempt`-th wrong guess, if any.
+    pub fn hint_for_attempt(&self, attempt: u32) -> Option<HintKind> {
+        self.steps
+            .iter()
+            .find(|&&(after, _)| after == attempt)
+            .map(|&(_, kind)| kind)
+    }
+}
+
+/// The hint due after `attempt` wrong guesses on the default Escalation.
+pub fn hint_for_attempt(attempt: u32) -> Option<HintKind> {
+    Escalation::default().hint_for_attempt(attempt)
+}
+
+/// A guess earns a hint when it rules out at least this share of the candidates.
+pub const EARN_PERCENT: f64 = 50.0;
+
+/// Hints as a resource: one to start with, one more for every good guess.
+///
+/// Undoing a guess (see [`HintBank::undo`]) revokes the hint it earned, but
+/// never refunds one that was spent: the player has already read it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HintBank {
+    balance: u32,
+    // Revoked hints that had already been spent, paid back by the next earns
+    owed: u32,
+    // Whether each guess passed to earn, in order, earned a hint
+    earned: Vec<bool>,
+}
+
+/// Error returned when asking for a hint with an empty HintBank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoHintsLeft;
+
+impl fmt::Display for NoHintsLeft {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "you have no hints left; a guess that rules out at least half of the remaining numbers earns one"
+        )
+    }
+}
+
+impl std::error::Error for NoHintsLeft {}
+
+impl Default for HintBank {
+    fn default() -> HintBank {
+        HintBank {
+            balance: 1,
+            owed: 0,
+            earned: Vec::new(),
+        }
+    }
+}
+
+impl HintBank {
+    pub fn new() -> HintBank {
+        HintBank::default()
+    }
+
+    pub fn balance(&self) -> u32 {
+        self.balance
+    }
+
+    /// Credits a hint if the guess that narrowed `before` to `after` was good
+    /// enough, using the same elimination math as the coach. Returns whether it
+    /// was; a hint still owed from an undo is paid back first.
+    pub fn earn(&mut self, before: &RangeInclusive<u32>, after: &RangeInclusive<u32>) -> bool {
+        let earned = analysis::percent_eliminated(before, after) >= EARN_PERCENT;
+        self.earned.push(earned);
+        if earned {
+            match self.owed.checked_sub(1) {
+                Some(owed) => self.owed = owed,
+                None => self.balance += 1,
+            }
+        }
+        earned
+    }
+
+    /// Takes back the latest guess passed to `earn`. The hint it earned, if
+    /// any, is revoked, or owed when it has already been spent, so undoing and
+    /// replaying a good guess can't mint hints. Returns whether one was revoked.
+    pub fn undo(&mut self) -> bool {
+        let earned = self.earned.pop().unwrap_or(false);
+        if earned {
+            match self.balance.checked_sub(1) {
+                Some(balance) => self.balance = balance,
+                None => self.owed += 1,
+            }
+        }
+        earned
+    }
+
+    pub fn spend(&mut self) -> Result<(), NoHintsLeft> {
+        self.balance = self.balance.checked_sub(1).ok_or(NoHintsLeft)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(range: RangeInclusive<u32>, secret: u32) -> String {
+        HintKind::Zone.text(&Game::with_secret(range, secret))
+    }
+
+    #[test]
+    fn zones_split_the_range_into_thirds() {
+        assert_eq!(
+            zone(1..=90, 1),
+            "The number is in the lower third of the range."
+        );
+        assert_eq!(
+            zone(1..=90, 30),
+            "The number is in the lower third of the range."
+        );
+        assert_eq!(
+            zone(1..=90, 31),
+            "The number is in the middle third of the range."
+        );
+        assert_eq!(
+            zone(1..=90, 60),
+            "The number is in the middle third of the range."
+        );
+        assert_eq!(
+            zone(1..=90, 61),
+            "The number is in the upper third of the range."
+        );
+        assert_eq!(
+            zone(1..=90, 90),
+            "The number is in the upper third of the range."
+        );
+    }
+
+    #[test]
+    fn narrow_ranges_still_have_a_lower_zone() {
+        assert!(zone(1..=2, 1).contains("lower"));
+        assert!(zone(1..=2, 2).contains("middle"));
+        assert!(zone(5..=5, 5).contains("lower"));
+    }
+
+    #[test]
+    fn a_bank_starts_with_one_hint() {
+        let mut bank = HintBank::new();
+        assert_eq!(bank.spend(), Ok(()));
+        assert_eq!(bank.spend(), Err(NoHintsLeft));
+        assert_eq!(bank.balance(), 0);
+    }
+
+    #[test]
+    fn ruling_out_exactly_half_earns_a_hint() {
+        let mut bank = HintBank::new();
+        // 1..=100 down to 51..=100 is exactly 50%
+        assert!(bank.earn(&(1..=100), &(51..=100)));
+        assert_eq!(bank.balance(), 2);
+        // One fewer is 49%
+        assert!(!bank.earn(&(51..=100), &(50..=100)));
+        assert!(!bank.earn(&(1..=100), &(50..=100)));
+        assert_eq!(bank.balance(), 2);
+    }
+
+    #[test]
+    fn every_good_guess_in_a_round_earns() {
+        let mut bank = HintBank::new();
+        for (before, after) in [(1..=100, 1..=49), (1..=49, 26..=49), (26..=49, 26..=37)] {
+            assert!(bank.earn(&before, &after));
+        }
+        assert_eq!(bank.balance(), 4);
+    }
+
+    #[test]
+    fn undo_revokes_the_hint_a_guess_earned() {
+        let mut bank = HintBank::new();
+        bank.earn(&(1..=100), &(1..=49));
+        bank.earn(&(1..=49), &(1..=48));
+        // The poor guess earned nothing, so undoing it takes nothing
+        assert!(!bank.undo());
+        assert_eq!(bank.balance(), 2);
+        assert!(bank.undo());
+        assert_eq!(bank.balance(), 1);
+        // Nothing left to undo
+        assert!(!bank.undo());
+        assert_eq!(bank.balance(), 1);
+    }
+
+    #[test]
+    fn undo_does_not_refund_a_spent_hint() {
+        let mut bank = HintBank::new();
+        bank.earn(&(1..=100), &(1..=49));
+        bank.spend().unwrap();
+        bank.spend().unwrap();
+        // The earned hint was spent, so it is owed
+        assert!(bank.undo());
+        assert_eq!(bank.balance(), 0);
+        // Replaying the good guess pays the debt instead of crediting
+        assert!(bank.earn(&(1..=100), &(1..=49)));
+        assert_eq!(bank.balance(), 0);
+        assert!(bank.earn(&(1..=49), &(1..=24)));
+        assert_eq!(bank.balance(), 1);
+    }
+
+    #[test]
+    fn the_full_range_does_not_overflow() {
+        assert!(zone(0..=u32::MAX, 0).contains("lower"));
+        assert!(zone(0..=u32::MAX, u32::MAX / 2).contains("middle"));
+        assert!(zone(0..=u32::MAX, u32::MAX).contains("upper"));
+    }
+
+    #[test]
+    fn default_hints_escalate_at_three_five_and_seven() {
+        let given: Vec<(u32, HintKind)> = (0..=20)
+            .filter_map(|attempt| hint_for_attempt(attempt).map(|kind| (attempt, kind)))
+            .collect();
+        assert_eq!(
+            given,
+            [
+                (3, HintKind::Parity),
+                (5, HintKind::Zone),
+                (7, HintKind::Midpoint)
+            ]
+        );
+    }
+
+    #[test]
+    fn counts_between_the_thresholds_get_no_hint() {
+        for attempt in [0, 1, 2, 4, 6, 8, 100] {
+            assert_eq!(
+                hint_for_attempt(attempt),
+                None,
+                "after {attempt} wrong guesses"
+            );
+        }
+    }
+
+    #[test]
+    fn a_custom_escalation_uses_its_own_steps() {
+        let escalation = Escalation::new([(1, HintKind::Midpoint), (2, HintKind::Property)]);
+        assert_eq!(escalation.hint_for_attempt(1), Some(HintKind::Midpoint));
+        assert_eq!(escalation.hint_for_attempt(2), Some(HintKind::Property));
+        assert_eq!(escalation.hint_for_attempt(3), None);
+        assert_eq!(Escalation::new([]).hint_for_attempt(3), None);
+    }
+}
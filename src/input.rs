@@ -0,0 +1,53 @@
+// Where the game's lines of input come from
+// The game (src/app.rs) reads through this trait instead of calling stdin
+// directly, so a round can be played from a script and its output captured
+// in a buffer
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+/// A source of input lines, one per prompt.
+pub trait InputSource {
+    /// The next line, trimmed, or None at the end of input.
+    fn read_line(&mut self) -> io::Result<Option<String>>;
+}
+
+/// Reads lines from standard input.
+#[derive(Debug, Default)]
+pub struct StdinInput;
+
+impl InputSource for StdinInput {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        let bytes_read = io::stdin().lock().read_line(&mut line)?;
+        Ok((bytes_read > 0).then(|| line.trim().to_string()))
+    }
+}
+
+/// Hands out a fixed list of lines, then reports the end of input.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedInput {
+    lines: VecDeque<String>,
+}
+
+impl ScriptedInput {
+    pub fn new<I, S>(lines: I) -> ScriptedInput
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        ScriptedInput {
+            lines: lines.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// How many lines haven't been read yet.
+    pub fn remaining(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self.lines.pop_front().map(|line| line.trim().to_string()))
+    }
+}
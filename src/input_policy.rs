@@ -0,0 +1,224 @@
+// What to do when a line of input is neither a guess nor a command
+// Patient re-prompting suits children, a scripted run wants every bad line on
+// record, and a classroom may want three strikes to end the round
+use std::fmt;
+use std::str::FromStr;
+
+/// How invalid input is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidInputPolicy {
+    /// Ask again, with more help each time it happens in a row.
+    #[default]
+    Lenient,
+    /// Ask again, but this many invalid lines in a row end the round.
+    Strict { strikes: u32 },
+    /// Ask again, and count every invalid line as a failure for the round summary.
+    Counted,
+}
+
+/// Strict mode's strikes when none are given.
+pub const DEFAULT_STRIKES: u32 = 3;
+
+impl fmt::Display for InvalidInputPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidInputPolicy::Lenient => write!(f, "lenient"),
+            InvalidInputPolicy::Strict { strikes } => write!(f, "strict:{strikes}"),
+            InvalidInputPolicy::Counted => write!(f, "counted"),
+        }
+    }
+}
+
+/// Parses "lenient", "counted", "strict" or "strict:N".
+impl FromStr for InvalidInputPolicy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<InvalidInputPolicy, ()> {
+        match s.split_once(':') {
+            None if s == "lenient" => Ok(InvalidInputPolicy::Lenient),
+            None if s == "counted" => Ok(InvalidInputPolicy::Counted),
+            None if s == "strict" => Ok(InvalidInputPolicy::Strict {
+                strikes: DEFAULT_STRIKES,
+            }),
+            Some(("strict", n)) => match n.parse() {
+                Ok(strikes) if strikes > 0 => Ok(InvalidInputPolicy::Strict { strikes }),
+                _ => Err(()),
+            },
+            _ => Err(()),
+        }
+    }
+}
+
+/// What to do about one invalid line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// Say this and ask again.
+    Reprompt(String),
+    /// Say this and end the round.
+    EndRound(String),
+}
+
+/// Applies a policy to a stream of input, one line at a time.
+#[derive(Debug, Clone)]
+pub struct InputGuard {
+    policy: InvalidInputPolicy,
+    in_a_row: u32,
+    failures: u32,
+}
+
+impl InputGuard {
+    pub fn new(policy: InvalidInputPolicy) -> InputGuard {
+        InputGuard {
+            policy,
+            in_a_row: 0,
+            failures: 0,
+        }
+    }
+
+    /// A line was understood: the run of invalid lines, and any strikes, start over.
+    pub fn valid(&mut self) {
+        self.in_a_row = 0;
+    }
+
+    /// A line wasn't understood. `example` is a number worth suggesting.
+    pub fn invalid(&mut self, example: u32) -> Response {
+        self.in_a_row += 1;
+        self.failures += 1;
+        // Each repeat in a row gets a more concrete suggestion
+        let message = match self.in_a_row {
+            1 => "Please type a number, or 'help' to see what else you can type.".to_string(),
+            2 => format!("That isn't a number either. Try typing just a number, like {example}."),
+            _ => format!("Type only the digits of a number, like {example}, and then press Enter."),
+        };
+        match self.policy {
+            InvalidInputPolicy::Strict { strikes } if self.in_a_row >= strikes => {
+                Response::EndRound(format!(
+                    "That's {strikes} strike{} in a row, so the round is over.",
+                    if strikes == 1 { "" } else { "s" }
+                ))
+            }
+            InvalidInputPolicy::Strict { strikes } => Response::Reprompt(format!(
+                "{message} ({} strike{} left)",
+                strikes - self.in_a_row,
+                if strikes - self.in_a_row == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            )),
+            InvalidInputPolicy::Lenient | InvalidInputPolicy::Counted => {
+                Response::Reprompt(message)
+            }
+        }
+    }
+
+    /// The number of invalid lines counted as failures: all of them under
+    /// `Counted`, none otherwise. The game's attempt counter only ever counts
+    /// real guesses.
+    pub fn counted_failures(&self) -> u32 {
+        match self.policy {
+            InvalidInputPolicy::Counted => self.failures,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict(strikes: u32) -> InputGuard {
+        InputGuard::new(InvalidInputPolicy::Strict { strikes })
+    }
+
+    #[test]
+    fn policies_parse_and_display() {
+        let cases = [
+            ("lenient", Ok(InvalidInputPolicy::Lenient)),
+            ("counted", Ok(InvalidInputPolicy::Counted)),
+            ("strict", Ok(InvalidInputPolicy::Strict { strikes: 3 })),
+            ("strict:1", Ok(InvalidInputPolicy::Strict { strikes: 1 })),
+            ("strict:0", Err(())),
+            ("strict:x", Err(())),
+            ("lenient:2", Err(())),
+            ("harsh", Err(())),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(text.parse(), expected, "{text}");
+        }
+        assert_eq!(
+            InvalidInputPolicy::Strict { strikes: 2 }.to_string(),
+            "strict:2"
+        );
+    }
+
+    #[test]
+    fn strikes_run_out_after_that_many_in_a_row() {
+        let mut guard = strict(3);
+        assert_eq!(
+            guard.invalid(50),
+            Response::Reprompt(
+                "Please type a number, or 'help' to see what else you can type. (2 strikes left)"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            guard.invalid(50),
+            Response::Reprompt(
+                "That isn't a number either. Try typing just a number, like 50. (1 strike left)"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            guard.invalid(50),
+            Response::EndRound("That's 3 strikes in a row, so the round is over.".to_string())
+        );
+    }
+
+    #[test]
+    fn a_single_strike_ends_the_round_at_once() {
+        assert_eq!(
+            strict(1).invalid(50),
+            Response::EndRound("That's 1 strike in a row, so the round is over.".to_string())
+        );
+    }
+
+    #[test]
+    fn a_valid_line_resets_the_strikes() {
+        let mut guard = strict(2);
+        assert!(matches!(guard.invalid(50), Response::Reprompt(_)));
+        guard.valid();
+        assert!(matches!(guard.invalid(50), Response::Reprompt(_)));
+        assert!(matches!(guard.invalid(50), Response::EndRound(_)));
+    }
+
+    #[test]
+    fn lenient_input_never_ends_the_round() {
+        let mut guard = InputGuard::new(InvalidInputPolicy::Lenient);
+        for _ in 0..10 {
+            assert!(matches!(guard.invalid(50), Response::Reprompt(_)));
+        }
+        assert_eq!(
+            guard.invalid(50),
+            Response::Reprompt(
+                "Type only the digits of a number, like 50, and then press Enter.".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn only_counted_input_reports_failures() {
+        for (policy, failures) in [
+            (InvalidInputPolicy::Counted, 3),
+            (InvalidInputPolicy::Lenient, 0),
+            (InvalidInputPolicy::Strict { strikes: 5 }, 0),
+        ] {
+            let mut guard = InputGuard::new(policy);
+            guard.invalid(50);
+            guard.valid();
+            guard.invalid(50);
+            guard.invalid(50);
+            assert_eq!(guard.counted_failures(), failures, "{policy}");
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// Interval mode: instead of one number, the player names a range each turn and
+// is told whether the secret is inside it. A range that holds the secret scores
+// more the narrower it is, so the game rewards knowing how sure you are
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::range_math;
+
+/// Where the secret is, relative to an interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalOutcome {
+    Contains,
+    /// The secret is smaller than the interval's low end.
+    Below,
+    /// The secret is larger than the interval's high end.
+    Above,
+}
+
+/// Error for an interval whose low end is above its high end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvertedInterval {
+    pub low: u32,
+    pub high: u32,
+}
+
+impl fmt::Display for InvertedInterval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} is above {}", self.low, self.high)
+    }
+}
+
+impl std::error::Error for InvertedInterval {}
+
+/// Whether `secret` lies in `low..=high`, or which side of it.
+pub fn interval_outcome(
+    low: u32,
+    high: u32,
+    secret: u32,
+) -> Result<IntervalOutcome, InvertedInterval> {
+    if low > high {
+        return Err(InvertedInterval { low, high });
+    }
+    Ok(if secret < low {
+        IntervalOutcome::Below
+    } else if secret > high {
+        IntervalOutcome::Above
+    } else {
+        IntervalOutcome::Contains
+    })
+}
+
+/// Points for an interval that holds the secret, from 100 for a single number
+/// down to 0 for one as wide as `range`. Misses score nothing, so this is only
+/// asked about hits.
+pub fn interval_score(low: u32, high: u32, range: &RangeInclusive<u32>) -> u32 {
+    let span = range_math::width(range);
+    let width = (high as u64).saturating_sub(low as u64) + 1;
+    if span <= 1 || width >= span {
+        return 0;
+    }
+    ((span - width) * 100 / (span - 1)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_secret_inside_the_interval_is_contained() {
+        assert_eq!(interval_outcome(40, 60, 50), Ok(IntervalOutcome::Contains));
+        assert_eq!(interval_outcome(40, 60, 40), Ok(IntervalOutcome::Contains));
+        assert_eq!(interval_outcome(40, 60, 60), Ok(IntervalOutcome::Contains));
+        assert_eq!(interval_outcome(7, 7, 7), Ok(IntervalOutcome::Contains));
+    }
+
+    #[test]
+    fn a_secret_below_the_interval_is_below() {
+        assert_eq!(interval_outcome(40, 60, 39), Ok(IntervalOutcome::Below));
+        assert_eq!(interval_outcome(40, 60, 0), Ok(IntervalOutcome::Below));
+    }
+
+    #[test]
+    fn a_secret_above_the_interval_is_above() {
+        assert_eq!(interval_outcome(40, 60, 61), Ok(IntervalOutcome::Above));
+        assert_eq!(
+            interval_outcome(40, 60, u32::MAX),
+            Ok(IntervalOutcome::Above)
+        );
+    }
+
+    #[test]
+    fn an_inverted_interval_is_rejected() {
+        let err = interval_outcome(60, 40, 50).unwrap_err();
+        assert_eq!(err, InvertedInterval { low: 60, high: 40 });
+        assert_eq!(err.to_string(), "60 is above 40");
+    }
+}
@@ -0,0 +1,546 @@
+// Best results per range, saved to a JSON file between runs together with
+// the player's Profile
+// Every result is kept, so that each season's standings can be worked out
+// later; what is shown is the best few of them
+use std::cmp::Ordering;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::json::{self, Value};
+use crate::persisted_file::{self, Kind};
+use crate::progress::{Difficulty, Profile};
+use crate::recent_secrets::RecentSecrets;
+use crate::season::{Season, Seasons};
+
+/// How many entries a range's standings show.
+pub const MAX_ENTRIES: usize = 5;
+
+/// The header leaderboard files are saved with.
+pub const FILE_KIND: Kind = Kind {
+    name: "leaderboard",
+    version: 1,
+};
+
+/// One result. Entries order best first: fewest attempts, then by name
+/// (anonymous entries after named ones), then earliest recorded. Every field
+/// takes part, so entries from any number of sources always sort the same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: Option<String>,
+    pub attempts: u32,
+    /// Seconds since the Unix epoch; 0 for results saved before it was kept.
+    pub recorded_at: u64,
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Entry) -> Ordering {
+        self.attempts
+            .cmp(&other.attempts)
+            .then_with(|| {
+                (self.name.is_none(), &self.name).cmp(&(other.name.is_none(), &other.name))
+            })
+            .then_with(|| self.recorded_at.cmp(&other.recorded_at))
+    }
+}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Entry) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The results for every range that has been played, fewest attempts first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Leaderboard {
+    // A handful of ranges at most, so a Vec is simpler than a map keyed by range
+    boards: Vec<(RangeInclusive<u32>, Vec<Entry>)>,
+    profile: Profile,
+    // The season the game last ran in, to notice when a new one begins
+    season: Option<Season>,
+    // The name given with the latest named result
+    player: Option<String>,
+    // What --no-repeat avoids, kept here since each run plays one round
+    recent: RecentSecrets,
+}
+
+impl Leaderboard {
+    pub fn new() -> Leaderboard {
+        Leaderboard::default()
+    }
+
+    /// Adds a result for `range`. A named result also makes its name the
+    /// leaderboard's player, whose placement `placement` can look up.
+    ///
+    /// Ties on attempts are settled by `Entry`'s order, not by who got there
+    /// first. Returns the 0-based all-time position of the new entry, or
+    /// `None` if it isn't in the top `MAX_ENTRIES`.
+    pub fn record(
+        &mut self,
+        range: RangeInclusive<u32>,
+        name: Option<String>,
+        attempts: u32,
+        recorded_at: u64,
+    ) -> Option<usize> {
+        if name.is_some() {
+            self.player.clone_from(&name);
+        }
+        let entry = Entry {
+            name,
+            attempts,
+            recorded_at,
+        };
+        let entries = self.board_mut(range);
+        // partition_point finds the first entry that is strictly worse
+        let position = entries.partition_point(|e| *e <= entry);
+        entries.insert(position, entry);
+        (position < MAX_ENTRIES).then_some(position)
+    }
+
+    /// Adds every entry of `other` to this leaderboard, range by range.
+    ///
+    /// The result depends only on the entries, not on which board they came
+    /// from, so merging the same boards in any order gives the same file. An
+    /// entry already present (the same in every field) isn't added twice. The
+    /// profile, season and player stay this leaderboard's own.
+    pub fn merge(&mut self, other: &Leaderboard) {
+        for (range, theirs) in &other.boards {
+            let entries = self.board_mut(range.clone());
+            entries.extend(theirs.iter().cloned());
+            entries.sort();
+            entries.dedup();
+        }
+    }
+
+    // The entries for `range`, starting an empty board if there is none yet
+    fn board_mut(&mut self, range: RangeInclusive<u32>) -> &mut Vec<Entry> {
+        match self.boards.iter().position(|(r, _)| *r == range) {
+            Some(i) => &mut self.boards[i].1,
+            None => {
+                self.boards.push((range, Vec::new()));
+                &mut self.boards.last_mut().unwrap().1
+            }
+        }
+    }
+
+    // Every entry for `range`, best first
+    fn entries(&self, range: &RangeInclusive<u32>) -> &[Entry] {
+        self.boards
+            .iter()
+            .find(|(r, _)| r == range)
+            .map_or(&[], |(_, entries)| entries.as_slice())
+    }
+
+    /// The best `MAX_ENTRIES` entries for `range` of all time, best first
+    /// (empty if the range was never played).
+    pub fn top(&self, range: &RangeInclusive<u32>) -> &[Entry] {
+        let entries = self.entries(range);
+        &entries[..entries.len().min(MAX_ENTRIES)]
+    }
+
+    /// Every entry for `range` set in `season`, best first.
+    pub fn season_entries(
+        &self,
+        range: &RangeInclusive<u32>,
+        seasons: Seasons,
+        season: Season,
+    ) -> Vec<&Entry> {
+        self.entries(range)
+            .iter()
+            .filter(|entry| seasons.season_of(entry.recorded_at) == season)
+            .collect()
+    }
+
+    /// The best `MAX_ENTRIES` entries for `range` set in `season`.
+    pub fn top_in_season(
+        &self,
+        range: &RangeInclusive<u32>,
+        seasons: Seasons,
+        season: Season,
+    ) -> Vec<&Entry> {
+        let mut entries = self.season_entries(range, seasons, season);
+        entries.truncate(MAX_ENTRIES);
+        entries
+    }
+
+    /// Where `name`'s best entry for `range` in `season` placed (0-based),
+    /// counting every entry of the season, not just the top ones.
+    pub fn placement(
+        &self,
+        range: &RangeInclusive<u32>,
+        seasons: Seasons,
+        season: Season,
+        name: &str,
+    ) -> Option<usize> {
+        self.season_entries(range, seasons, season)
+            .iter()
+            .position(|entry| entry.name.as_deref() == Some(name))
+    }
+
+    /// Every range that has been played, in the order first played.
+    pub fn ranges(&self) -> impl Iterator<Item = &RangeInclusive<u32>> {
+        self.boards.iter().map(|(range, _)| range)
+    }
+
+    /// The name given with the latest named result.
+    pub fn player(&self) -> Option<&str> {
+        self.player.as_deref()
+    }
+
+    /// Notes that the game is running in `current`, and returns the season it
+    /// last ran in if that was a different one. The change is only reported
+    /// once, so save the leaderboard afterwards. A leaderboard that has never
+    /// seen a season has nothing to report.
+    pub fn roll_season(&mut self, current: Season) -> Option<Season> {
+        self.season
+            .replace(current)
+            .filter(|&previous| previous != current)
+    }
+
+    /// The latest secrets of each range, for --no-repeat.
+    pub fn recent_secrets(&self) -> &RecentSecrets {
+        &self.recent
+    }
+
+    pub fn recent_secrets_mut(&mut self) -> &mut RecentSecrets {
+        &mut self.recent
+    }
+
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
+    pub fn profile_mut(&mut self) -> &mut Profile {
+        &mut self.profile
+    }
+
+    /// Reads a leaderboard saved by `save`, or an empty one if the file doesn't exist yet.
+    pub fn load(path: &Path) -> io::Result<Leaderboard> {
+        let Some(text) = persisted_file::read(path, FILE_KIND)? else {
+            return Ok(Leaderboard::new());
+        };
+        let value = json::parse(&text).map_err(|err| invalid(&err.to_string()))?;
+        Leaderboard::from_json(&value).ok_or_else(|| invalid("unexpected leaderboard layout"))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        persisted_file::write(path, FILE_KIND, &self.to_json().to_string())
+    }
+
+    // The file looks like:
+    // {"boards":[{"range":[1,100],"entries":[{"name":"ann","attempts":5,
+    //  "recorded_at":1760400000}]}],
+    //  "profile":{"unlocked":"medium","streak":1},"season":"2025-10","player":"ann",
+    //  "recent":[{"range":[1,100],"secrets":[42,7]}]}
+    fn to_json(&self) -> Value {
+        let boards = self
+            .boards
+            .iter()
+            .map(|(range, entries)| {
+                let entries = entries
+                    .iter()
+                    .map(|entry| {
+                        Value::Object(vec![
+                            (
+                                "name".to_string(),
+                                entry.name.as_deref().map_or(Value::Null, Value::from),
+                            ),
+                            ("attempts".to_string(), Value::from(entry.attempts)),
+                            ("recorded_at".to_string(), Value::from(entry.recorded_at)),
+                        ])
+                    })
+                    .collect();
+                Value::Object(vec![
+                    (
+                        "range".to_string(),
+                        Value::Array(vec![Value::from(*range.start()), Value::from(*range.end())]),
+                    ),
+                    ("entries".to_string(), Value::Array(entries)),
+                ])
+            })
+            .collect();
+        let profile = Value::Object(vec![
+            (
+                "unlocked".to_string(),
+                Value::from(self.profile.unlocked.name()),
+            ),
+            ("streak".to_string(), Value::from(self.profile.streak)),
+        ]);
+        let mut fields = vec![
+            ("boards".to_string(), Value::Array(boards)),
+            ("profile".to_string(), profile),
+        ];
+        if let Some(season) = self.season {
+            fields.push((
+                "season".to_string(),
+                Value::from(season.to_string().as_str()),
+            ));
+        }
+        if let Some(player) = &self.player {
+            fields.push(("player".to_string(), Value::from(player.as_str())));
+        }
+        if !self.recent.is_empty() {
+            fields.push(("recent".to_string(), self.recent.to_json()));
+        }
+        Value::Object(fields)
+    }
+
+    fn from_json(value: &Value) -> Option<Leaderboard> {
+        let mut leaderboard = Leaderboard::new();
+        for board in value.get("boards")?.as_array()? {
+            let [start, end] = board.get("range")?.as_array()? else {
+                return None;
+            };
+            let range = start.as_u32()?..=end.as_u32()?;
+            let mut entries = Vec::new();
+            for entry in board.get("entries")?.as_array()? {
+                let name = match entry.get("name")? {
+                    Value::Null => None,
+                    name => Some(name.as_str()?.to_string()),
+                };
+                let attempts = entry.get("attempts")?.as_u32()?;
+                // Older files have no timestamps
+                let recorded_at = match entry.get("recorded_at") {
+                    Some(value) => value.as_u64()?,
+                    None => 0,
+                };
+                entries.push(Entry {
+                    name,
+                    attempts,
+                    recorded_at,
+                });
+            }
+            // Files from before the full order list ties in the order they
+            // were set, so put them in the order record and merge expect
+            entries.sort();
+            leaderboard.boards.push((range, entries));
+        }
+        // Files written before profiles existed simply start from the default one
+        if let Some(profile) = value.get("profile") {
+            leaderboard.profile = Profile {
+                unlocked: profile
+                    .get("unlocked")?
+                    .as_str()?
+                    .parse::<Difficulty>()
+                    .ok()?,
+                streak: profile.get("streak")?.as_u32()?,
+            };
+        }
+        // Both are missing from files written before seasons
+        if let Some(season) = value.get("season") {
+            leaderboard.season = Some(season.as_str()?.parse().ok()?);
+        }
+        if let Some(player) = value.get("player") {
+            leaderboard.player = Some(player.as_str()?.to_string());
+        }
+        // Only written once --no-repeat has been used
+        if let Some(recent) = value.get("recent") {
+            leaderboard.recent = RecentSecrets::from_json(recent)?;
+        }
+        Some(leaderboard)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> TempDir {
+            let path =
+                std::env::temp_dir().join(format!("leaderboard-{}-{test}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const NOV_2024: Season = Season::Starting {
+        year: 2024,
+        month: 11,
+    };
+    const DEC_2024: Season = Season::Starting {
+        year: 2024,
+        month: 12,
+    };
+
+    const RANGE: RangeInclusive<u32> = 1..=100;
+
+    fn name(name: &str) -> Option<String> {
+        Some(name.to_string())
+    }
+
+    // (name, attempts) of each shown entry, best first
+    fn shown(leaderboard: &Leaderboard) -> Vec<(Option<&str>, u32)> {
+        leaderboard
+            .top(&RANGE)
+            .iter()
+            .map(|entry| (entry.name.as_deref(), entry.attempts))
+            .collect()
+    }
+
+    #[test]
+    fn entries_are_kept_best_first_whatever_order_they_arrive_in() {
+        let mut leaderboard = Leaderboard::new();
+        assert_eq!(leaderboard.record(RANGE, name("ann"), 7, 10), Some(0));
+        assert_eq!(leaderboard.record(RANGE, name("bob"), 4, 20), Some(0));
+        assert_eq!(leaderboard.record(RANGE, name("cat"), 9, 30), Some(2));
+        assert_eq!(leaderboard.record(RANGE, name("dan"), 5, 40), Some(1));
+        assert_eq!(
+            shown(&leaderboard),
+            [
+                (Some("bob"), 4),
+                (Some("dan"), 5),
+                (Some("ann"), 7),
+                (Some("cat"), 9)
+            ]
+        );
+        // Other ranges have boards of their own
+        assert!(leaderboard.top(&(1..=10)).is_empty());
+    }
+
+    #[test]
+    fn only_the_best_max_entries_are_shown() {
+        let mut leaderboard = Leaderboard::new();
+        for attempts in 1..=MAX_ENTRIES as u32 {
+            leaderboard.record(RANGE, name("ann"), attempts, 0);
+        }
+        // Worse than every shown entry: kept, but not in the top
+        assert_eq!(leaderboard.record(RANGE, name("bob"), 99, 0), None);
+        // Better than the last one: shown, pushing that one out
+        assert_eq!(leaderboard.record(RANGE, name("cat"), 2, 0), Some(2));
+        let shown = shown(&leaderboard);
+        assert_eq!(shown.len(), MAX_ENTRIES);
+        assert_eq!(shown[2], (Some("cat"), 2));
+        assert_eq!(shown.last(), Some(&(Some("ann"), 4)));
+    }
+
+    #[test]
+    fn ties_go_by_name_then_by_who_was_first() {
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.record(RANGE, None, 5, 1);
+        leaderboard.record(RANGE, name("bob"), 5, 2);
+        leaderboard.record(RANGE, name("ann"), 5, 4);
+        // The same name later ranks below the earlier result
+        assert_eq!(leaderboard.record(RANGE, name("ann"), 5, 3), Some(0));
+        let times: Vec<(Option<&str>, u64)> = leaderboard
+            .top(&RANGE)
+            .iter()
+            .map(|entry| (entry.name.as_deref(), entry.recorded_at))
+            .collect();
+        assert_eq!(
+            times,
+            [
+                (Some("ann"), 3),
+                (Some("ann"), 4),
+                (Some("bob"), 2),
+                (None, 1)
+            ]
+        );
+    }
+
+    fn entry(name: Option<&str>, attempts: u32, recorded_at: u64) -> Entry {
+        Entry {
+            name: name.map(str::to_string),
+            attempts,
+            recorded_at,
+        }
+    }
+
+    #[test]
+    fn entries_order_by_attempts_then_name_then_time() {
+        let mut entries = vec![
+            entry(None, 3, 1),
+            entry(Some("bob"), 3, 1),
+            entry(Some("ann"), 3, 9),
+            entry(Some("ann"), 3, 2),
+            entry(Some("zed"), 2, 5),
+        ];
+        entries.sort();
+        assert_eq!(
+            entries,
+            [
+                entry(Some("zed"), 2, 5),
+                entry(Some("ann"), 3, 2),
+                entry(Some("ann"), 3, 9),
+                entry(Some("bob"), 3, 1),
+                entry(None, 3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_boards_with_ties_agree_whichever_way_round() {
+        let mut home = Leaderboard::new();
+        home.record(RANGE, name("bob"), 4, 100);
+        home.record(RANGE, name("ann"), 6, 300);
+        home.record(RANGE, None, 4, 50);
+        let mut work = Leaderboard::new();
+        work.record(RANGE, name("ann"), 4, 200);
+        work.record(RANGE, name("bob"), 4, 100);
+        work.record(1..=10, name("cat"), 2, 400);
+
+        let mut here = home.clone();
+        here.merge(&work);
+        let mut there = work.clone();
+        there.merge(&home);
+
+        // bob's result was on both boards, and is only kept once
+        let canonical = [
+            entry(Some("ann"), 4, 200),
+            entry(Some("bob"), 4, 100),
+            entry(None, 4, 50),
+            entry(Some("ann"), 6, 300),
+        ];
+        assert_eq!(here.top(&RANGE), canonical);
+        assert_eq!(there.top(&RANGE), canonical);
+        assert_eq!(here.top(&(1..=10)), [entry(Some("cat"), 2, 400)]);
+        assert_eq!(there.top(&(1..=10)), here.top(&(1..=10)));
+    }
+
+    #[test]
+    fn merging_again_adds_nothing() {
+        let mut home = Leaderboard::new();
+        home.record(RANGE, name("ann"), 4, 1);
+        let work = home.clone();
+        home.merge(&work);
+        home.merge(&work);
+        assert_eq!(home.top(&RANGE), [entry(Some("ann"), 4, 1)]);
+    }
+
+    #[test]
+    fn a_new_season_is_reported_exactly_once() {
+        let mut leaderboard = Leaderboard::new();
+        // Nothing to report the first time a season is seen
+        assert_eq!(leaderboard.roll_season(NOV_2024), None);
+        assert_eq!(leaderboard.roll_season(NOV_2024), None);
+        assert_eq!(leaderboard.roll_season(DEC_2024), Some(NOV_2024));
+        assert_eq!(leaderboard.roll_season(DEC_2024), None);
+    }
+
+    #[test]
+    fn the_season_survives_a_save() {
+        let dir = TempDir::new("roll");
+        let path = dir.0.join("leaderboard.json");
+        let mut leaderboard = Leaderboard::new();
+        leaderboard.roll_season(NOV_2024);
+        leaderboard.roll_season(DEC_2024);
+        leaderboard.save(&path).unwrap();
+        let mut loaded = Leaderboard::load(&path).unwrap();
+        assert_eq!(loaded.roll_season(DEC_2024), None);
+    }
+}
@@ -0,0 +1,64 @@
+// The library half of this crate (see notes/binary-vs-library.md)
+// The files in src/bin/ are standalone lessons, while src/main.rs is the
+// full guessing game built on top of the modules declared here (the game's
+// own logic is in app)
+// Modules only the game itself needs are pub(crate); what an embedder can
+// reach is the prelude plus the pub modules below, which the binaries use too
+pub(crate) mod accessible;
+pub mod analysis;
+pub mod app;
+pub mod batch;
+pub mod capabilities;
+pub(crate) mod celebration;
+pub(crate) mod chart;
+pub(crate) mod commands;
+pub mod commitment;
+pub mod config;
+pub mod daily;
+pub mod game;
+pub(crate) mod grading;
+pub mod hints;
+pub mod input;
+pub mod input_policy;
+pub(crate) mod intervals;
+pub mod json;
+pub mod leaderboard;
+pub(crate) mod number_words;
+pub mod observers;
+pub(crate) mod persisted_file;
+pub mod progress;
+pub(crate) mod property_hints;
+pub mod radix;
+pub(crate) mod range_math;
+pub mod recent_secrets;
+pub mod record;
+pub(crate) mod save;
+pub mod season;
+pub(crate) mod spectator;
+pub mod strategy;
+pub mod terminal;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod timing;
+pub mod tutorial;
+pub mod types_repl;
+pub mod wallet;
+pub(crate) mod warmup;
+pub(crate) mod weakness;
+
+/// The types most programs need, in one import: `use learning_rust::prelude::*;`
+///
+/// Items here stay put even if the modules behind them get reorganised.
+pub mod prelude {
+    pub use crate::config::{ArgError, Config};
+    pub use crate::game::{
+        Bound, Game, Guess, GuessOutcome, OutOfRange, TempPolicy, Temperature, Turn,
+    };
+    pub use crate::hints::{HintBank, HintKind};
+    pub use crate::input::{InputSource, StdinInput};
+    pub use crate::observers::OutcomeObserver;
+    pub use crate::progress::{Difficulty, RoundResult};
+    pub use crate::record::GameRecord;
+    pub use crate::strategy::Strategy;
+    pub use crate::wallet::Wallet;
+}
@@ -1,3 +1,52 @@
+// The full guessing game, built on the library in src/lib.rs
+// Compare with src/bin/guess_with_random.rs, which does everything inline.
+// The game itself is in src/app.rs; this only reads the arguments, looks at
+// the terminal and turns errors into exit codes
+use std::env;
+use std::io::{self, Write};
+use std::process;
+
+use learning_rust::app::{self, RunError};
+use learning_rust::capabilities::{Capabilities, Feature};
+use learning_rust::config;
+use learning_rust::prelude::*;
+use learning_rust::terminal::{Overflow, SymbolSet, TerminalWriter};
+
 fn main() {
-    println!("Hello, world!");
+    let mut config = match Config::from_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", config::USAGE);
+            process::exit(2);
+        }
+    };
+
+    let caps = Capabilities::detect().overridden(config.tty, config.utf8, config.term_width);
+    if config.capabilities {
+        print!("{}", caps.report());
+        return;
+    }
+    // Glyphs the terminal can't show would be worse than the plain ones
+    if config.symbols == Some(SymbolSet::Unicode) && !caps.supports(Feature::UnicodeSymbols) {
+        config.symbols = Some(SymbolSet::Ascii);
+    }
+
+    let out = TerminalWriter::new(io::stdout().lock());
+    // --terse promises one line per guess, so a long line is cut off rather than wrapped
+    let mut out = match config.max_line_width {
+        Some(width) if config.terse => out.max_width(width, Overflow::Truncate),
+        Some(width) => out.max_width(width, Overflow::Wrap),
+        None => out,
+    };
+    let result = app::play(&config, &caps, &mut StdinInput, &mut out);
+    // process::exit skips destructors, so flush by hand before an error can exit
+    let flushed = out.flush();
+    let result = result.and_then(|()| flushed.map_err(RunError::from));
+    if let Err(err) = result {
+        eprintln!("{err}");
+        process::exit(match err {
+            RunError::Usage(_) => 2,
+            RunError::Setup(_) | RunError::Io(_) => 1,
+        });
+    }
 }
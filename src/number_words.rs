@@ -0,0 +1,119 @@
+// Reading numbers written out in English words, like "forty-two"
+const UNITS: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+/// Parses a number from 0 to 999 written in words: "zero", "twenty-one",
+/// "one hundred", "three hundred and five". Case and the choice of hyphens or
+/// spaces don't matter. Returns None for anything else, so callers can fall
+/// back to reading digits.
+pub fn parse_number_word(s: &str) -> Option<u32> {
+    let lower = s.trim().to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| c == '-' || c.is_whitespace())
+        .filter(|w| !w.is_empty() && *w != "and")
+        .collect();
+
+    match words.iter().position(|&w| w == "hundred") {
+        Some(at) => {
+            let hundreds = match &words[..at] {
+                ["a"] => 1,
+                // "zero hundred" isn't a number anyone says
+                before => parse_below_hundred(before).filter(|n| (1..10).contains(n))?,
+            };
+            let rest = match &words[at + 1..] {
+                [] => 0,
+                after => parse_below_hundred(after)?,
+            };
+            Some(hundreds * 100 + rest)
+        }
+        None => parse_below_hundred(&words),
+    }
+}
+
+// 0 to 99: "seven", "seventeen", "seventy", "seventy seven"
+fn parse_below_hundred(words: &[&str]) -> Option<u32> {
+    let unit = |w: &str| UNITS.iter().position(|&u| u == w).map(|n| n as u32);
+    let tens = |w: &str| {
+        TENS.iter()
+            .position(|&t| t == w)
+            .map(|n| 20 + 10 * n as u32)
+    };
+    match words {
+        [w] => unit(w).or_else(|| tens(w)),
+        [t, u] => {
+            // "twenty zero" and "twenty twelve" aren't numbers
+            let u = unit(u).filter(|n| (1..10).contains(n))?;
+            Some(tens(t)? + u)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_words_parse() {
+        let cases = [
+            ("zero", 0),
+            ("seven", 7),
+            ("nineteen", 19),
+            ("forty", 40),
+            ("twenty-one", 21),
+            ("twenty one", 21),
+            ("Twenty-One", 21),
+            ("one hundred", 100),
+            ("a hundred", 100),
+            ("three hundred and five", 305),
+            ("nine hundred ninety-nine", 999),
+            ("  fifty  ", 50),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(parse_number_word(text), Some(expected), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn anything_else_is_not_a_number_word() {
+        let cases = [
+            "",
+            "42",
+            "eleventy",
+            "twenty zero",
+            "twenty twelve",
+            "zero hundred",
+            "ten hundred",
+            "hundred hundred",
+            "one thousand",
+            "forty two three",
+        ];
+        for text in cases {
+            assert_eq!(parse_number_word(text), None, "{text:?}");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Reactions to guesses that live outside the game loop
+// The game (src/app.rs) calls every registered observer after each guess is
+// answered, so a new reaction (a bell, a tally, a log) is a new observer
+// rather than another branch in the loop
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+
+use crate::game::GuessOutcome;
+
+/// Something told about every guess the game answers, in order.
+pub trait OutcomeObserver {
+    fn on_outcome(&mut self, guess: u32, outcome: &GuessOutcome);
+}
+
+/// Any closure taking the guess and its outcome is an observer.
+impl<F: FnMut(u32, &GuessOutcome)> OutcomeObserver for F {
+    fn on_outcome(&mut self, guess: u32, outcome: &GuessOutcome) {
+        self(guess, outcome)
+    }
+}
+
+/// A shared observer, so whoever registered it can still read it afterwards.
+impl<T: OutcomeObserver> OutcomeObserver for Rc<RefCell<T>> {
+    fn on_outcome(&mut self, guess: u32, outcome: &GuessOutcome) {
+        self.borrow_mut().on_outcome(guess, outcome)
+    }
+}
+
+/// Rings the terminal bell (ASCII BEL) on a win.
+#[derive(Debug)]
+pub struct BellOnWin<W: Write> {
+    out: W,
+}
+
+impl<W: Write> BellOnWin<W> {
+    pub fn new(out: W) -> BellOnWin<W> {
+        BellOnWin { out }
+    }
+}
+
+impl<W: Write> OutcomeObserver for BellOnWin<W> {
+    fn on_outcome(&mut self, _guess: u32, outcome: &GuessOutcome) {
+        if *outcome == GuessOutcome::Correct {
+            // A bell that can't be rung is no reason to stop the game
+            let _ = self.out.write_all(b"\x07").and_then(|()| self.out.flush());
+        }
+    }
+}
+
+/// Counts the outcomes it has seen and keeps every guess, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutcomeStats {
+    pub too_small: u32,
+    pub too_big: u32,
+    pub correct: u32,
+    pub guesses: Vec<u32>,
+}
+
+impl OutcomeStats {
+    pub fn new() -> OutcomeStats {
+        OutcomeStats::default()
+    }
+}
+
+impl OutcomeObserver for OutcomeStats {
+    fn on_outcome(&mut self, guess: u32, outcome: &GuessOutcome) {
+        self.guesses.push(guess);
+        match outcome {
+            GuessOutcome::TooSmall => self.too_small += 1,
+            GuessOutcome::TooBig => self.too_big += 1,
+            GuessOutcome::Correct => self.correct += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_count_each_outcome_and_keep_the_guesses() {
+        let mut stats = OutcomeStats::new();
+        for (guess, outcome) in [
+            (50, GuessOutcome::TooSmall),
+            (75, GuessOutcome::TooBig),
+            (60, GuessOutcome::TooSmall),
+            (65, GuessOutcome::Correct),
+        ] {
+            stats.on_outcome(guess, &outcome);
+        }
+        assert_eq!((stats.too_small, stats.too_big, stats.correct), (2, 1, 1));
+        assert_eq!(stats.guesses, [50, 75, 60, 65]);
+    }
+
+    #[test]
+    fn the_bell_rings_only_on_a_win() {
+        let mut out = Vec::new();
+        {
+            let mut bell = BellOnWin::new(&mut out);
+            bell.on_outcome(50, &GuessOutcome::TooSmall);
+            bell.on_outcome(75, &GuessOutcome::TooBig);
+        }
+        assert!(out.is_empty());
+        BellOnWin::new(&mut out).on_outcome(65, &GuessOutcome::Correct);
+        assert_eq!(out, b"\x07");
+    }
+}
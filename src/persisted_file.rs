@@ -0,0 +1,406 @@
+// A header on every file the game writes, so damage is noticed at startup
+// The first line says what the file holds, the layout version, and the length
+// and checksum of everything after it:
+//     learning-rust leaderboard v1 len=183 fnv=9c2d0e1a44b0f3a7
+// The payload follows unchanged. FNV-1a catches files cut short and flipped
+// bits, not deliberate edits, since it isn't a cryptographic hash. Every
+// successful save also copies the file to one with .bak added to the name,
+// to repair it from. Files from before headers existed are read as they are,
+// as long as they still hold JSON: anything else is damage too
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::json;
+
+/// The first word of every header.
+pub const MAGIC: &str = "learning-rust";
+
+/// What a file holds, and the newest layout of it this build can read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Kind {
+    pub name: &'static str,
+    pub version: u32,
+}
+
+/// Why a file's header and payload don't agree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntegrityError {
+    /// Nothing in it at all, as when a save never got as far as writing.
+    Empty,
+    /// The first line starts like a header but isn't one, or there is no
+    /// header and the rest isn't a file from before headers either.
+    Header,
+    WrongKind {
+        expected: &'static str,
+        found: String,
+    },
+    /// Written by a newer build, in a layout this one can't read.
+    TooNew { version: u32, supported: u32 },
+    /// Shorter than the header says, as when a save was cut off.
+    Truncated { expected: usize, found: usize },
+    /// The payload isn't the one the checksum was taken of.
+    Checksum,
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntegrityError::Empty => write!(f, "it is empty"),
+            IntegrityError::Header => write!(f, "its header line is damaged"),
+            IntegrityError::WrongKind { expected, found } => {
+                write!(f, "it holds a {found} file, not a {expected} file")
+            }
+            IntegrityError::TooNew { version, supported } => write!(
+                f,
+                "it was written by a newer version of the game (layout v{version}; this one reads up to v{supported})"
+            ),
+            IntegrityError::Truncated { expected, found } => {
+                write!(f, "it is cut short ({found} of {expected} bytes)")
+            }
+            IntegrityError::Checksum => write!(f, "its contents don't match their checksum"),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// The 64-bit FNV-1a hash of `bytes`.
+pub fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// `payload` with a header for `kind` in front of it.
+pub fn encode(kind: Kind, payload: &str) -> String {
+    format!(
+        "{MAGIC} {} v{} len={} fnv={:016x}\n{payload}",
+        kind.name,
+        kind.version,
+        payload.len(),
+        checksum(payload.as_bytes())
+    )
+}
+
+/// The payload of `text`, after checking it against its header. Text with
+/// no header at all predates headers and is returned whole, if it is JSON.
+pub fn decode(kind: Kind, text: &str) -> Result<&str, IntegrityError> {
+    if !text.starts_with(&format!("{MAGIC} ")) {
+        return if text.trim().is_empty() {
+            Err(IntegrityError::Empty)
+        } else if is_legacy(text) {
+            Ok(text)
+        } else {
+            Err(IntegrityError::Header)
+        };
+    }
+    let (header, payload) = text.split_once('\n').ok_or(IntegrityError::Header)?;
+    let [_, name, version, len, fnv] = header.split(' ').collect::<Vec<_>>()[..] else {
+        return Err(IntegrityError::Header);
+    };
+    if name != kind.name {
+        return Err(IntegrityError::WrongKind {
+            expected: kind.name,
+            found: name.to_string(),
+        });
+    }
+    let version =
+        u32::try_from(header_field(version, "v", 10)?).map_err(|_| IntegrityError::Header)?;
+    let len = header_field(len, "len=", 10)? as usize;
+    let fnv = header_field(fnv, "fnv=", 16)?;
+    if version > kind.version {
+        return Err(IntegrityError::TooNew {
+            version,
+            supported: kind.version,
+        });
+    }
+    if payload.len() < len {
+        return Err(IntegrityError::Truncated {
+            expected: len,
+            found: payload.len(),
+        });
+    }
+    if payload.len() != len || checksum(payload.as_bytes()) != fnv {
+        return Err(IntegrityError::Checksum);
+    }
+    Ok(payload)
+}
+
+// Every file from before headers was JSON: one document (a leaderboard or a
+// save), or one per line (a history)
+fn is_legacy(text: &str) -> bool {
+    json::parse(text).is_ok()
+        || text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .all(|line| json::parse(line).is_ok())
+}
+
+// The number after `prefix` in one word of the header
+fn header_field(word: &str, prefix: &str, radix: u32) -> Result<u64, IntegrityError> {
+    word.strip_prefix(prefix)
+        .and_then(|digits| u64::from_str_radix(digits, radix).ok())
+        .ok_or(IntegrityError::Header)
+}
+
+/// Where the copy of `path` made by the last successful save is.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// Writes `payload` to `path` with a header, then the same to its backup.
+pub fn write(path: &Path, kind: Kind, payload: &str) -> io::Result<()> {
+    let text = encode(kind, payload);
+    fs::write(path, &text)?;
+    fs::write(backup_path(path), &text)
+}
+
+/// The checked payload of the file at `path`, or None if there is no such
+/// file. A damaged file is an `InvalidData` error.
+pub fn read(path: &Path, kind: Kind) -> io::Result<Option<String>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    decode(kind, &text)
+        .map(|payload| Some(payload.to_string()))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))
+}
+
+/// What is wrong with the file at `path`, if anything; a missing file is fine.
+pub fn check(path: &Path, kind: Kind) -> io::Result<Option<IntegrityError>> {
+    // Bytes that aren't UTF-8 are damage like any other; read them anyway
+    // and let the checksum catch them
+    let text = match fs::read(path) {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    Ok(decode(kind, &text).err())
+}
+
+/// Whether `path` has a backup that passes its own check.
+pub fn has_good_backup(path: &Path, kind: Kind) -> bool {
+    fs::read_to_string(backup_path(path)).is_ok_and(|text| decode(kind, &text).is_ok())
+}
+
+/// Puts the backup back in place of `path`, if the backup is undamaged.
+pub fn restore(path: &Path, kind: Kind) -> io::Result<()> {
+    let text = fs::read_to_string(backup_path(path))?;
+    decode(kind, &text).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("the backup is damaged too: {err}"),
+        )
+    })?;
+    fs::write(path, text)
+}
+
+/// Moves `path` aside, to a name ending in `.corrupt-` and the time, so the
+/// game starts without it; returns the new name.
+pub fn quarantine(path: &Path) -> io::Result<PathBuf> {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".corrupt-{seconds}"));
+    let aside = PathBuf::from(name);
+    fs::rename(path, &aside)?;
+    Ok(aside)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KIND: Kind = Kind {
+        name: "leaderboard",
+        version: 1,
+    };
+    const PAYLOAD: &str = "{\"boards\":[]}\n";
+
+    // A directory of its own for each test, removed at the end even if the
+    // test fails
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> TempDir {
+            let path =
+                std::env::temp_dir().join(format!("persisted-file-{}-{test}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn an_intact_file_decodes() {
+        assert_eq!(decode(KIND, &encode(KIND, PAYLOAD)), Ok(PAYLOAD));
+        // Files from before headers existed are read as they are
+        assert_eq!(decode(KIND, PAYLOAD), Ok(PAYLOAD));
+        let history = "{\"secret\":1}\n\n{\"secret\":2}\n";
+        assert_eq!(decode(KIND, history), Ok(history));
+    }
+
+    #[test]
+    fn an_empty_file_is_damaged() {
+        assert_eq!(decode(KIND, ""), Err(IntegrityError::Empty));
+        assert_eq!(decode(KIND, "\n  \n"), Err(IntegrityError::Empty));
+    }
+
+    #[test]
+    fn a_corrupted_magic_is_damaged() {
+        let text = encode(KIND, PAYLOAD).replacen("learning-rust", "learning-rusT", 1);
+        assert_eq!(decode(KIND, &text), Err(IntegrityError::Header));
+        // Half a JSON document isn't a file from before headers either
+        assert_eq!(decode(KIND, "{\"boards\":["), Err(IntegrityError::Header));
+    }
+
+    #[test]
+    fn an_empty_file_is_caught_at_startup() {
+        let dir = TempDir::new("empty");
+        let path = dir.0.join("leaderboard.json");
+        fs::write(&path, "").unwrap();
+        assert_eq!(check(&path, KIND).unwrap(), Some(IntegrityError::Empty));
+        assert_eq!(
+            read(&path, KIND).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn a_truncated_file_is_noticed() {
+        let text = encode(KIND, PAYLOAD);
+        assert_eq!(
+            decode(KIND, &text[..text.len() - 3]),
+            Err(IntegrityError::Truncated {
+                expected: PAYLOAD.len(),
+                found: PAYLOAD.len() - 3,
+            })
+        );
+    }
+
+    #[test]
+    fn a_flipped_byte_fails_the_checksum() {
+        let text = encode(KIND, PAYLOAD);
+        let header_len = text.find('\n').unwrap() + 1;
+        for at in header_len..text.len() {
+            let mut bytes = text.clone().into_bytes();
+            bytes[at] ^= 0x01;
+            let damaged = String::from_utf8(bytes).unwrap();
+            assert_eq!(
+                decode(KIND, &damaged),
+                Err(IntegrityError::Checksum),
+                "byte {at}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_file_from_a_newer_version_is_refused() {
+        let newer = Kind {
+            version: KIND.version + 1,
+            ..KIND
+        };
+        assert_eq!(
+            decode(KIND, &encode(newer, PAYLOAD)),
+            Err(IntegrityError::TooNew {
+                version: 2,
+                supported: 1,
+            })
+        );
+        // Older layouts are still read
+        assert_eq!(decode(newer, &encode(KIND, PAYLOAD)), Ok(PAYLOAD));
+    }
+
+    #[test]
+    fn a_damaged_header_or_kind_is_noticed() {
+        let text = encode(KIND, PAYLOAD).replace("len=", "length=");
+        assert_eq!(decode(KIND, &text), Err(IntegrityError::Header));
+        let save = Kind {
+            name: "save",
+            version: 1,
+        };
+        assert_eq!(
+            decode(save, &encode(KIND, PAYLOAD)),
+            Err(IntegrityError::WrongKind {
+                expected: "save",
+                found: "leaderboard".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn a_damaged_file_is_repaired_from_its_backup() {
+        let dir = TempDir::new("repair");
+        let path = dir.0.join("leaderboard.json");
+        write(&path, KIND, PAYLOAD).unwrap();
+        assert_eq!(
+            fs::read_to_string(backup_path(&path)).unwrap(),
+            encode(KIND, PAYLOAD)
+        );
+
+        // Cut short, as by a crash halfway through a save
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &text[..text.len() - 5]).unwrap();
+        assert!(matches!(
+            check(&path, KIND).unwrap(),
+            Some(IntegrityError::Truncated { .. })
+        ));
+        assert_eq!(
+            read(&path, KIND).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(has_good_backup(&path, KIND));
+
+        restore(&path, KIND).unwrap();
+        assert_eq!(check(&path, KIND).unwrap(), None);
+        assert_eq!(read(&path, KIND).unwrap().as_deref(), Some(PAYLOAD));
+    }
+
+    #[test]
+    fn a_damaged_backup_is_not_restored() {
+        let dir = TempDir::new("bad-backup");
+        let path = dir.0.join("leaderboard.json");
+        write(&path, KIND, PAYLOAD).unwrap();
+        fs::write(backup_path(&path), "learning-rust leaderboard v1\n").unwrap();
+        assert!(!has_good_backup(&path, KIND));
+        assert!(restore(&path, KIND).is_err());
+        // The file itself is left as it was
+        assert_eq!(read(&path, KIND).unwrap().as_deref(), Some(PAYLOAD));
+    }
+
+    #[test]
+    fn a_quarantined_file_is_moved_aside() {
+        let dir = TempDir::new("quarantine");
+        let path = dir.0.join("leaderboard.json");
+        fs::write(&path, "learning-rust leaderboard v1 len=99 fnv=0\nshort").unwrap();
+        let aside = quarantine(&path).unwrap();
+        assert!(!path.exists());
+        assert!(
+            aside
+                .to_string_lossy()
+                .contains("leaderboard.json.corrupt-")
+        );
+        assert_eq!(
+            fs::read_to_string(&aside).unwrap(),
+            "learning-rust leaderboard v1 len=99 fnv=0\nshort"
+        );
+        // With the damaged file gone, the game starts as if on a first run
+        assert_eq!(read(&path, KIND).unwrap(), None);
+        assert_eq!(check(&path, KIND).unwrap(), None);
+    }
+}
@@ -0,0 +1,451 @@
+// Difficulty levels, and the profile that tracks which of them are unlocked
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How many wins in a row at the hardest unlocked level open up the next one.
+pub const WINS_TO_UNLOCK: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+        }
+    }
+
+    pub fn range(self) -> RangeInclusive<u32> {
+        match self {
+            Difficulty::Easy => 1..=10,
+            Difficulty::Medium => 1..=100,
+            Difficulty::Hard => 1..=1000,
+            Difficulty::Expert => 1..=10000,
+        }
+    }
+
+    /// The level whose range is exactly `range`, if there is one.
+    pub fn from_range(range: &RangeInclusive<u32>) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|d| d.range() == *range)
+    }
+
+    pub fn next(self) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|&d| d > self)
+    }
+
+    pub fn previous(self) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().rev().find(|&d| d < self)
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Difficulty, ()> {
+        Difficulty::ALL
+            .into_iter()
+            .find(|d| d.name() == s)
+            .ok_or(())
+    }
+}
+
+/// Error returned when asking to play a level that hasn't been unlocked yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locked {
+    pub requested: Difficulty,
+    pub unlocked: Difficulty,
+}
+
+impl fmt::Display for Locked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Levels unlock one at a time, so the way forward is always the level after `unlocked`
+        let next = self.unlocked.next().unwrap_or(self.requested);
+        write!(
+            f,
+            "{} is locked. Win {WINS_TO_UNLOCK} {} games in a row to unlock {next}.",
+            self.requested, self.unlocked
+        )
+    }
+}
+
+impl std::error::Error for Locked {}
+
+/// What the player has unlocked, saved alongside the leaderboard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// The hardest level the player may play; every easier one is open too.
+    pub unlocked: Difficulty,
+    /// Wins in a row at the `unlocked` level.
+    pub streak: u32,
+}
+
+// Medium is the classic 1..=100 game, so nobody has to grind through easy first
+impl Default for Profile {
+    fn default() -> Profile {
+        Profile {
+            unlocked: Difficulty::Medium,
+            streak: 0,
+        }
+    }
+}
+
+impl Profile {
+    pub fn check(&self, difficulty: Difficulty) -> Result<(), Locked> {
+        if difficulty <= self.unlocked {
+            Ok(())
+        } else {
+            Err(Locked {
+                requested: difficulty,
+                unlocked: self.unlocked,
+            })
+        }
+    }
+}
+
+/// How a finished (or abandoned) round went.
+///
+/// Build one with `RoundResult::builder`, which checks that the fields agree
+/// with each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundResult {
+    pub difficulty: Difficulty,
+    pub won: bool,
+    pub attempts: u32,
+    pub secret: u32,
+    /// Every guess, in order.
+    pub history: Vec<u32>,
+    /// How long the round took, when it was timed.
+    pub duration: Option<Duration>,
+    pub score: i32,
+}
+
+/// Error returned by `RoundResultBuilder::build` for a result that can't be right.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    MissingSecret,
+    /// A round can't be won without a single guess.
+    WonWithoutAttempts,
+    SecretOutOfRange {
+        secret: u32,
+        range: RangeInclusive<u32>,
+    },
+    /// The number of guesses in the history differs from `attempts`.
+    HistoryMismatch {
+        attempts: u32,
+        history: usize,
+    },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildError::MissingSecret => write!(f, "the secret was never set"),
+            BuildError::WonWithoutAttempts => write!(f, "a won round needs at least one attempt"),
+            BuildError::SecretOutOfRange { secret, range } => write!(
+                f,
+                "secret {secret} is not between {} and {}",
+                range.start(),
+                range.end()
+            ),
+            BuildError::HistoryMismatch { attempts, history } => write!(
+                f,
+                "{attempts} attempts but {history} guesses in the history"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+impl RoundResult {
+    pub fn builder(difficulty: Difficulty) -> RoundResultBuilder {
+        RoundResultBuilder {
+            difficulty,
+            won: false,
+            attempts: 0,
+            secret: None,
+            history: Vec::new(),
+            duration: None,
+            score: 0,
+        }
+    }
+}
+
+/// Collects the fields of a `RoundResult`; see `RoundResult::builder`.
+#[derive(Debug, Clone)]
+pub struct RoundResultBuilder {
+    difficulty: Difficulty,
+    won: bool,
+    attempts: u32,
+    secret: Option<u32>,
+    history: Vec<u32>,
+    duration: Option<Duration>,
+    score: i32,
+}
+
+impl RoundResultBuilder {
+    pub fn won(mut self, won: bool) -> RoundResultBuilder {
+        self.won = won;
+        self
+    }
+
+    pub fn attempts(mut self, attempts: u32) -> RoundResultBuilder {
+        self.attempts = attempts;
+        self
+    }
+
+    pub fn secret(mut self, secret: u32) -> RoundResultBuilder {
+        self.secret = Some(secret);
+        self
+    }
+
+    pub fn history(mut self, history: Vec<u32>) -> RoundResultBuilder {
+        self.history = history;
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> RoundResultBuilder {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn score(mut self, score: i32) -> RoundResultBuilder {
+        self.score = score;
+        self
+    }
+
+    /// Checks the invariants and returns the result, or the first one that failed.
+    pub fn build(self) -> Result<RoundResult, BuildError> {
+        let secret = self.secret.ok_or(BuildError::MissingSecret)?;
+        if self.won && self.attempts == 0 {
+            return Err(BuildError::WonWithoutAttempts);
+        }
+        let range = self.difficulty.range();
+        if !range.contains(&secret) {
+            return Err(BuildError::SecretOutOfRange { secret, range });
+        }
+        if self.history.len() != self.attempts as usize {
+            return Err(BuildError::HistoryMismatch {
+                attempts: self.attempts,
+                history: self.history.len(),
+            });
+        }
+        Ok(RoundResult {
+            difficulty: self.difficulty,
+            won: self.won,
+            attempts: self.attempts,
+            secret,
+            history: self.history,
+            duration: self.duration,
+            score: self.score,
+        })
+    }
+}
+
+/// Updates the streak with `result` and returns the level it unlocked, if any.
+///
+/// Only rounds at the hardest unlocked level count: a win there extends the
+/// streak and a loss resets it, while easier rounds leave it alone.
+pub fn maybe_unlock(profile: &mut Profile, result: &RoundResult) -> Option<Difficulty> {
+    if result.difficulty != profile.unlocked {
+        return None;
+    }
+    if !result.won {
+        profile.streak = 0;
+        return None;
+    }
+    profile.streak += 1;
+    let next = profile.unlocked.next()?;
+    if profile.streak < WINS_TO_UNLOCK {
+        return None;
+    }
+    profile.unlocked = next;
+    profile.streak = 0;
+    Some(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(difficulty: Difficulty, won: bool) -> RoundResult {
+        let secret = *difficulty.range().start();
+        RoundResult::builder(difficulty)
+            .won(won)
+            .attempts(1)
+            .secret(secret)
+            .history(vec![secret])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn three_wins_in_a_row_unlock_each_level_in_turn() {
+        let mut profile = Profile::default();
+        for (level, next) in [
+            (Difficulty::Medium, Difficulty::Hard),
+            (Difficulty::Hard, Difficulty::Expert),
+        ] {
+            assert_eq!(maybe_unlock(&mut profile, &round(level, true)), None);
+            assert_eq!(maybe_unlock(&mut profile, &round(level, true)), None);
+            assert_eq!(profile.streak, 2);
+            assert_eq!(maybe_unlock(&mut profile, &round(level, true)), Some(next));
+            assert_eq!(
+                profile,
+                Profile {
+                    unlocked: next,
+                    streak: 0
+                }
+            );
+        }
+        // There is nothing past expert, so wins there only build the streak
+        for _ in 0..WINS_TO_UNLOCK {
+            assert_eq!(
+                maybe_unlock(&mut profile, &round(Difficulty::Expert, true)),
+                None
+            );
+        }
+        assert_eq!(profile.unlocked, Difficulty::Expert);
+    }
+
+    #[test]
+    fn a_loss_at_the_top_level_resets_the_streak() {
+        let mut profile = Profile::default();
+        maybe_unlock(&mut profile, &round(Difficulty::Medium, true));
+        maybe_unlock(&mut profile, &round(Difficulty::Medium, true));
+        maybe_unlock(&mut profile, &round(Difficulty::Medium, false));
+        assert_eq!(profile.streak, 0);
+        assert_eq!(profile.unlocked, Difficulty::Medium);
+    }
+
+    #[test]
+    fn easier_rounds_leave_the_streak_alone() {
+        let mut profile = Profile {
+            unlocked: Difficulty::Medium,
+            streak: 2,
+        };
+        assert_eq!(
+            maybe_unlock(&mut profile, &round(Difficulty::Easy, true)),
+            None
+        );
+        assert_eq!(
+            maybe_unlock(&mut profile, &round(Difficulty::Easy, false)),
+            None
+        );
+        assert_eq!(profile.streak, 2);
+    }
+
+    #[test]
+    fn a_locked_level_is_refused_with_the_way_to_unlock_it() {
+        let profile = Profile::default();
+        assert_eq!(profile.check(Difficulty::Easy), Ok(()));
+        assert_eq!(profile.check(Difficulty::Medium), Ok(()));
+        let locked = profile.check(Difficulty::Hard).unwrap_err();
+        assert_eq!(
+            locked.to_string(),
+            "hard is locked. Win 3 medium games in a row to unlock hard."
+        );
+        // Skipping a level still points at the next one, not the one asked for
+        let locked = profile.check(Difficulty::Expert).unwrap_err();
+        assert_eq!(
+            locked.to_string(),
+            "expert is locked. Win 3 medium games in a row to unlock hard."
+        );
+    }
+
+    #[test]
+    fn a_consistent_result_builds() {
+        let result = RoundResult::builder(Difficulty::Medium)
+            .won(true)
+            .attempts(3)
+            .secret(65)
+            .history(vec![50, 75, 65])
+            .duration(Duration::from_secs(12))
+            .score(8)
+            .build();
+        assert_eq!(
+            result,
+            Ok(RoundResult {
+                difficulty: Difficulty::Medium,
+                won: true,
+                attempts: 3,
+                secret: 65,
+                history: vec![50, 75, 65],
+                duration: Some(Duration::from_secs(12)),
+                score: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn each_broken_invariant_is_reported() {
+        let base = || RoundResult::builder(Difficulty::Easy).secret(4);
+        let cases = [
+            (
+                RoundResult::builder(Difficulty::Easy),
+                BuildError::MissingSecret,
+                "the secret was never set",
+            ),
+            (
+                base().won(true),
+                BuildError::WonWithoutAttempts,
+                "a won round needs at least one attempt",
+            ),
+            (
+                base().secret(11),
+                BuildError::SecretOutOfRange {
+                    secret: 11,
+                    range: 1..=10,
+                },
+                "secret 11 is not between 1 and 10",
+            ),
+            (
+                base().attempts(2).history(vec![4]),
+                BuildError::HistoryMismatch {
+                    attempts: 2,
+                    history: 1,
+                },
+                "2 attempts but 1 guesses in the history",
+            ),
+        ];
+        for (builder, expected, message) in cases {
+            let err = builder.build().unwrap_err();
+            assert_eq!(err.to_string(), message);
+            assert_eq!(err, expected);
+        }
+    }
+
+    #[test]
+    fn a_lost_round_may_have_no_attempts() {
+        let result = RoundResult::builder(Difficulty::Easy)
+            .secret(4)
+            .build()
+            .unwrap();
+        assert!(!result.won);
+        assert!(result.history.is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+// Hints that tell a property of the secret ("the number is divisible by 3")
+// instead of narrowing the range. Each one is chosen from a small catalog to
+// split the numbers still possible as evenly as it can, which is the most
+// information a single fact can carry, but never so sharply that it gives the
+// secret away on its own
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::range_math;
+
+/// A property a number can have, each splitting numbers into classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    /// Even or odd.
+    Parity,
+    /// Whether the number is a multiple of this.
+    DivisibleBy(u32),
+    /// The sum of the decimal digits.
+    DigitSum,
+    /// Prime or not.
+    Prime,
+}
+
+/// Every property a hint can tell, in the order ties are broken.
+pub const CATALOG: [Property; 5] = [
+    Property::Parity,
+    Property::DivisibleBy(3),
+    Property::DivisibleBy(5),
+    Property::DigitSum,
+    Property::Prime,
+];
+
+/// Property hints look at every candidate, so they are refused on ranges wider than this.
+pub const MAX_CANDIDATES: u64 = 100_000;
+
+fn digit_sum(mut n: u32) -> u32 {
+    let mut sum = 0;
+    while n > 0 {
+        sum += n % 10;
+        n /= 10;
+    }
+    sum
+}
+
+fn is_prime(n: u32) -> bool {
+    if n < 2 {
+        return false;
+    }
+    // Trial division up to the square root; d * d in u64 so it can't overflow
+    (2..)
+        .take_while(|&d| d as u64 * d as u64 <= n as u64)
+        .all(|d| !n.is_multiple_of(d))
+}
+
+impl Property {
+    /// Which class `n` falls in: two numbers agree on the property exactly when
+    /// their classes are equal.
+    pub fn class(self, n: u32) -> u32 {
+        match self {
+            Property::Parity => n % 2,
+            Property::DivisibleBy(d) => n.is_multiple_of(d) as u32,
+            Property::DigitSum => digit_sum(n),
+            Property::Prime => is_prime(n) as u32,
+        }
+    }
+}
+
+/// One property, told truthfully about the secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyHint {
+    pub property: Property,
+    /// The secret's class for `property`.
+    pub class: u32,
+}
+
+impl PropertyHint {
+    pub fn about(property: Property, secret: u32) -> PropertyHint {
+        PropertyHint {
+            property,
+            class: property.class(secret),
+        }
+    }
+
+    /// Whether `n` has the property the hint says the secret has.
+    pub fn admits(&self, n: u32) -> bool {
+        self.property.class(n) == self.class
+    }
+}
+
+impl fmt::Display for PropertyHint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.property, self.class) {
+            (Property::Parity, 0) => write!(f, "The number is even."),
+            (Property::Parity, _) => write!(f, "The number is odd."),
+            (Property::DivisibleBy(d), 0) => write!(f, "The number is not divisible by {d}."),
+            (Property::DivisibleBy(d), _) => write!(f, "The number is divisible by {d}."),
+            (Property::DigitSum, sum) => write!(f, "The digit sum is {sum}."),
+            (Property::Prime, 0) => write!(f, "It is not prime."),
+            (Property::Prime, _) => write!(f, "It is prime."),
+        }
+    }
+}
+
+/// Why no property hint could be given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PropertyError {
+    /// More than MAX_CANDIDATES numbers are still possible.
+    TooManyCandidates(u64),
+    /// Every property not told yet would leave only the secret, or rule nothing out.
+    NoSafeProperty,
+}
+
+impl fmt::Display for PropertyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropertyError::TooManyCandidates(n) => write!(
+                f,
+                "{n} numbers are still possible, too many to pick a property from (at most {MAX_CANDIDATES})"
+            ),
+            PropertyError::NoSafeProperty => write!(
+                f,
+                "every property left would either give the number away or tell you nothing new"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PropertyError {}
+
+/// How evenly `property` splits `candidates`, as the entropy of the split in
+/// bits: 1.0 for two equal halves, 0.0 when every candidate is in one class.
+pub fn split_bits(property: Property, candidates: &[u32]) -> f64 {
+    let mut classes: Vec<u32> = candidates.iter().map(|&n| property.class(n)).collect();
+    classes.sort_unstable();
+    let total = candidates.len() as f64;
+    classes
+        .chunk_by(|a, b| a == b)
+        .map(|class| {
+            let p = class.len() as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The property hints told so far in a round, so each one says something new.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyHints {
+    given: Vec<PropertyHint>,
+}
+
+impl PropertyHints {
+    pub fn new() -> PropertyHints {
+        PropertyHints::default()
+    }
+
+    /// The numbers in `feasible` that agree with every hint told so far.
+    pub fn candidates(&self, feasible: &RangeInclusive<u32>) -> Result<Vec<u32>, PropertyError> {
+        let width = range_math::width(feasible);
+        if width > MAX_CANDIDATES {
+            return Err(PropertyError::TooManyCandidates(width));
+        }
+        Ok(feasible
+            .clone()
+            .filter(|&n| self.given.iter().all(|hint| hint.admits(n)))
+            .collect())
+    }
+
+    /// Picks the most informative property not told yet, without telling it.
+    ///
+    /// A property is only a candidate if, told about `secret`, it would leave
+    /// more than one number possible and split the candidates at all; among
+    /// those, the one whose split has the most entropy wins, ties going to the
+    /// earliest in CATALOG.
+    pub fn choose(
+        &self,
+        feasible: &RangeInclusive<u32>,
+        secret: u32,
+    ) -> Result<PropertyHint, PropertyError> {
+        let candidates = self.candidates(feasible)?;
+        let mut best: Option<(PropertyHint, f64)> = None;
+        for property in CATALOG {
+            if self.given.iter().any(|hint| hint.property == property) {
+                continue;
+            }
+            let hint = PropertyHint::about(property, secret);
+            if candidates.iter().filter(|&&n| hint.admits(n)).count() <= 1 {
+                continue;
+            }
+            let bits = split_bits(property, &candidates);
+            if bits > 0.0 && best.is_none_or(|(_, best_bits)| bits > best_bits) {
+                best = Some((hint, bits));
+            }
+        }
+        best.map(|(hint, _)| hint)
+            .ok_or(PropertyError::NoSafeProperty)
+    }
+
+    /// Records a hint as told, so the property isn't chosen again.
+    pub fn give(&mut self, hint: PropertyHint) {
+        self.given.push(hint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_bits_measures_how_evenly_a_property_splits() {
+        let candidates = [1, 2, 3, 4];
+        assert_eq!(split_bits(Property::Parity, &candidates), 1.0);
+        assert_eq!(split_bits(Property::DigitSum, &candidates), 2.0);
+        assert_eq!(split_bits(Property::DivisibleBy(5), &candidates), 0.0);
+        let third = split_bits(Property::DivisibleBy(3), &candidates);
+        assert!((third - 0.811_278_124_459_132_8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn the_best_split_wins_and_ties_go_to_the_catalog_order() {
+        // Parity and Prime both split 1..=4 in half; Parity comes first
+        let hint = PropertyHints::new().choose(&(1..=4), 2).unwrap();
+        assert_eq!(hint, PropertyHint::about(Property::Parity, 2));
+        assert_eq!(hint.to_string(), "The number is even.");
+        // On 1..=100 the digit sum carries the most information
+        let hint = PropertyHints::new().choose(&(1..=100), 42).unwrap();
+        assert_eq!(hint.to_string(), "The digit sum is 6.");
+    }
+
+    #[test]
+    fn a_property_that_would_give_the_secret_away_is_refused() {
+        // Every number in 1..=4 has its own digit sum, so that split would
+        // be best if it didn't leave only the secret
+        let hint = PropertyHints::new().choose(&(1..=4), 2).unwrap();
+        assert_ne!(hint.property, Property::DigitSum);
+        let mut hints = PropertyHints::new();
+        hints.give(hint);
+        // Of 2 and 4, only 2 is prime, and digit sums still tell them apart
+        assert_eq!(
+            hints.choose(&(1..=4), 2),
+            Err(PropertyError::NoSafeProperty)
+        );
+    }
+
+    #[test]
+    fn each_hint_in_a_round_is_a_new_property() {
+        let mut hints = PropertyHints::new();
+        let mut told = Vec::new();
+        while let Ok(hint) = hints.choose(&(1..=100), 42) {
+            assert!(hint.admits(42));
+            assert!(
+                !told.contains(&hint.property),
+                "{:?} told twice",
+                hint.property
+            );
+            told.push(hint.property);
+            hints.give(hint);
+        }
+        assert_eq!(
+            told,
+            [
+                Property::DigitSum,
+                Property::Parity,
+                Property::DivisibleBy(5)
+            ]
+        );
+        assert_eq!(hints.candidates(&(1..=100)).unwrap(), [6, 24, 42]);
+    }
+
+    #[test]
+    fn wide_ranges_are_refused() {
+        assert_eq!(
+            PropertyHints::new().choose(&(0..=u32::MAX), 7),
+            Err(PropertyError::TooManyCandidates(1 << 32))
+        );
+    }
+}
@@ -0,0 +1,113 @@
+// Writing and reading numbers in bases other than ten, for --base
+// Only the digits are written, with no 0x-style prefix; reading accepts the
+// prefix that matches the base, so "0x41" and "41" are both 65 in base 16
+/// The bases --base accepts.
+pub const BASES: [u32; 4] = [2, 8, 10, 16];
+
+/// `n` written in `base`, with lowercase letters for digits above 9.
+///
+/// Panics if `base` is outside 2..=36.
+pub fn format_in_base(mut n: u32, base: u32) -> String {
+    assert!(
+        (2..=36).contains(&base),
+        "base {base} is not between 2 and 36"
+    );
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit(n % base, base).expect("n % base is a digit of base"));
+        n /= base;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+/// Reads `text` as a number in `base`, allowing the base's own prefix
+/// (0b, 0o or 0x); None if it isn't one.
+pub fn parse_in_base(text: &str, base: u32) -> Option<u32> {
+    let prefix = match base {
+        2 => Some("0b"),
+        8 => Some("0o"),
+        16 => Some("0x"),
+        _ => None,
+    };
+    let digits = prefix
+        .and_then(|prefix| {
+            text.strip_prefix(prefix)
+                .or_else(|| text.strip_prefix(&prefix.to_uppercase()))
+        })
+        .unwrap_or(text);
+    // from_str_radix would also take a leading '+'
+    if digits.starts_with('+') {
+        return None;
+    }
+    u32::from_str_radix(digits, base).ok()
+}
+
+/// What a base is called, for telling the player which one is in use.
+pub fn base_name(base: u32) -> &'static str {
+    match base {
+        2 => "binary",
+        8 => "octal",
+        10 => "decimal",
+        16 => "hexadecimal",
+        _ => "an unusual base",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sixty_five_in_each_base() {
+        let expected = ["1000001", "101", "65", "41"];
+        for (base, digits) in BASES.into_iter().zip(expected) {
+            assert_eq!(format_in_base(65, base), digits, "base {base}");
+            assert_eq!(parse_in_base(digits, base), Some(65), "base {base}");
+        }
+    }
+
+    #[test]
+    fn every_base_round_trips() {
+        for base in BASES {
+            for n in [0, 1, 7, 8, 15, 16, 255, 1000, u32::MAX] {
+                assert_eq!(parse_in_base(&format_in_base(n, base), base), Some(n));
+            }
+        }
+    }
+
+    #[test]
+    fn each_base_takes_only_its_own_prefix() {
+        assert_eq!(parse_in_base("0b101", 2), Some(5));
+        assert_eq!(parse_in_base("0o17", 8), Some(15));
+        assert_eq!(parse_in_base("0x41", 16), Some(65));
+        assert_eq!(parse_in_base("0XfF", 16), Some(255));
+        assert_eq!(parse_in_base("0x41", 10), None);
+        assert_eq!(parse_in_base("0b101", 16), Some(0xb101));
+    }
+
+    #[test]
+    fn digits_outside_the_base_are_rejected() {
+        assert_eq!(parse_in_base("2", 2), None);
+        assert_eq!(parse_in_base("8", 8), None);
+        assert_eq!(parse_in_base("a", 10), None);
+        assert_eq!(parse_in_base("g", 16), None);
+        assert_eq!(parse_in_base("+5", 10), None);
+        assert_eq!(parse_in_base("", 10), None);
+        assert_eq!(parse_in_base("100000000", 16), None);
+    }
+
+    #[test]
+    fn letters_are_lowercase() {
+        assert_eq!(format_in_base(0xabc, 16), "abc");
+        assert_eq!(format_in_base(35, 36), "z");
+    }
+
+    #[test]
+    #[should_panic(expected = "base 1 is not between 2 and 36")]
+    fn a_base_below_two_panics() {
+        format_in_base(5, 1);
+    }
+}
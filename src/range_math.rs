@@ -0,0 +1,189 @@
+// Arithmetic on inclusive u32 ranges, kept in one place
+// Widths are computed in u64 and scaled positions in u128, so even the full
+// range 0..=u32::MAX can't overflow, and an empty range (start > end) gives
+// a harmless answer instead of a subtraction that wraps or panics
+use std::ops::RangeInclusive;
+
+/// How many numbers an inclusive range holds, as a u64 so `0..=u32::MAX` fits.
+pub fn width(range: &RangeInclusive<u32>) -> u64 {
+    if range.is_empty() {
+        0
+    } else {
+        *range.end() as u64 - *range.start() as u64 + 1
+    }
+}
+
+/// The number `numerator / denominator` of the way from the start of `range`
+/// to its end, rounded down. An empty range gives its start.
+///
+/// Panics unless `numerator <= denominator` and `denominator > 0`.
+pub fn fraction_point(range: &RangeInclusive<u32>, numerator: u64, denominator: u64) -> u32 {
+    assert!(
+        numerator <= denominator && denominator > 0,
+        "{numerator}/{denominator} is not a fraction from 0 to 1"
+    );
+    if range.is_empty() {
+        return *range.start();
+    }
+    let span = (range.end() - range.start()) as u128;
+    // At most span, so adding it to the start stays within the range
+    range.start() + (span * numerator as u128 / denominator as u128) as u32
+}
+
+/// The guess that splits `range` most evenly, i.e. the binary search choice.
+pub fn midpoint(range: &RangeInclusive<u32>) -> u32 {
+    // low + (high - low) / 2 never overflows, unlike (low + high) / 2
+    fraction_point(range, 1, 2)
+}
+
+/// The range `spread_percent` percent of `center` either side of it, so
+/// `range_from_center(500, 10)` is `450..=550`. It always holds at least
+/// `center` itself, and is cut off at 0 and `u32::MAX`.
+pub fn range_from_center(center: u32, spread_percent: u8) -> RangeInclusive<u32> {
+    // In u64, since center * 255 doesn't fit a u32, and rounded down
+    let half = center as u64 * spread_percent as u64 / 100;
+    let low = (center as u64).saturating_sub(half) as u32;
+    let high = (center as u64 + half).min(u32::MAX as u64) as u32;
+    low..=high
+}
+
+/// Share of the candidates in `before` that are gone in `after`, from 0 to 100.
+pub fn percent_eliminated(before: &RangeInclusive<u32>, after: &RangeInclusive<u32>) -> f64 {
+    let (before, after) = (width(before), width(after));
+    if before == 0 {
+        return 0.0;
+    }
+    100.0 * before.saturating_sub(after) as f64 / before as f64
+}
+
+/// How many candidates in `before` are gone in `after`. On the first guess
+/// `before` is the whole range; on the winning one `after` is just the
+/// secret. Only `after` emptying all of `0..=u32::MAX` is too many for a
+/// u32, and that gives `u32::MAX`.
+pub fn eliminated(before: &RangeInclusive<u32>, after: &RangeInclusive<u32>) -> u32 {
+    u32::try_from(width(before).saturating_sub(width(after))).unwrap_or(u32::MAX)
+}
+
+/// Which of `cells` equal slots `value` lands in when `range` is laid out
+/// across them, from 0 to `cells - 1`, as when drawing a bar. Values outside
+/// the range land in the first or last slot; with no cells or an empty range
+/// everything is slot 0.
+pub fn scale(value: u32, range: &RangeInclusive<u32>, cells: usize) -> usize {
+    let width = width(range);
+    if cells == 0 || width == 0 {
+        return 0;
+    }
+    let offset = value.clamp(*range.start(), *range.end()) - range.start();
+    // offset < width, so the result is below cells
+    (offset as u128 * cells as u128 / width as u128) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FULL: RangeInclusive<u32> = 0..=u32::MAX;
+    const TOP: RangeInclusive<u32> = u32::MAX..=u32::MAX;
+    // Empty, as its start is past its end
+    #[allow(clippy::reversed_empty_ranges)]
+    const EMPTY: RangeInclusive<u32> = 5..=4;
+
+    #[test]
+    fn width_at_the_bounds() {
+        assert_eq!(width(&FULL), 1 << 32);
+        assert_eq!(width(&(0..=0)), 1);
+        assert_eq!(width(&(7..=7)), 1);
+        assert_eq!(width(&TOP), 1);
+        assert_eq!(width(&(u32::MAX - 1..=u32::MAX)), 2);
+        assert_eq!(width(&EMPTY), 0);
+    }
+
+    #[test]
+    fn midpoint_at_the_bounds() {
+        assert_eq!(midpoint(&FULL), u32::MAX / 2);
+        assert_eq!(midpoint(&(0..=0)), 0);
+        assert_eq!(midpoint(&(7..=7)), 7);
+        assert_eq!(midpoint(&TOP), u32::MAX);
+        assert_eq!(midpoint(&(u32::MAX - 1..=u32::MAX)), u32::MAX - 1);
+        assert_eq!(midpoint(&(u32::MAX - 2..=u32::MAX)), u32::MAX - 1);
+    }
+
+    #[test]
+    fn percent_eliminated_at_the_bounds() {
+        assert_eq!(percent_eliminated(&FULL, &FULL), 0.0);
+        assert_eq!(
+            percent_eliminated(&FULL, &TOP),
+            100.0 * (u32::MAX as f64) / (1u64 << 32) as f64
+        );
+        assert_eq!(percent_eliminated(&FULL, &(0..=u32::MAX / 2)), 50.0);
+        // A single value has nothing left to eliminate
+        assert_eq!(percent_eliminated(&TOP, &TOP), 0.0);
+        assert_eq!(percent_eliminated(&(7..=7), &(7..=7)), 0.0);
+        assert_eq!(percent_eliminated(&EMPTY, &TOP), 0.0);
+        assert_eq!(percent_eliminated(&(1..=4), &EMPTY), 100.0);
+    }
+
+    #[test]
+    fn eliminated_at_the_bounds() {
+        assert_eq!(eliminated(&FULL, &TOP), u32::MAX);
+        assert_eq!(eliminated(&FULL, &FULL), 0);
+        assert_eq!(eliminated(&TOP, &TOP), 0);
+    }
+
+    #[test]
+    fn scale_at_the_bounds() {
+        assert_eq!(scale(0, &FULL, 80), 0);
+        assert_eq!(scale(u32::MAX, &FULL, 80), 79);
+        assert_eq!(scale(u32::MAX / 2, &FULL, 80), 39);
+        assert_eq!(scale(u32::MAX / 2 + 1, &FULL, 80), 40);
+        // A single value always lands in the first slot
+        assert_eq!(scale(u32::MAX, &TOP, 80), 0);
+        assert_eq!(scale(7, &(7..=7), 80), 0);
+        // Values outside the range are clamped to it
+        assert_eq!(scale(0, &TOP, 80), 0);
+        assert_eq!(scale(u32::MAX, &(0..=9), 10), 9);
+        assert_eq!(scale(u32::MAX, &FULL, 0), 0);
+    }
+
+    #[test]
+    fn range_from_center_at_the_bounds() {
+        assert_eq!(range_from_center(u32::MAX, 255), 0..=u32::MAX);
+        assert_eq!(range_from_center(0, 255), 0..=0);
+        assert_eq!(range_from_center(u32::MAX, 0), TOP);
+    }
+
+    #[test]
+    fn range_from_center_spreads_either_side() {
+        assert_eq!(range_from_center(500, 10), 450..=550);
+        assert_eq!(range_from_center(1000, 50), 500..=1500);
+        assert_eq!(range_from_center(100, 100), 0..=200);
+        // 10% of 55 is 5.5, rounded down
+        assert_eq!(range_from_center(55, 10), 50..=60);
+    }
+
+    #[test]
+    fn a_zero_spread_is_just_the_center() {
+        assert_eq!(range_from_center(500, 0), 500..=500);
+        assert_eq!(range_from_center(0, 0), 0..=0);
+    }
+
+    #[test]
+    fn eliminated_by_the_first_guess_counts_from_the_whole_range() {
+        // 50 too small on 1..=100
+        assert_eq!(eliminated(&(1..=100), &(51..=100)), 50);
+    }
+
+    #[test]
+    fn eliminated_by_a_narrowing_guess() {
+        // Then 75 too big
+        assert_eq!(eliminated(&(51..=100), &(51..=74)), 26);
+        // A guess outside what was left rules nothing out
+        assert_eq!(eliminated(&(51..=74), &(51..=74)), 0);
+    }
+
+    #[test]
+    fn eliminated_by_the_winning_guess_leaves_only_the_secret() {
+        assert_eq!(eliminated(&(51..=74), &(65..=65)), 23);
+        assert_eq!(eliminated(&(1..=100), &(42..=42)), 99);
+    }
+}
@@ -0,0 +1,253 @@
+// The last few secrets of each range, so quick rounds on a small range don't
+// deal the same number twice in a row
+// Every run of the game plays one round, so the secrets are kept in the
+// leaderboard file between runs. A secret that came up recently is redrawn
+// from a new seed worked out from the old one, so the same seed and the same
+// recent secrets always give the same round, and the seed that was finally
+// used replays it on its own. When the range is too small to avoid every
+// recent secret, the first draw is kept and the repeat is allowed
+use std::ops::RangeInclusive;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::analysis;
+use crate::json::Value;
+
+/// The most recent secrets --no-repeat can be asked to avoid.
+pub const MAX_WINDOW: usize = 100;
+
+/// How many times a recent secret is redrawn before the repeat is allowed.
+pub const MAX_REDRAWS: u32 = 32;
+
+// Added to a seed to get the next one to try, as in SplitMix64. StdRng
+// scrambles its seed, so nearby seeds still give unrelated secrets
+const SEED_STEP: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A secret for a new round, with the seed that gives it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Draw {
+    /// Passing this to `Game::seeded` gives `secret`.
+    pub seed: u64,
+    pub secret: u32,
+    /// How many recent secrets were drawn and thrown back first.
+    pub redraws: u32,
+    /// The secret is a recent one after all, because no other could be found.
+    pub repeated: bool,
+}
+
+/// The latest secrets of every range played, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentSecrets {
+    // A handful of ranges at most, as in Leaderboard
+    ranges: Vec<(RangeInclusive<u32>, Vec<u32>)>,
+}
+
+impl RecentSecrets {
+    pub fn new() -> RecentSecrets {
+        RecentSecrets::default()
+    }
+
+    /// The secrets kept for `range`, oldest first.
+    pub fn recent(&self, range: &RangeInclusive<u32>) -> &[u32] {
+        self.ranges
+            .iter()
+            .find(|(r, _)| r == range)
+            .map_or(&[], |(_, secrets)| secrets.as_slice())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Adds `secret` as the newest for `range`, keeping only the last `window`.
+    pub fn remember(&mut self, range: &RangeInclusive<u32>, secret: u32, window: usize) {
+        let index = match self.ranges.iter().position(|(r, _)| r == range) {
+            Some(index) => index,
+            None => {
+                self.ranges.push((range.clone(), Vec::new()));
+                self.ranges.len() - 1
+            }
+        };
+        let secrets = &mut self.ranges[index].1;
+        secrets.push(secret);
+        let extra = secrets.len().saturating_sub(window);
+        secrets.drain(..extra);
+    }
+
+    /// A secret from `range` that isn't one of its last `window`, starting
+    /// from `seed`. With nothing recent this is the secret `Game::seeded`
+    /// would pick for `seed`.
+    ///
+    /// When every number in the range is recent, or `MAX_REDRAWS` redraws
+    /// all come up recent, the first draw is returned with `repeated` set.
+    pub fn draw(&self, range: &RangeInclusive<u32>, seed: u64, window: usize) -> Draw {
+        let recent = self.recent(range);
+        let recent = &recent[recent.len().saturating_sub(window)..];
+        let secret_for = |seed: u64| StdRng::seed_from_u64(seed).random_range(range.clone());
+        let first = Draw {
+            seed,
+            secret: secret_for(seed),
+            redraws: 0,
+            repeated: false,
+        };
+        let mut distinct = recent.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() as u64 >= analysis::width(range) {
+            return Draw {
+                repeated: true,
+                ..first
+            };
+        }
+        let mut draw = first;
+        while recent.contains(&draw.secret) {
+            if draw.redraws == MAX_REDRAWS {
+                return Draw {
+                    repeated: true,
+                    ..first
+                };
+            }
+            let seed = draw.seed.wrapping_add(SEED_STEP);
+            draw = Draw {
+                seed,
+                secret: secret_for(seed),
+                redraws: draw.redraws + 1,
+                repeated: false,
+            };
+        }
+        draw
+    }
+
+    /// As kept in the leaderboard file: `[{"range":[1,30],"secrets":[4,17,9]}]`.
+    pub fn to_json(&self) -> Value {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|(range, secrets)| {
+                Value::Object(vec![
+                    (
+                        "range".to_string(),
+                        Value::Array(vec![Value::from(*range.start()), Value::from(*range.end())]),
+                    ),
+                    (
+                        "secrets".to_string(),
+                        Value::Array(secrets.iter().map(|&s| Value::from(s)).collect()),
+                    ),
+                ])
+            })
+            .collect();
+        Value::Array(ranges)
+    }
+
+    /// Reads what `to_json` wrote; None if it is laid out any other way.
+    pub fn from_json(value: &Value) -> Option<RecentSecrets> {
+        let mut recent = RecentSecrets::new();
+        for entry in value.as_array()? {
+            let [start, end] = entry.get("range")?.as_array()? else {
+                return None;
+            };
+            let secrets = entry
+                .get("secrets")?
+                .as_array()?
+                .iter()
+                .map(Value::as_u32)
+                .collect::<Option<Vec<u32>>>()?;
+            recent
+                .ranges
+                .push((start.as_u32()?..=end.as_u32()?, secrets));
+        }
+        Some(recent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    // The seed `redraws` steps on from `seed`
+    fn stepped(seed: u64, redraws: u64) -> u64 {
+        seed.wrapping_add(SEED_STEP.wrapping_mul(redraws))
+    }
+
+    fn draw(seed: u64, secret: u32, redraws: u32) -> Draw {
+        Draw {
+            seed,
+            secret,
+            redraws,
+            repeated: false,
+        }
+    }
+
+    #[test]
+    fn fixed_seeds_give_a_fixed_sequence_avoiding_the_last_three() {
+        let range = 1..=5;
+        let mut recent = RecentSecrets::new();
+        let mut drawn = Vec::new();
+        for seed in 7..15 {
+            let next = recent.draw(&range, seed, 3);
+            recent.remember(&range, next.secret, 3);
+            drawn.push(next);
+        }
+        assert_eq!(
+            drawn,
+            [
+                draw(7, 3, 0),
+                draw(8, 4, 0),
+                draw(stepped(9, 2), 1, 2),
+                draw(10, 2, 0),
+                draw(stepped(11, 3), 5, 3),
+                draw(12, 3, 0),
+                draw(stepped(13, 1), 4, 1),
+                draw(stepped(14, 3), 2, 3),
+            ]
+        );
+        assert_eq!(*recent.recent(&range), [3, 4, 2]);
+    }
+
+    #[test]
+    fn the_seed_of_a_draw_replays_its_secret() {
+        let range = 1..=5;
+        let mut recent = RecentSecrets::new();
+        for secret in [1, 3] {
+            recent.remember(&range, secret, 3);
+        }
+        let next = recent.draw(&range, 9, 3);
+        assert!(next.redraws > 0);
+        assert_eq!(Game::seeded(range, next.seed).secret(), next.secret);
+    }
+
+    #[test]
+    fn nothing_recent_keeps_the_first_draw() {
+        let first = RecentSecrets::new().draw(&(1..=3), 7, 3);
+        assert_eq!(first, draw(7, 2, 0));
+    }
+
+    #[test]
+    fn a_range_too_small_to_avoid_repeats_keeps_the_first_draw() {
+        let range = 1..=3;
+        let mut recent = RecentSecrets::new();
+        for secret in [1, 2, 3] {
+            recent.remember(&range, secret, 3);
+        }
+        assert_eq!(
+            recent.draw(&range, 7, 3),
+            Draw {
+                repeated: true,
+                ..draw(7, 2, 0)
+            }
+        );
+    }
+
+    #[test]
+    fn only_the_last_window_secrets_are_kept() {
+        let range = 1..=100;
+        let mut recent = RecentSecrets::new();
+        for secret in [10, 20, 30, 40] {
+            recent.remember(&range, secret, 3);
+        }
+        assert_eq!(*recent.recent(&range), [20, 30, 40]);
+        assert!(recent.recent(&(1..=10)).is_empty());
+    }
+}
@@ -0,0 +1,405 @@
+// A finished (or abandoned) round as plain data, for tools outside the game
+// Unlike a save file, a record keeps every answer the game gave, so a tool
+// reading one can check the answers against the secret with replay_check
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use crate::game::{Game, Guess, GuessOutcome, Turn};
+use crate::json::{self, Value};
+
+/// Bumped whenever the layout of a record changes.
+pub const RECORD_VERSION: u32 = 1;
+
+/// How the recorded round ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordOutcome {
+    Won,
+    /// Quit or saved before finding the secret.
+    Unfinished,
+}
+
+/// Everything about one round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub range: RangeInclusive<u32>,
+    pub secret: u32,
+    pub turns: Vec<Turn>,
+    pub outcome: RecordOutcome,
+    /// Thinking time for each turn, or empty when it wasn't measured.
+    pub durations: Vec<Duration>,
+    /// How close to the secret counted as a win.
+    pub tolerance: u32,
+}
+
+impl Game {
+    /// The round so far as a record. Add thinking times with
+    /// [`GameRecord::with_durations`] if they were measured.
+    pub fn into_record(self) -> GameRecord {
+        let outcome = match self.history().last() {
+            Some(turn) if turn.outcome == GuessOutcome::Correct => RecordOutcome::Won,
+            _ => RecordOutcome::Unfinished,
+        };
+        GameRecord {
+            range: self.range().clone(),
+            secret: self.secret(),
+            turns: self.history().to_vec(),
+            outcome,
+            durations: Vec::new(),
+            tolerance: self.tolerance(),
+        }
+    }
+}
+
+/// A record that contradicts itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReplayError {
+    SecretOutOfRange,
+    /// Turn `index` (counting from 0) guessed outside the range.
+    GuessOutOfRange {
+        index: usize,
+        guess: u32,
+    },
+    /// Turn `index` records an answer the game would not have given.
+    WrongFeedback {
+        index: usize,
+        recorded: GuessOutcome,
+        actual: GuessOutcome,
+    },
+    /// Turn `index` comes after the secret was already found.
+    TurnAfterWin {
+        index: usize,
+    },
+    /// The recorded outcome doesn't match how the turns end.
+    WrongOutcome {
+        recorded: RecordOutcome,
+    },
+    /// There are durations, but not one per turn.
+    DurationCount {
+        durations: usize,
+        turns: usize,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::SecretOutOfRange => write!(f, "the secret is outside the range"),
+            ReplayError::GuessOutOfRange { index, guess } => {
+                write!(f, "turn {index}: guess {guess} is outside the range")
+            }
+            ReplayError::WrongFeedback {
+                index,
+                recorded,
+                actual,
+            } => write!(
+                f,
+                "turn {index}: recorded {} but the game says {}",
+                outcome_name(*recorded),
+                outcome_name(*actual)
+            ),
+            ReplayError::TurnAfterWin { index } => {
+                write!(f, "turn {index} comes after the secret was found")
+            }
+            ReplayError::WrongOutcome { recorded } => {
+                write!(f, "the turns don't end the way {recorded:?} says")
+            }
+            ReplayError::DurationCount { durations, turns } => {
+                write!(f, "{durations} durations for {turns} turns")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// A record that couldn't be read back from JSON.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RecordError {
+    Json(json::ParseError),
+    UnsupportedVersion(u32),
+    /// This field is missing or has the wrong type.
+    Field(&'static str),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Json(err) => write!(f, "{err}"),
+            RecordError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported record version {version} (expected {RECORD_VERSION})"
+            ),
+            RecordError::Field(key) => write!(f, "missing or invalid \"{key}\""),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl GameRecord {
+    pub fn with_durations(mut self, durations: Vec<Duration>) -> GameRecord {
+        self.durations = durations;
+        self
+    }
+
+    /// Plays every turn again against the secret and checks that each
+    /// recorded answer is the one the game gives. Returns the replayed game.
+    pub fn replay_check(&self) -> Result<Game, ReplayError> {
+        if !self.range.contains(&self.secret) {
+            return Err(ReplayError::SecretOutOfRange);
+        }
+        if !self.durations.is_empty() && self.durations.len() != self.turns.len() {
+            return Err(ReplayError::DurationCount {
+                durations: self.durations.len(),
+                turns: self.turns.len(),
+            });
+        }
+        let mut game =
+            Game::with_secret(self.range.clone(), self.secret).with_tolerance(self.tolerance);
+        let mut won = false;
+        for (index, turn) in self.turns.iter().enumerate() {
+            if won {
+                return Err(ReplayError::TurnAfterWin { index });
+            }
+            let guess =
+                Guess::new(turn.guess, game.range()).map_err(|_| ReplayError::GuessOutOfRange {
+                    index,
+                    guess: turn.guess,
+                })?;
+            let actual = game.guess(guess);
+            if actual != turn.outcome {
+                return Err(ReplayError::WrongFeedback {
+                    index,
+                    recorded: turn.outcome,
+                    actual,
+                });
+            }
+            won = actual == GuessOutcome::Correct;
+        }
+        if won != (self.outcome == RecordOutcome::Won) {
+            return Err(ReplayError::WrongOutcome {
+                recorded: self.outcome,
+            });
+        }
+        Ok(game)
+    }
+
+    // {"version":1,"range":[1,100],"secret":42,"tolerance":0,"outcome":"won",
+    //  "turns":[{"guess":50,"outcome":"too-big"},{"guess":42,"outcome":"correct"}],
+    //  "durations_ms":[1200,800]}
+    pub fn to_json(&self) -> String {
+        let turns = self
+            .turns
+            .iter()
+            .map(|turn| {
+                Value::Object(vec![
+                    ("guess".to_string(), Value::from(turn.guess)),
+                    (
+                        "outcome".to_string(),
+                        Value::from(outcome_name(turn.outcome)),
+                    ),
+                ])
+            })
+            .collect();
+        let durations = self
+            .durations
+            .iter()
+            .map(|d| Value::Number(d.as_millis() as f64))
+            .collect();
+        let outcome = match self.outcome {
+            RecordOutcome::Won => "won",
+            RecordOutcome::Unfinished => "unfinished",
+        };
+        Value::Object(vec![
+            ("version".to_string(), Value::from(RECORD_VERSION)),
+            (
+                "range".to_string(),
+                Value::Array(vec![
+                    Value::from(*self.range.start()),
+                    Value::from(*self.range.end()),
+                ]),
+            ),
+            ("secret".to_string(), Value::from(self.secret)),
+            ("tolerance".to_string(), Value::from(self.tolerance)),
+            ("outcome".to_string(), Value::from(outcome)),
+            ("turns".to_string(), Value::Array(turns)),
+            ("durations_ms".to_string(), Value::Array(durations)),
+        ])
+        .to_string()
+    }
+
+    /// Reads a record written by `to_json`. The JSON is only checked for
+    /// shape; call `replay_check` to check the record's contents.
+    pub fn from_json(text: &str) -> Result<GameRecord, RecordError> {
+        let value = json::parse(text).map_err(RecordError::Json)?;
+        let number = |key: &'static str| {
+            value
+                .get(key)
+                .and_then(Value::as_u32)
+                .ok_or(RecordError::Field(key))
+        };
+        let version = number("version")?;
+        if version != RECORD_VERSION {
+            return Err(RecordError::UnsupportedVersion(version));
+        }
+        let range = match value.get("range").and_then(Value::as_array) {
+            Some([start, end]) => match (start.as_u32(), end.as_u32()) {
+                (Some(start), Some(end)) if start <= end => start..=end,
+                _ => return Err(RecordError::Field("range")),
+            },
+            _ => return Err(RecordError::Field("range")),
+        };
+        let outcome = match value.get("outcome").and_then(Value::as_str) {
+            Some("won") => RecordOutcome::Won,
+            Some("unfinished") => RecordOutcome::Unfinished,
+            _ => return Err(RecordError::Field("outcome")),
+        };
+        let turns = value
+            .get("turns")
+            .and_then(Value::as_array)
+            .ok_or(RecordError::Field("turns"))?
+            .iter()
+            .map(|turn| {
+                let guess = turn.get("guess").and_then(Value::as_u32);
+                let outcome = turn
+                    .get("outcome")
+                    .and_then(Value::as_str)
+                    .and_then(outcome_from_name);
+                match (guess, outcome) {
+                    (Some(guess), Some(outcome)) => Ok(Turn { guess, outcome }),
+                    _ => Err(RecordError::Field("turns")),
+                }
+            })
+            .collect::<Result<Vec<Turn>, RecordError>>()?;
+        let durations = value
+            .get("durations_ms")
+            .and_then(Value::as_array)
+            .ok_or(RecordError::Field("durations_ms"))?
+            .iter()
+            .map(|ms| {
+                ms.as_u32()
+                    .map(|ms| Duration::from_millis(ms as u64))
+                    .ok_or(RecordError::Field("durations_ms"))
+            })
+            .collect::<Result<Vec<Duration>, RecordError>>()?;
+        Ok(GameRecord {
+            range,
+            secret: number("secret")?,
+            turns,
+            outcome,
+            durations,
+            tolerance: number("tolerance")?,
+        })
+    }
+}
+
+fn outcome_name(outcome: GuessOutcome) -> &'static str {
+    match outcome {
+        GuessOutcome::TooSmall => "too-small",
+        GuessOutcome::TooBig => "too-big",
+        GuessOutcome::Correct => "correct",
+    }
+}
+
+fn outcome_from_name(name: &str) -> Option<GuessOutcome> {
+    match name {
+        "too-small" => Some(GuessOutcome::TooSmall),
+        "too-big" => Some(GuessOutcome::TooBig),
+        "correct" => Some(GuessOutcome::Correct),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A won round on 1..=100 with secret 42: 50 is too big, 25 too small
+    fn won_round() -> GameRecord {
+        let mut game = Game::with_secret(1..=100, 42);
+        for guess in [50, 25, 42] {
+            game.guess(Guess::new(guess, game.range()).unwrap());
+        }
+        game.into_record().with_durations(vec![
+            Duration::from_millis(1200),
+            Duration::from_millis(800),
+            Duration::from_millis(450),
+        ])
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let record = won_round();
+        assert_eq!(GameRecord::from_json(&record.to_json()), Ok(record));
+        let unfinished = Game::with_secret(0..=u32::MAX, u32::MAX)
+            .with_tolerance(3)
+            .into_record();
+        assert_eq!(GameRecord::from_json(&unfinished.to_json()), Ok(unfinished));
+    }
+
+    #[test]
+    fn an_honest_record_replays() {
+        let record = GameRecord::from_json(&won_round().to_json()).unwrap();
+        let game = record.replay_check().unwrap();
+        assert_eq!(game.attempts(), 3);
+        assert_eq!(game.history(), record.turns);
+    }
+
+    #[test]
+    fn tampered_feedback_is_rejected() {
+        let mut record = won_round();
+        record.turns[0].outcome = GuessOutcome::TooSmall;
+        assert_eq!(
+            record.replay_check().unwrap_err(),
+            ReplayError::WrongFeedback {
+                index: 0,
+                recorded: GuessOutcome::TooSmall,
+                actual: GuessOutcome::TooBig,
+            }
+        );
+    }
+
+    #[test]
+    fn reordered_turns_are_rejected() {
+        // The winning guess moved to the front leaves turns after the win
+        let mut record = won_round();
+        record.turns.rotate_right(1);
+        assert_eq!(
+            record.replay_check().unwrap_err(),
+            ReplayError::TurnAfterWin { index: 1 }
+        );
+        // So does a win moved into the middle
+        let mut record = won_round();
+        record.turns.swap(1, 2);
+        assert_eq!(
+            record.replay_check().unwrap_err(),
+            ReplayError::TurnAfterWin { index: 2 }
+        );
+    }
+
+    #[test]
+    fn tampered_outcome_is_rejected() {
+        let mut record = won_round();
+        record.outcome = RecordOutcome::Unfinished;
+        assert_eq!(
+            record.replay_check().unwrap_err(),
+            ReplayError::WrongOutcome {
+                recorded: RecordOutcome::Unfinished
+            }
+        );
+    }
+
+    #[test]
+    fn a_record_from_a_newer_version_is_refused() {
+        let text = won_round()
+            .to_json()
+            .replace("\"version\":1", "\"version\":2");
+        assert_eq!(
+            GameRecord::from_json(&text),
+            Err(RecordError::UnsupportedVersion(2))
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// Saving a round in progress so it can be resumed later
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::game::{Game, Guess, GuessOutcome};
+use crate::json::{self, Value};
+use crate::persisted_file::{self, Kind};
+
+/// Bumped whenever the layout of a save file changes.
+pub const SAVE_VERSION: u32 = 1;
+
+/// The header save files are written with.
+pub const FILE_KIND: Kind = Kind {
+    name: "save",
+    version: SAVE_VERSION,
+};
+
+// The file looks like:
+// {"version":1,"range":[1,100],"secret":42,"tolerance":0,"attempts":2,
+//  "feasible":[26,49],"history":[50,25]}
+pub fn save_game(path: &Path, game: &Game) -> io::Result<()> {
+    let history = game
+        .history()
+        .iter()
+        .map(|turn| Value::from(turn.guess))
+        .collect();
+    let value = Value::Object(vec![
+        ("version".to_string(), Value::from(SAVE_VERSION)),
+        (
+            "range".to_string(),
+            pair(*game.range().start(), *game.range().end()),
+        ),
+        ("secret".to_string(), Value::from(game.secret())),
+        ("tolerance".to_string(), Value::from(game.tolerance())),
+        ("attempts".to_string(), Value::from(game.attempts())),
+        (
+            "feasible".to_string(),
+            pair(*game.feasible().start(), *game.feasible().end()),
+        ),
+        ("history".to_string(), Value::Array(history)),
+    ]);
+    persisted_file::write(path, FILE_KIND, &value.to_string())
+}
+
+/// Reads a round saved by `save_game`.
+///
+/// The guesses are replayed against the saved secret, so a file whose
+/// attempts or feasible range don't match its own history is rejected
+/// instead of producing a game that contradicts itself.
+pub fn load_game(path: &Path) -> io::Result<Game> {
+    let text = persisted_file::read(path, FILE_KIND)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such save file"))?;
+    let value = json::parse(&text).map_err(|err| invalid(err.to_string()))?;
+
+    let version = field(&value, "version")?;
+    if version != SAVE_VERSION {
+        return Err(invalid(format!(
+            "unsupported save version {version} (expected {SAVE_VERSION})"
+        )));
+    }
+    let range = read_pair(&value, "range")?;
+    let secret = field(&value, "secret")?;
+    let tolerance = field(&value, "tolerance")?;
+    let attempts = field(&value, "attempts")?;
+    let feasible = read_pair(&value, "feasible")?;
+    let history = value
+        .get("history")
+        .and_then(Value::as_array)
+        .ok_or_else(|| invalid("missing or invalid \"history\"".to_string()))?;
+
+    if !range.contains(&secret) {
+        return Err(invalid("the secret is outside the range".to_string()));
+    }
+    let mut game = Game::with_secret(range, secret).with_tolerance(tolerance);
+    for (i, turn) in history.iter().enumerate() {
+        let number = turn
+            .as_u32()
+            .ok_or_else(|| invalid(format!("guess #{} is not a number", i + 1)))?;
+        let guess = Guess::new(number, game.range()).map_err(|err| invalid(err.to_string()))?;
+        // Only rounds that are still going get saved
+        if game.guess(guess) == GuessOutcome::Correct {
+            return Err(invalid("the saved round is already won".to_string()));
+        }
+    }
+    if game.attempts() != attempts || *game.feasible() != feasible {
+        return Err(invalid(
+            "the saved attempts or feasible range don't match the history".to_string(),
+        ));
+    }
+    Ok(game)
+}
+
+fn pair(start: u32, end: u32) -> Value {
+    Value::Array(vec![Value::from(start), Value::from(end)])
+}
+
+fn field(value: &Value, key: &str) -> io::Result<u32> {
+    value
+        .get(key)
+        .and_then(Value::as_u32)
+        .ok_or_else(|| invalid(format!("missing or invalid \"{key}\"")))
+}
+
+fn read_pair(value: &Value, key: &str) -> io::Result<RangeInclusive<u32>> {
+    match value.get(key).and_then(Value::as_array) {
+        Some([start, end]) => match (start.as_u32(), end.as_u32()) {
+            (Some(start), Some(end)) if start <= end => Ok(start..=end),
+            _ => Err(invalid(format!("invalid \"{key}\""))),
+        },
+        _ => Err(invalid(format!("missing or invalid \"{key}\""))),
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> TempDir {
+            let path = std::env::temp_dir().join(format!("save-{}-{test}", std::process::id()));
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn mid_round() -> Game {
+        let mut game = Game::with_secret(1..=100, 42).with_tolerance(1);
+        for value in [50, 25, 30] {
+            game.guess(Guess::new(value, game.range()).unwrap());
+        }
+        game
+    }
+
+    #[test]
+    fn a_round_in_progress_loads_as_it_was_saved() {
+        let dir = TempDir::new("round-trip");
+        let path = dir.0.join("round.json");
+        let game = mid_round();
+        save_game(&path, &game).unwrap();
+        let loaded = load_game(&path).unwrap();
+        assert_eq!(loaded, game);
+        assert_eq!(loaded.attempts(), 3);
+        // A tolerance of 1 rules out one more number next to each wrong guess
+        assert_eq!(*loaded.feasible(), 32..=48);
+        assert_eq!(loaded.tolerance(), 1);
+        let guesses: Vec<u32> = loaded.history().iter().map(|turn| turn.guess).collect();
+        assert_eq!(guesses, [50, 25, 30]);
+    }
+
+    #[test]
+    fn a_round_with_no_guesses_round_trips() {
+        let dir = TempDir::new("fresh");
+        let path = dir.0.join("round.json");
+        let game = Game::with_secret(10..=20, 15);
+        save_game(&path, &game).unwrap();
+        assert_eq!(load_game(&path).unwrap(), game);
+    }
+
+    #[test]
+    fn a_missing_save_is_not_found() {
+        let dir = TempDir::new("missing");
+        let err = load_game(&dir.0.join("round.json")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
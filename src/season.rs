@@ -0,0 +1,216 @@
+// Ranked seasons: the leaderboard's results grouped by when they were set
+// Nothing is thrown away when a season ends. Which season a result belongs to
+// is worked out from its timestamp, in UTC so that every machine agrees, and
+// results saved before timestamps were kept make up a "legacy" season
+use std::fmt;
+use std::str::FromStr;
+
+use crate::daily::NaiveDate;
+
+/// The longest a season can be, in months.
+pub const MAX_SEASON_MONTHS: u32 = 12;
+
+/// One season, named after the month it starts in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Season {
+    /// Results with no timestamp, from before seasons existed.
+    Legacy,
+    /// The season that starts on the first of this month.
+    Starting { year: i32, month: u32 },
+}
+
+/// Written as `2024-11`, or `legacy`.
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Season::Legacy => write!(f, "legacy"),
+            Season::Starting { year, month } => write!(f, "{year:04}-{month:02}"),
+        }
+    }
+}
+
+/// Parses `YYYY-MM` or `legacy`. The month needn't be the first of a season;
+/// `Seasons::align` finds the season it is in.
+impl FromStr for Season {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Season, ()> {
+        if s == "legacy" {
+            return Ok(Season::Legacy);
+        }
+        let (year, month) = s.split_once('-').ok_or(())?;
+        let year = year.parse().map_err(|_| ())?;
+        let month = month.parse().map_err(|_| ())?;
+        if !(1..=12).contains(&month) {
+            return Err(());
+        }
+        Ok(Season::Starting { year, month })
+    }
+}
+
+/// How long seasons last. Each year starts a new season in January and then
+/// every `months` months, so 3 gives the quarters; a length that doesn't
+/// divide 12 leaves a shorter season at the end of the year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seasons {
+    months: u32,
+}
+
+impl Default for Seasons {
+    /// Seasons of one calendar month.
+    fn default() -> Seasons {
+        Seasons { months: 1 }
+    }
+}
+
+impl Seasons {
+    /// Seasons of `months` months, or None unless it is 1 to `MAX_SEASON_MONTHS`.
+    pub fn new(months: u32) -> Option<Seasons> {
+        (1..=MAX_SEASON_MONTHS)
+            .contains(&months)
+            .then_some(Seasons { months })
+    }
+
+    pub fn months(self) -> u32 {
+        self.months
+    }
+
+    /// The season that `month` (1 to 12) of `year` is in.
+    pub fn containing(self, year: i32, month: u32) -> Season {
+        Season::Starting {
+            year,
+            month: (month - 1) / self.months * self.months + 1,
+        }
+    }
+
+    /// The season a result belongs to, from when it was recorded (seconds
+    /// since the Unix epoch). Time zones play no part: a result set just
+    /// after midnight UTC on the 1st is in the new season everywhere.
+    pub fn season_of(self, recorded_at: u64) -> Season {
+        // 0 is what results saved before timestamps were kept read as
+        if recorded_at == 0 {
+            return Season::Legacy;
+        }
+        let date = NaiveDate::from_days_since_epoch((recorded_at / 86_400) as i64);
+        self.containing(date.year(), date.month())
+    }
+
+    /// The season `today` is in. Pass `NaiveDate::today_utc()` for the real
+    /// one; taking the date keeps the system clock out of season logic.
+    pub fn current(self, today: NaiveDate) -> Season {
+        self.containing(today.year(), today.month())
+    }
+
+    /// The season that `season`'s month is in, so that `2024-11` names the
+    /// season 2024-10 when seasons are quarters.
+    pub fn align(self, season: Season) -> Season {
+        match season {
+            Season::Legacy => Season::Legacy,
+            Season::Starting { year, month } => self.containing(year, month),
+        }
+    }
+}
+
+/// Which results a leaderboard query covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeasonView {
+    /// Every result, whichever season it was set in.
+    AllTime,
+    Season(Season),
+}
+
+impl fmt::Display for SeasonView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeasonView::AllTime => write!(f, "all-time"),
+            SeasonView::Season(season) => write!(f, "{season}"),
+        }
+    }
+}
+
+/// Parses `all-time`, or a season as `Season` does.
+impl FromStr for SeasonView {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<SeasonView, ()> {
+        match s {
+            "all-time" => Ok(SeasonView::AllTime),
+            _ => s.parse().map(SeasonView::Season),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOV_2024: Season = Season::Starting {
+        year: 2024,
+        month: 11,
+    };
+    const DEC_2024: Season = Season::Starting {
+        year: 2024,
+        month: 12,
+    };
+    const JAN_2025: Season = Season::Starting {
+        year: 2025,
+        month: 1,
+    };
+    // Midnight UTC at the start of 2024-12-01 and 2025-01-01
+    const DEC_1_2024: u64 = 1_733_011_200;
+    const JAN_1_2025: u64 = 1_735_689_600;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn monthly_seasons_change_on_the_first() {
+        let seasons = Seasons::default();
+        assert_eq!(seasons.current(date(2024, 11, 30)), NOV_2024);
+        assert_eq!(seasons.current(date(2024, 12, 1)), DEC_2024);
+        assert_eq!(seasons.current(date(2024, 12, 31)), DEC_2024);
+        assert_eq!(seasons.current(date(2025, 1, 1)), JAN_2025);
+    }
+
+    #[test]
+    fn longer_seasons_start_with_the_year() {
+        let quarters = Seasons::new(3).unwrap();
+        assert_eq!(quarters.current(date(2024, 11, 15)).to_string(), "2024-10");
+        assert_eq!(quarters.current(date(2024, 12, 31)).to_string(), "2024-10");
+        assert_eq!(quarters.current(date(2025, 1, 1)), JAN_2025);
+        // 5 doesn't divide 12, so November and December make a short season
+        let fives = Seasons::new(5).unwrap();
+        assert_eq!(fives.current(date(2024, 10, 31)).to_string(), "2024-06");
+        assert_eq!(fives.current(date(2024, 11, 1)).to_string(), "2024-11");
+        assert_eq!(fives.align(DEC_2024).to_string(), "2024-11");
+    }
+
+    #[test]
+    fn a_result_belongs_to_the_utc_month_it_was_set_in() {
+        let seasons = Seasons::default();
+        assert_eq!(seasons.season_of(DEC_1_2024 - 1), NOV_2024);
+        assert_eq!(seasons.season_of(DEC_1_2024), DEC_2024);
+        assert_eq!(seasons.season_of(JAN_1_2025 - 1), DEC_2024);
+        assert_eq!(seasons.season_of(JAN_1_2025), JAN_2025);
+        // Results from before timestamps were kept
+        assert_eq!(seasons.season_of(0), Season::Legacy);
+    }
+
+    #[test]
+    fn season_lengths_are_one_to_twelve_months() {
+        assert_eq!(Seasons::new(0), None);
+        assert_eq!(Seasons::new(MAX_SEASON_MONTHS + 1), None);
+        assert_eq!(Seasons::new(12).map(Seasons::months), Some(12));
+    }
+
+    #[test]
+    fn seasons_parse_and_print() {
+        assert_eq!("2024-11".parse(), Ok(NOV_2024));
+        assert_eq!("legacy".parse(), Ok(Season::Legacy));
+        assert_eq!("2024-13".parse::<Season>(), Err(()));
+        assert_eq!(NOV_2024.to_string(), "2024-11");
+        assert_eq!("all-time".parse(), Ok(SeasonView::AllTime));
+        assert_eq!("2024-11".parse(), Ok(SeasonView::Season(NOV_2024)));
+    }
+}
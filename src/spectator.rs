@@ -0,0 +1,190 @@
+// A running account of the round in plain sentences, for someone watching
+// The game (src/app.rs) writes one line per event and flushes it straight
+// away, so `tail -f` on the file keeps up with the game. The secret stays out
+// of every line until the round is over, and out of all of them for a round
+// saved to finish later
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::game::GuessOutcome;
+
+/// Something that happened in a round, as the spectator log tells it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Started {
+        range: RangeInclusive<u32>,
+        secret: u32,
+    },
+    Guessed {
+        attempt: u32,
+        guess: u32,
+        outcome: GuessOutcome,
+    },
+    /// A command, as typed and as understood (the same unless it was an
+    /// alias or an abbreviation).
+    Command { typed: String, command: String },
+    /// The round is over, won or not.
+    Ended {
+        secret: u32,
+        attempts: u32,
+        won: bool,
+    },
+    /// The round was saved to finish later, so it isn't over yet.
+    Saved { attempts: u32 },
+}
+
+/// One event as a sentence; only `Ended` says what the secret is.
+pub fn spectator_line(event: &Event) -> String {
+    match event {
+        // The secret is in the event but deliberately not in the line
+        Event::Started { range, secret: _ } => format!(
+            "A new round: the secret is between {} and {} (hidden until the end).",
+            range.start(),
+            range.end()
+        ),
+        Event::Guessed {
+            attempt,
+            guess,
+            outcome,
+        } => {
+            let answer = match outcome {
+                GuessOutcome::TooSmall => "too small",
+                GuessOutcome::TooBig => "too big",
+                GuessOutcome::Correct => "correct!",
+            };
+            format!("Guess {attempt}: {guess}, {answer}")
+        }
+        Event::Command { typed, command } if typed == command => format!("Command: {command}"),
+        Event::Command { typed, command } => format!("Command: {typed} (for {command})"),
+        Event::Ended {
+            secret,
+            attempts,
+            won,
+        } => {
+            let guesses = if *attempts == 1 { "guess" } else { "guesses" };
+            if *won {
+                format!("Round over: the secret was {secret}, found in {attempts} {guesses}.")
+            } else {
+                format!(
+                    "Round over: the player stopped after {attempts} {guesses}; the secret was {secret}."
+                )
+            }
+        }
+        Event::Saved { attempts } => format!(
+            "Round saved after {attempts} {} to finish later; the secret stays hidden.",
+            if *attempts == 1 { "guess" } else { "guesses" }
+        ),
+    }
+}
+
+/// Writes each event as its own line and flushes it at once.
+#[derive(Debug)]
+pub struct SpectatorLog<W: Write> {
+    out: W,
+}
+
+impl SpectatorLog<File> {
+    /// Appends to the file at `path`, creating it if needed, so one file can
+    /// follow several rounds.
+    pub fn append(path: &Path) -> io::Result<SpectatorLog<File>> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(SpectatorLog::new(file))
+    }
+}
+
+impl<W: Write> SpectatorLog<W> {
+    pub fn new(out: W) -> SpectatorLog<W> {
+        SpectatorLog { out }
+    }
+
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        writeln!(self.out, "{}", spectator_line(event))?;
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: u32 = 4242;
+
+    fn logged(events: &[Event]) -> Vec<String> {
+        let mut log = SpectatorLog::new(Vec::new());
+        for event in events {
+            log.record(event).unwrap();
+        }
+        String::from_utf8(log.out)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn mid_game() -> Vec<Event> {
+        vec![
+            Event::Started {
+                range: 1..=10_000,
+                secret: SECRET,
+            },
+            Event::Guessed {
+                attempt: 1,
+                guess: 5000,
+                outcome: GuessOutcome::TooBig,
+            },
+            Event::Command {
+                typed: "h".to_string(),
+                command: "hint".to_string(),
+            },
+            Event::Guessed {
+                attempt: 2,
+                guess: 2500,
+                outcome: GuessOutcome::TooSmall,
+            },
+        ]
+    }
+
+    #[test]
+    fn the_secret_is_revealed_only_in_the_final_line() {
+        let mut events = mid_game();
+        events.push(Event::Ended {
+            secret: SECRET,
+            attempts: 2,
+            won: false,
+        });
+        let lines = logged(&events);
+        assert_eq!(lines.len(), 5);
+        for line in &lines[..4] {
+            assert!(!line.contains(&SECRET.to_string()), "{line}");
+        }
+        assert_eq!(
+            lines[4],
+            "Round over: the player stopped after 2 guesses; the secret was 4242."
+        );
+        assert_eq!(lines[2], "Command: h (for hint)");
+    }
+
+    #[test]
+    fn a_saved_round_keeps_the_secret_hidden() {
+        let mut events = mid_game();
+        events.push(Event::Saved { attempts: 2 });
+        let lines = logged(&events);
+        assert!(lines.iter().all(|line| !line.contains(&SECRET.to_string())));
+        assert_eq!(
+            lines[4],
+            "Round saved after 2 guesses to finish later; the secret stays hidden."
+        );
+    }
+
+    #[test]
+    fn a_won_round_names_the_secret() {
+        let line = spectator_line(&Event::Ended {
+            secret: SECRET,
+            attempts: 1,
+            won: true,
+        });
+        assert_eq!(line, "Round over: the secret was 4242, found in 1 guess.");
+    }
+}
@@ -0,0 +1,326 @@
+// Guessing strategies that play the game on their own, and a harness that
+// measures how well they do over many games
+use std::ops::RangeInclusive;
+
+use crate::analysis;
+use crate::game::{Game, Guess, GuessOutcome};
+use crate::range_math;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Picks the next guess given the numbers that are still possible.
+pub trait Strategy {
+    fn next_guess(&mut self, feasible: &RangeInclusive<u32>) -> u32;
+}
+
+/// Binary search: always guess the middle of what's left.
+pub struct Midpoint;
+
+impl Strategy for Midpoint {
+    fn next_guess(&mut self, feasible: &RangeInclusive<u32>) -> u32 {
+        analysis::midpoint(feasible)
+    }
+}
+
+/// A cautious human: always guess a quarter of the way up.
+pub struct LowQuarter;
+
+impl Strategy for LowQuarter {
+    fn next_guess(&mut self, feasible: &RangeInclusive<u32>) -> u32 {
+        range_math::fraction_point(feasible, 1, 4)
+    }
+}
+
+/// The worst sensible strategy: count up one number at a time.
+pub struct Linear;
+
+impl Strategy for Linear {
+    fn next_guess(&mut self, feasible: &RangeInclusive<u32>) -> u32 {
+        *feasible.start()
+    }
+}
+
+/// Guesses anywhere in what's left, like someone who ignores the middle.
+pub struct Random {
+    rng: StdRng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Strategy for Random {
+    fn next_guess(&mut self, feasible: &RangeInclusive<u32>) -> u32 {
+        self.rng.random_range(feasible.clone())
+    }
+}
+
+pub const NAMES: [&str; 4] = ["midpoint", "low-quarter", "linear", "random"];
+
+/// Looks up a built-in strategy. `random` always starts from the same seed, so
+/// runs can be compared.
+pub fn by_name(name: &str) -> Option<Box<dyn Strategy>> {
+    match name {
+        "midpoint" => Some(Box::new(Midpoint)),
+        "low-quarter" => Some(Box::new(LowQuarter)),
+        "linear" => Some(Box::new(Linear)),
+        "random" => Some(Box::new(Random::new(0))),
+        _ => None,
+    }
+}
+
+/// Totals from playing a strategy over many games.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluation {
+    pub games: u32,
+    pub wins: u32,
+    pub total_guesses: u64,
+    /// `wins_by_guesses[n]` is how many games were won in exactly `n` guesses.
+    pub wins_by_guesses: Vec<u32>,
+}
+
+impl Evaluation {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+
+    pub fn average_guesses(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.total_guesses as f64 / self.games as f64
+    }
+}
+
+/// Plays one game, giving up after `budget` guesses. Returns the guesses used
+/// and whether the secret was found.
+pub fn play(strategy: &mut dyn Strategy, game: &mut Game, budget: u32) -> (u32, bool) {
+    while game.attempts() < budget {
+        // A strategy that wanders outside the range gets pulled back to its edge
+        let value = strategy
+            .next_guess(game.feasible())
+            .clamp(*game.range().start(), *game.range().end());
+        let guess = Guess::new(value, game.range()).expect("clamped into range");
+        if game.guess(guess) == GuessOutcome::Correct {
+            return (game.attempts(), true);
+        }
+    }
+    (game.attempts(), false)
+}
+
+/// Lets `strategy` play `game` until it finds the secret and returns every
+/// guess it made, in order. Nothing is printed: showing the guesses is up to
+/// the caller.
+///
+/// Gives up after one guess per number in the range, which only a strategy
+/// that repeats guesses outside the feasible range can reach.
+pub fn auto_solve(strategy: &mut dyn Strategy, game: &mut Game) -> Vec<u32> {
+    let budget = analysis::width(game.range()).min(u32::MAX as u64) as u32;
+    play(strategy, game, budget);
+    game.history().iter().map(|turn| turn.guess).collect()
+}
+
+/// Plays `games` seeded games on `range`, game `i` using seed `seed + i`, so
+/// every run (and every strategy) faces the same secrets.
+pub fn evaluate(
+    strategy: &mut dyn Strategy,
+    range: &RangeInclusive<u32>,
+    games: u32,
+    budget: u32,
+    seed: u64,
+) -> Evaluation {
+    let mut evaluation = Evaluation {
+        games,
+        wins: 0,
+        total_guesses: 0,
+        wins_by_guesses: vec![0; budget as usize + 1],
+    };
+    for i in 0..games {
+        let mut game = Game::seeded(range.clone(), seed.wrapping_add(i as u64));
+        let (guesses, won) = play(strategy, &mut game, budget);
+        evaluation.total_guesses += guesses as u64;
+        if won {
+            evaluation.wins += 1;
+            evaluation.wins_by_guesses[guesses as usize] += 1;
+        }
+    }
+    evaluation
+}
+
+/// The secrets for a tournament, the same ones `evaluate` would use for
+/// `seed`, so every strategy faces identical problems.
+pub fn shared_secrets(range: &RangeInclusive<u32>, count: u32, seed: u64) -> Vec<u32> {
+    (0..count)
+        .map(|i| Game::seeded(range.clone(), seed.wrapping_add(i as u64)).secret())
+        .collect()
+}
+
+/// The number of guesses `strategy` needs for each of `secrets`, in order.
+/// A game still unsolved after `budget` guesses counts as `budget`.
+pub fn guesses_per_secret(
+    strategy: &mut dyn Strategy,
+    range: &RangeInclusive<u32>,
+    secrets: &[u32],
+    budget: u32,
+) -> Vec<u32> {
+    secrets
+        .iter()
+        .map(|&secret| {
+            play(
+                strategy,
+                &mut Game::with_secret(range.clone(), secret),
+                budget,
+            )
+            .0
+        })
+        .collect()
+}
+
+/// Headline numbers for one strategy's results.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    /// 95% of games took at most this many guesses.
+    pub p95: u32,
+    pub worst: u32,
+}
+
+pub fn summarize(guesses: &[u32]) -> Summary {
+    if guesses.is_empty() {
+        return Summary {
+            mean: 0.0,
+            p95: 0,
+            worst: 0,
+        };
+    }
+    let mut sorted = guesses.to_vec();
+    sorted.sort_unstable();
+    // Nearest-rank percentile: the smallest value at or above 95% of the results
+    let rank = (sorted.len() * 95).div_ceil(100);
+    Summary {
+        mean: sorted.iter().map(|&g| g as f64).sum::<f64>() / sorted.len() as f64,
+        p95: sorted[rank - 1],
+        worst: sorted[sorted.len() - 1],
+    }
+}
+
+/// `wins[i][j]` is the number of secrets on which strategy `i` needed fewer
+/// guesses than strategy `j`. Every results list must cover the same secrets,
+/// so for any pair `wins[i][j] + wins[j][i]` plus the ties is the secret count.
+pub fn head_to_head(results: &[Vec<u32>]) -> Vec<Vec<u32>> {
+    results
+        .iter()
+        .map(|mine| {
+            results
+                .iter()
+                .map(|theirs| {
+                    mine.iter()
+                        .zip(theirs)
+                        .filter(|(me, them)| me < them)
+                        .count() as u32
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANGE: RangeInclusive<u32> = 1..=100;
+
+    #[test]
+    fn midpoint_wins_every_game_within_the_worst_case_budget() {
+        let budget = analysis::worst_case_guesses(&RANGE);
+        assert_eq!(budget, 7);
+        let evaluation = evaluate(&mut Midpoint, &RANGE, 200, budget, 1);
+        assert_eq!(evaluation.wins, 200);
+        assert_eq!(evaluation.win_rate(), 1.0);
+        // Every secret, not just the seeded ones
+        let all: Vec<u32> = RANGE.collect();
+        let guesses = guesses_per_secret(&mut Midpoint, &RANGE, &all, budget);
+        assert!(guesses.iter().all(|&g| g <= budget));
+    }
+
+    #[test]
+    fn linear_does_worse_than_midpoint() {
+        let budget = analysis::worst_case_guesses(&RANGE);
+        let midpoint = evaluate(&mut Midpoint, &RANGE, 200, budget, 1);
+        let linear = evaluate(&mut Linear, &RANGE, 200, budget, 1);
+        assert!(linear.win_rate() < midpoint.win_rate());
+        // Within 7 guesses, counting up only finds secrets 1 to 7
+        let secrets = shared_secrets(&RANGE, 200, 1);
+        let low = secrets.iter().filter(|&&secret| secret <= budget).count() as u32;
+        assert_eq!(linear.wins, low);
+        assert!(linear.wins < 200);
+        assert!(linear.average_guesses() > midpoint.average_guesses());
+    }
+
+    #[test]
+    fn an_empty_evaluation_has_no_rates() {
+        let evaluation = evaluate(&mut Midpoint, &RANGE, 0, 7, 1);
+        assert_eq!(evaluation.win_rate(), 0.0);
+        assert_eq!(evaluation.average_guesses(), 0.0);
+    }
+
+    #[test]
+    fn summarize_gives_mean_p95_and_worst() {
+        let guesses: Vec<u32> = (1..=20).rev().collect();
+        let summary = summarize(&guesses);
+        assert_eq!(summary.mean, 10.5);
+        // 95% of 20 results is the 19th smallest
+        assert_eq!(summary.p95, 19);
+        assert_eq!(summary.worst, 20);
+        assert_eq!(
+            summarize(&[]),
+            Summary {
+                mean: 0.0,
+                p95: 0,
+                worst: 0
+            }
+        );
+    }
+
+    #[test]
+    fn head_to_head_wins_losses_and_ties_cover_every_secret() {
+        let secrets = shared_secrets(&RANGE, 100, 7);
+        let results = [
+            guesses_per_secret(&mut Midpoint, &RANGE, &secrets, 100),
+            guesses_per_secret(&mut Random::new(7), &RANGE, &secrets, 100),
+            guesses_per_secret(&mut LowQuarter, &RANGE, &secrets, 100),
+        ];
+        let wins = head_to_head(&results);
+        for i in 0..results.len() {
+            assert_eq!(wins[i][i], 0);
+            for j in 0..results.len() {
+                let ties = results[i]
+                    .iter()
+                    .zip(&results[j])
+                    .filter(|(a, b)| a == b)
+                    .count() as u32;
+                assert_eq!(wins[i][j] + wins[j][i] + ties, secrets.len() as u32);
+            }
+        }
+        // On these secrets binary search beats guessing at random
+        assert!(wins[0][1] > wins[1][0]);
+        assert!(summarize(&results[0]).mean < summarize(&results[1]).mean);
+    }
+
+    #[test]
+    fn auto_solve_returns_every_guess_in_order() {
+        let mut game = Game::with_secret(RANGE, 65);
+        let guesses = auto_solve(&mut Midpoint, &mut game);
+        assert_eq!(guesses, [50, 75, 62, 68, 65]);
+        assert_eq!(game.attempts(), 5);
+        let mut game = Game::with_secret(RANGE, 3);
+        assert_eq!(auto_solve(&mut Linear, &mut game), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,331 @@
+// Output for slow or narrow terminals, such as a serial console at 9600 baud
+// TerminalWriter sits between the game and stdout: it holds everything written
+// until the next flush, so a turn's lines go out in one piece instead of
+// trickling out and tearing, and it keeps every line within a maximum width
+use std::io::{self, Write};
+
+use crate::game::GuessOutcome;
+
+/// What to do with the part of a line past the maximum width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Carry on at the start of a new line.
+    Wrap,
+    /// Drop it.
+    Truncate,
+}
+
+/// Glyphs that set each answer apart by shape as well as by wording, so no
+/// one has to tell answers apart by colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolSet {
+    /// ▲ ▼ ★
+    Unicode,
+    /// ^ v *, for terminals that can't show the Unicode ones.
+    Ascii,
+}
+
+impl SymbolSet {
+    /// The glyph that goes in front of an answer.
+    pub fn glyph(self, outcome: GuessOutcome) -> &'static str {
+        match (self, outcome) {
+            (SymbolSet::Unicode, GuessOutcome::TooBig) => "▲",
+            (SymbolSet::Unicode, GuessOutcome::TooSmall) => "▼",
+            (SymbolSet::Unicode, GuessOutcome::Correct) => "★",
+            (SymbolSet::Ascii, GuessOutcome::TooBig) => "^",
+            (SymbolSet::Ascii, GuessOutcome::TooSmall) => "v",
+            (SymbolSet::Ascii, GuessOutcome::Correct) => "*",
+        }
+    }
+}
+
+/// Where the writer is inside an ANSI escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Escape {
+    None,
+    /// Just saw ESC.
+    Started,
+    /// Inside `ESC [ ...`, which ends at a byte from `@` to `~`.
+    Csi,
+}
+
+/// How many columns a char takes up on a terminal: 0, 1 or 2.
+///
+/// A small stand-in for the unicode-width crate, covering combining marks,
+/// the variation selectors and joiner that build up emoji, and the common
+/// wide (CJK and emoji) blocks.
+pub fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036F | 0x200B..=0x200F | 0xFE00..=0xFE0F | 0x20D0..=0x20FF => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F900..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ if c.is_control() => 0,
+        _ => 1,
+    }
+}
+
+/// A writer that buffers until flushed and keeps lines within `max_width` columns.
+///
+/// Widths are counted in terminal columns, not bytes: a line is only ever
+/// broken between whole chars, and ANSI escape sequences (colours, cursor
+/// movement) take no room and are never split.
+#[derive(Debug)]
+pub struct TerminalWriter<W: Write> {
+    inner: W,
+    max_width: Option<usize>,
+    overflow: Overflow,
+    /// Written but not yet flushed, possibly ending partway through a char.
+    pending: Vec<u8>,
+    /// State carried over from the last flush, since a line can span several.
+    column: usize,
+    escape: Escape,
+    truncated: bool,
+}
+
+impl<W: Write> TerminalWriter<W> {
+    /// Passes lines through whole, only buffering them until a flush.
+    pub fn new(inner: W) -> TerminalWriter<W> {
+        TerminalWriter {
+            inner,
+            max_width: None,
+            overflow: Overflow::Wrap,
+            pending: Vec::new(),
+            column: 0,
+            escape: Escape::None,
+            truncated: false,
+        }
+    }
+
+    /// Keeps every line within `max_width` columns, handling longer ones as `overflow` says.
+    pub fn max_width(mut self, max_width: usize, overflow: Overflow) -> TerminalWriter<W> {
+        // A width of 0 could never fit anything, so wrapping would never end
+        self.max_width = Some(max_width.max(1));
+        self.overflow = overflow;
+        self
+    }
+
+    // Lays out one char, appending it (and any line break it needs) to `out`
+    fn push_char(&mut self, c: char, out: &mut String) {
+        match self.escape {
+            Escape::Started => {
+                self.escape = if c == '[' { Escape::Csi } else { Escape::None };
+                out.push(c);
+                return;
+            }
+            Escape::Csi => {
+                if ('@'..='~').contains(&c) {
+                    self.escape = Escape::None;
+                }
+                out.push(c);
+                return;
+            }
+            Escape::None => {}
+        }
+        match c {
+            // Escapes pass through even on a truncated line, so a colour that
+            // was switched on in the dropped part still gets switched off
+            '\x1b' => {
+                self.escape = Escape::Started;
+                out.push(c);
+            }
+            '\n' => {
+                self.column = 0;
+                self.truncated = false;
+                out.push(c);
+            }
+            '\r' => {
+                self.column = 0;
+                out.push(c);
+            }
+            _ => {
+                let width = char_width(c);
+                if let Some(max) = self.max_width
+                    && self.column + width > max
+                {
+                    match self.overflow {
+                        // Unless the char is wider than a whole line, which no
+                        // amount of wrapping will fix
+                        Overflow::Wrap if self.column > 0 => {
+                            out.push('\n');
+                            self.column = 0;
+                        }
+                        Overflow::Wrap => {}
+                        Overflow::Truncate => self.truncated = true,
+                    }
+                }
+                // Once a char has been dropped, so is everything after it on the
+                // line, including any accent that would have landed on the char before
+                if !self.truncated {
+                    out.push(c);
+                    self.column += width;
+                }
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for TerminalWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    /// Lays out everything written since the last flush and sends it in a single write.
+    fn flush(&mut self) -> io::Result<()> {
+        // The buffer can end in the first bytes of a char whose rest hasn't been
+        // written yet; those wait for the next flush
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+        let pending = std::mem::take(&mut self.pending);
+        let (text, rest) = pending.split_at(complete);
+        let text = std::str::from_utf8(text).expect("checked above");
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            self.push_char(c, &mut out);
+        }
+        self.pending = rest.to_vec();
+        self.inner.write_all(out.as_bytes())?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for TerminalWriter<W> {
+    fn drop(&mut self) {
+        // Errors can't be reported from drop; call flush first to see them
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: &str = "\x1b[31m";
+    const RESET: &str = "\x1b[0m";
+
+    // What reaches the terminal when `text` is written and flushed at `width`
+    fn laid_out(text: &str, width: usize, overflow: Overflow) -> String {
+        let mut out = Vec::new();
+        {
+            let mut writer = TerminalWriter::new(&mut out).max_width(width, overflow);
+            writer.write_all(text.as_bytes()).unwrap();
+            writer.flush().unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn long_lines_wrap_or_truncate() {
+        let text = "abcdefgh\nxy\n";
+        assert_eq!(laid_out(text, 3, Overflow::Wrap), "abc\ndef\ngh\nxy\n");
+        assert_eq!(laid_out(text, 3, Overflow::Truncate), "abc\nxy\n");
+        // Lines that fit are left alone
+        assert_eq!(laid_out(text, 8, Overflow::Wrap), text);
+    }
+
+    #[test]
+    fn a_very_long_line_wraps_into_full_lines() {
+        let long = "x".repeat(1000) + "\n";
+        let wrapped = laid_out(&long, 40, Overflow::Wrap);
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(lines.len(), 25);
+        assert!(lines.iter().all(|line| line.len() == 40));
+        assert_eq!(
+            laid_out(&long, 40, Overflow::Truncate),
+            "x".repeat(40) + "\n"
+        );
+    }
+
+    #[test]
+    fn colour_codes_take_no_room_and_are_never_split() {
+        let text = format!("{RED}abcdef{RESET}\n");
+        assert_eq!(
+            laid_out(&text, 3, Overflow::Wrap),
+            format!("{RED}abc\ndef{RESET}\n")
+        );
+        // The reset still gets through, so the colour doesn't leak
+        assert_eq!(
+            laid_out(&text, 3, Overflow::Truncate),
+            format!("{RED}abc{RESET}\n")
+        );
+        // A width of 1 is narrower than the escape, but it isn't broken up
+        assert_eq!(
+            laid_out(&format!("{RED}ab\n"), 1, Overflow::Wrap),
+            format!("{RED}a\nb\n")
+        );
+    }
+
+    #[test]
+    fn wide_chars_count_two_columns_and_stay_whole() {
+        assert_eq!(laid_out("日本語\n", 4, Overflow::Wrap), "日本\n語\n");
+        assert_eq!(laid_out("🎉🎉🎉\n", 5, Overflow::Truncate), "🎉🎉\n");
+        // A wide char on a line narrower than it is shown anyway
+        assert_eq!(laid_out("🎉\n", 1, Overflow::Wrap), "🎉\n");
+        // An accent takes no room of its own
+        assert_eq!(
+            laid_out("e\u{301}e\u{301}\n", 1, Overflow::Wrap),
+            "e\u{301}\ne\u{301}\n"
+        );
+    }
+
+    #[test]
+    fn a_char_split_across_writes_waits_for_its_other_bytes() {
+        let mut out = Vec::new();
+        {
+            let mut writer = TerminalWriter::new(&mut out).max_width(2, Overflow::Wrap);
+            let bytes = "éé é\n".as_bytes();
+            writer.write_all(&bytes[..1]).unwrap();
+            writer.flush().unwrap();
+            writer.write_all(&bytes[1..]).unwrap();
+            writer.flush().unwrap();
+        }
+        assert_eq!(String::from_utf8(out).unwrap(), "éé\n é\n");
+    }
+
+    #[test]
+    fn nothing_is_written_until_a_flush() {
+        let mut out = Vec::new();
+        let mut writer = TerminalWriter::new(&mut out);
+        writer.write_all(b"50 low\n").unwrap();
+        assert!(writer.inner.is_empty());
+        writer.flush().unwrap();
+        assert_eq!(writer.inner.as_slice(), b"50 low\n");
+    }
+
+    #[test]
+    fn every_outcome_has_its_own_glyph_in_each_set() {
+        let outcomes = [
+            GuessOutcome::TooBig,
+            GuessOutcome::TooSmall,
+            GuessOutcome::Correct,
+        ];
+        let unicode: Vec<&str> = outcomes
+            .iter()
+            .map(|&o| SymbolSet::Unicode.glyph(o))
+            .collect();
+        let ascii: Vec<&str> = outcomes
+            .iter()
+            .map(|&o| SymbolSet::Ascii.glyph(o))
+            .collect();
+        assert_eq!(unicode, ["▲", "▼", "★"]);
+        assert_eq!(ascii, ["^", "v", "*"]);
+        // ASCII glyphs fit any terminal, and each Unicode one takes one column
+        assert!(ascii.iter().all(|glyph| glyph.is_ascii()));
+        for glyph in unicode {
+            assert_eq!(glyph.chars().map(char_width).sum::<usize>(), 1);
+        }
+    }
+}
@@ -0,0 +1,220 @@
+// Fakes for driving the library from tests, behind the test-util feature:
+//   learning-rust = { version = "0.1", features = ["test-util"] }
+// The game reads input through InputSource, time through Clock and its secret
+// through RandomSource, so these are all a test needs to play a round without
+// a terminal, a real clock or luck
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use crate::game::RandomSource;
+pub use crate::input::ScriptedInput;
+use crate::timing::Clock;
+pub use crate::timing::FakeClock;
+
+/// A clock stopped at one moment: every lap it measures is zero.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedClock {
+    now: Duration,
+}
+
+impl FixedClock {
+    pub fn at(now: Duration) -> FixedClock {
+        FixedClock { now }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> Duration {
+        self.now
+    }
+}
+
+/// A clock that moves on by `step` every time it is read, so every lap a
+/// Stopwatch measures on it is exactly `step` long.
+#[derive(Debug, Default)]
+pub struct SteppingClock {
+    next: Cell<Duration>,
+    step: Duration,
+}
+
+impl SteppingClock {
+    pub fn new(step: Duration) -> SteppingClock {
+        SteppingClock {
+            next: Cell::new(Duration::ZERO),
+            step,
+        }
+    }
+}
+
+impl Clock for SteppingClock {
+    fn now(&self) -> Duration {
+        let now = self.next.get();
+        self.next.set(now + self.step);
+        now
+    }
+}
+
+/// Hands out a fixed list of numbers, in order.
+///
+/// Panics when the list runs out, or when the next number isn't in the range
+/// asked for, since either means the test set up the wrong numbers.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceRng {
+    values: VecDeque<u32>,
+}
+
+impl SequenceRng {
+    pub fn new<I: IntoIterator<Item = u32>>(values: I) -> SequenceRng {
+        SequenceRng {
+            values: values.into_iter().collect(),
+        }
+    }
+
+    /// How many numbers are still to come.
+    pub fn remaining(&self) -> usize {
+        self.values.len()
+    }
+}
+
+impl RandomSource for SequenceRng {
+    #[track_caller]
+    fn pick(&mut self, range: &RangeInclusive<u32>) -> u32 {
+        let Some(value) = self.values.pop_front() else {
+            panic!("SequenceRng ran out of values (asked for one in {range:?})");
+        };
+        assert!(
+            range.contains(&value),
+            "SequenceRng value {value} is outside the range {range:?}"
+        );
+        value
+    }
+}
+
+/// Everything written to it, kept for a test to look at.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapturedOutput {
+    bytes: Vec<u8>,
+}
+
+impl CapturedOutput {
+    pub fn new() -> CapturedOutput {
+        CapturedOutput::default()
+    }
+
+    /// The output so far, with invalid UTF-8 replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.bytes).into_owned()
+    }
+
+    /// The output so far, one entry per line, without the line endings.
+    pub fn lines(&self) -> Vec<String> {
+        self.text().lines().map(str::to_string).collect()
+    }
+
+    /// Panics, showing the whole output, unless some line is exactly `line`.
+    #[track_caller]
+    pub fn assert_contains_line(&self, line: &str) {
+        let lines = self.lines();
+        assert!(
+            lines.iter().any(|l| l == line),
+            "no line {line:?} in the output:\n{}",
+            lines.join("\n")
+        );
+    }
+}
+
+impl Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+    use crate::input::InputSource;
+    use crate::timing::Stopwatch;
+
+    #[test]
+    fn fixed_clock_never_moves() {
+        let clock = FixedClock::at(Duration::from_secs(5));
+        assert_eq!(clock.now(), Duration::from_secs(5));
+        assert_eq!(clock.now(), Duration::from_secs(5));
+        let mut stopwatch = Stopwatch::new(&clock);
+        assert_eq!(stopwatch.lap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn stepping_clock_advances_by_its_step() {
+        let clock = SteppingClock::new(Duration::from_millis(250));
+        assert_eq!(clock.now(), Duration::ZERO);
+        assert_eq!(clock.now(), Duration::from_millis(250));
+        assert_eq!(clock.now(), Duration::from_millis(500));
+        // The stopwatch reads it once to start and once per lap
+        let mut stopwatch = Stopwatch::new(&clock);
+        assert_eq!(stopwatch.lap(), Duration::from_millis(250));
+        assert_eq!(stopwatch.lap(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn sequence_rng_hands_out_its_values_in_order() {
+        let mut rng = SequenceRng::new([65, 3]);
+        assert_eq!(Game::drawn(1..=100, &mut rng).secret(), 65);
+        assert_eq!(rng.pick(&(1..=10)), 3);
+        assert_eq!(rng.remaining(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "SequenceRng ran out of values")]
+    fn sequence_rng_panics_when_it_runs_out() {
+        let mut rng = SequenceRng::new([1]);
+        rng.pick(&(1..=10));
+        rng.pick(&(1..=10));
+    }
+
+    #[test]
+    #[should_panic(expected = "SequenceRng value 50 is outside the range 1..=10")]
+    fn sequence_rng_panics_on_a_value_outside_the_range() {
+        SequenceRng::new([50]).pick(&(1..=10));
+    }
+
+    #[test]
+    fn captured_output_keeps_every_write() {
+        let mut out = CapturedOutput::new();
+        write!(out, "Too ").unwrap();
+        writeln!(out, "small!").unwrap();
+        writeln!(out, "You win!").unwrap();
+        out.flush().unwrap();
+        assert_eq!(out.text(), "Too small!\nYou win!\n");
+        assert_eq!(out.lines(), ["Too small!", "You win!"]);
+        out.assert_contains_line("You win!");
+    }
+
+    #[test]
+    #[should_panic(expected = "no line \"You lose\" in the output")]
+    fn captured_output_reports_a_missing_line() {
+        let mut out = CapturedOutput::new();
+        writeln!(out, "You win!").unwrap();
+        out.assert_contains_line("You lose");
+    }
+
+    #[test]
+    fn scripted_input_ends_with_eof() {
+        let mut input = ScriptedInput::new(["50", "quit"]);
+        assert_eq!(input.read_line().unwrap().as_deref(), Some("50"));
+        assert_eq!(input.read_line().unwrap().as_deref(), Some("quit"));
+        assert_eq!(input.read_line().unwrap(), None);
+        // and stays at the end
+        assert_eq!(input.read_line().unwrap(), None);
+        assert_eq!(input.remaining(), 0);
+    }
+}
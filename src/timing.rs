@@ -0,0 +1,131 @@
+// Measuring how long the player thinks about each guess
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// A source of time. Code that takes a Clock instead of calling
+/// `Instant::now` directly can be driven by a [`FakeClock`].
+pub trait Clock {
+    /// Time since the clock's own starting point; only differences mean anything.
+    fn now(&self) -> Duration;
+}
+
+/// The real, monotonic clock.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that stands still until it is told to move.
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    now: Cell<Duration>,
+}
+
+impl FakeClock {
+    pub fn new() -> FakeClock {
+        FakeClock::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+// A shared reference is a clock too, so a test can keep advancing a FakeClock
+// it has lent out
+impl<C: Clock> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
+
+/// Measures the time between consecutive laps, starting when it is created.
+#[derive(Debug)]
+pub struct Stopwatch<C: Clock> {
+    clock: C,
+    last: Duration,
+}
+
+impl<C: Clock> Stopwatch<C> {
+    pub fn new(clock: C) -> Stopwatch<C> {
+        let last = clock.now();
+        Stopwatch { clock, last }
+    }
+
+    /// Time since the previous lap (or since the stopwatch was created).
+    pub fn lap(&mut self) -> Duration {
+        let now = self.clock.now();
+        let lap = now.saturating_sub(self.last);
+        self.last = now;
+        lap
+    }
+}
+
+/// The fastest and slowest of some durations, or None if there are none.
+pub fn timing_summary(durations: &[Duration]) -> Option<(Duration, Duration)> {
+    let fastest = durations.iter().min()?;
+    let slowest = durations.iter().max()?;
+    Some((*fastest, *slowest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn summary_is_the_fastest_and_slowest() {
+        let durations = [secs(4), secs(1), secs(9), secs(3)];
+        assert_eq!(timing_summary(&durations), Some((secs(1), secs(9))));
+    }
+
+    #[test]
+    fn one_duration_is_both_fastest_and_slowest() {
+        assert_eq!(timing_summary(&[secs(2)]), Some((secs(2), secs(2))));
+    }
+
+    #[test]
+    fn no_durations_have_no_summary() {
+        assert_eq!(timing_summary(&[]), None);
+    }
+
+    #[test]
+    fn laps_measure_the_time_since_the_last_one() {
+        let clock = FakeClock::new();
+        let mut stopwatch = Stopwatch::new(&clock);
+        clock.advance(secs(3));
+        assert_eq!(stopwatch.lap(), secs(3));
+        clock.advance(secs(5));
+        assert_eq!(stopwatch.lap(), secs(5));
+        assert_eq!(stopwatch.lap(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,166 @@
+// A guided first round: the same game, with a few words of coaching after every guess
+// The secret is always the same, so a first-timer who follows the advice sees
+// exactly the round the advice was written for
+use crate::analysis;
+use crate::game::{Game, GuessOutcome};
+
+/// The tutorial is always played on this range...
+pub const RANGE: std::ops::RangeInclusive<u32> = 1..=100;
+/// ...with the secret drawn from this seed.
+pub const SEED: u64 = 3;
+
+/// Where the player is in the tutorial. It moves on once per guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    /// Before the first guess.
+    Intro,
+    /// After the first answer, which needs the most explaining.
+    FirstAnswer,
+    /// Any later answer that wasn't correct.
+    Narrowing,
+    /// The secret was found.
+    Finished,
+}
+
+impl TutorialStep {
+    pub fn new() -> TutorialStep {
+        TutorialStep::Intro
+    }
+
+    /// The step after a guess with this outcome.
+    pub fn advance(self, outcome: GuessOutcome) -> TutorialStep {
+        match (self, outcome) {
+            (TutorialStep::Finished, _) | (_, GuessOutcome::Correct) => TutorialStep::Finished,
+            (TutorialStep::Intro, _) => TutorialStep::FirstAnswer,
+            _ => TutorialStep::Narrowing,
+        }
+    }
+
+    pub fn is_finished(self) -> bool {
+        self == TutorialStep::Finished
+    }
+
+    /// What to tell the player at this step, given the game so far.
+    pub fn message(self, game: &Game) -> String {
+        let feasible = game.feasible();
+        let suggestion = analysis::midpoint(feasible);
+        let last = game.history().last();
+        match self {
+            TutorialStep::Intro => format!(
+                "Welcome! I'm thinking of a number from {} to {}. You don't know anything yet, so start right in the middle: try {suggestion}.",
+                game.range().start(),
+                game.range().end()
+            ),
+            TutorialStep::FirstAnswer => {
+                let (direction, guess) = match last {
+                    Some(turn) if turn.outcome == GuessOutcome::TooBig => ("lower", turn.guess),
+                    Some(turn) => ("higher", turn.guess),
+                    None => unreachable!("FirstAnswer comes after a guess"),
+                };
+                format!(
+                    "Notice what it said about {guess}: the secret must be {direction}. That one answer ruled out every number on the other side, so only {} to {} are left. Keep going from the middle of those: try {suggestion}.",
+                    feasible.start(),
+                    feasible.end()
+                )
+            }
+            TutorialStep::Narrowing => format!(
+                "Now it's between {} and {}, {} numbers in all. Halving again, {suggestion} is the best guess.",
+                feasible.start(),
+                feasible.end(),
+                analysis::width(feasible)
+            ),
+            TutorialStep::Finished => format!(
+                "That's it! Guessing the middle every time finds any number from {} to {} in at most {} tries. You're ready for a real game: run it again without --tutorial.",
+                game.range().start(),
+                game.range().end(),
+                analysis::worst_case_guesses(game.range())
+            ),
+        }
+    }
+}
+
+impl Default for TutorialStep {
+    fn default() -> TutorialStep {
+        TutorialStep::new()
+    }
+}
+
+/// The tutorial's round.
+pub fn tutorial_game() -> Game {
+    Game::seeded(RANGE, SEED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Guess;
+
+    // Plays the tutorial taking every suggestion, noting each guess and the step after it
+    fn follow_the_advice() -> (Game, Vec<(u32, TutorialStep)>) {
+        let mut game = tutorial_game();
+        let mut step = TutorialStep::new();
+        let mut seen = Vec::new();
+        while !step.is_finished() {
+            let value = analysis::midpoint(game.feasible());
+            let outcome = game.guess(Guess::new(value, game.range()).unwrap());
+            step = step.advance(outcome);
+            seen.push((value, step));
+        }
+        (game, seen)
+    }
+
+    #[test]
+    fn the_steps_progress_in_order_to_the_end() {
+        let (game, seen) = follow_the_advice();
+        assert_eq!(
+            seen,
+            [
+                (50, TutorialStep::FirstAnswer),
+                (75, TutorialStep::Narrowing),
+                (62, TutorialStep::Narrowing),
+                (68, TutorialStep::Narrowing),
+                (65, TutorialStep::Finished),
+            ]
+        );
+        assert_eq!(game.secret(), 65);
+    }
+
+    #[test]
+    fn a_lucky_first_guess_finishes_straight_away() {
+        assert_eq!(
+            TutorialStep::new().advance(GuessOutcome::Correct),
+            TutorialStep::Finished
+        );
+    }
+
+    #[test]
+    fn a_finished_tutorial_stays_finished() {
+        let finished = TutorialStep::Finished;
+        assert_eq!(finished.advance(GuessOutcome::TooBig), finished);
+        assert!(finished.is_finished());
+        assert!(!TutorialStep::Narrowing.is_finished());
+    }
+
+    #[test]
+    fn each_message_suggests_the_middle_of_what_is_left() {
+        let mut game = tutorial_game();
+        assert!(TutorialStep::Intro.message(&game).ends_with("try 50."));
+        game.guess(Guess::new(50, game.range()).unwrap());
+        assert!(
+            TutorialStep::FirstAnswer
+                .message(&game)
+                .starts_with("Notice what it said about 50: the secret must be higher.")
+        );
+        game.guess(Guess::new(75, game.range()).unwrap());
+        assert_eq!(
+            TutorialStep::Narrowing.message(&game),
+            "Now it's between 51 and 74, 24 numbers in all. Halving again, 62 is the best guess."
+        );
+        game.guess(Guess::new(65, game.range()).unwrap());
+        assert!(
+            TutorialStep::Finished
+                .message(&game)
+                .contains("from 1 to 100 in at most 7 tries")
+        );
+    }
+}
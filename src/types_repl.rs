@@ -0,0 +1,964 @@
+// The model behind src/bin/types_repl.rs: parsing its commands, holding the
+// tuple or array being explored, and naming the types Rust would infer for it.
+// Literals follow Rust's rules: an unsuffixed integer is an i32 and an
+// unsuffixed float an f64, unless an array's other elements say otherwise
+use std::fmt;
+
+/// What the REPL prints for `help`.
+pub const HELP: &str = "\
+Commands:
+  tuple 500 6.4 1          build a tuple and show its inferred type
+  array 1 2 3 4 5          build an array (every element one type)
+  index 2                  read one element of the current value
+  destructure (a, _, c)    bind its elements with a pattern ([a, .., b] for arrays)
+  slice 1..3               borrow part of the current array
+  show                     print the current value again
+  help                     this list
+  quit                     leave
+Literals: 7, -3, 6.4, 255u8, 1i64, 2.5f32, true, 'c', \"text\"";
+
+/// The Rust type of a piece of data, as it would be written in source.
+pub trait TypeName {
+    const NAME: &'static str;
+}
+
+impl TypeName for i32 {
+    const NAME: &'static str = "i32";
+}
+
+impl TypeName for i64 {
+    const NAME: &'static str = "i64";
+}
+
+impl TypeName for u8 {
+    const NAME: &'static str = "u8";
+}
+
+impl TypeName for f32 {
+    const NAME: &'static str = "f32";
+}
+
+impl TypeName for f64 {
+    const NAME: &'static str = "f64";
+}
+
+impl TypeName for bool {
+    const NAME: &'static str = "bool";
+}
+
+impl TypeName for char {
+    const NAME: &'static str = "char";
+}
+
+impl TypeName for &str {
+    const NAME: &'static str = "&str";
+}
+
+/// The integer types a literal can be given, by suffix or inference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntType {
+    I32,
+    I64,
+    U8,
+}
+
+/// The float types a literal can be given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatType {
+    F32,
+    F64,
+}
+
+impl IntType {
+    fn name(self) -> &'static str {
+        match self {
+            IntType::I32 => i32::NAME,
+            IntType::I64 => i64::NAME,
+            IntType::U8 => u8::NAME,
+        }
+    }
+}
+
+/// A literal as typed, before its type is settled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Int(i128, Option<IntType>),
+    Float(f64, Option<FloatType>),
+    Bool(bool),
+    Char(char),
+    Str(String),
+}
+
+/// One element of a tuple or array, with its type settled.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Element {
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    Char(char),
+    Str(String),
+}
+
+impl Element {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Element::I32(_) => i32::NAME,
+            Element::I64(_) => i64::NAME,
+            Element::U8(_) => u8::NAME,
+            Element::F32(_) => f32::NAME,
+            Element::F64(_) => f64::NAME,
+            Element::Bool(_) => bool::NAME,
+            Element::Char(_) => char::NAME,
+            Element::Str(_) => <&str>::NAME,
+        }
+    }
+}
+
+// Debug formatting writes each value the way it would be written as a literal:
+// 1.0 rather than 1, quotes around chars and strings
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Element::I32(n) => write!(f, "{n}"),
+            Element::I64(n) => write!(f, "{n}"),
+            Element::U8(n) => write!(f, "{n}"),
+            Element::F32(x) => write!(f, "{x:?}"),
+            Element::F64(x) => write!(f, "{x:?}"),
+            Element::Bool(b) => write!(f, "{b}"),
+            Element::Char(c) => write!(f, "{c:?}"),
+            Element::Str(s) => write!(f, "{s:?}"),
+        }
+    }
+}
+
+/// The value being explored.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Tuple(Vec<Element>),
+    /// Never empty: `[]` has no element to infer a type from.
+    Array(Vec<Element>),
+}
+
+impl Value {
+    pub fn elements(&self) -> &[Element] {
+        match self {
+            Value::Tuple(elements) | Value::Array(elements) => elements,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Tuple(_) => "tuple",
+            Value::Array(_) => "array",
+        }
+    }
+
+    /// The type Rust infers for the value: `(i32, f64)` or `[u8; 3]`.
+    pub fn type_name(&self) -> String {
+        match self {
+            Value::Tuple(elements) if elements.len() == 1 => {
+                format!("({},)", elements[0].type_name())
+            }
+            Value::Tuple(elements) => format!("({})", type_list(elements)),
+            Value::Array(elements) => format!("[{}; {}]", elements[0].type_name(), elements.len()),
+        }
+    }
+}
+
+fn type_list(elements: &[Element]) -> String {
+    let names: Vec<&str> = elements.iter().map(Element::type_name).collect();
+    names.join(", ")
+}
+
+fn element_list(elements: &[Element]) -> String {
+    let values: Vec<String> = elements.iter().map(Element::to_string).collect();
+    values.join(", ")
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Tuple(elements) if elements.len() == 1 => write!(f, "({},)", elements[0]),
+            Value::Tuple(elements) => write!(f, "({})", element_list(elements)),
+            Value::Array(elements) => write!(f, "[{}]", element_list(elements)),
+        }
+    }
+}
+
+/// One place in a destructuring pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternItem {
+    Bind(String),
+    /// `_`: matches one element without binding it.
+    Ignore,
+    /// `..`: matches any number of elements, at most once per pattern.
+    Rest,
+}
+
+/// A destructuring pattern: `(a, _, c)` for a tuple or `[first, .., last]`
+/// for an array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub array: bool,
+    pub items: Vec<PatternItem>,
+}
+
+/// A range to slice an array with, end exclusive; no end means "to the end".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+/// A parsed command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Tuple(Vec<Literal>),
+    Array(Vec<Literal>),
+    Index(usize),
+    Destructure(Pattern),
+    Slice(SliceRange),
+    Show,
+    Help,
+    Quit,
+}
+
+/// Why a command couldn't be parsed or carried out.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ReplError {
+    UnknownCommand(String),
+    /// The command needs an argument; holds an example of its use.
+    MissingArgument(&'static str),
+    BadLiteral(String),
+    /// An integer literal that doesn't fit the type it ended up with.
+    LiteralOutOfRange(String, &'static str),
+    MixedArray {
+        index: usize,
+        expected: &'static str,
+        found: &'static str,
+    },
+    EmptyArray,
+    NoValue,
+    IndexOutOfBounds {
+        index: usize,
+        len: usize,
+        tuple: bool,
+    },
+    BadPattern(String),
+    PatternKind {
+        array_pattern: bool,
+    },
+    PatternLength {
+        items: usize,
+        len: usize,
+        kind: &'static str,
+    },
+    BadRange(String),
+    TupleSlice,
+    SliceOutOfBounds {
+        end: usize,
+        len: usize,
+    },
+    SliceOrder {
+        start: usize,
+        end: usize,
+    },
+}
+
+impl fmt::Display for ReplError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplError::UnknownCommand(word) => {
+                write!(f, "unknown command '{word}' (try 'help')")
+            }
+            ReplError::MissingArgument(usage) => write!(f, "missing an argument, as in '{usage}'"),
+            ReplError::BadLiteral(text) => write!(f, "'{text}' is not a literal this REPL knows"),
+            ReplError::LiteralOutOfRange(text, ty) => write!(
+                f,
+                "{text} doesn't fit in {ty}; Rust rejects the literal (a suffix like 1i64 picks a wider type)"
+            ),
+            ReplError::MixedArray {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "element {index} is {found} but the array holds {expected}; every element of an array has one type"
+            ),
+            ReplError::EmptyArray => write!(
+                f,
+                "an empty array gives Rust nothing to infer the element type from"
+            ),
+            ReplError::NoValue => {
+                write!(f, "nothing to work on yet: build a tuple or an array first")
+            }
+            ReplError::IndexOutOfBounds {
+                index,
+                len,
+                tuple: true,
+            } => write!(
+                f,
+                "the tuple has {len} elements, so .{index} doesn't exist; in Rust that is a compile error (no field `{index}`)"
+            ),
+            ReplError::IndexOutOfBounds { index, len, .. } => write!(
+                f,
+                "index {index} is out of bounds for an array of length {len}; Rust would panic here, while .get({index}) returns None"
+            ),
+            ReplError::BadPattern(text) => write!(f, "can't read the pattern '{text}'"),
+            ReplError::PatternKind {
+                array_pattern: true,
+            } => write!(f, "a [..] pattern matches an array; use (..) for a tuple"),
+            ReplError::PatternKind { .. } => {
+                write!(f, "a (..) pattern matches a tuple; use [..] for an array")
+            }
+            ReplError::PatternLength { items, len, kind } => write!(
+                f,
+                "the pattern has {items} elements but the {kind} has {len}; add .. to skip the rest"
+            ),
+            ReplError::BadRange(text) => {
+                write!(f, "'{text}' is not a range like 1..3, 2.., ..=4 or ..")
+            }
+            ReplError::TupleSlice => write!(
+                f,
+                "a tuple can't be sliced: its elements may differ in type, so they can't form a [T]"
+            ),
+            ReplError::SliceOutOfBounds { end, len } => write!(
+                f,
+                "range end {end} is out of range for an array of length {len}; Rust would panic here, while .get(..) returns None"
+            ),
+            ReplError::SliceOrder { start, end } => {
+                write!(f, "the slice starts at {start} but ends at {end}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplError {}
+
+// Splits on whitespace, keeping a double-quoted string together even if it has spaces
+fn tokens(text: &str) -> Result<Vec<String>, ReplError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            let mut token = String::from(chars.next().unwrap());
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => token.push(c),
+                    None => return Err(ReplError::BadLiteral(token)),
+                }
+            }
+            token.push('"');
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+            tokens.push(token);
+        }
+    }
+    Ok(tokens)
+}
+
+const SUFFIXES: [&str; 5] = ["i32", "i64", "u8", "f32", "f64"];
+
+/// Reads one literal the way Rust would: `7`, `-3`, `6.4`, `255u8`, `true`,
+/// `'c'` or `"text"`. Underscores between digits are allowed.
+pub fn parse_literal(text: &str) -> Result<Literal, ReplError> {
+    let bad = || ReplError::BadLiteral(text.to_string());
+    match text {
+        "true" => return Ok(Literal::Bool(true)),
+        "false" => return Ok(Literal::Bool(false)),
+        _ => {}
+    }
+    if let Some(inner) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+        let mut chars = inner.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(Literal::Char(c)),
+            _ => Err(bad()),
+        };
+    }
+    if text.len() >= 2
+        && let Some(inner) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"'))
+    {
+        return Ok(Literal::Str(inner.to_string()));
+    }
+
+    let (number, suffix) = SUFFIXES
+        .iter()
+        .find_map(|&suffix| {
+            text.strip_suffix(suffix)
+                .map(|number| (number, Some(suffix)))
+        })
+        .unwrap_or((text, None));
+    let digits = number.replace('_', "");
+    // Checked before the underscores go, since _1 is a name in Rust, not a number
+    let starts_with_digit = number
+        .strip_prefix('-')
+        .unwrap_or(number)
+        .starts_with(|c: char| c.is_ascii_digit());
+    if !starts_with_digit || number.ends_with('_') {
+        return Err(bad());
+    }
+    let is_float = digits.contains(['.', 'e', 'E']);
+    let float = match suffix {
+        Some("f32") => digits
+            .parse()
+            .map(|x| Literal::Float(x, Some(FloatType::F32))),
+        Some("f64") => digits
+            .parse()
+            .map(|x| Literal::Float(x, Some(FloatType::F64))),
+        // A float can't take an integer suffix: 1.5u8 is an error in Rust too
+        Some(_) if is_float => return Err(bad()),
+        None if is_float => digits.parse().map(|x| Literal::Float(x, None)),
+        _ => {
+            let ty = match suffix {
+                Some("i32") => Some(IntType::I32),
+                Some("i64") => Some(IntType::I64),
+                Some("u8") => Some(IntType::U8),
+                _ => None,
+            };
+            return digits
+                .parse()
+                .map(|n| Literal::Int(n, ty))
+                .map_err(|_| bad());
+        }
+    };
+    float.map_err(|_| bad())
+}
+
+// Gives a literal its final type: its own suffix if it has one, otherwise the
+// type inferred for its kind of literal
+fn settle(
+    text: &str,
+    literal: Literal,
+    int_type: IntType,
+    float_type: FloatType,
+) -> Result<Element, ReplError> {
+    Ok(match literal {
+        Literal::Int(n, ty) => {
+            let ty = ty.unwrap_or(int_type);
+            let out_of_range = || ReplError::LiteralOutOfRange(text.to_string(), ty.name());
+            match ty {
+                IntType::I32 => Element::I32(n.try_into().map_err(|_| out_of_range())?),
+                IntType::I64 => Element::I64(n.try_into().map_err(|_| out_of_range())?),
+                IntType::U8 => Element::U8(n.try_into().map_err(|_| out_of_range())?),
+            }
+        }
+        Literal::Float(x, ty) => match ty.unwrap_or(float_type) {
+            FloatType::F32 => Element::F32(x as f32),
+            FloatType::F64 => Element::F64(x),
+        },
+        Literal::Bool(b) => Element::Bool(b),
+        Literal::Char(c) => Element::Char(c),
+        Literal::Str(s) => Element::Str(s),
+    })
+}
+
+/// Settles the types of a tuple's elements: each one on its own, so unsuffixed
+/// integers are i32 and unsuffixed floats f64.
+pub fn tuple_of(literals: Vec<Literal>) -> Result<Value, ReplError> {
+    literals
+        .into_iter()
+        .map(|literal| {
+            let text = literal_text(&literal);
+            settle(&text, literal, IntType::I32, FloatType::F64)
+        })
+        .collect::<Result<_, _>>()
+        .map(Value::Tuple)
+}
+
+/// Settles the types of an array's elements together: a suffix on any integer
+/// (or float) gives the unsuffixed ones the same type, as inference would.
+pub fn array_of(literals: Vec<Literal>) -> Result<Value, ReplError> {
+    if literals.is_empty() {
+        return Err(ReplError::EmptyArray);
+    }
+    let int_type = literals
+        .iter()
+        .find_map(|literal| match literal {
+            Literal::Int(_, ty) => *ty,
+            _ => None,
+        })
+        .unwrap_or(IntType::I32);
+    let float_type = literals
+        .iter()
+        .find_map(|literal| match literal {
+            Literal::Float(_, ty) => *ty,
+            _ => None,
+        })
+        .unwrap_or(FloatType::F64);
+    let elements: Vec<Element> = literals
+        .into_iter()
+        .map(|literal| {
+            let text = literal_text(&literal);
+            settle(&text, literal, int_type, float_type)
+        })
+        .collect::<Result<_, _>>()?;
+    let expected = elements[0].type_name();
+    if let Some(index) = elements.iter().position(|e| e.type_name() != expected) {
+        return Err(ReplError::MixedArray {
+            index,
+            expected,
+            found: elements[index].type_name(),
+        });
+    }
+    Ok(Value::Array(elements))
+}
+
+fn literal_text(literal: &Literal) -> String {
+    match literal {
+        Literal::Int(n, _) => n.to_string(),
+        Literal::Float(x, _) => format!("{x:?}"),
+        Literal::Bool(b) => b.to_string(),
+        Literal::Char(c) => format!("{c:?}"),
+        Literal::Str(s) => format!("{s:?}"),
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Reads a pattern such as `(a, _, c)`, `(x,)` or `[first, .., last]`.
+pub fn parse_pattern(text: &str) -> Result<Pattern, ReplError> {
+    let bad = || ReplError::BadPattern(text.to_string());
+    let text = text.trim();
+    let (array, inner) =
+        if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            (false, inner)
+        } else if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            (true, inner)
+        } else {
+            return Err(bad());
+        };
+    let mut parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+    // A trailing comma, as in (a,), leaves one empty part at the end
+    if parts.len() > 1 && parts.last() == Some(&"") {
+        parts.pop();
+    }
+    if parts == [""] {
+        parts.clear();
+    }
+    let items = parts
+        .into_iter()
+        .map(|part| match part {
+            "_" => Ok(PatternItem::Ignore),
+            ".." => Ok(PatternItem::Rest),
+            name if is_identifier(name) => Ok(PatternItem::Bind(name.to_string())),
+            _ => Err(bad()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if items
+        .iter()
+        .filter(|&item| *item == PatternItem::Rest)
+        .count()
+        > 1
+    {
+        return Err(bad());
+    }
+    Ok(Pattern { array, items })
+}
+
+/// Reads a range: `1..3`, `1..=3`, `2..`, `..4`, `..=4` or `..`.
+pub fn parse_range(text: &str) -> Result<SliceRange, ReplError> {
+    let bad = || ReplError::BadRange(text.to_string());
+    let (start, end, inclusive) = if let Some((start, end)) = text.split_once("..=") {
+        (start, end, true)
+    } else if let Some((start, end)) = text.split_once("..") {
+        (start, end, false)
+    } else {
+        return Err(bad());
+    };
+    let start = match start {
+        "" => 0,
+        start => start.parse().map_err(|_| bad())?,
+    };
+    let end = match (end, inclusive) {
+        ("", false) => None,
+        ("", true) => return Err(bad()),
+        (end, false) => Some(end.parse().map_err(|_| bad())?),
+        (end, true) => Some(
+            end.parse::<usize>()
+                .map_err(|_| bad())?
+                .checked_add(1)
+                .ok_or_else(bad)?,
+        ),
+    };
+    Ok(SliceRange { start, end })
+}
+
+/// Reads one line of input as a command.
+pub fn parse_command(line: &str) -> Result<Command, ReplError> {
+    let line = line.trim();
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    let literals = || {
+        tokens(rest)?
+            .iter()
+            .map(|token| parse_literal(token))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    match word.to_ascii_lowercase().as_str() {
+        "tuple" => Ok(Command::Tuple(literals()?)),
+        "array" => Ok(Command::Array(literals()?)),
+        "index" if rest.is_empty() => Err(ReplError::MissingArgument("index 2")),
+        "index" => rest
+            .parse()
+            .map(Command::Index)
+            .map_err(|_| ReplError::BadLiteral(rest.to_string())),
+        "destructure" if rest.is_empty() => {
+            Err(ReplError::MissingArgument("destructure (a, _, c)"))
+        }
+        "destructure" => parse_pattern(rest).map(Command::Destructure),
+        "slice" if rest.is_empty() => Err(ReplError::MissingArgument("slice 1..3")),
+        "slice" => parse_range(rest).map(Command::Slice),
+        "show" => Ok(Command::Show),
+        "help" => Ok(Command::Help),
+        "quit" | "exit" => Ok(Command::Quit),
+        _ => Err(ReplError::UnknownCommand(word.to_string())),
+    }
+}
+
+/// The REPL's state: the value the last `tuple` or `array` built.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Session {
+    value: Option<Value>,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
+
+    fn current(&self) -> Result<&Value, ReplError> {
+        self.value.as_ref().ok_or(ReplError::NoValue)
+    }
+
+    /// Carries out a command, returning what to print. Quit is left to the
+    /// caller and prints nothing.
+    pub fn execute(&mut self, command: Command) -> Result<String, ReplError> {
+        match command {
+            Command::Tuple(literals) => self.set(tuple_of(literals)?),
+            Command::Array(literals) => self.set(array_of(literals)?),
+            Command::Index(index) => self.index(index),
+            Command::Destructure(pattern) => self.destructure(&pattern),
+            Command::Slice(range) => self.slice(range),
+            Command::Show => self.current().map(describe),
+            Command::Help => Ok(HELP.to_string()),
+            Command::Quit => Ok(String::new()),
+        }
+    }
+
+    fn set(&mut self, value: Value) -> Result<String, ReplError> {
+        let description = describe(&value);
+        self.value = Some(value);
+        Ok(description)
+    }
+
+    fn index(&self, index: usize) -> Result<String, ReplError> {
+        let value = self.current()?;
+        let elements = value.elements();
+        let Some(element) = elements.get(index) else {
+            return Err(ReplError::IndexOutOfBounds {
+                index,
+                len: elements.len(),
+                tuple: matches!(value, Value::Tuple(_)),
+            });
+        };
+        Ok(match value {
+            Value::Tuple(_) => format!("value.{index} = {element}: {}", element.type_name()),
+            Value::Array(_) => format!("value[{index}] = {element}: {}", element.type_name()),
+        })
+    }
+
+    fn destructure(&self, pattern: &Pattern) -> Result<String, ReplError> {
+        let value = self.current()?;
+        if pattern.array != matches!(value, Value::Array(_)) {
+            return Err(ReplError::PatternKind {
+                array_pattern: pattern.array,
+            });
+        }
+        let elements = value.elements();
+        let rest = pattern
+            .items
+            .iter()
+            .position(|item| *item == PatternItem::Rest);
+        let fixed = pattern.items.len() - rest.is_some() as usize;
+        if fixed > elements.len() || (rest.is_none() && fixed != elements.len()) {
+            return Err(ReplError::PatternLength {
+                items: fixed,
+                len: elements.len(),
+                kind: value.kind(),
+            });
+        }
+        // Items before the .. take elements from the front, items after it from the back
+        let skipped = elements.len() - fixed;
+        let mut lines = Vec::new();
+        let mut next = 0;
+        for item in &pattern.items {
+            match item {
+                PatternItem::Bind(name) => {
+                    let element = &elements[next];
+                    lines.push(format!("{name} = {element}: {}", element.type_name()));
+                    next += 1;
+                }
+                PatternItem::Ignore => {
+                    lines.push(format!("_ skips {}", elements[next]));
+                    next += 1;
+                }
+                PatternItem::Rest => {
+                    let plural = if skipped == 1 { "" } else { "s" };
+                    lines.push(format!(".. skips {skipped} element{plural}"));
+                    next += skipped;
+                }
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn slice(&self, range: SliceRange) -> Result<String, ReplError> {
+        let elements = match self.current()? {
+            Value::Tuple(_) => return Err(ReplError::TupleSlice),
+            Value::Array(elements) => elements,
+        };
+        let len = elements.len();
+        let end = range.end.unwrap_or(len);
+        if range.start > end {
+            return Err(ReplError::SliceOrder {
+                start: range.start,
+                end,
+            });
+        }
+        if end > len {
+            return Err(ReplError::SliceOutOfBounds { end, len });
+        }
+        let slice = &elements[range.start..end];
+        let text = match range.end {
+            Some(end) => format!("{}..{end}", range.start),
+            None => format!("{}..", range.start),
+        };
+        Ok(format!(
+            "&value[{text}] = [{}]: &[{}] of length {}",
+            element_list(slice),
+            elements[0].type_name(),
+            slice.len()
+        ))
+    }
+}
+
+fn describe(value: &Value) -> String {
+    format!("value = {value}: {}", value.type_name())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(values: &[i128]) -> Vec<Literal> {
+        values.iter().map(|&n| Literal::Int(n, None)).collect()
+    }
+
+    fn literals(texts: &[&str]) -> Vec<Literal> {
+        texts
+            .iter()
+            .map(|text| parse_literal(text).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn commands_parse() {
+        let cases = [
+            (
+                "tuple 500 6.4 1",
+                Command::Tuple(literals(&["500", "6.4", "1"])),
+            ),
+            ("  ARRAY 1 2 3 ", Command::Array(ints(&[1, 2, 3]))),
+            ("tuple", Command::Tuple(Vec::new())),
+            (
+                "tuple \"two words\" 'x'",
+                Command::Tuple(vec![
+                    Literal::Str("two words".to_string()),
+                    Literal::Char('x'),
+                ]),
+            ),
+            ("index 2", Command::Index(2)),
+            (
+                "destructure (a, _, ..)",
+                Command::Destructure(Pattern {
+                    array: false,
+                    items: vec![
+                        PatternItem::Bind("a".to_string()),
+                        PatternItem::Ignore,
+                        PatternItem::Rest,
+                    ],
+                }),
+            ),
+            (
+                "slice 1..=3",
+                Command::Slice(SliceRange {
+                    start: 1,
+                    end: Some(4),
+                }),
+            ),
+            ("show", Command::Show),
+            ("help", Command::Help),
+            ("quit", Command::Quit),
+            ("exit", Command::Quit),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse_command(line), Ok(expected), "{line:?}");
+        }
+    }
+
+    #[test]
+    fn bad_commands_say_what_went_wrong() {
+        let cases = [
+            ("list", "unknown command 'list' (try 'help')"),
+            ("index", "missing an argument, as in 'index 2'"),
+            ("index two", "'two' is not a literal this REPL knows"),
+            ("slice", "missing an argument, as in 'slice 1..3'"),
+            (
+                "slice 1-3",
+                "'1-3' is not a range like 1..3, 2.., ..=4 or ..",
+            ),
+            (
+                "slice 2..=",
+                "'2..=' is not a range like 1..3, 2.., ..=4 or ..",
+            ),
+            ("destructure a, b", "can't read the pattern 'a, b'"),
+            (
+                "destructure (.., a, ..)",
+                "can't read the pattern '(.., a, ..)'",
+            ),
+            ("tuple 1.5u8", "'1.5u8' is not a literal this REPL knows"),
+            ("tuple \"open", "'\"open' is not a literal this REPL knows"),
+        ];
+        for (line, message) in cases {
+            assert_eq!(
+                parse_command(line).unwrap_err().to_string(),
+                message,
+                "{line:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn literals_parse_like_rust() {
+        let cases = [
+            ("7", Literal::Int(7, None)),
+            ("-3", Literal::Int(-3, None)),
+            ("1_000", Literal::Int(1000, None)),
+            ("255u8", Literal::Int(255, Some(IntType::U8))),
+            ("1i64", Literal::Int(1, Some(IntType::I64))),
+            ("6.4", Literal::Float(6.4, None)),
+            ("2.5f32", Literal::Float(2.5, Some(FloatType::F32))),
+            ("1e3", Literal::Float(1000.0, None)),
+            ("true", Literal::Bool(true)),
+            ("'c'", Literal::Char('c')),
+            ("\"\"", Literal::Str(String::new())),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(parse_literal(text), Ok(expected), "{text:?}");
+        }
+        for text in ["x", "'ab'", "1_", "_1", "\"", "u8"] {
+            assert!(parse_literal(text).is_err(), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn tuples_get_the_default_types() {
+        let cases = [
+            (vec!["500", "6.4", "1"], "(i32, f64, i32)"),
+            (vec!["1u8", "2.5f32", "true"], "(u8, f32, bool)"),
+            (vec!["'x'", "\"s\"", "1i64"], "(char, &str, i64)"),
+            (vec!["1"], "(i32,)"),
+            (vec![], "()"),
+        ];
+        for (texts, type_name) in cases {
+            let value = tuple_of(literals(&texts)).unwrap();
+            assert_eq!(value.type_name(), type_name, "{texts:?}");
+        }
+    }
+
+    #[test]
+    fn a_suffix_types_the_whole_array() {
+        let cases = [
+            (vec!["1", "2", "3"], "[i32; 3]"),
+            (vec!["1", "2u8", "3"], "[u8; 3]"),
+            (vec!["1i64", "2"], "[i64; 2]"),
+            (vec!["1.5", "2.5f32"], "[f32; 2]"),
+            (vec!["true"], "[bool; 1]"),
+        ];
+        for (texts, type_name) in cases {
+            let value = array_of(literals(&texts)).unwrap();
+            assert_eq!(value.type_name(), type_name, "{texts:?}");
+        }
+    }
+
+    #[test]
+    fn arrays_reject_what_rust_would() {
+        assert_eq!(array_of(Vec::new()), Err(ReplError::EmptyArray));
+        assert_eq!(
+            array_of(literals(&["1", "2.5"])),
+            Err(ReplError::MixedArray {
+                index: 1,
+                expected: "i32",
+                found: "f64",
+            })
+        );
+        assert_eq!(
+            array_of(literals(&["1", "2u8", "300"])),
+            Err(ReplError::LiteralOutOfRange("300".to_string(), "u8"))
+        );
+        assert_eq!(
+            tuple_of(ints(&[3_000_000_000])),
+            Err(ReplError::LiteralOutOfRange(
+                "3000000000".to_string(),
+                "i32"
+            ))
+        );
+    }
+
+    #[test]
+    fn a_session_needs_a_value_first() {
+        let mut session = Session::new();
+        assert_eq!(session.execute(Command::Show), Err(ReplError::NoValue));
+        assert_eq!(
+            session.execute(Command::Array(ints(&[1, 2, 3]))),
+            Ok("value = [1, 2, 3]: [i32; 3]".to_string())
+        );
+        assert_eq!(
+            session.execute(Command::Index(0)),
+            Ok("value[0] = 1: i32".to_string())
+        );
+        assert_eq!(
+            session.execute(Command::Slice(SliceRange {
+                start: 1,
+                end: None
+            })),
+            Ok("&value[1..] = [2, 3]: &[i32] of length 2".to_string())
+        );
+        assert_eq!(
+            session.execute(Command::Slice(SliceRange {
+                start: 3,
+                end: Some(2)
+            })),
+            Err(ReplError::SliceOrder { start: 3, end: 2 })
+        );
+    }
+}
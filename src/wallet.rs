@@ -0,0 +1,142 @@
+// The points economy: hints cost points, and whatever is left is the reward for winning
+use std::fmt;
+
+/// Points the player can spend on hints during a round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wallet {
+    points: i32,
+    // Whether the next purchase costs nothing
+    free: bool,
+}
+
+/// Error returned when a purchase costs more than the wallet holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insufficient {
+    pub cost: u32,
+    pub available: i32,
+}
+
+impl fmt::Display for Insufficient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "that costs {} points but you only have {}",
+            self.cost, self.available
+        )
+    }
+}
+
+impl std::error::Error for Insufficient {}
+
+impl Wallet {
+    pub fn new(points: i32) -> Wallet {
+        Wallet {
+            points,
+            free: false,
+        }
+    }
+
+    /// The same wallet, with its first purchase free whatever it costs, even
+    /// when the wallet is empty. Later purchases are paid for as usual.
+    pub fn with_free_first(mut self) -> Wallet {
+        self.free = true;
+        self
+    }
+
+    /// Whether the next purchase is the free one.
+    pub fn next_is_free(&self) -> bool {
+        self.free
+    }
+
+    pub fn points(&self) -> i32 {
+        self.points
+    }
+
+    /// Takes `cost` points out of the wallet, or leaves it untouched if it can't
+    /// afford them. The free purchase, if there is one left, takes nothing.
+    pub fn spend(&mut self, cost: u32) -> Result<(), Insufficient> {
+        if self.free {
+            self.free = false;
+            return Ok(());
+        }
+        let insufficient = Insufficient {
+            cost,
+            available: self.points,
+        };
+        let cost = i32::try_from(cost).map_err(|_| insufficient.clone())?;
+        if cost > self.points {
+            return Err(insufficient);
+        }
+        self.points -= cost;
+        Ok(())
+    }
+
+    /// Points awarded at the end of a round: the unspent budget for a win, nothing otherwise.
+    pub fn award(&self, won: bool) -> i32 {
+        if won { self.points } else { 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spending_within_budget_takes_the_cost() {
+        let mut wallet = Wallet::new(10);
+        assert_eq!(wallet.spend(3), Ok(()));
+        assert_eq!(wallet.spend(7), Ok(()));
+        assert_eq!(wallet.points(), 0);
+    }
+
+    #[test]
+    fn overspending_is_rejected_and_leaves_the_wallet_alone() {
+        let mut wallet = Wallet::new(4);
+        let err = wallet.spend(5).unwrap_err();
+        assert_eq!(
+            err,
+            Insufficient {
+                cost: 5,
+                available: 4
+            }
+        );
+        assert_eq!(err.to_string(), "that costs 5 points but you only have 4");
+        assert_eq!(wallet.points(), 4);
+        // Too big for an i32 is still just too expensive
+        assert!(wallet.spend(u32::MAX).is_err());
+        assert_eq!(wallet.points(), 4);
+    }
+
+    #[test]
+    fn a_win_awards_what_is_left_and_a_loss_nothing() {
+        let mut wallet = Wallet::new(20);
+        wallet.spend(5).unwrap();
+        assert_eq!(wallet.award(true), 15);
+        assert_eq!(wallet.award(false), 0);
+    }
+
+    #[test]
+    fn with_free_first_the_first_purchase_is_free_and_the_next_charged() {
+        let mut wallet = Wallet::new(10).with_free_first();
+        assert!(wallet.next_is_free());
+        assert_eq!(wallet.spend(5), Ok(()));
+        assert_eq!(wallet.points(), 10);
+        assert!(!wallet.next_is_free());
+        assert_eq!(wallet.spend(3), Ok(()));
+        assert_eq!(wallet.points(), 7);
+    }
+
+    #[test]
+    fn the_free_purchase_works_from_an_empty_wallet_but_only_once() {
+        let mut wallet = Wallet::new(0).with_free_first();
+        assert_eq!(wallet.spend(5), Ok(()));
+        assert_eq!(
+            wallet.spend(2),
+            Err(Insufficient {
+                cost: 2,
+                available: 0
+            })
+        );
+        assert_eq!(wallet.award(true), 0);
+    }
+}
@@ -0,0 +1,282 @@
+// A warm-up before the real game: work out the range itself from a few clues
+// Kept apart from Game, since the only thing it shares with it is the range
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::range_math;
+
+/// How one guessed bound compares with the real one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundCheck {
+    Exact,
+    TooLow(u32),
+    TooHigh(u32),
+}
+
+impl BoundCheck {
+    fn new(guessed: u32, actual: u32) -> BoundCheck {
+        match guessed.cmp(&actual) {
+            std::cmp::Ordering::Less => BoundCheck::TooLow(actual - guessed),
+            std::cmp::Ordering::Equal => BoundCheck::Exact,
+            std::cmp::Ordering::Greater => BoundCheck::TooHigh(guessed - actual),
+        }
+    }
+}
+
+/// Feedback on a guessed `(low, high)` pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundsFeedback {
+    pub low: BoundCheck,
+    pub high: BoundCheck,
+}
+
+impl BoundsFeedback {
+    pub fn is_exact(&self) -> bool {
+        self.low == BoundCheck::Exact && self.high == BoundCheck::Exact
+    }
+
+    /// The guess sits inside the real range without covering all of it.
+    pub fn is_too_narrow(&self) -> bool {
+        !self.is_exact()
+            && matches!(self.low, BoundCheck::Exact | BoundCheck::TooHigh(_))
+            && matches!(self.high, BoundCheck::Exact | BoundCheck::TooLow(_))
+    }
+
+    /// The guess covers the real range and more.
+    pub fn is_too_wide(&self) -> bool {
+        !self.is_exact()
+            && matches!(self.low, BoundCheck::Exact | BoundCheck::TooLow(_))
+            && matches!(self.high, BoundCheck::Exact | BoundCheck::TooHigh(_))
+    }
+}
+
+fn describe(f: &mut fmt::Formatter, name: &str, check: BoundCheck) -> fmt::Result {
+    match check {
+        BoundCheck::Exact => write!(f, "{name} is right"),
+        BoundCheck::TooLow(by) => write!(f, "{name} is {by} too low"),
+        BoundCheck::TooHigh(by) => write!(f, "{name} is {by} too high"),
+    }
+}
+
+impl fmt::Display for BoundsFeedback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_exact() {
+            return write!(f, "Exactly right");
+        }
+        describe(f, "The start", self.low)?;
+        write!(f, " and ")?;
+        describe(f, "the end", self.high)?;
+        if self.is_too_narrow() {
+            write!(f, " (too narrow)")
+        } else if self.is_too_wide() {
+            write!(f, " (too wide)")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Compares a guessed `(low, high)` with the real range, bound by bound.
+pub fn check_bounds_guess(guessed: (u32, u32), actual: &RangeInclusive<u32>) -> BoundsFeedback {
+    BoundsFeedback {
+        low: BoundCheck::new(guessed.0, *actual.start()),
+        high: BoundCheck::new(guessed.1, *actual.end()),
+    }
+}
+
+/// Reads a pair of bounds typed as "1 100", "1-100", "1..100" or "1..=100".
+pub fn parse_bounds(input: &str) -> Option<(u32, u32)> {
+    let input = input.trim();
+    let (low, high) = ["..=", "..", "-", " "]
+        .iter()
+        .find_map(|sep| input.split_once(sep))?;
+    Some((low.trim().parse().ok()?, high.trim().parse().ok()?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Playing, with this many clues shown so far.
+    Clues(usize),
+    Solved,
+    /// Every clue was used up without finding the range.
+    GaveUp,
+}
+
+/// The warm-up round: a wrong answer earns the next clue, until they run out.
+#[derive(Debug, Clone)]
+pub struct Warmup {
+    actual: RangeInclusive<u32>,
+    state: State,
+}
+
+/// How many clues there are, and so how many wrong answers end the warm-up.
+pub const CLUES: usize = 3;
+
+impl Warmup {
+    pub fn new(actual: RangeInclusive<u32>) -> Warmup {
+        Warmup {
+            actual,
+            state: State::Clues(0),
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        !matches!(self.state, State::Clues(_))
+    }
+
+    pub fn solved(&self) -> bool {
+        self.state == State::Solved
+    }
+
+    /// Shows the next clue, or None when they have all been shown (or the warm-up is over).
+    pub fn next_clue(&mut self) -> Option<String> {
+        let State::Clues(shown) = self.state else {
+            return None;
+        };
+        // The first two pin the range down for anyone who does the arithmetic;
+        // the last one gives the game away
+        let clue = match shown {
+            0 => format!(
+                "The range holds {} numbers.",
+                range_math::width(&self.actual)
+            ),
+            1 => format!("Its middle is {}.", range_math::midpoint(&self.actual)),
+            2 => format!("It starts at {}.", self.actual.start()),
+            _ => return None,
+        };
+        self.state = State::Clues(shown + 1);
+        Some(clue)
+    }
+
+    /// Checks an answer. Once every clue has been shown, a wrong answer ends the warm-up.
+    pub fn guess(&mut self, guessed: (u32, u32)) -> BoundsFeedback {
+        let feedback = check_bounds_guess(guessed, &self.actual);
+        if let State::Clues(shown) = self.state {
+            if feedback.is_exact() {
+                self.state = State::Solved;
+            } else if shown >= CLUES {
+                self.state = State::GaveUp;
+            }
+        }
+        feedback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTUAL: RangeInclusive<u32> = 1..=100;
+
+    #[test]
+    fn the_real_range_is_exact() {
+        let feedback = check_bounds_guess((1, 100), &ACTUAL);
+        assert!(feedback.is_exact());
+        assert!(!feedback.is_too_narrow());
+        assert!(!feedback.is_too_wide());
+        assert_eq!(feedback.to_string(), "Exactly right");
+    }
+
+    #[test]
+    fn a_range_inside_the_real_one_is_too_narrow() {
+        let cases = [
+            (
+                (10, 90),
+                "The start is 9 too high and the end is 10 too low (too narrow)",
+            ),
+            (
+                (1, 90),
+                "The start is right and the end is 10 too low (too narrow)",
+            ),
+            (
+                (10, 100),
+                "The start is 9 too high and the end is right (too narrow)",
+            ),
+        ];
+        for (guessed, text) in cases {
+            let feedback = check_bounds_guess(guessed, &ACTUAL);
+            assert!(feedback.is_too_narrow(), "{guessed:?}");
+            assert!(!feedback.is_too_wide(), "{guessed:?}");
+            assert_eq!(feedback.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn a_range_around_the_real_one_is_too_wide() {
+        let range = 10..=90;
+        let cases = [
+            (
+                (0, 100),
+                "The start is 10 too low and the end is 10 too high (too wide)",
+            ),
+            (
+                (10, 95),
+                "The start is right and the end is 5 too high (too wide)",
+            ),
+            (
+                (5, 90),
+                "The start is 5 too low and the end is right (too wide)",
+            ),
+        ];
+        for (guessed, text) in cases {
+            let feedback = check_bounds_guess(guessed, &range);
+            assert!(feedback.is_too_wide(), "{guessed:?}");
+            assert!(!feedback.is_too_narrow(), "{guessed:?}");
+            assert_eq!(feedback.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn a_shifted_range_is_neither_too_narrow_nor_too_wide() {
+        let feedback = check_bounds_guess((20, 120), &ACTUAL);
+        assert_eq!(
+            feedback,
+            BoundsFeedback {
+                low: BoundCheck::TooHigh(19),
+                high: BoundCheck::TooHigh(20),
+            }
+        );
+        assert!(!feedback.is_too_narrow() && !feedback.is_too_wide());
+        assert_eq!(
+            feedback.to_string(),
+            "The start is 19 too high and the end is 20 too high"
+        );
+    }
+
+    #[test]
+    fn bounds_parse_with_any_separator() {
+        for text in ["1 100", "1-100", "1..100", "1..=100", " 1 .. 100 "] {
+            assert_eq!(parse_bounds(text), Some((1, 100)), "{text:?}");
+        }
+        assert_eq!(parse_bounds("1"), None);
+        assert_eq!(parse_bounds("one-100"), None);
+    }
+
+    #[test]
+    fn a_wrong_answer_after_the_last_clue_gives_up() {
+        let mut warmup = Warmup::new(ACTUAL);
+        let clues: Vec<String> = std::iter::from_fn(|| warmup.next_clue()).collect();
+        assert_eq!(
+            clues,
+            [
+                "The range holds 100 numbers.",
+                "Its middle is 50.",
+                "It starts at 1."
+            ]
+        );
+        assert!(!warmup.is_over());
+        warmup.guess((1, 99));
+        assert!(warmup.is_over() && !warmup.solved());
+    }
+
+    #[test]
+    fn the_right_answer_solves_it() {
+        let mut warmup = Warmup::new(ACTUAL);
+        warmup.next_clue();
+        warmup.guess((1, 50));
+        assert!(!warmup.is_over());
+        warmup.guess((1, 100));
+        assert!(warmup.solved());
+        assert_eq!(warmup.next_clue(), None);
+    }
+}
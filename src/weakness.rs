@@ -0,0 +1,170 @@
+// Finding the part of the range where a player does worst, and practising it
+// Each round in the history file is scored by how close it came to binary
+// search, and filed under the quarter of its range the secret was in. Ranges
+// of any size pool together, since only the secret's position counts
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::analysis;
+use crate::persisted_file::{self, Kind};
+use crate::record::{GameRecord, RecordOutcome};
+
+/// How many zones a range is split into.
+pub const ZONES: usize = 4;
+
+/// How often a practice secret comes from the weak zone; the rest of the time
+/// it is drawn from the whole range, so the zone can't simply be assumed.
+pub const PRACTICE_BIAS: f64 = 0.75;
+
+/// The header history files are written with.
+pub const HISTORY_KIND: Kind = Kind {
+    name: "history",
+    version: 1,
+};
+
+/// The rounds whose secret was in one zone.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ZoneStats {
+    pub rounds: u32,
+    /// Mean of binary search's guesses divided by the player's, from 0 to 1
+    /// (it can pass 1 on a lucky round); 0 when there are no rounds.
+    pub efficiency: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaknessReport {
+    /// One per zone, lowest first.
+    pub zones: [ZoneStats; ZONES],
+    /// The zone with the lowest efficiency, if any round was won.
+    pub weakest: Option<usize>,
+}
+
+/// Which of the `ZONES` equal parts of `range` the number is in.
+pub fn zone_of(range: &RangeInclusive<u32>, n: u32) -> usize {
+    let offset = n.saturating_sub(*range.start()) as u64;
+    ((offset * ZONES as u64 / analysis::width(range).max(1)) as usize).min(ZONES - 1)
+}
+
+/// The numbers of `range` in zone `zone`.
+pub fn zone_range(range: &RangeInclusive<u32>, zone: usize) -> RangeInclusive<u32> {
+    let width = analysis::width(range);
+    let start = *range.start() as u64;
+    let low = start + width * zone as u64 / ZONES as u64;
+    // A zone of a range narrower than ZONES can come out empty, and on a range
+    // starting at 0 its end would go below 0: it is then just its start
+    let high = (start + width * (zone as u64 + 1) / ZONES as u64)
+        .saturating_sub(1)
+        .max(low)
+        .min(*range.end() as u64);
+    low as u32..=high as u32
+}
+
+/// Scores every won round and finds the zone where they went worst. Rounds
+/// that weren't won say little about guessing, so they are left out.
+pub fn weakness_profile(sessions: &[GameRecord]) -> WeaknessReport {
+    let mut totals = [(0u32, 0.0f64); ZONES];
+    for record in sessions {
+        if record.outcome != RecordOutcome::Won || record.turns.is_empty() {
+            continue;
+        }
+        let optimal = analysis::optimal_guesses(&record.range, record.secret).len();
+        let (rounds, sum) = &mut totals[zone_of(&record.range, record.secret)];
+        *rounds += 1;
+        *sum += optimal as f64 / record.turns.len() as f64;
+    }
+    let zones = totals.map(|(rounds, sum)| ZoneStats {
+        rounds,
+        efficiency: if rounds == 0 {
+            0.0
+        } else {
+            sum / rounds as f64
+        },
+    });
+    // The first zone wins a tie, so the answer doesn't depend on float noise
+    let weakest = (0..ZONES)
+        .filter(|&zone| zones[zone].rounds > 0)
+        .min_by(|&a, &b| zones[a].efficiency.total_cmp(&zones[b].efficiency));
+    WeaknessReport { zones, weakest }
+}
+
+/// A secret for a practice round, drawn from `zone` of `range` most of the
+/// time (see `PRACTICE_BIAS`). The same seed always gives the same secret.
+pub fn practice_secret(range: &RangeInclusive<u32>, zone: usize, seed: u64) -> u32 {
+    let mut rng = StdRng::seed_from_u64(seed);
+    if rng.random_bool(PRACTICE_BIAS) {
+        rng.random_range(zone_range(range, zone))
+    } else {
+        rng.random_range(range.clone())
+    }
+}
+
+/// Adds a record to the end of a history file, one JSON record per line.
+///
+/// The header's checksum covers every line, so this rewrites the whole file;
+/// a damaged file is left alone and reported instead.
+pub fn append_history(path: &Path, record: &GameRecord) -> io::Result<()> {
+    let mut text = persisted_file::read(path, HISTORY_KIND)?.unwrap_or_default();
+    text.push_str(&record.to_json());
+    text.push('\n');
+    persisted_file::write(path, HISTORY_KIND, &text)
+}
+
+/// Every record in a history file; a file that doesn't exist yet is empty.
+pub fn load_history(path: &Path) -> io::Result<Vec<GameRecord>> {
+    let Some(text) = persisted_file::read(path, HISTORY_KIND)? else {
+        return Ok(Vec::new());
+    };
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            GameRecord::from_json(line).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {err}", i + 1))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_range_stays_in_narrow_zero_based_ranges() {
+        for end in 0..=2 {
+            let range = 0..=end;
+            for zone in 0..ZONES {
+                let zone_range = zone_range(&range, zone);
+                assert!(
+                    zone_range.start() <= zone_range.end(),
+                    "{range:?} zone {zone}"
+                );
+                assert!(range.contains(zone_range.start()), "{range:?} zone {zone}");
+                assert!(range.contains(zone_range.end()), "{range:?} zone {zone}");
+            }
+        }
+    }
+
+    #[test]
+    fn zone_range_splits_a_wide_range_into_quarters() {
+        assert_eq!(zone_range(&(1..=100), 0), 1..=25);
+        assert_eq!(zone_range(&(1..=100), 3), 76..=100);
+        assert_eq!(zone_range(&(0..=u32::MAX), 3).end(), &u32::MAX);
+    }
+
+    #[test]
+    fn practice_secret_stays_in_narrow_zero_based_ranges() {
+        for end in 0..=2 {
+            let range = 0..=end;
+            for zone in 0..ZONES {
+                for seed in 0..20 {
+                    assert!(range.contains(&practice_secret(&range, zone, seed)));
+                }
+            }
+        }
+    }
+}
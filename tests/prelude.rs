@@ -0,0 +1,37 @@
+// Everything here comes from the prelude alone, so a type dropped from it
+// breaks this build
+use learning_rust::prelude::*;
+
+// The prelude has the InputSource trait but no scripted input, so a caller
+// writes their own
+struct OneLine(Option<String>);
+
+impl InputSource for OneLine {
+    fn read_line(&mut self) -> std::io::Result<Option<String>> {
+        Ok(self.0.take())
+    }
+}
+
+#[test]
+fn a_round_can_be_played_with_only_the_prelude() {
+    let mut game = Game::with_secret(1..=100, 42);
+    let mut input = OneLine(Some("42".to_string()));
+    let mut seen = Vec::new();
+    {
+        let mut observer: Box<dyn OutcomeObserver + '_> =
+            Box::new(|guess: u32, outcome: &GuessOutcome| seen.push((guess, *outcome)));
+
+        let line = input.read_line().unwrap().expect("a line to read");
+        let guess = Guess::new(line.parse().unwrap(), game.range()).unwrap();
+        let outcome = game.guess(guess);
+        observer.on_outcome(guess.value(), &outcome);
+        assert_eq!(outcome, GuessOutcome::Correct);
+    }
+    assert_eq!(seen, [(42, GuessOutcome::Correct)]);
+    assert!(input.read_line().unwrap().is_none());
+
+    let record: GameRecord = game.into_record();
+    assert_eq!(record.secret, 42);
+    assert_eq!(record.turns.len(), 1);
+    assert_eq!(record.range, 1..=100);
+}
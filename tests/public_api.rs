@@ -0,0 +1,85 @@
+// A compile-time contract for embedders: a whole scripted game played with
+// nothing but the prelude. If a type this needs stops being public, or
+// leaves the prelude, this file stops building
+use learning_rust::prelude::*;
+
+// Lines handed out one by one, like a player typing them
+struct Script(std::vec::IntoIter<&'static str>);
+
+impl InputSource for Script {
+    fn read_line(&mut self) -> std::io::Result<Option<String>> {
+        Ok(self.0.next().map(str::to_string))
+    }
+}
+
+// An outside strategy, to check the trait can be implemented downstream
+struct Lowest;
+
+impl Strategy for Lowest {
+    fn next_guess(&mut self, feasible: &std::ops::RangeInclusive<u32>) -> u32 {
+        *feasible.start()
+    }
+}
+
+#[test]
+fn a_scripted_game_is_played_through_the_prelude() {
+    let config = Config::from_args(["--difficulty".to_string(), "medium".to_string()])
+        .expect("valid arguments");
+    let mut game = Game::with_secret(config.difficulty.range(), 42);
+    let mut input = Script(vec!["fifty", "50", "500", "25", "37", "43", "42"].into_iter());
+    let mut log = Vec::new();
+    let mut rejected = 0;
+    {
+        let mut observers: Vec<Box<dyn OutcomeObserver + '_>> =
+            vec![Box::new(|guess: u32, outcome: &GuessOutcome| {
+                log.push((guess, *outcome))
+            })];
+
+        while let Some(line) = input.read_line().unwrap() {
+            let Ok(value) = line.trim().parse() else {
+                rejected += 1;
+                continue;
+            };
+            let guess = match Guess::new(value, game.range()) {
+                Ok(guess) => guess,
+                Err(OutOfRange { .. }) => {
+                    rejected += 1;
+                    continue;
+                }
+            };
+            let outcome = game.guess(guess);
+            for observer in observers.iter_mut() {
+                observer.on_outcome(guess.value(), &outcome);
+            }
+            if outcome == GuessOutcome::Correct {
+                break;
+            }
+        }
+    }
+
+    assert_eq!(rejected, 2);
+    assert_eq!(
+        log,
+        [
+            (50, GuessOutcome::TooBig),
+            (25, GuessOutcome::TooSmall),
+            (37, GuessOutcome::TooSmall),
+            (43, GuessOutcome::TooBig),
+            (42, GuessOutcome::Correct),
+        ]
+    );
+    assert_eq!(*game.feasible(), 42..=42);
+    assert_eq!(Lowest.next_guess(game.feasible()), 42);
+
+    let attempts = game.attempts();
+    let record: GameRecord = game.into_record();
+    let result = RoundResult::builder(config.difficulty)
+        .won(true)
+        .attempts(attempts)
+        .secret(record.secret)
+        .history(record.turns.iter().map(|turn| turn.guess).collect())
+        .build()
+        .expect("a consistent result");
+    assert_eq!(result.attempts, 5);
+    assert_eq!(result.history, [50, 25, 37, 43, 42]);
+}
@@ -0,0 +1,11 @@
+#!/bin/sh
+# Builds the wasm module into pkg/, ready for index.html to import
+# Needs wasm-pack (cargo install wasm-pack) and the wasm32 target
+# (rustup target add wasm32-unknown-unknown)
+set -e
+cd "$(dirname "$0")"
+wasm-pack build --target web
+
+# Any static file server works; the page can't be opened as file:// because
+# browsers refuse to load wasm modules from the filesystem
+echo "Now run: python3 -m http.server 8000   and open http://localhost:8000/www/"
@@ -0,0 +1,52 @@
+// The guessing game from src/bin/guess_with_random.rs, compiled to WebAssembly
+// The rules and messages are kept the same as that file, so a logic bug in one
+// shows up in the other. What changes is the plumbing: instead of a loop reading
+// stdin, JavaScript calls guess() once per number typed into the page
+use std::cmp::Ordering;
+
+use wasm_bindgen::prelude::*;
+
+// #[wasm_bindgen] generates the glue that lets JavaScript construct a Game and
+// call its methods; the fields stay private, so the secret can't be read from JS
+#[wasm_bindgen]
+pub struct Game {
+    secret: u32,
+    attempts: u32,
+}
+
+#[wasm_bindgen]
+impl Game {
+    // `new Game()` in JavaScript
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Game {
+        // Same range as guess_with_random.rs: 1..=100
+        let secret = (js_sys::Math::random() * 100.0) as u32 + 1;
+        Game {
+            secret,
+            attempts: 0,
+        }
+    }
+
+    // wasm-bindgen can't hand JavaScript a reference into Rust memory, so the
+    // message is returned as an owned String (a JS string on the other side)
+    pub fn guess(&mut self, n: u32) -> String {
+        self.attempts += 1;
+        match n.cmp(&self.secret) {
+            Ordering::Less => "Too small!",
+            Ordering::Greater => "Too big!",
+            Ordering::Equal => "You win!",
+        }
+        .to_string()
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+}
+
+// Clippy expects a type with a no-argument new() to have a Default as well
+impl Default for Game {
+    fn default() -> Game {
+        Game::new()
+    }
+}
@@ -0,0 +1,32 @@
+// wasm-pack --target web writes pkg/wasm_guess.js: import it, then await
+// init() once to download and instantiate the .wasm file
+import init, { Game } from "../pkg/wasm_guess.js";
+
+await init();
+let game = new Game();
+
+const form = document.getElementById("form");
+const input = document.getElementById("guess");
+const message = document.getElementById("message");
+const attempts = document.getElementById("attempts");
+
+form.addEventListener("submit", (event) => {
+  event.preventDefault();
+  // Same check as parse() in guess_with_random.rs: only whole numbers count
+  const n = Number(input.value);
+  if (!Number.isInteger(n) || n < 0) {
+    message.textContent = "Please type a number!";
+    return;
+  }
+  const answer = game.guess(n);
+  message.textContent = `You guessed: ${n}. ${answer}`;
+  attempts.textContent = `Attempts: ${game.attempts()}`;
+  if (answer === "You win!") {
+    // Start a fresh round; free() releases the old Game's Rust memory
+    game.free();
+    game = new Game();
+    message.textContent += " A new secret has been picked.";
+  }
+  input.value = "";
+  input.focus();
+});